@@ -0,0 +1,45 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use licensa::commands::bench::{
+    apply_headers, detect_headers, scan_workspace, synthesize_workspace,
+};
+
+/// Number of files in the synthesized workspace.
+const FILES: usize = 2_000;
+
+fn scan(c: &mut Criterion) {
+    let workspace = synthesize_workspace(FILES).unwrap();
+    let candidates = scan_workspace(workspace.root()).unwrap();
+
+    let mut group = c.benchmark_group("scan");
+    group.throughput(Throughput::Elements(FILES as u64));
+    group.bench_function("find_candidates", |b| {
+        b.iter(|| scan_workspace(workspace.root()).unwrap())
+    });
+    group.bench_function("detect_headers", |b| b.iter(|| detect_headers(&candidates)));
+    group.finish();
+}
+
+fn apply(c: &mut Criterion) {
+    let mut group = c.benchmark_group("apply");
+    group.throughput(Throughput::Elements(FILES as u64));
+    group.sample_size(10);
+    group.bench_function("apply_headers", |b| {
+        // Applying modifies the files, so every iteration gets a fresh workspace
+        b.iter_batched(
+            || {
+                let workspace = synthesize_workspace(FILES).unwrap();
+                let candidates = scan_workspace(workspace.root()).unwrap();
+                (workspace, candidates)
+            },
+            |(_workspace, candidates)| apply_headers(&candidates).unwrap(),
+            BatchSize::PerIteration,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, scan, apply);
+criterion_main!(benches);
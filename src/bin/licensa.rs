@@ -2,7 +2,12 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
 use licensa::cli::{Cli, Command};
+use licensa::color;
 use licensa::commands;
+use licensa::config;
+use licensa::jobs;
+use licensa::logger;
+use licensa::workspace::lock;
 
 use anyhow::Result;
 use clap::Parser;
@@ -13,12 +18,22 @@ fn main() -> Result<()> {
 
 fn run() -> Result<()> {
     let cli = Cli::parse();
+    color::init(cli.color);
+    logger::init(cli.log_level(), cli.log_format);
+    jobs::init(cli.jobs());
+    config::init_config_file(cli.config.clone());
+    config::init_profile(cli.profile.clone());
+    lock::init_lock_timeout(cli.lock_timeout);
 
     match cli.command {
         Command::Init(args) => {
             commands::init::run(&args)?;
         }
 
+        Command::Migrate(args) => {
+            commands::migrate::run(&args)?;
+        }
+
         Command::Apply(args) => {
             commands::apply::run(&args)?;
         }
@@ -26,6 +41,66 @@ fn run() -> Result<()> {
         Command::Verify(mut args) => {
             commands::verify::run(&mut args)?;
         }
+
+        Command::Normalize(mut args) => {
+            commands::normalize::run(&mut args)?;
+        }
+
+        Command::Lint(mut args) => {
+            commands::lint::run(&mut args)?;
+        }
+
+        Command::Clean(args) => {
+            commands::clean::run(&args)?;
+        }
+
+        Command::Undo(args) => {
+            commands::undo::run(&args)?;
+        }
+
+        Command::Stats(mut args) => {
+            commands::stats::run(&mut args)?;
+        }
+
+        Command::Sbom(mut args) => {
+            commands::sbom::run(&mut args)?;
+        }
+
+        Command::Notice(mut args) => {
+            commands::notice::run(&mut args)?;
+        }
+
+        Command::Deps(args) => {
+            commands::deps::run(&args)?;
+        }
+
+        Command::CheckCompat(mut args) => {
+            commands::check_compat::run(&mut args)?;
+        }
+
+        Command::List(args) => {
+            commands::list::run(&args)?;
+        }
+
+        Command::Show(mut args) => {
+            commands::show::run(&mut args)?;
+        }
+
+        Command::Preview(mut args) => {
+            commands::preview::run(&mut args)?;
+        }
+
+        Command::Config(args) => {
+            commands::config::run(&args)?;
+        }
+
+        Command::Man(args) => {
+            commands::man::run(&args)?;
+        }
+
+        Command::Bench(args) => {
+            commands::bench::run(&args)?;
+        }
     };
 
     Ok(())
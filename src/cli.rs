@@ -2,10 +2,31 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
 use crate::commands::apply::ApplyArgs;
+use crate::commands::bench::BenchArgs;
+use crate::commands::check_compat::CheckCompatArgs;
+use crate::commands::clean::CleanArgs;
+use crate::commands::config::ConfigArgs;
+use crate::commands::deps::DepsArgs;
 use crate::commands::init::InitArgs;
+use crate::commands::lint::LintArgs;
+use crate::commands::list::ListArgs;
+use crate::commands::man::ManArgs;
+use crate::commands::migrate::MigrateArgs;
+use crate::commands::normalize::NormalizeArgs;
+use crate::commands::notice::NoticeArgs;
+use crate::commands::preview::PreviewArgs;
+use crate::commands::sbom::SbomArgs;
+use crate::commands::show::ShowArgs;
+use crate::commands::stats::StatsArgs;
+use crate::commands::undo::UndoArgs;
 use crate::commands::verify::VerifyArgs;
 
-use clap::{Parser, Subcommand};
+use crate::color::ColorChoice;
+use crate::logger::{self, LevelFilter, LogFormat};
+
+use clap::{ArgAction, Parser, Subcommand};
+
+use std::path::PathBuf;
 
 /// Licensa is a powerful CLI tool designed for seamless source code license management.
 ///
@@ -16,13 +37,79 @@ use clap::{Parser, Subcommand};
 #[command(propagate_version = true)]
 #[command(next_line_help = true)]
 pub struct Cli {
-    #[arg(short, long, default_value_t = false)]
-    pub verbose: bool,
+    /// Increase output verbosity. Pass twice (`-vv`) for trace output.
+    #[arg(short, long, action = ArgAction::Count, global = true)]
+    pub verbose: u8,
+
+    /// Only print errors and the final result of a command.
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+
+    /// Output format of log messages written to stderr.
+    #[arg(
+        long,
+        value_enum,
+        value_name = "FORMAT",
+        global = true,
+        default_value_t
+    )]
+    pub log_format: LogFormat,
+
+    /// When to use colors in command output.
+    ///
+    /// With `auto`, colors are used if writing to a terminal and the `NO_COLOR`
+    /// environment variable is not set.
+    #[arg(long, value_enum, value_name = "WHEN", global = true, default_value_t)]
+    pub color: ColorChoice,
+
+    /// Number of parallel jobs used to scan and process files.
+    ///
+    /// Defaults to the number of available CPUs.
+    #[arg(
+        short,
+        long,
+        value_name = "N",
+        global = true,
+        value_parser = clap::value_parser!(u16).range(1..)
+    )]
+    pub jobs: Option<u16>,
+
+    /// Path to a Licensa config file.
+    ///
+    /// When set, this file is used instead of discovering a `.licensarc`
+    /// file in the workspace.
+    #[arg(long, value_name = "PATH", global = true)]
+    pub config: Option<PathBuf>,
+
+    /// Name of a profile defined in the `profiles` section of the config file.
+    ///
+    /// The fields of the profile are merged over the base config.
+    #[arg(long, value_name = "NAME", global = true)]
+    pub profile: Option<String>,
+
+    /// Seconds to wait for another run of a command modifying the workspace.
+    ///
+    /// Commands such as `apply` and `undo` lock the workspace while they run.
+    /// By default, they fail right away if the workspace is already locked.
+    #[arg(long, value_name = "SECONDS", global = true)]
+    pub lock_timeout: Option<u64>,
 
     #[command(subcommand)]
     pub command: Command,
 }
 
+impl Cli {
+    /// Returns the maximum log level selected by the verbosity flags.
+    pub fn log_level(&self) -> LevelFilter {
+        logger::level_filter(self.verbose, self.quiet)
+    }
+
+    /// Returns the number of parallel jobs selected with `--jobs`, if any.
+    pub fn jobs(&self) -> Option<usize> {
+        self.jobs.map(usize::from)
+    }
+}
+
 #[derive(Debug, Subcommand)]
 pub enum Command {
     /// Initialize Licensa configuration for the current workspace.
@@ -47,6 +134,20 @@ pub enum Command {
     #[command(name = "init")]
     Init(InitArgs),
 
+    /// Convert the configuration of another license header tool.
+    ///
+    /// Settings of google/addlicense, passed as its command line after `--`, or of
+    /// Apache SkyWalking Eyes (`license-eye`), read from `.licenserc.yaml`, are written
+    /// to a `.licensarc` file, and their ignored paths to `.licensaignore`. Settings
+    /// without a Licensa equivalent are reported as warnings.
+    ///
+    /// # Errors
+    ///
+    /// The `migrate` workflow fails if the current working directory already
+    /// contains a `.licensarc` file, or if the configuration can't be parsed.
+    #[command(name = "migrate")]
+    Migrate(MigrateArgs),
+
     /// Verify presence of license headers in one or more files.
     ///
     /// A glob pattern may be used to verify multiple files that recursively.
@@ -81,4 +182,137 @@ pub enum Command {
     /// - Insufficient read/write permissions for source files
     #[command(name = "apply")]
     Apply(ApplyArgs),
+
+    /// Rewrite existing license headers in the configured format.
+    ///
+    /// Recognized headers are parsed into their copyright owner, years and license,
+    /// and rendered again with the configured `format` and comment style. Headers
+    /// with content that can't be represented in the configured format, e.g.
+    /// additional copyright lines, are left untouched.
+    #[command(name = "normalize")]
+    Normalize(NormalizeArgs),
+
+    /// Report common problems in existing license headers.
+    ///
+    /// Flags misspellings of "Copyright", copyright lines without a year, SPDX IDs
+    /// in the wrong casing, duplicate `SPDX-License-Identifier` tags, tabs and
+    /// trailing whitespace. Each problem is reported with its location and a
+    /// diagnostic code. Fails if any problem is found.
+    #[command(name = "lint")]
+    Lint(LintArgs),
+
+    /// Remove artifacts created by Licensa in the current workspace.
+    ///
+    /// Without any flags, all known artifacts are removed. Use flags to select
+    /// specific artifacts, e.g. `--backups` for backup copies created by `apply --backup`.
+    #[command(name = "clean")]
+    Clean(CleanArgs),
+
+    /// Revert the file modifications of the most recent `apply` run.
+    ///
+    /// Every modification is recorded in the `.licensa/journal` file of the workspace.
+    /// Files that have been modified since the run are left untouched.
+    #[command(name = "undo")]
+    Undo(UndoArgs),
+
+    /// Report license header coverage of the current workspace.
+    ///
+    /// Candidate files are grouped by language, directory and file extension. For each group,
+    /// the number of files with and without a license header is reported, along with
+    /// the SPDX license expressions found in existing headers.
+    #[command(name = "stats")]
+    Stats(StatsArgs),
+
+    /// Generate a software bill of materials (SBOM) of per-file license information.
+    ///
+    /// Every candidate file is listed with its checksums, the SPDX license expression
+    /// and the copyright notice found in its header. The license declared in the
+    /// workspace configuration is recorded as the declared license of the workspace.
+    #[command(name = "sbom")]
+    Sbom(SbomArgs),
+
+    /// Create or update the NOTICE file of the workspace.
+    ///
+    /// The notice file names the project, followed by the copyright lines of the
+    /// workspace owner and path owners, and the `attributions` of bundled third-party
+    /// works from the config file, as required by the Apache License 2.0. The year of
+    /// an existing notice file is extended to the current year unless `year` is set.
+    #[command(name = "notice")]
+    Notice(NoticeArgs),
+
+    /// List the licenses of third-party dependencies.
+    ///
+    /// Dependencies are read from `Cargo.lock` (or `Cargo.toml`), `package.json` and
+    /// `pyproject.toml` in the current working directory. Licenses are resolved from
+    /// locally installed packages, e.g. the Cargo registry cache, `node_modules` or a
+    /// Python virtual environment in `.venv`. No network requests are made.
+    #[command(name = "deps")]
+    Deps(DepsArgs),
+
+    /// Check whether licenses used in the workspace are compatible with the project license.
+    ///
+    /// The SPDX license expressions found in file headers and the licenses of
+    /// third-party dependencies are checked against the project license, e.g. GPL
+    /// code in an MIT project, using a built-in compatibility matrix of common
+    /// licenses. Licenses not covered by the matrix are reported as unknown.
+    #[command(name = "check-compat")]
+    CheckCompat(CheckCompatArgs),
+
+    /// List the licenses of the SPDX license list.
+    ///
+    /// Each license is printed with its SPDX identifier, its full name and whether
+    /// it's OSI approved, FSF free/libre, copyleft or deprecated. Pass `--output json`
+    /// to consume the license catalog in scripts.
+    #[command(name = "list")]
+    List(ListArgs),
+
+    /// Print the license header written for a license, and optionally the license text.
+    ///
+    /// The header is rendered with the owner, year and notice format of the workspace
+    /// configuration, unless overridden by the command arguments, and commented in the
+    /// style of the file extension selected with `--extension`.
+    #[command(name = "show")]
+    Show(ShowArgs),
+
+    /// Print the license header `apply` would insert into a file, without modifying it.
+    ///
+    /// The comment style is selected by the file name and, for scripts without a
+    /// known extension, by the hash-bang line, which helps to debug the header
+    /// selected for a file.
+    #[command(name = "preview")]
+    Preview(PreviewArgs),
+
+    /// Inspect and validate Licensa config files.
+    ///
+    /// Use `config schema` to export a JSON Schema of the `.licensarc` file for
+    /// editor support, and `config validate` to check a config file in CI.
+    #[command(name = "config")]
+    Config(ConfigArgs),
+
+    /// Generate man pages of Licensa and its subcommands.
+    ///
+    /// With `--output`, a page is written for every command, e.g. `licensa.1`,
+    /// `licensa-apply.1` and `licensa-config-schema.1`, so that packages can
+    /// install them. Otherwise, the page of `licensa` is printed to stdout.
+    #[command(name = "man")]
+    Man(ManArgs),
+
+    /// Measure scanner and apply throughput on a synthesized workspace.
+    ///
+    /// A temporary workspace with the given number of files is created, scanned,
+    /// checked for license headers and licensed. The fastest of several runs of
+    /// each stage is reported.
+    #[command(name = "bench", hide = true)]
+    Bench(BenchArgs),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_cli() {
+        Cli::command().debug_assert();
+    }
 }
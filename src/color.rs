@@ -0,0 +1,58 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Process-wide color settings.
+//!
+//! The choice made with `--color` applies to all colored output, including log
+//! messages, per-file results, result statistics and diffs. With the default `auto`,
+//! colors are only used if both stdout and stderr are terminals and the `NO_COLOR`
+//! environment variable is not set to a non-empty value (see <https://no-color.org>).
+
+use clap::ValueEnum;
+
+use std::env;
+use std::io::{stderr, stdout, IsTerminal};
+
+/// When to use colors in command output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ColorChoice {
+    /// Use colors if writing to a terminal and `NO_COLOR` is not set.
+    #[default]
+    Auto,
+
+    /// Always use colors.
+    Always,
+
+    /// Never use colors.
+    Never,
+}
+
+/// Enables or disables colored output for all commands according to `choice`.
+pub fn init(choice: ColorChoice) {
+    let no_color = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+    let is_terminal = stdout().is_terminal() && stderr().is_terminal();
+    colored::control::set_override(use_colors(choice, no_color, is_terminal));
+}
+
+/// Checks whether to use colors for `choice`.
+fn use_colors(choice: ColorChoice, no_color: bool, is_terminal: bool) -> bool {
+    match choice {
+        ColorChoice::Auto => !no_color && is_terminal,
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_use_colors() {
+        assert!(use_colors(ColorChoice::Auto, false, true));
+        assert!(!use_colors(ColorChoice::Auto, true, true));
+        assert!(!use_colors(ColorChoice::Auto, false, false));
+        assert!(use_colors(ColorChoice::Always, true, false));
+        assert!(!use_colors(ColorChoice::Never, false, true));
+    }
+}
@@ -1,26 +1,49 @@
 // Copyright 2024 Nelson Dominguez
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
-use crate::config::Config;
+use crate::config::{self, Config, FileLicense, FilePlugin};
 use crate::error;
-use crate::ops::scan::{get_path_suffix, is_candidate};
+use crate::ops::backup::Backup;
+use crate::ops::cache::{FileStamp, FileStatus, ScanCache};
+use crate::ops::diff::{colorize_preview, insertion_preview, PREVIEW_LINES};
+use crate::ops::journal::{Journal, JournalEntry, JournalRun};
+use crate::ops::permissions::ReadOnlyPolicy;
+use crate::ops::plugin::{changed_ranges, run_plugin, PluginContext};
+use crate::ops::progress::Progress;
+use crate::ops::scan::{
+    find_unmatched_patterns, get_header_lookup_name, get_path_suffix, points_outside_root,
+    Candidates, Scan, ScanConfig,
+};
 use crate::ops::stats::{WorkTreeRunnerStatistics, WorkTreeRunnerStatus};
-use crate::ops::work_tree::{FileTaskResponse, WorkTree};
+use crate::ops::work_tree::{FileTaskResponse, WorkTree, DEFAULT_READ_WINDOW};
+use crate::schema::LicenseId;
 use crate::template::cache::{Cachable, Cache};
-use crate::template::copyright::SPDX_COPYRIGHT_NOTICE;
-use crate::template::has_copyright_notice;
-use crate::template::header::{extract_hash_bang, SourceHeaders};
-use crate::workspace::walker::WalkBuilder;
+use crate::template::header::{
+    extract_prologue, find_doc_block, find_interpreter_extension, FileTypes, HeadDirectives,
+    HeaderPlacement, SourceHeaders, DEFAULT_BLANK_LINES_AFTER_HEADER,
+};
+use crate::template::notice::{self, NoticeContext};
+use crate::template::parse::find_foreign_header;
+use crate::template::partials::{Partials, DEFAULT_TEMPLATES_DIRNAME};
+use crate::template::{extend_copyright_year, has_copyright_notice, is_generated};
+use crate::utils::{current_year, display_path};
+use crate::workspace::git;
+use crate::workspace::lock::WorkspaceLock;
+use crate::workspace::members::resolve_members;
+use crate::workspace::store::Store;
 use crate::workspace::LicensaWorkspace;
 
-use anyhow::Result;
+use anyhow::{anyhow, Context, Result};
 use clap::Parser;
 use colored::Colorize;
-use rayon::prelude::*;
+use globset::GlobSet;
 use serde::Serialize;
+use tracing::{debug, debug_span, error, info, info_span, warn, Span};
 
+use std::collections::HashSet;
 use std::env::current_dir;
-use std::fs;
+use std::io::{self, Read, Write};
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
@@ -28,14 +51,165 @@ use std::sync::{Arc, Mutex};
 pub struct ApplyArgs {
     #[command(flatten)]
     config: Config,
+
+    /// Copy each file to `<name>.licensa.bak` before prepending a license header.
+    ///
+    /// Backups can be removed with `licensa clean --backups`.
+    #[arg(long, verbatim_doc_comment)]
+    backup: bool,
+
+    /// Store backups in this directory instead of next to the original files.
+    ///
+    /// The directory mirrors the workspace structure. Implies `--backup`.
+    #[arg(long, value_name = "DIR", verbatim_doc_comment)]
+    backup_dir: Option<PathBuf>,
+
+    /// Only process files matching these glob patterns.
+    ///
+    /// Patterns follow `.gitignore` syntax and are matched relative to the
+    /// workspace root. Files matching an include pattern are processed even if
+    /// they match an `--exclude` pattern. Patterns starting with `!` exclude
+    /// files that would be included otherwise.
+    ///
+    ///     licensa apply --include src/**/*.rs '!src/generated/**'
+    #[cfg(not(doctest))]
+    #[arg(long, verbatim_doc_comment)]
+    #[arg(value_name = "GLOB", num_args = 1..)]
+    include: Vec<String>,
+
+    /// Only process files changed in a Git revision range, e.g. `v1.0..HEAD`.
+    ///
+    /// The range is passed to `git diff`: `A...B` selects the files changed on `B`
+    /// since it diverged from `A`, and a single revision selects the files changed
+    /// since that revision, including uncommitted changes. Deleted files and files
+    /// excluded otherwise are skipped.
+    ///
+    ///     licensa apply --rev v1.0..release/2.0
+    #[cfg(not(doctest))]
+    #[arg(
+        long,
+        value_name = "A..B",
+        conflicts_with = "stdin",
+        verbatim_doc_comment
+    )]
+    rev: Option<String>,
+
+    /// Read file content from stdin and write it to stdout with a license header,
+    /// instead of processing the files of the workspace.
+    ///
+    /// The comment style is selected with `--filetype`. Content that already contains
+    /// a license header is written unchanged.
+    ///
+    ///     licensa apply --stdin --filetype rs < main.rs
+    #[cfg(not(doctest))]
+    #[arg(long, requires = "filetype", verbatim_doc_comment)]
+    stdin: bool,
+
+    /// The type of the content read with `--stdin`.
+    ///
+    /// Either a file extension such as `rs`, or the name of a well-known file such
+    /// as `Makefile`. Scripts starting with a hash-bang line are detected if the
+    /// file type is unknown.
+    #[arg(long, value_name = "TYPE", requires = "stdin", verbatim_doc_comment)]
+    filetype: Option<String>,
+
+    /// Read every file instead of skipping unchanged files that previous runs found
+    /// licensed or generated, as cached in `.licensa/cache`.
+    #[arg(long)]
+    no_cache: bool,
+
+    /// Extend the copyright year of existing license headers to the current year,
+    /// e.g. `Copyright 2020 Bilbo` becomes `Copyright 2020-2024 Bilbo`.
+    ///
+    /// Headers without a year or with a year range ending in `present` are left
+    /// untouched.
+    #[arg(long, verbatim_doc_comment)]
+    extend_years: bool,
+
+    /// Keep existing copyright notices of other owners that lack an
+    /// `SPDX-License-Identifier` tag and add the license header beneath them,
+    /// instead of skipping those files.
+    ///
+    /// Only notices in the first comment of a file are considered.
+    #[arg(long, conflicts_with = "stdin", verbatim_doc_comment)]
+    append: bool,
+
+    /// Fail before modifying any file if an `--include` pattern matches no file or
+    /// points outside of the workspace, e.g. `../shared/**`.
+    #[arg(long, verbatim_doc_comment)]
+    strict: bool,
+
+    /// Report the files that would be modified without writing any file.
+    ///
+    /// Backups are not created and nothing is recorded for `licensa undo`.
+    #[arg(long, verbatim_doc_comment)]
+    dry_run: bool,
+
+    /// Print the first lines of each file that would be modified, with the inserted
+    /// license header highlighted, to review comment style and placement.
+    #[arg(long, requires = "dry_run", verbatim_doc_comment)]
+    preview: bool,
 }
 
 impl ApplyArgs {
-    // Merge self with config::Config
-    fn to_config(&self) -> Result<LicensaWorkspace> {
-        let workspace_root = current_dir()?;
-        let config = self.config.clone().with_workspace_config(workspace_root)?;
+    /// Resolves the packages to process and their merged configuration.
+    ///
+    /// Without a `workspaces` list in the root config, the workspace root is the only
+    /// package. Otherwise, every member is a package of its own, and the root package
+    /// covers all files outside of the members.
+    fn resolve_packages(&self, workspace_root: &Path) -> Result<Vec<Package>> {
+        let root_config = Config::from_workspace(workspace_root)?.unwrap_or_default();
+        let members = resolve_members(workspace_root, &root_config.workspaces)?;
 
+        let mut packages = Vec::with_capacity(members.len() + 1);
+        for package_root in
+            std::iter::once(workspace_root).chain(members.iter().map(|m| m.as_path()))
+        {
+            let mut config = root_config.clone();
+            config.workspaces.clear();
+            // An explicit `--config` file applies to all packages
+            if package_root != workspace_root && config::config_file().is_none() {
+                if let Some(member_config) = Config::from_member(package_root)? {
+                    config.update(member_config);
+                }
+            }
+            config.update(self.config.clone());
+            config.fill_from_metadata(package_root);
+            config.check_policy()?;
+
+            // Members nested in this package are processed on their own
+            let nested_members = members
+                .iter()
+                .filter(|member| member.as_path() != package_root)
+                .filter_map(|member| member.strip_prefix(package_root).ok())
+                .map(|relative| format!("/{}/", path_to_pattern(relative)));
+            config.exclude.extend(nested_members);
+
+            let scan = ScanConfig {
+                plugin_extensions: config
+                    .plugins
+                    .iter()
+                    .flat_map(|plugin| plugin.extensions.clone())
+                    .collect(),
+                ..ScanConfig::from_config(package_root, &config)?
+            };
+            let config = Self::to_config(config)?;
+            // Render notices upfront so that config errors, e.g. undefined template
+            // variables, are reported before any file is modified
+            let notices = Notices::render(&config)?;
+            packages.push(Package {
+                root: package_root.to_path_buf(),
+                config,
+                notices: Arc::new(notices),
+                scan,
+            });
+        }
+
+        Ok(packages)
+    }
+
+    // Convert the merged config::Config into the apply config
+    pub(crate) fn to_config(config: Config) -> Result<LicensaWorkspace> {
         // Verify required fields such es `license`, `owner` and `format` are set.
         Self::check_required_fields(&config);
 
@@ -52,6 +226,13 @@ impl ApplyArgs {
         Ok(config.unwrap())
     }
 
+    fn backup(&self) -> Option<Backup> {
+        if self.dry_run || (!self.backup && self.backup_dir.is_none()) {
+            return None;
+        }
+        Some(Backup::new(self.backup_dir.clone()))
+    }
+
     fn check_required_fields(config: &Config) {
         if config.license.is_none() {
             error::missing_required_arg_error("-t, --type <LICENSE>")
@@ -63,17 +244,346 @@ impl ApplyArgs {
 }
 
 pub fn run(args: &ApplyArgs) -> Result<()> {
+    let workspace_root = current_dir()?;
+    if args.stdin {
+        return apply_stdin(args, &workspace_root);
+    }
+
     let mut runner_stats = WorkTreeRunnerStatistics::new("apply", "modified");
+    let packages = args.resolve_packages(&workspace_root)?;
+    if args.strict {
+        check_include_patterns(&args.include, &packages)?;
+    }
+    let changed_files = args
+        .rev
+        .as_deref()
+        .map(|range| git::changed_files(&workspace_root, range).map(Arc::new))
+        .transpose()?;
+    let store = Store::new(&workspace_root);
+    // Held until all files are modified and the run is journaled
+    let _lock = (!args.dry_run)
+        .then(|| WorkspaceLock::acquire(&store, "apply"))
+        .transpose()?;
+    let progress = Progress::new("apply", "modified");
+    let journal_entries = Arc::new(Mutex::new(Vec::new()));
+    let scan_cache = (!args.no_cache).then(|| {
+        let read_window = packages[0].config.read_window;
+        let cache = ScanCache::load(&store, read_window.unwrap_or(DEFAULT_READ_WINDOW));
+        Arc::new(Mutex::new(cache))
+    });
+
+    for package in packages.iter() {
+        let package_stats = apply_package(
+            args,
+            &workspace_root,
+            package,
+            &progress,
+            &journal_entries,
+            scan_cache.clone(),
+            changed_files.clone(),
+        )?;
+
+        if packages.len() > 1 {
+            let name = package.name(&workspace_root);
+            info!(
+                package = %name,
+                "package {name}: {} modified; {} failed; {} ignored",
+                package_stats.count_passed(),
+                package_stats.count_failed(),
+                package_stats.count_ignored()
+            );
+        }
+        runner_stats.merge(&package_stats);
+    }
+    progress.finish();
+
+    if let Some(scan_cache) = scan_cache {
+        if let Err(err) = scan_cache.lock().unwrap().save(&store, &workspace_root) {
+            warn!("failed to update scan cache: {err:#}");
+        }
+    }
+
+    // Record modifications so they can be reverted with `licensa undo`
+    if !args.dry_run {
+        let mut journal = Journal::load(&store)?;
+        let mut entries = std::mem::take(&mut *journal_entries.lock().unwrap());
+        entries.sort_by(|a: &JournalEntry, b| a.path.cmp(&b.path));
+        journal.push(JournalRun {
+            command: "apply".into(),
+            entries,
+        });
+        journal.save(&store)?;
+    }
+
+    // Print output statistics
+    let num_failed = runner_stats.count_failed();
+    if num_failed > 0 {
+        runner_stats.set_status(WorkTreeRunnerStatus::Failed);
+    } else {
+        runner_stats.set_status(WorkTreeRunnerStatus::Ok);
+    }
+    runner_stats.print(true);
+
+    if num_failed > 0 {
+        return Err(anyhow!("failed to license {num_failed} file(s)"));
+    }
+
+    Ok(())
+}
+
+/// Checks that every `--include` pattern points into the workspace and matches a
+/// file of one of the `packages`.
+///
+/// Negated patterns must match a file matched by the other patterns.
+fn check_include_patterns(patterns: &[String], packages: &[Package]) -> Result<()> {
+    let (outside, patterns): (Vec<&str>, Vec<&str>) = patterns
+        .iter()
+        .map(String::as_str)
+        .partition(|pattern| points_outside_root(pattern));
+
+    let positive: Vec<String> = patterns
+        .iter()
+        .filter(|pattern| !pattern.starts_with('!'))
+        .map(|pattern| pattern.to_string())
+        .collect();
+    let mut unmatched = patterns;
+    for package in packages {
+        if unmatched.is_empty() {
+            break;
+        }
+        let candidates = Scan::new(package.scan_config(positive.clone(), None))?
+            .find_candidates()?
+            .files;
+        let paths: Vec<&Path> = candidates.iter().map(|entry| entry.path()).collect();
+        unmatched = find_unmatched_patterns(&package.root, &unmatched, &paths)?;
+    }
+
+    if outside.is_empty() && unmatched.is_empty() {
+        return Ok(());
+    }
+    for pattern in outside.iter() {
+        error!("include pattern `{pattern}` points outside of the workspace");
+    }
+    for pattern in unmatched.iter() {
+        error!("include pattern `{pattern}` matches no file");
+    }
+    Err(anyhow!(
+        "{} include pattern(s) without effect",
+        outside.len() + unmatched.len()
+    ))
+}
+
+/// Reads file content from stdin and writes it to stdout with a license header.
+///
+/// The configuration of the workspace root applies, but no files are modified and
+/// nothing is recorded in the journal.
+fn apply_stdin(args: &ApplyArgs, workspace_root: &Path) -> Result<()> {
+    let mut packages = args.resolve_packages(workspace_root)?;
+    let package = packages.swap_remove(0);
+    let config = &package.config;
+
+    let mut content = vec![];
+    io::stdin()
+        .read_to_end(&mut content)
+        .context("failed to read stdin")?;
 
-    let workspace_root = std::env::current_dir()?;
-    let workspace_config = args.to_config()?;
+    let filetype = args.filetype.as_deref().unwrap_or_default();
+    let lookup_name = match filetype {
+        name if SourceHeaders::well_known_file_extension(name).is_some() => name.to_owned(),
+        extension if extension.starts_with('.') => extension.to_owned(),
+        extension => format!(".{extension}"),
+    };
+    let lookup_name = match SourceHeaders::find_header_definition_by_extension(
+        &lookup_name,
+        &config.file_types,
+    ) {
+        Some(_) => lookup_name,
+        None => find_interpreter_extension(&content)
+            .map(str::to_owned)
+            .ok_or_else(|| anyhow!("unsupported file type `{filetype}`"))?,
+    };
+
+    let output = match license_content(config, &package.notices, &lookup_name, &content)? {
+        Some(output) => output,
+        None => {
+            debug!(
+                status = "skipped",
+                "skip stdin: license header found, generated or not UTF-8 text"
+            );
+            content
+        }
+    };
+
+    io::stdout()
+        .write_all(&output)
+        .context("failed to write stdout")?;
+    Ok(())
+}
+
+/// Returns `content` with the license header that `apply` inserts into files
+/// looked up by `lookup_name`, e.g. `.rs`.
+///
+/// Returns `None` if `content` already contains a license header, is generated by a tool
+/// or is not UTF-8 text, or if there's no comment style for `lookup_name`.
+pub(crate) fn license_content(
+    config: &LicensaWorkspace,
+    notices: &Notices,
+    lookup_name: &str,
+    content: &[u8],
+) -> Result<Option<Vec<u8>>> {
+    license_insertion(config, notices, lookup_name, content)
+        .map(|licensed| licensed.map(|(content, _)| content))
+}
+
+/// Same as [`license_content`], along with the byte range of the inserted header.
+pub(crate) fn license_insertion(
+    config: &LicensaWorkspace,
+    notices: &Notices,
+    lookup_name: &str,
+    content: &[u8],
+) -> Result<Option<(Vec<u8>, Range<usize>)>> {
+    let Some(template) = license_header(config, notices, lookup_name)? else {
+        return Ok(None);
+    };
+    let read_window = config.read_window.unwrap_or(DEFAULT_READ_WINDOW);
+    let head = &content[..content.len().min(read_window)];
+    if has_copyright_notice(head) || is_generated(head) {
+        return Ok(None);
+    }
+    let Ok(text) = std::str::from_utf8(content) else {
+        return Ok(None);
+    };
+
+    let doc_block = match config.placement.unwrap_or_default() {
+        HeaderPlacement::Top => None,
+        HeaderPlacement::AfterDocs => find_doc_block(lookup_name, text),
+    };
+    Ok(Some(prepend_license_notice(
+        template,
+        content,
+        doc_block,
+        &config.head_directives,
+    )))
+}
+
+/// Returns the license header `apply` inserts into files looked up by `lookup_name`,
+/// or `None` if there's no comment style for `lookup_name`.
+pub(crate) fn license_header(
+    config: &LicensaWorkspace,
+    notices: &Notices,
+    lookup_name: &str,
+) -> Result<Option<String>> {
+    let Some(header) =
+        SourceHeaders::find_header_definition_by_extension(lookup_name, &config.file_types)
+    else {
+        return Ok(None);
+    };
+    let blank_lines = config
+        .blank_lines_after_header
+        .unwrap_or(DEFAULT_BLANK_LINES_AFTER_HEADER);
+    header
+        .header_prefix
+        .apply_with_blank_lines(notices.for_extension(lookup_name), blank_lines)
+        .map(Some)
+}
+
+/// Returns the byte offset in `text` at which `apply` inserts license headers, i.e.
+/// after a hash-bang line or XML prologue, or after module-level documentation.
+pub(crate) fn license_header_offset(
+    config: &LicensaWorkspace,
+    lookup_name: &str,
+    text: &str,
+) -> usize {
+    let doc_block = match config.placement.unwrap_or_default() {
+        HeaderPlacement::Top => None,
+        HeaderPlacement::AfterDocs => find_doc_block(lookup_name, text),
+    };
+    doc_block.unwrap_or_else(|| {
+        extract_prologue(text.as_bytes(), &config.head_directives).map_or(0, |line| line.len())
+    })
+}
+
+/// A directory processed with its own configuration.
+struct Package {
+    root: PathBuf,
+    config: LicensaWorkspace,
+    notices: Arc<Notices>,
+    /// Scan options of the package, including the extensions of files handled by plugins.
+    scan: ScanConfig,
+}
+
+impl Package {
+    /// Returns the configuration of a scan of the package restricted to `include` patterns
+    /// and, if given, to the files in `paths`.
+    fn scan_config(
+        &self,
+        include: Vec<String>,
+        paths: Option<Arc<HashSet<PathBuf>>>,
+    ) -> ScanConfig {
+        ScanConfig {
+            include: Some(include),
+            paths,
+            ..self.scan.clone()
+        }
+    }
+
+    /// Returns the path of the package relative to the workspace root.
+    fn name(&self, workspace_root: &Path) -> String {
+        match self.root.strip_prefix(workspace_root) {
+            Ok(relative) if relative.as_os_str().is_empty() => ".".into(),
+            Ok(relative) => path_to_pattern(relative),
+            Err(_) => self.root.display().to_string(),
+        }
+    }
+}
+
+/// Applies license headers to all candidate files of a single package.
+fn apply_package(
+    args: &ApplyArgs,
+    workspace_root: &Path,
+    package: &Package,
+    progress: &Progress,
+    journal_entries: &Arc<Mutex<Vec<JournalEntry>>>,
+    scan_cache: Option<Arc<Mutex<ScanCache>>>,
+    changed_files: Option<Arc<HashSet<PathBuf>>>,
+) -> Result<WorkTreeRunnerStatistics> {
+    let mut runner_stats = WorkTreeRunnerStatistics::new("apply", "modified");
+    let workspace_config = &package.config;
 
     // ========================================================
     // Scanning process
     // ========================================================
-    let candidates = scan_workspace(&workspace_root, &workspace_config)?;
+    progress.start_scan();
+    let scan = Scan::new(package.scan_config(args.include.clone(), changed_files))?
+        .with_progress(progress);
+    let Candidates { files, skipped } = info_span!("scan").in_scope(|| scan.find_candidates())?;
+    let candidates: Vec<PathBuf> = files.into_iter().map(|entry| entry.into_path()).collect();
 
     runner_stats.set_items(candidates.len());
+    runner_stats.add_ignored(skipped);
+    debug!(
+        files = candidates.len(),
+        "found {} candidate file(s)",
+        candidates.len()
+    );
+    progress.start_processing(candidates.len());
+
+    // Cached statuses are only valid for the read window they were detected with
+    let read_window = workspace_config.read_window.unwrap_or(DEFAULT_READ_WINDOW);
+    let scan_cache = scan_cache.filter(|cache| cache.lock().unwrap().read_window() == read_window);
+    let extend_years = args.extend_years.then(current_year);
+    let candidates = match scan_cache.as_ref() {
+        Some(cache) => skip_cached(
+            candidates,
+            workspace_root,
+            &cache.lock().unwrap(),
+            extend_years,
+            args.append,
+            &mut runner_stats,
+            progress,
+        ),
+        None => candidates,
+    };
 
     // ========================================================
     // File processing
@@ -81,31 +591,105 @@ pub fn run(args: &ApplyArgs) -> Result<()> {
     let runner_stats = Arc::new(Mutex::new(runner_stats));
     let cache = Cache::<HeaderTemplate>::new();
 
-    let template_engine = handlebars::Handlebars::new();
-    let template = template_engine.render_template(SPDX_COPYRIGHT_NOTICE, &workspace_config)?;
-    let template = Arc::new(Mutex::new(template));
-
+    let process_span = info_span!("process", files = candidates.len());
     let context = ScanContext {
-        root: workspace_root,
+        root: workspace_root.to_path_buf(),
         cache: cache.clone(),
         runner_stats: runner_stats.clone(),
-        template,
+        notices: package.notices.clone(),
+        placement: workspace_config.placement.unwrap_or_default(),
+        blank_lines: workspace_config
+            .blank_lines_after_header
+            .unwrap_or(DEFAULT_BLANK_LINES_AFTER_HEADER),
+        backup: args.backup(),
+        dry_run: args.dry_run,
+        preview: args.preview,
+        plugins: Arc::new(workspace_config.plugins.clone()),
+        file_types: workspace_config.file_types.clone(),
+        head_directives: workspace_config.head_directives.clone(),
+        journal_entries: journal_entries.clone(),
+        scan_cache,
+        extend_years,
+        append: args.append,
+        read_only: workspace_config.read_only.unwrap_or_default(),
+        preserve_mtime: workspace_config.preserve_mtime.unwrap_or_default(),
+        progress: progress.clone(),
+        span: process_span.clone(),
     };
 
     let mut worktree = WorkTree::new();
-    worktree.add_task(context, apply_license_notice);
-    worktree.run(candidates);
+    worktree.read_window(read_window);
+    let results = worktree.add_task(context, |context, response| {
+        let result = apply_license_notice(context, response);
+        if result.is_err() {
+            context.progress.inc_failed();
+        }
+        result
+    });
+    process_span.in_scope(|| worktree.run(candidates));
+
+    // Close the process span before reporting results
+    drop(worktree);
+    drop(process_span);
 
     // ========================================================
     // Clear cache
     cache.clear();
 
-    // Print output statistics
-    let mut runner_stats = runner_stats.lock().unwrap();
-    runner_stats.set_status(WorkTreeRunnerStatus::Ok);
-    runner_stats.print(true);
+    let mut runner_stats = runner_stats.lock().unwrap().clone();
+    for err in results.try_iter().filter_map(Result::err) {
+        runner_stats.add_fail();
+        progress.suspend(|| print_task_failure(err));
+    }
 
-    Ok(())
+    Ok(runner_stats)
+}
+
+/// Returns the `candidates` that need to be read, skipping files that are unchanged
+/// since a previous run found them licensed or generated.
+///
+/// Licensed files with a copyright year older than `extend_years` are not skipped,
+/// nor, with `append`, licensed files without a license identifier.
+fn skip_cached(
+    candidates: Vec<PathBuf>,
+    workspace_root: &Path,
+    scan_cache: &ScanCache,
+    extend_years: Option<u32>,
+    append: bool,
+    runner_stats: &mut WorkTreeRunnerStatistics,
+    progress: &Progress,
+) -> Vec<PathBuf> {
+    candidates
+        .into_iter()
+        .filter(|path| {
+            let relative_path = path.strip_prefix(workspace_root).unwrap_or(path);
+            let status =
+                FileStamp::read(path).and_then(|stamp| scan_cache.get(relative_path, stamp));
+            match status {
+                Some(status) if is_outdated(status, extend_years) => return true,
+                Some(status) if append && is_appendable(status) => return true,
+                Some(status) if status.has_header => {
+                    debug!(
+                        status = "skipped",
+                        "skip {}: license header found (cached)",
+                        path.display()
+                    );
+                    runner_stats.add_ignore();
+                }
+                Some(status) if !status.candidate => {
+                    debug!(
+                        status = "generated",
+                        "skip {}: generated file (cached)",
+                        path.display()
+                    );
+                    runner_stats.add_generated();
+                }
+                _ => return true,
+            }
+            progress.inc_skipped();
+            false
+        })
+        .collect()
 }
 
 #[derive(Clone)]
@@ -113,7 +697,152 @@ struct ScanContext {
     pub root: PathBuf,
     pub runner_stats: Arc<Mutex<WorkTreeRunnerStatistics>>,
     pub cache: Arc<Cache<HeaderTemplate>>,
-    pub template: Arc<Mutex<String>>,
+    pub notices: Arc<Notices>,
+    pub placement: HeaderPlacement,
+    pub blank_lines: u8,
+    pub backup: Option<Backup>,
+    /// Whether files are left unmodified, as with `--dry-run`.
+    pub dry_run: bool,
+    /// Whether to print a preview of each file that would be modified.
+    pub preview: bool,
+    pub plugins: Arc<Vec<FilePlugin>>,
+    /// File types of the package, mapping extensions to those of known file types.
+    pub file_types: FileTypes,
+    /// Head directives of the package, kept above inserted headers.
+    pub head_directives: HeadDirectives,
+    pub journal_entries: Arc<Mutex<Vec<JournalEntry>>>,
+    pub scan_cache: Option<Arc<Mutex<ScanCache>>>,
+    /// The year to extend outdated copyright years to, if enabled.
+    pub extend_years: Option<u32>,
+    /// Whether to add headers beneath copyright notices of other owners.
+    pub append: bool,
+    /// How read-only files are treated.
+    pub read_only: ReadOnlyPolicy,
+    /// Whether files keep their modification time when modified.
+    pub preserve_mtime: bool,
+    pub progress: Progress,
+    pub span: Span,
+}
+
+impl ScanContext {
+    /// Returns the notices of the file at the absolute `path`.
+    fn notices_for(&self, path: &Path) -> &Notices {
+        self.notices
+            .for_path(path.strip_prefix(&self.root).unwrap_or(path))
+    }
+}
+
+/// Copyright notices rendered for the workspace license and every file-specific license.
+pub(crate) struct Notices {
+    license: LicenseId,
+    default: String,
+    file_licenses: Vec<(FileLicense, String)>,
+    /// Notices of the owners of specific paths, along with the matcher of their paths.
+    path_owners: Vec<(GlobSet, Notices)>,
+}
+
+impl Notices {
+    pub(crate) fn render(config: &LicensaWorkspace) -> Result<Self> {
+        info_span!("template").in_scope(|| {
+            // Partials are shared so that each is read once
+            let partials = workspace_partials(config);
+            let mut notices = Self::render_for_owner(config, &config.owner, &partials)?;
+            notices.path_owners = config
+                .path_owners
+                .iter()
+                .map(|path_owner| {
+                    let notices = Self::render_for_owner(config, &path_owner.owner, &partials)?;
+                    Ok((path_owner.matcher()?, notices))
+                })
+                .collect::<Result<_>>()?;
+            Ok(notices)
+        })
+    }
+
+    /// Renders the notices of all licenses with `owner` as the copyright owner.
+    fn render_for_owner(
+        config: &LicensaWorkspace,
+        owner: &str,
+        partials: &Arc<Partials>,
+    ) -> Result<Self> {
+        let default = render_notice(config, owner, partials, &config.license)?;
+        let file_licenses = config
+            .file_licenses
+            .iter()
+            .map(|file_license| {
+                let notice = render_notice(config, owner, partials, &file_license.license)?;
+                Ok((file_license.clone(), notice))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Notices {
+            license: config.license.clone(),
+            default,
+            file_licenses,
+            path_owners: vec![],
+        })
+    }
+
+    /// Returns the notices of the file at `path`, relative to the workspace root.
+    ///
+    /// These differ from the workspace notices if a path owner applies to the file.
+    pub(crate) fn for_path(&self, path: &Path) -> &Notices {
+        self.path_owner(path)
+            .map_or(self, |index| &self.path_owners[index].1)
+    }
+
+    /// Returns the index of the first path owner of the file at `path`, if any.
+    fn path_owner(&self, path: &Path) -> Option<usize> {
+        let path = display_path(path);
+        self.path_owners
+            .iter()
+            .position(|(matcher, _)| matcher.is_match(&path))
+    }
+
+    /// Returns the license of files with the given extension.
+    fn license_for_extension(&self, extension: &str) -> &str {
+        self.file_licenses
+            .iter()
+            .find(|(file_license, _)| file_license.matches(extension))
+            .map_or(&self.license, |(file_license, _)| &file_license.license)
+    }
+
+    /// Returns the notice for files with the given extension.
+    fn for_extension(&self, extension: &str) -> &str {
+        self.file_licenses
+            .iter()
+            .find(|(file_license, _)| file_license.matches(extension))
+            .map_or(&self.default, |(_, notice)| notice)
+    }
+}
+
+/// Returns the partials of the templates directory of the workspace.
+pub(crate) fn workspace_partials(config: &LicensaWorkspace) -> Arc<Partials> {
+    let templates = config.templates.as_deref();
+    Arc::new(Partials::new(
+        templates.unwrap_or(Path::new(DEFAULT_TEMPLATES_DIRNAME)),
+    ))
+}
+
+/// Renders the copyright notice of `license` for `owner` in the configured format.
+pub(crate) fn render_notice(
+    config: &LicensaWorkspace,
+    owner: &str,
+    partials: &Arc<Partials>,
+    license: &LicenseId,
+) -> Result<String> {
+    let context = NoticeContext {
+        owner: owner.to_string(),
+        email: config.email.clone(),
+        project: config.project.clone(),
+        project_url: config.project_url.clone(),
+        contributors: config.contributors.clone(),
+        year: config.year.as_ref().map(|year| year.to_string()),
+        variables: config.variables.clone(),
+        compact_sentence: config.compact_sentence.clone(),
+        partials: Some(partials.clone()),
+    };
+    notice::render_notice(config.format.unwrap_or_default(), license, &context)
 }
 
 #[derive(Debug, Clone)]
@@ -128,40 +857,111 @@ impl Cachable for HeaderTemplate {
     }
 }
 
-// FIXME: Refactor to more generic, re-usable fn
-fn scan_workspace<P>(workspace_root: P, config: &LicensaWorkspace) -> Result<Vec<PathBuf>>
-where
-    P: AsRef<Path>,
-{
-    let mut walk_builder = WalkBuilder::new(&workspace_root);
-    walk_builder.exclude(Some(config.exclude.clone()))?;
+fn apply_license_notice(context: &mut ScanContext, response: &FileTaskResponse) -> Result<()> {
+    // Worker threads don't inherit the current span, so the parent is set explicitly
+    let _span =
+        debug_span!(parent: &context.span, "file", path = %response.path.display()).entered();
 
-    let mut walker = walk_builder.build()?;
-    walker.quit_while(|res| res.is_err());
-    walker.send_while(|res| is_candidate(res.unwrap()));
+    let status = FileStatus::detect(&response.head);
+    if let Some(year) = context.extend_years {
+        if is_outdated(&status, Some(year)) {
+            return extend_license_year(context, response, year);
+        }
+    }
 
-    let candidates = walker
-        .run_task()
-        .iter()
-        .par_bridge()
-        .into_par_iter()
-        .filter_map(Result::ok)
-        .map(|e| e.path().to_path_buf())
-        .collect::<Vec<PathBuf>>();
+    // Headers of other owners are kept with `--append`, adding ours beneath them
+    let append = context.append && is_appendable(&status);
 
-    Ok(candidates)
-}
+    // Unless the file is modified below, its status holds until the file changes
+    if (status.has_header && !append) || !status.candidate {
+        cache_status(context, response, status.clone());
+    }
 
-fn apply_license_notice(context: &mut ScanContext, response: &FileTaskResponse) -> Result<()> {
     // Ignore file that already contains a copyright notice
-    if has_copyright_notice(response.content.as_bytes()) {
+    if status.has_header && !append {
+        return skip_licensed(context, response);
+    }
+
+    // Ignore files generated by tools, as they are overwritten on the next generation
+    if !status.candidate {
+        debug!(
+            status = "generated",
+            "skip {}: generated file",
+            response.path.display()
+        );
+        context.runner_stats.lock().unwrap().add_generated();
+        context.progress.inc_skipped();
+        return Ok(());
+    }
+
+    // Files handled by an external plugin, e.g. of formats without a comment style
+    let suffix = get_path_suffix(&response.path, &context.file_types);
+    if let Some(plugin) = FilePlugin::find(&context.plugins, &suffix) {
+        if append {
+            return skip_licensed(context, response);
+        }
+        return apply_plugin(context, response, plugin, &suffix);
+    }
+
+    // Only text files are considered for license header application
+    let content = response
+        .read_content()
+        .with_context(|| format!("failed to read {}", response.path.display()))?;
+    let Ok(text) = std::str::from_utf8(&content) else {
+        debug!(
+            status = "skipped",
+            "skip {}: not a UTF-8 text file",
+            response.path.display()
+        );
         context.runner_stats.lock().unwrap().add_ignore();
+        context.progress.inc_skipped();
+        return Ok(());
+    };
+    // Scripts without a known suffix are identified by their hash-bang line, as in
+    // `license_insertion`
+    let lookup_name = get_header_lookup_name(&response.path, &response.head, &context.file_types);
+    let doc_block = if append {
+        let notices = context.notices_for(&response.path);
+        match find_foreign_header(
+            text,
+            notices.for_extension(&lookup_name),
+            &context.head_directives,
+        ) {
+            Some(foreign_header) => Some(foreign_header.end),
+            None => return skip_licensed(context, response),
+        }
+    } else {
+        match context.placement {
+            HeaderPlacement::Top => None,
+            HeaderPlacement::AfterDocs => find_doc_block(&lookup_name, text),
+        }
+    };
+    if !check_writable(context, response)? {
         return Ok(());
     }
 
+    if let Some(backup) = context.backup.as_ref() {
+        backup
+            .create(&context.root, &response.path)
+            .with_context(|| format!("failed to back up {}", response.path.display()))?;
+    }
+
     let header = resolve_header_template(context, response);
-    let content = prepend_license_notice(&header.template, &response.content);
-    fs::write(&response.path, content)?;
+    let (content, inserted) = prepend_license_notice(
+        &header.template,
+        content,
+        doc_block,
+        &context.head_directives,
+    );
+    if context.dry_run {
+        return report_dry_run(context, response, &content, inserted);
+    }
+    debug_span!("write").in_scope(|| {
+        context
+            .read_only
+            .write(&response.path, &content, context.preserve_mtime)
+            .with_context(|| format!("failed to write {}", response.path.display()))
+    })?;
 
     let file_path = &response
         .path
@@ -170,37 +970,305 @@ fn apply_license_notice(context: &mut ScanContext, response: &FileTaskResponse)
         .to_str()
         .unwrap();
 
+    let entry = JournalEntry::new(file_path, inserted, &content);
+    context.journal_entries.lock().unwrap().push(entry);
+
     // Capture task success
     context.runner_stats.lock().unwrap().add_action_count();
+    context.progress.inc_processed();
+
+    context.progress.suspend(|| print_task_success(file_path));
+
+    Ok(())
+}
+
+/// Adds a license header to the file of `response` with an external `plugin`.
+///
+/// Files left unchanged by the plugin are counted as ignored.
+fn apply_plugin(
+    context: &ScanContext,
+    response: &FileTaskResponse,
+    plugin: &FilePlugin,
+    extension: &str,
+) -> Result<()> {
+    let content = response
+        .read_content()
+        .with_context(|| format!("failed to read {}", response.path.display()))?;
+    let file_path = response
+        .path
+        .strip_prefix(&context.root)
+        .unwrap_or(&response.path);
+    let path = path_to_pattern(file_path);
+    let notices = context.notices_for(&response.path);
+    let plugin_context = PluginContext {
+        path: &path,
+        license: notices.license_for_extension(extension),
+        notice: notices.for_extension(extension),
+    };
+    let licensed = debug_span!("plugin")
+        .in_scope(|| run_plugin(&plugin.command, &context.root, &plugin_context, &content))
+        .with_context(|| format!("failed to process {}", response.path.display()))?;
+
+    let (replaced, inserted) = changed_ranges(&content, &licensed);
+    if replaced.is_empty() && inserted.is_empty() {
+        debug!(
+            status = "skipped",
+            "skip {}: unchanged by plugin",
+            response.path.display()
+        );
+        context.runner_stats.lock().unwrap().add_ignore();
+        context.progress.inc_skipped();
+        return Ok(());
+    }
+    if !check_writable(context, response)? {
+        return Ok(());
+    }
+    if context.dry_run {
+        return report_dry_run(context, response, &licensed, inserted);
+    }
+
+    if let Some(backup) = context.backup.as_ref() {
+        backup
+            .create(&context.root, &response.path)
+            .with_context(|| format!("failed to back up {}", response.path.display()))?;
+    }
+    debug_span!("write").in_scope(|| {
+        context
+            .read_only
+            .write(&response.path, &licensed, context.preserve_mtime)
+            .with_context(|| format!("failed to write {}", response.path.display()))
+    })?;
+
+    let replaced = String::from_utf8_lossy(&content[replaced]);
+    let entry = JournalEntry::replacement(file_path, inserted, &replaced, &licensed);
+    context.journal_entries.lock().unwrap().push(entry);
+
+    context.runner_stats.lock().unwrap().add_action_count();
+    context.progress.inc_processed();
+    context.progress.suspend(|| print_task_success(file_path));
+
+    Ok(())
+}
+
+/// Skips the file of `response`, which already contains a license header.
+fn skip_licensed(context: &ScanContext, response: &FileTaskResponse) -> Result<()> {
+    debug!(
+        status = "skipped",
+        "skip {}: license header found",
+        response.path.display()
+    );
+    context.runner_stats.lock().unwrap().add_ignore();
+    context.progress.inc_skipped();
+    Ok(())
+}
+
+/// Checks whether the file of `response` may be modified, given the read-only policy.
+///
+/// Returns `Ok(false)` if the file is read-only and skipped.
+fn check_writable(context: &ScanContext, response: &FileTaskResponse) -> Result<bool> {
+    if context.read_only.check(&response.path)? {
+        return Ok(true);
+    }
+    debug!(
+        status = "read-only",
+        "skip {}: read-only file",
+        response.path.display()
+    );
+    context.runner_stats.lock().unwrap().add_read_only();
+    context.progress.inc_skipped();
+    Ok(false)
+}
+
+/// Checks whether the file with `status` has a copyright notice without a license
+/// identifier, to which `--append` may add a license header.
+fn is_appendable(status: &FileStatus) -> bool {
+    status.has_header && status.license.is_none()
+}
+
+/// Checks whether the file with `status` has a license header with a copyright year
+/// older than `extend_years`.
+fn is_outdated(status: &FileStatus, extend_years: Option<u32>) -> bool {
+    let outdated = status.year.zip(extend_years);
+    status.has_header && outdated.is_some_and(|(year, extend_to)| year < extend_to)
+}
+
+/// Extends the copyright year of the license header in the file of `response` to `year`.
+fn extend_license_year(
+    context: &mut ScanContext,
+    response: &FileTaskResponse,
+    year: u32,
+) -> Result<()> {
+    let content = response
+        .read_content()
+        .with_context(|| format!("failed to read {}", response.path.display()))?;
+    let extended = std::str::from_utf8(&content)
+        .ok()
+        .and_then(|text| extend_copyright_year(text, response.head.len(), year));
+    let Some((range, years)) = extended else {
+        debug!(
+            status = "skipped",
+            "skip {}: license header found",
+            response.path.display()
+        );
+        context.runner_stats.lock().unwrap().add_ignore();
+        context.progress.inc_skipped();
+        return Ok(());
+    };
+    if !check_writable(context, response)? {
+        return Ok(());
+    }
+
+    if let Some(backup) = context.backup.as_ref() {
+        backup
+            .create(&context.root, &response.path)
+            .with_context(|| format!("failed to back up {}", response.path.display()))?;
+    }
+
+    // Years are ASCII digits, so the replaced range is valid UTF-8
+    let replaced = String::from_utf8_lossy(&content[range.clone()]).into_owned();
+    let content = [
+        &content[..range.start],
+        years.as_bytes(),
+        &content[range.end..],
+    ]
+    .concat();
+    if context.dry_run {
+        let inserted = range.start..range.start + years.len();
+        return report_dry_run(context, response, &content, inserted);
+    }
+    debug_span!("write").in_scope(|| {
+        context
+            .read_only
+            .write(&response.path, &content, context.preserve_mtime)
+            .with_context(|| format!("failed to write {}", response.path.display()))
+    })?;
+
+    let file_path = response
+        .path
+        .strip_prefix(&context.root)
+        .unwrap_or(&response.path);
+    let inserted = range.start..range.start + years.len();
+    let entry = JournalEntry::replacement(file_path, inserted, &replaced, &content);
+    context.journal_entries.lock().unwrap().push(entry);
+
+    context.runner_stats.lock().unwrap().add_action_count();
+    context.progress.inc_processed();
+    context.progress.suspend(|| print_task_success(file_path));
+
+    Ok(())
+}
 
-    print_task_success(file_path);
+/// Reports the file of `response` as modified without writing `content`, the new content
+/// of the file, in which the byte range `inserted` was changed.
+fn report_dry_run(
+    context: &ScanContext,
+    response: &FileTaskResponse,
+    content: &[u8],
+    inserted: Range<usize>,
+) -> Result<()> {
+    let file_path = response
+        .path
+        .strip_prefix(&context.root)
+        .unwrap_or(&response.path);
+
+    context.runner_stats.lock().unwrap().add_action_count();
+    context.progress.inc_processed();
+    context.progress.suspend(|| {
+        print_task_success(file_path);
+        if context.preview {
+            // Headers are only inserted into text files
+            let content = String::from_utf8_lossy(content);
+            let lines = insertion_preview(&content, inserted, PREVIEW_LINES + 1);
+            let truncated = lines.len() > PREVIEW_LINES;
+            let path = path_to_pattern(file_path);
+            print!(
+                "{}",
+                colorize_preview(&path, &lines[..lines.len().min(PREVIEW_LINES)], truncated)
+            );
+        }
+    });
 
     Ok(())
 }
 
-fn prepend_license_notice<H, F>(header: H, file_content: F) -> Vec<u8>
+/// Records the `status` of the file of `response` in the scan cache, if enabled.
+fn cache_status(context: &ScanContext, response: &FileTaskResponse, status: FileStatus) {
+    let (Some(scan_cache), Some(stamp)) =
+        (context.scan_cache.as_ref(), FileStamp::read(&response.path))
+    else {
+        return;
+    };
+    let relative_path = response
+        .path
+        .strip_prefix(&context.root)
+        .unwrap_or(&response.path);
+    scan_cache
+        .lock()
+        .unwrap()
+        .insert(relative_path, stamp, status);
+}
+
+/// Prepends `header` to `file_content`, preserving a leading hash-bang line, XML prologue
+/// or one of the `directives`.
+///
+/// If `doc_block` is given, `header` is inserted after the module-level documentation
+/// occupying the first `doc_block` bytes instead.
+///
+/// Returns the new content and the byte range of the inserted header.
+fn prepend_license_notice<H, F>(
+    header: H,
+    file_content: F,
+    doc_block: Option<usize>,
+    directives: &HeadDirectives,
+) -> (Vec<u8>, Range<usize>)
 where
     H: AsRef<str>,
-    F: AsRef<str>,
+    F: AsRef<[u8]>,
 {
     let template = header.as_ref().as_bytes().to_vec();
-    let file_content = file_content.as_ref().as_bytes();
-    let mut line = extract_hash_bang(file_content).unwrap_or_default();
+    let file_content = file_content.as_ref();
+    if let Some(end) = doc_block {
+        return insert_after_docs(&template, file_content, end);
+    }
+    let mut line = extract_prologue(file_content, directives).unwrap_or_default();
     let mut content = file_content.to_vec();
 
     let line_break = b'\n';
 
+    let mut inserted = 0..template.len();
     if !line.is_empty() {
         content = content.split_off(line.len());
+        inserted = line.len()..line.len() + template.len();
         if line[line.len() - 1] != line_break {
             line.push(line_break);
+            inserted.end += 1;
         }
         content = [line, template, content].concat();
     } else {
         content = [template, content].concat();
     }
 
-    content
+    (content, inserted)
+}
+
+/// Inserts `template` after the first `end` bytes of `file_content`, separated by a blank line.
+fn insert_after_docs(template: &[u8], file_content: &[u8], end: usize) -> (Vec<u8>, Range<usize>) {
+    let (docs, rest) = file_content.split_at(end);
+
+    let mut header = Vec::with_capacity(template.len() + 2);
+    if !docs.is_empty() && !docs.ends_with(b"\n") {
+        header.push(b'\n');
+    }
+    header.push(b'\n');
+    header.extend_from_slice(template);
+    // A blank line ending the template is redundant before an existing one
+    let blank_line = rest.is_empty() || rest.starts_with(b"\n") || rest.starts_with(b"\r\n");
+    if blank_line && header.ends_with(b"\n\n") {
+        header.pop();
+    }
+
+    let inserted = end..end + header.len();
+    ([docs, &header, rest].concat(), inserted)
 }
 
 fn resolve_header_template(
@@ -208,16 +1276,29 @@ fn resolve_header_template(
     task: &FileTaskResponse,
 ) -> Arc<HeaderTemplate> {
     // FIXME: Compute cache id in FileTree
-    let cache_id = get_path_suffix(&task.path);
+    let lookup_name = get_header_lookup_name(&task.path, &task.head, &context.file_types);
+    let relative_path = task.path.strip_prefix(&context.root).unwrap_or(&task.path);
+    // Templates differ by the owner of the file
+    let cache_id = match context.notices.path_owner(relative_path) {
+        Some(index) => format!("{index}:{lookup_name}"),
+        None => lookup_name.clone(),
+    };
 
     // Reuse cached template for this candidate
     if !context.cache.contains(&cache_id) {
         // Compile and cache template for this candidate
 
-        let header = SourceHeaders::find_header_definition_by_extension(&cache_id).unwrap();
-        let template = context.template.lock().unwrap();
-        let template = template.as_str();
-        let compiled_template = header.header_prefix.apply(template).unwrap();
+        let header =
+            SourceHeaders::find_header_definition_by_extension(&lookup_name, &context.file_types)
+                .unwrap();
+        let template = context
+            .notices
+            .for_path(relative_path)
+            .for_extension(&lookup_name);
+        let compiled_template = header
+            .header_prefix
+            .apply_with_blank_lines(template, context.blank_lines)
+            .unwrap();
 
         // FIXME: Use unique cache_id for header prefixes to prevent compiling
         // that use the same format.
@@ -235,5 +1316,19 @@ where
     P: AsRef<Path>,
 {
     let result_type = "ok".green();
-    println!("apply {} ... {result_type}", path.as_ref().display())
+    let path = display_path(path);
+    info!(%path, status = "modified", "apply {path} ... {result_type}")
+}
+
+/// Converts a relative path into a `/`-separated glob pattern.
+fn path_to_pattern(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn print_task_failure(err: anyhow::Error) {
+    let result_type = "failed".red();
+    error!(status = "failed", "apply {err:#} ... {result_type}")
 }
@@ -0,0 +1,261 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Throughput measurements of the scanner and of `apply`.
+//!
+//! The hidden `bench` command and the criterion benchmarks in `benches/` run the
+//! same stages against a synthesized workspace, so that performance regressions
+//! in the scanner and the work tree are visible.
+
+use crate::commands::apply::{license_content, ApplyArgs, Notices};
+use crate::config::Config;
+use crate::ops::cache::FileStatus;
+use crate::ops::scan::{get_header_lookup_name, Scan, ScanConfig};
+use crate::ops::work_tree::DEFAULT_READ_WINDOW;
+use crate::utils::{read_head, write_atomic};
+
+use anyhow::{Context, Result};
+use clap::Args;
+use rayon::prelude::*;
+use tempfile::TempDir;
+use tracing::info;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// File extensions of synthesized files along with their content.
+const SAMPLE_FILES: &[(&str, &str)] = &[
+    ("rs", "fn main() {\n    println!(\"Hello, world!\");\n}\n"),
+    (
+        "ts",
+        "export function greet(name: string): string {\n  return `Hello, ${name}!`;\n}\n",
+    ),
+    ("py", "def greet(name):\n    return f\"Hello, {name}!\"\n"),
+    ("go", "package main\n\nfunc main() {}\n"),
+    ("css", "body {\n  margin: 0;\n}\n"),
+    ("sh", "#!/bin/sh\necho \"Hello, world!\"\n"),
+];
+
+/// Every n-th synthesized file carries a license header.
+const LICENSED_EVERY: usize = 2;
+
+/// Every n-th synthesized file is marked as generated, unless it carries a license header.
+const GENERATED_EVERY: usize = 5;
+
+/// Maximum number of files per synthesized directory.
+const FILES_PER_DIR: usize = 100;
+
+#[derive(Args, Debug)]
+pub struct BenchArgs {
+    /// Number of files in the synthesized workspace.
+    #[arg(long, value_name = "N", default_value_t = 10_000)]
+    files: usize,
+
+    /// Number of runs of each stage. The fastest run is reported.
+    #[arg(long, value_name = "N", default_value_t = 3)]
+    runs: usize,
+}
+
+/// A temporary workspace with synthesized source files.
+pub struct SyntheticWorkspace {
+    dir: TempDir,
+
+    /// Number of files carrying a license header.
+    pub licensed: usize,
+
+    /// Number of generated files.
+    pub generated: usize,
+}
+
+impl SyntheticWorkspace {
+    /// Returns the root directory of the workspace.
+    pub fn root(&self) -> &Path {
+        self.dir.path()
+    }
+}
+
+/// Creates a temporary workspace with `files` source files of various types, spread
+/// over nested directories.
+///
+/// Every second file carries a license header, and some of the others are marked
+/// as generated.
+pub fn synthesize_workspace(files: usize) -> Result<SyntheticWorkspace> {
+    let dir = tempfile::tempdir().context("failed to create temporary workspace")?;
+    let mut workspace = SyntheticWorkspace {
+        dir,
+        licensed: 0,
+        generated: 0,
+    };
+
+    for i in 0..files {
+        let (extension, body) = SAMPLE_FILES[i % SAMPLE_FILES.len()];
+        let comment = if extension == "ts" || extension == "rs" || extension == "go" {
+            "//"
+        } else if extension == "css" {
+            "/*"
+        } else {
+            "#"
+        };
+        let closing = if comment == "/*" { " */" } else { "" };
+
+        let mut content = String::new();
+        let (shebang, body) = match body.split_once('\n') {
+            Some((line, rest)) if line.starts_with("#!") => (format!("{line}\n"), rest),
+            _ => (String::new(), body),
+        };
+        content.push_str(&shebang);
+        if i % LICENSED_EVERY == 0 {
+            content.push_str(&format!(
+                "{comment} Copyright 2024 Bench{closing}\n{comment} SPDX-License-Identifier: MIT{closing}\n\n"
+            ));
+            workspace.licensed += 1;
+        } else if i % GENERATED_EVERY == 1 {
+            content.push_str(&format!(
+                "{comment} Code generated by bench. DO NOT EDIT.{closing}\n"
+            ));
+            workspace.generated += 1;
+        }
+        content.push_str(body);
+
+        let dir = workspace
+            .root()
+            .join(format!("pkg{}", i % 10))
+            .join(format!("mod{}", i / (FILES_PER_DIR * 10)));
+        fs::create_dir_all(&dir).with_context(|| format!("failed to create {}", dir.display()))?;
+        let path = dir.join(format!("file{i}.{extension}"));
+        fs::write(&path, content).with_context(|| format!("failed to write {}", path.display()))?;
+    }
+
+    Ok(workspace)
+}
+
+/// Scans the workspace at `root` and returns the paths of all candidate files.
+pub fn scan_workspace(root: &Path) -> Result<Vec<PathBuf>> {
+    let candidates = Scan::new(ScanConfig::new(root, None, None))?.find_candidates()?;
+    Ok(candidates
+        .files
+        .into_iter()
+        .map(|entry| entry.into_path())
+        .collect())
+}
+
+/// Detects license headers in the files at `paths` and returns the number of
+/// files carrying one.
+pub fn detect_headers(paths: &[PathBuf]) -> usize {
+    paths
+        .par_iter()
+        .filter_map(|path| read_head(path, DEFAULT_READ_WINDOW).ok())
+        .filter(|head| FileStatus::detect(head).has_header)
+        .count()
+}
+
+/// Inserts an MIT license header into the files at `paths`, as `apply` does, and
+/// returns the number of modified files.
+pub fn apply_headers(paths: &[PathBuf]) -> Result<usize> {
+    let config = ApplyArgs::to_config(Config {
+        license: Some("MIT".into()),
+        owner: Some("Bench".into()),
+        ..Config::new()
+    })?;
+    let notices = Notices::render(&config)?;
+
+    paths
+        .par_iter()
+        .map(|path| {
+            let content =
+                fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+            let lookup_name = get_header_lookup_name(path, &content, &config.file_types);
+            let Some(content) = license_content(&config, &notices, &lookup_name, &content)? else {
+                return Ok(0);
+            };
+            write_atomic(path, &content)
+                .with_context(|| format!("failed to write {}", path.display()))?;
+            Ok(1)
+        })
+        .sum()
+}
+
+pub fn run(args: &BenchArgs) -> Result<()> {
+    let runs = args.runs.max(1);
+    let workspace = synthesize_workspace(args.files)?;
+    info!(
+        "Synthesized {} files, {} licensed and {} generated",
+        args.files, workspace.licensed, workspace.generated
+    );
+
+    let (candidates, elapsed) = fastest(runs, || scan_workspace(workspace.root()))?;
+    info!(
+        "scan    {} candidate(s) in {} ({})",
+        candidates.len(),
+        format_duration(elapsed),
+        format_throughput(candidates.len(), elapsed)
+    );
+
+    let (detected, elapsed) = fastest(runs, || Ok(detect_headers(&candidates)))?;
+    info!(
+        "detect  {detected} of {} license header(s) ({:.1}%) in {} ({})",
+        workspace.licensed,
+        percentage(detected, workspace.licensed),
+        format_duration(elapsed),
+        format_throughput(candidates.len(), elapsed)
+    );
+
+    // Every run modifies files, so each run gets a fresh workspace
+    let mut fastest_apply: Option<(usize, Duration)> = None;
+    for _ in 0..runs {
+        let workspace = synthesize_workspace(args.files)?;
+        let candidates = scan_workspace(workspace.root())?;
+        let (modified, elapsed) = fastest(1, || apply_headers(&candidates))?;
+        if fastest_apply.map_or(true, |(_, fastest)| elapsed < fastest) {
+            fastest_apply = Some((modified, elapsed));
+        }
+    }
+    let (modified, elapsed) = fastest_apply.unwrap_or_default();
+    info!(
+        "apply   {modified} file(s) modified in {} ({})",
+        format_duration(elapsed),
+        format_throughput(candidates.len(), elapsed)
+    );
+
+    Ok(())
+}
+
+/// Runs `stage` `runs` times and returns the result and duration of the fastest run.
+fn fastest<T, F>(runs: usize, mut stage: F) -> Result<(T, Duration)>
+where
+    F: FnMut() -> Result<T>,
+{
+    let mut fastest: Option<(T, Duration)> = None;
+    for _ in 0..runs.max(1) {
+        let start = Instant::now();
+        let result = stage()?;
+        let elapsed = start.elapsed();
+        if fastest
+            .as_ref()
+            .map_or(true, |(_, fastest)| elapsed < *fastest)
+        {
+            fastest = Some((result, elapsed));
+        }
+    }
+    Ok(fastest.unwrap())
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.3}s", duration.as_secs_f64())
+}
+
+fn format_throughput(files: usize, duration: Duration) -> String {
+    let secs = duration.as_secs_f64();
+    if secs == 0.0 {
+        return "n/a".into();
+    }
+    format!("{:.0} files/s", files as f64 / secs)
+}
+
+fn percentage(part: usize, total: usize) -> f64 {
+    if total == 0 {
+        return 100.0;
+    }
+    part as f64 / total as f64 * 100.0
+}
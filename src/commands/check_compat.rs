@@ -0,0 +1,162 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::config::Config;
+use crate::deps;
+use crate::ops::compat::{check_compatibility, Compatibility};
+use crate::ops::progress::Progress;
+use crate::ops::scan::{Scan, ScanConfig};
+use crate::ops::stats::{WorkTreeRunnerStatistics, WorkTreeRunnerStatus};
+use crate::ops::work_tree::DEFAULT_READ_WINDOW;
+use crate::template::{find_license_id, has_copyright_notice};
+use crate::utils::read_head;
+
+use anyhow::{anyhow, Result};
+use clap::Args;
+use rayon::prelude::*;
+use tracing::{debug, error, info_span, warn};
+
+use std::env::current_dir;
+use std::path::Path;
+
+#[derive(Args, Debug)]
+pub struct CheckCompatArgs {
+    /// Skip the licenses of third-party dependencies.
+    #[arg(long)]
+    no_deps: bool,
+
+    #[command(flatten)]
+    config: Config,
+}
+
+/// A license found in the workspace, along with where it was found.
+struct Component {
+    /// Relative path of the file, or name and version of the dependency.
+    name: String,
+
+    /// The SPDX license expression, if any.
+    license: Option<String>,
+}
+
+pub fn run(args: &mut CheckCompatArgs) -> Result<()> {
+    let workspace_root = current_dir()?;
+    let mut config = args.config.with_workspace_config(&workspace_root)?;
+    config.fill_from_metadata(&workspace_root);
+    let Some(project_license) = config.license.as_ref().map(|license| license.to_string()) else {
+        return Err(anyhow!(
+            "no project license configured, set `license` in the config file or pass --type"
+        ));
+    };
+
+    let mut components = find_file_licenses(&config, &workspace_root)?;
+    if !args.no_deps {
+        components.extend(
+            deps::collect(&workspace_root)?
+                .into_iter()
+                .map(|dependency| Component {
+                    name: format!(
+                        "{} {}@{}",
+                        dependency.ecosystem, dependency.name, dependency.version
+                    ),
+                    license: dependency.license,
+                }),
+        );
+    }
+
+    // ========================================================
+    // Check compatibility
+    // ========================================================
+    let mut runner_stats = WorkTreeRunnerStatistics::new("check-compat", "compatible");
+    runner_stats.set_items(components.len());
+    for component in components.iter() {
+        let Some(license) = component.license.as_deref() else {
+            runner_stats.add_ignore();
+            continue;
+        };
+
+        match check_compatibility(license, &project_license) {
+            Compatibility::Compatible => {
+                runner_stats.add_action_count();
+            }
+            Compatibility::Incompatible => {
+                runner_stats.add_fail();
+                error!(
+                    "{}: license `{license}` is incompatible with the project license `{project_license}`",
+                    component.name
+                );
+            }
+            Compatibility::Unknown => {
+                runner_stats.add_ignore();
+                warn!(
+                    "{}: compatibility of license `{license}` with `{project_license}` is unknown",
+                    component.name
+                );
+            }
+        }
+    }
+
+    let num_incompatible = runner_stats.count_failed();
+    if num_incompatible > 0 {
+        runner_stats.set_status(WorkTreeRunnerStatus::Failed);
+        runner_stats.print(true);
+        return Err(anyhow!(
+            "{num_incompatible} license(s) incompatible with the project license `{project_license}`"
+        ));
+    }
+
+    runner_stats.set_status(WorkTreeRunnerStatus::Ok);
+    runner_stats.print(true);
+
+    Ok(())
+}
+
+/// Finds the SPDX license expressions in the headers of all candidate files.
+fn find_file_licenses(config: &Config, workspace_root: &Path) -> Result<Vec<Component>> {
+    let progress = Progress::new("check-compat", "checked");
+    progress.start_scan();
+    let scan_span = info_span!("scan").entered();
+
+    let candidates = Scan::new(ScanConfig::from_config(workspace_root, config)?)?
+        .with_progress(&progress)
+        .find_candidates()?
+        .files;
+    scan_span.exit();
+
+    debug!(
+        files = candidates.len(),
+        "found {} candidate file(s)",
+        candidates.len()
+    );
+    progress.start_processing(candidates.len());
+
+    let read_window = config.read_window.unwrap_or(DEFAULT_READ_WINDOW);
+    let mut components: Vec<Component> =
+        info_span!("check", files = candidates.len()).in_scope(|| {
+            candidates
+                .par_iter()
+                .filter_map(|entry| {
+                    let Ok(head) = read_head(entry.path(), read_window) else {
+                        progress.inc_skipped();
+                        return None;
+                    };
+                    progress.inc_processed();
+
+                    let license = has_copyright_notice(&head)
+                        .then(|| find_license_id(&head))
+                        .flatten()?;
+                    let path = entry
+                        .path()
+                        .strip_prefix(workspace_root)
+                        .unwrap_or(entry.path());
+                    Some(Component {
+                        name: path.to_string_lossy().replace('\\', "/"),
+                        license: Some(license),
+                    })
+                })
+                .collect()
+        });
+    progress.finish();
+
+    components.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(components)
+}
@@ -0,0 +1,95 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::ops::backup::remove_backups;
+use crate::ops::cache::{SCAN_CACHE_FILENAME, VERIFY_CACHE_FILENAME};
+use crate::workspace::lock::WorkspaceLock;
+use crate::workspace::store::Store;
+
+use anyhow::Result;
+use clap::Args;
+use tracing::info;
+
+use std::env::current_dir;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Args, Debug, Clone)]
+pub struct CleanArgs {
+    /// Remove backup copies created by `apply --backup`.
+    #[arg(long)]
+    backups: bool,
+
+    /// Directory passed to `apply --backup-dir` containing backup copies.
+    ///
+    /// Implies `--backups`. The directory is removed if it is empty afterwards.
+    #[arg(long, value_name = "DIR")]
+    backup_dir: Option<PathBuf>,
+
+    /// Remove the caches of previous `apply` and `verify` runs in `.licensa`.
+    #[arg(long)]
+    cache: bool,
+}
+
+impl CleanArgs {
+    /// Returns `true` if no specific target was selected, meaning everything is cleaned.
+    fn clean_all(&self) -> bool {
+        !self.backups && self.backup_dir.is_none() && !self.cache
+    }
+
+    /// Returns `true` if backup copies are removed.
+    fn clean_backups(&self) -> bool {
+        self.backups || self.backup_dir.is_some() || self.clean_all()
+    }
+
+    /// Returns `true` if the caches are removed.
+    fn clean_cache(&self) -> bool {
+        self.cache || self.clean_all()
+    }
+}
+
+pub fn run(args: &CleanArgs) -> Result<()> {
+    let workspace_root = current_dir()?;
+    let store = Store::new(&workspace_root);
+    let _lock = WorkspaceLock::acquire(&store, "clean")?;
+
+    if args.clean_backups() {
+        let mut removed = remove_backups(&workspace_root)?;
+        if let Some(backup_dir) = args.backup_dir.as_ref() {
+            let backup_dir = workspace_root.join(backup_dir);
+            if backup_dir.is_dir() {
+                removed.append(&mut remove_backups(&backup_dir)?);
+                remove_empty_dirs(&backup_dir)?;
+            }
+        }
+
+        for path in removed.iter() {
+            let path = path.strip_prefix(&workspace_root).unwrap_or(path);
+            info!("removed {}", path.display());
+        }
+        info!("Removed {} backup file(s)", removed.len());
+    }
+
+    if args.clean_cache() {
+        store.remove(SCAN_CACHE_FILENAME)?;
+        store.remove(VERIFY_CACHE_FILENAME)?;
+        info!("Removed caches");
+    }
+
+    Ok(())
+}
+
+/// Recursively removes `dir` and its subdirectories if they don't contain any files.
+fn remove_empty_dirs(dir: &Path) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            remove_empty_dirs(&entry.path())?;
+        }
+    }
+    if fs::read_dir(dir)?.next().is_none() {
+        fs::remove_dir(dir)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,232 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::config::{self, Config, LICENSA_CONFIG_FILENAME};
+use crate::ops::workspace::find_workspace_config_path;
+use crate::utils::display_path;
+use crate::workspace::ops::{interpolate_env, parse_config_json, strip_json_comments};
+
+use anyhow::{anyhow, Context, Result};
+use clap::{Args, Subcommand};
+use schemars::schema_for;
+use serde::Serialize;
+use serde_json::ser::PrettyFormatter;
+use serde_json::{Map, Value};
+use tracing::{info, warn};
+
+use std::env::current_dir;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Args, Debug, Clone)]
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    command: ConfigCommand,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+enum ConfigCommand {
+    /// Print the JSON Schema of the `.licensarc` config file.
+    ///
+    /// Reference the schema from the `$schema` field of a config file to get
+    /// completion and validation in editors.
+    Schema,
+
+    /// Check a config file for unknown fields and invalid values.
+    Validate(ValidateArgs),
+
+    /// Print the value of a config field, e.g. `licensa config get license`.
+    ///
+    /// String values are printed as is, all other values as JSON.
+    Get(GetArgs),
+
+    /// Set the value of a config field, e.g. `licensa config set owner "ACME Inc."`.
+    ///
+    /// Values are parsed as JSON if possible, e.g. `2024` or `["dist/"]`, and used
+    /// as strings otherwise. Setting a field to `null` removes it. Other fields,
+    /// their order and the indentation of the file are preserved. The config file
+    /// is created if it doesn't exist.
+    Set(SetArgs),
+}
+
+#[derive(Args, Debug, Clone)]
+struct GetArgs {
+    /// Name of the config field, e.g. `owner` or `maxFileSize`.
+    #[arg(value_name = "KEY")]
+    key: String,
+}
+
+#[derive(Args, Debug, Clone)]
+struct SetArgs {
+    /// Name of the config field, e.g. `owner` or `maxFileSize`.
+    #[arg(value_name = "KEY")]
+    key: String,
+
+    /// The new value of the field.
+    #[arg(value_name = "VALUE")]
+    value: String,
+}
+
+#[derive(Args, Debug, Clone)]
+struct ValidateArgs {
+    /// Path to the config file.
+    ///
+    /// Defaults to the file selected with `--config`, or the `.licensarc` file
+    /// in the current working directory.
+    #[arg(value_name = "PATH")]
+    path: Option<PathBuf>,
+}
+
+pub fn run(args: &ConfigArgs) -> Result<()> {
+    match &args.command {
+        ConfigCommand::Schema => {
+            let schema = schema_for!(Config);
+            println!("{}", serde_json::to_string_pretty(&schema)?);
+        }
+        ConfigCommand::Validate(validate) => {
+            let workspace_root = current_dir()?;
+            let path = match validate.path.as_ref() {
+                Some(path) => path.to_owned(),
+                None => config_path(&workspace_root)?,
+            };
+            let display_path = path.strip_prefix(&workspace_root).unwrap_or(&path);
+
+            Config::from_file(&path)
+                .and_then(|config| config.with_extends(&workspace_root))
+                .with_context(|| {
+                    format!("{} is not a valid config file", display_path.display())
+                })?;
+            info!("{} is valid", display_path.display());
+        }
+        ConfigCommand::Get(get) => {
+            let path = config_path(&current_dir()?)?;
+            let config = read_object(&path)?;
+            let key = field_name(&get.key);
+            match config.get(&key) {
+                Some(Value::String(value)) => println!("{value}"),
+                Some(value) => println!("{value}"),
+                None => return Err(anyhow!("Config field `{key}` is not set")),
+            }
+        }
+        ConfigCommand::Set(set) => {
+            let workspace_root = current_dir()?;
+            let path = config_path(&workspace_root)
+                .unwrap_or_else(|_| workspace_root.join(LICENSA_CONFIG_FILENAME));
+            let (mut config, indent) = match path.exists() {
+                true => {
+                    let content = fs::read_to_string(&path)?;
+                    if strip_json_comments(&content) != content {
+                        warn!(
+                            "comments and trailing commas of {} are not preserved",
+                            display_path(path.strip_prefix(&workspace_root).unwrap_or(&path))
+                        );
+                    }
+                    (read_object(&path)?, detect_indent(&content))
+                }
+                false => (Map::new(), DEFAULT_INDENT.to_string()),
+            };
+
+            let key = field_name(&set.key);
+            let value = check_field(&key, parse_value(&set.value))?;
+            match value {
+                Value::Null => config.remove(&key),
+                value => config.insert(key, value),
+            };
+
+            fs::write(
+                &path,
+                to_string_with_indent(&Value::Object(config), &indent)?,
+            )
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the path of the config file used by other commands in `workspace_root`.
+fn config_path(workspace_root: &Path) -> Result<PathBuf> {
+    if let Some(path) = config::config_file() {
+        return Ok(path.to_path_buf());
+    }
+    find_workspace_config_path(workspace_root)
+        .ok_or_else(|| anyhow!("No Licensa config file found in the current directory"))
+}
+
+const DEFAULT_INDENT: &str = "  ";
+
+/// Reads the config file at `path` as JSON object, keeping unknown fields and field order.
+///
+/// Comments and trailing commas are allowed, but aren't part of the returned object.
+fn read_object(path: &Path) -> Result<Map<String, Value>> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config file {}", path.display()))?;
+    match parse_config_json(&content)
+        .with_context(|| format!("Failed to parse config file {}", path.display()))?
+    {
+        Value::Object(config) => Ok(config),
+        _ => Err(anyhow!("{} must contain a JSON object", path.display())),
+    }
+}
+
+/// Converts a kebab-case or snake_case field name into the camelCase used in config files.
+///
+/// This allows using the names of command line flags, e.g. `max-file-size`.
+fn field_name(key: &str) -> String {
+    let mut name = String::with_capacity(key.len());
+    let mut upper = false;
+    for c in key.chars() {
+        match c {
+            '-' | '_' => upper = !name.is_empty(),
+            c if upper => {
+                name.extend(c.to_uppercase());
+                upper = false;
+            }
+            c => name.push(c),
+        }
+    }
+    name
+}
+
+/// Parses `value` as JSON, falling back to a plain string.
+fn parse_value(value: &str) -> Value {
+    serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.to_string()))
+}
+
+/// Checks that `key` is a known config field and `value` is valid for it.
+///
+/// Returns the normalized value, e.g. the canonical SPDX expression of a license.
+fn check_field(key: &str, value: Value) -> Result<Value> {
+    let interpolated = interpolate_env(value.clone())?;
+    let mut field = Map::new();
+    field.insert(key.to_string(), interpolated.clone());
+    let config = serde_json::from_value::<Config>(Value::Object(field))
+        .map_err(|err| anyhow!("Invalid value for config field `{key}`: {err}"))?;
+
+    // Keep environment variable references as written
+    if interpolated != value {
+        return Ok(value);
+    }
+    let normalized = serde_json::to_value(config)?.get(key).cloned();
+    Ok(normalized.unwrap_or(value))
+}
+
+/// Returns the indentation of the first indented line in `content`.
+fn detect_indent(content: &str) -> String {
+    content
+        .lines()
+        .map(|line| &line[..line.len() - line.trim_start().len()])
+        .find(|indent| !indent.is_empty())
+        .unwrap_or(DEFAULT_INDENT)
+        .to_string()
+}
+
+/// Serializes `value` as pretty-printed JSON using `indent`, followed by a newline.
+fn to_string_with_indent(value: &Value, indent: &str) -> Result<String> {
+    let mut buf = Vec::new();
+    let formatter = PrettyFormatter::with_indent(indent.as_bytes());
+    let mut serializer = serde_json::Serializer::with_formatter(&mut buf, formatter);
+    value.serialize(&mut serializer)?;
+    buf.push(b'\n');
+    Ok(String::from_utf8(buf)?)
+}
@@ -0,0 +1,132 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::config::Config;
+use crate::deps::{self, Dependency};
+
+use anyhow::{anyhow, Result};
+use clap::Args;
+use colored::Colorize;
+use spdx::{Expression, ParseMode};
+use tracing::{info, warn};
+
+use std::collections::BTreeMap;
+use std::env::current_dir;
+
+#[derive(Args, Debug, Clone)]
+pub struct DepsArgs {
+    /// Fail if the license of any dependency is unknown or not a valid SPDX expression.
+    #[arg(long)]
+    strict: bool,
+}
+
+pub fn run(args: &DepsArgs) -> Result<()> {
+    let workspace_root = current_dir()?;
+    let policy = Config::from_workspace(&workspace_root)?
+        .unwrap_or_default()
+        .policy;
+    let dependencies = deps::collect(&workspace_root)?;
+
+    if dependencies.is_empty() {
+        info!("No dependencies found");
+        return Ok(());
+    }
+
+    print_dependencies(&dependencies);
+
+    // ========================================================
+    // Check licenses
+    let mut num_failed = 0;
+    let mut num_denied = 0;
+    for dependency in dependencies.iter() {
+        let license = dependency.license.as_deref();
+        if let Some(Err(err)) = license.map(|license| policy.check(license)) {
+            num_denied += 1;
+            warn!(
+                ecosystem = %dependency.ecosystem,
+                name = %dependency.name,
+                "{} {}@{}: {err}",
+                dependency.ecosystem,
+                dependency.name,
+                dependency.version
+            );
+        }
+
+        if let Some(reason) = check_license(dependency) {
+            num_failed += 1;
+            warn!(
+                ecosystem = %dependency.ecosystem,
+                name = %dependency.name,
+                "{} {}@{}: {reason}",
+                dependency.ecosystem,
+                dependency.name,
+                dependency.version
+            );
+        }
+    }
+
+    print_summary(&dependencies);
+
+    if num_denied > 0 {
+        return Err(anyhow!(
+            "{num_denied} dependenc(ies) with a license denied by the license policy"
+        ));
+    }
+
+    if args.strict && num_failed > 0 {
+        return Err(anyhow!(
+            "{num_failed} dependenc(ies) without a valid SPDX license"
+        ));
+    }
+
+    Ok(())
+}
+
+/// Returns the reason why the license of `dependency` is not acceptable, if any.
+fn check_license(dependency: &Dependency) -> Option<String> {
+    let Some(license) = dependency.license.as_ref() else {
+        return Some("license unknown".into());
+    };
+    if Expression::parse_mode(license, ParseMode::LAX).is_err() {
+        return Some(format!(
+            "'{license}' is not a valid SPDX license expression"
+        ));
+    }
+    None
+}
+
+fn print_dependencies(dependencies: &[Dependency]) {
+    let name_width = dependencies.iter().map(|d| d.name.len()).max().unwrap_or(0);
+    let version_width = dependencies
+        .iter()
+        .map(|d| d.version.len())
+        .max()
+        .unwrap_or(0);
+
+    for dependency in dependencies {
+        let license = match dependency.license.as_ref() {
+            Some(license) => license.normal(),
+            None => "unknown".yellow(),
+        };
+        println!(
+            "{:<6}  {:<name_width$}  {:<version_width$}  {license}",
+            dependency.ecosystem.to_string(),
+            dependency.name,
+            dependency.version
+        );
+    }
+}
+
+fn print_summary(dependencies: &[Dependency]) {
+    let mut licenses = BTreeMap::<&str, usize>::new();
+    for dependency in dependencies {
+        let license = dependency.license.as_deref().unwrap_or("unknown");
+        *licenses.entry(license).or_default() += 1;
+    }
+
+    println!("\n{}", "Licenses".bold());
+    let width = licenses.keys().map(|l| l.len()).max().unwrap_or(0);
+    for (license, count) in licenses {
+        println!("  {license:<width$}  {count:>6}");
+    }
+}
@@ -6,11 +6,13 @@ use crate::config::{
 };
 use crate::schema::LicenseId;
 use crate::workspace::ops::{ensure_config_missing, save_config, save_ignore_file};
+use crate::workspace::presets::{compose_ignore_file, Preset};
 
 use anyhow::Result;
 use clap::Args;
 use inquire::{Select, Text};
 use lazy_static::lazy_static;
+use tracing::info;
 
 use std::env::current_dir;
 use std::str::FromStr;
@@ -23,12 +25,26 @@ lazy_static! {
 pub struct InitArgs {
     #[command(flatten)]
     config: Config,
+
+    /// Add ignore patterns for one or more language ecosystems to `.licensaignore`.
+    ///
+    /// Presets cover common build output, dependency and cache directories,
+    /// e.g. `target/` for Rust or `node_modules/` for Node.js.
+    #[arg(
+        long,
+        value_enum,
+        value_name = "PRESET",
+        value_delimiter = ',',
+        verbatim_doc_comment
+    )]
+    preset: Vec<Preset>,
 }
 
 impl InitArgs {
     pub fn into_config(&self) -> Result<Config> {
         let mut config = Config::default();
         config.update(self.config.clone());
+        config.fill_from_metadata(current_dir()?);
 
         if config.license.is_none() {
             let license_id = prompt_license_selection()?;
@@ -48,13 +64,14 @@ pub fn run(args: &InitArgs) -> Result<()> {
     ensure_config_missing(&workspace_root, LICENSA_CONFIG_FILENAME)?;
     let config = args.into_config()?;
     save_config(&workspace_root, LICENSA_CONFIG_FILENAME, config)?;
+    let ignore_file = compose_ignore_file(&LICENSA_IGNORE, &args.preset);
     save_ignore_file(
         workspace_root,
         LICENSA_IGNORE_FILENAME,
-        LICENSA_IGNORE.as_bytes(),
+        ignore_file.as_bytes(),
     )?;
 
-    println!("Successfully initialized Licensa workspace");
+    info!("Successfully initialized Licensa workspace");
     Ok(())
 }
 
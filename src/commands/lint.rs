@@ -0,0 +1,176 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::config::Config;
+use crate::ops::progress::Progress;
+use crate::ops::scan::{Candidates, Scan, ScanConfig};
+use crate::template::header::HeadDirectives;
+use crate::template::lint::{lint_header, Diagnostic};
+
+use anyhow::{anyhow, Context, Result};
+use clap::{Args, ValueEnum};
+use colored::Colorize;
+use rayon::prelude::*;
+use serde::Serialize;
+use tracing::{debug, info, info_span};
+
+use std::env::current_dir;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+#[derive(Args, Debug)]
+pub struct LintArgs {
+    #[command(flatten)]
+    config: Config,
+
+    /// Output format of the diagnostics.
+    ///
+    /// The `json` format prints an array of objects with the `path`, `line`,
+    /// `column`, `code` and `message` fields of each diagnostic.
+    #[arg(
+        long,
+        value_enum,
+        value_name = "FORMAT",
+        default_value_t,
+        verbatim_doc_comment
+    )]
+    output: LintFormat,
+}
+
+/// Output format of `lint`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum LintFormat {
+    /// One diagnostic per line, prefixed with its location.
+    #[default]
+    Text,
+
+    /// A JSON array of diagnostic objects.
+    Json,
+}
+
+/// A problem found in the license header of a file.
+#[derive(Debug, Serialize)]
+struct FileDiagnostic {
+    /// Path of the file relative to the workspace root, with `/` separators.
+    path: String,
+
+    #[serde(flatten)]
+    diagnostic: Diagnostic,
+}
+
+pub fn run(args: &mut LintArgs) -> Result<()> {
+    let workspace_root = current_dir()?;
+    let config = &args.config.with_workspace_config(&workspace_root)?;
+    let directives = config.head_directives()?;
+
+    // ========================================================
+    // Scanning process
+    // ========================================================
+    let progress = Progress::new("lint", "linted");
+    progress.start_scan();
+    let scan_span = info_span!("scan").entered();
+
+    let Candidates {
+        files: candidates, ..
+    } = Scan::new(ScanConfig::from_config(&workspace_root, config)?)?
+        .with_progress(&progress)
+        .find_candidates()?;
+    scan_span.exit();
+
+    debug!(
+        files = candidates.len(),
+        "found {} candidate file(s)",
+        candidates.len()
+    );
+    progress.start_processing(candidates.len());
+
+    // ========================================================
+    // File processing
+    // ========================================================
+    let results: Vec<Result<Vec<FileDiagnostic>>> = info_span!("process", files = candidates.len())
+        .in_scope(|| {
+            candidates
+                .par_iter()
+                .map(|entry| {
+                    let path = entry.path();
+                    let display_path = path.strip_prefix(&workspace_root).unwrap_or(path);
+                    let diagnostics = lint_file(path, display_path, &directives);
+                    match &diagnostics {
+                        Ok(diagnostics) if diagnostics.is_empty() => progress.inc_skipped(),
+                        Ok(_) => progress.inc_processed(),
+                        Err(_) => progress.inc_failed(),
+                    }
+                    diagnostics
+                })
+                .collect()
+        });
+    progress.finish();
+
+    let mut diagnostics = vec![];
+    for result in results {
+        diagnostics.extend(result?);
+    }
+    // Diagnostics of a file are already ordered by their position
+    diagnostics.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let output = match args.output {
+        LintFormat::Text => format_diagnostics(&diagnostics),
+        LintFormat::Json => format!("{}\n", serde_json::to_string_pretty(&diagnostics)?),
+    };
+    match io::stdout().lock().write_all(output.as_bytes()) {
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => {}
+        result => result?,
+    }
+
+    let mut paths: Vec<&str> = diagnostics.iter().map(|d| d.path.as_str()).collect();
+    paths.dedup();
+    if !diagnostics.is_empty() {
+        return Err(anyhow!(
+            "{} problem(s) found in {} file(s)",
+            diagnostics.len(),
+            paths.len()
+        ));
+    }
+
+    info!("no problems found in {} file(s)", candidates.len());
+    Ok(())
+}
+
+/// Lints the license header of the file at `path`.
+///
+/// Files that aren't valid UTF-8 are skipped.
+fn lint_file(
+    path: &Path,
+    display_path: &Path,
+    directives: &HeadDirectives,
+) -> Result<Vec<FileDiagnostic>> {
+    let content = fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+    let Ok(text) = std::str::from_utf8(&content) else {
+        debug!("skip {}: not valid UTF-8", display_path.display());
+        return Ok(vec![]);
+    };
+
+    let path = crate::utils::display_path(display_path);
+    Ok(lint_header(text, directives)
+        .into_iter()
+        .map(|diagnostic| FileDiagnostic {
+            path: path.clone(),
+            diagnostic,
+        })
+        .collect())
+}
+
+fn format_diagnostics(diagnostics: &[FileDiagnostic]) -> String {
+    let mut output = String::new();
+    for FileDiagnostic { path, diagnostic } in diagnostics {
+        let location = format!("{path}:{}:{}:", diagnostic.line, diagnostic.column);
+        output.push_str(&format!(
+            "{} {} {}\n",
+            location.bold(),
+            format!("{}:", diagnostic.code).yellow(),
+            diagnostic.message
+        ));
+    }
+    output
+}
@@ -0,0 +1,84 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::spdx::{list_licenses, LicenseEntry};
+
+use anyhow::Result;
+use clap::{Args, ValueEnum};
+use colored::Colorize;
+
+use std::io::{self, Write};
+
+#[derive(Args, Debug, Clone)]
+pub struct ListArgs {
+    /// Output format of the license list.
+    ///
+    /// The `json` format prints an array of objects with the `id`, `name`,
+    /// `osiApproved`, `fsfLibre`, `copyleft` and `deprecated` fields of each license.
+    #[arg(
+        long,
+        value_enum,
+        value_name = "FORMAT",
+        default_value_t,
+        verbatim_doc_comment
+    )]
+    output: ListFormat,
+}
+
+/// Output format of `list`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ListFormat {
+    /// One license per line, along with its name and flags.
+    #[default]
+    Text,
+
+    /// A JSON array of license objects.
+    Json,
+}
+
+pub fn run(args: &ListArgs) -> Result<()> {
+    let licenses = list_licenses();
+    let output = match args.output {
+        ListFormat::Text => format_licenses(&licenses),
+        ListFormat::Json => format!("{}\n", serde_json::to_string_pretty(&licenses)?),
+    };
+
+    // The list is usually piped into other commands, which may exit early
+    match io::stdout().lock().write_all(output.as_bytes()) {
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        result => Ok(result?),
+    }
+}
+
+fn format_licenses(licenses: &[LicenseEntry]) -> String {
+    let mut output = String::new();
+    let width = licenses
+        .iter()
+        .map(|l| l.id.len())
+        .max()
+        .unwrap_or_default();
+    for license in licenses {
+        let flags: Vec<&str> = [
+            (license.osi_approved, "OSI"),
+            (license.fsf_libre, "FSF"),
+            (license.copyleft, "copyleft"),
+            (license.deprecated, "deprecated"),
+        ]
+        .into_iter()
+        .filter_map(|(set, flag)| set.then_some(flag))
+        .collect();
+
+        let id = format!("{:<width$}", license.id);
+        let line = match flags.is_empty() {
+            true => format!("{}  {}\n", id.bold(), license.name),
+            false => format!(
+                "{}  {} {}\n",
+                id.bold(),
+                license.name,
+                format!("[{}]", flags.join(", ")).dimmed()
+            ),
+        };
+        output.push_str(&line);
+    }
+    output
+}
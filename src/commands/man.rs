@@ -0,0 +1,62 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::cli::Cli;
+
+use anyhow::{Context, Result};
+use clap::{Args, CommandFactory};
+use clap_mangen::Man;
+use tracing::info;
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+#[derive(Args, Debug)]
+pub struct ManArgs {
+    /// Directory to write the man pages to, e.g. `licensa.1` and `licensa-apply.1`.
+    ///
+    /// Without this option, only the page of `licensa` itself is printed to stdout.
+    #[arg(long, value_name = "DIR")]
+    output: Option<PathBuf>,
+}
+
+pub fn run(args: &ManArgs) -> Result<()> {
+    // Building the command propagates global options and names subcommand pages,
+    // e.g. `licensa-apply` for `licensa apply`
+    let mut cmd = Cli::command();
+    cmd.build();
+
+    let Some(dir) = args.output.as_ref() else {
+        let mut page = Vec::new();
+        Man::new(cmd).render(&mut page)?;
+        io::stdout()
+            .lock()
+            .write_all(&page)
+            .context("failed to print man page")?;
+        return Ok(());
+    };
+
+    fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    let mut pages = Vec::new();
+    collect_pages(&cmd, &mut pages);
+    for man in pages.iter() {
+        let path = dir.join(man.get_filename());
+        let mut page = Vec::new();
+        man.render(&mut page)?;
+        fs::write(&path, page).with_context(|| format!("failed to write {}", path.display()))?;
+    }
+    info!("Wrote {} man page(s) to {}", pages.len(), dir.display());
+    Ok(())
+}
+
+/// Collects the pages of `cmd` and all its visible subcommands.
+fn collect_pages(cmd: &clap::Command, pages: &mut Vec<Man>) {
+    pages.push(Man::new(cmd.clone()));
+    for subcommand in cmd.get_subcommands() {
+        // The `help` subcommand is added by clap and documented by every page
+        if !subcommand.is_hide_set() && subcommand.get_name() != "help" {
+            collect_pages(subcommand, pages);
+        }
+    }
+}
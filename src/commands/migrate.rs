@@ -0,0 +1,97 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::config::migrate::license_eye::{self, LICENSE_EYE_CONFIG_FILENAME};
+use crate::config::migrate::{addlicense, MigrationSource};
+use crate::config::{LICENSA_CONFIG_FILENAME, LICENSA_IGNORE_FILENAME};
+use crate::workspace::ops::{ensure_config_missing, save_config};
+
+use anyhow::{anyhow, Context, Result};
+use clap::Args;
+use lazy_static::lazy_static;
+use tracing::{info, warn};
+
+use std::env::current_dir;
+use std::fs;
+use std::path::PathBuf;
+
+lazy_static! {
+    static ref LICENSA_IGNORE: &'static str = std::include_str!("../../.licensaignore");
+}
+
+#[derive(Args, Debug, Clone)]
+pub struct MigrateArgs {
+    /// The tool to migrate from.
+    #[arg(long, value_enum, value_name = "TOOL")]
+    from: MigrationSource,
+
+    /// Path to the config file of the tool.
+    ///
+    /// Defaults to `.licenserc.yaml` for `license-eye`. Not supported for
+    /// `addlicense`, which is configured with command line arguments.
+    #[arg(long, value_name = "PATH", verbatim_doc_comment)]
+    input: Option<PathBuf>,
+
+    /// The addlicense command line to migrate, after `--`.
+    ///
+    /// For example: licensa migrate --from addlicense -- -c "ACME Inc." -l mit -ignore "vendor/**" .
+    #[arg(last = true, value_name = "ARGS", verbatim_doc_comment)]
+    args: Vec<String>,
+}
+
+pub fn run(args: &MigrateArgs) -> Result<()> {
+    let workspace_root = current_dir()?;
+    ensure_config_missing(&workspace_root, LICENSA_CONFIG_FILENAME)?;
+
+    let migration = match args.from {
+        MigrationSource::Addlicense => {
+            if args.input.is_some() {
+                return Err(anyhow!(
+                    "addlicense has no config file, pass its arguments after `--` instead"
+                ));
+            }
+            addlicense::migrate(&args.args)?
+        }
+        MigrationSource::LicenseEye => {
+            if !args.args.is_empty() {
+                return Err(anyhow!(
+                    "unexpected arguments for license-eye, use --input to select its config file"
+                ));
+            }
+            let input = args
+                .input
+                .clone()
+                .unwrap_or_else(|| PathBuf::from(LICENSE_EYE_CONFIG_FILENAME));
+            license_eye::migrate_file(workspace_root.join(input))?
+        }
+    };
+
+    for warning in migration.warnings.iter() {
+        warn!("{}: {warning}", args.from.title());
+    }
+
+    save_config(&workspace_root, LICENSA_CONFIG_FILENAME, &migration.config)?;
+
+    // Migrated patterns are appended to an existing ignore file
+    let ignore_path = workspace_root.join(LICENSA_IGNORE_FILENAME);
+    let ignore_file = match fs::read_to_string(&ignore_path) {
+        Ok(content) => content,
+        Err(_) => LICENSA_IGNORE.to_string(),
+    };
+    let ignore_file = match migration.ignore.is_empty() {
+        true => ignore_file,
+        false => format!(
+            "{}\n\n{}",
+            ignore_file.trim_end(),
+            migration.ignore_section(args.from)
+        ),
+    };
+    fs::write(&ignore_path, ignore_file)
+        .with_context(|| format!("failed to write {}", ignore_path.display()))?;
+
+    info!(
+        "Successfully migrated {} configuration to {LICENSA_CONFIG_FILENAME}",
+        args.from.title()
+    );
+    Ok(())
+}
@@ -2,5 +2,21 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
 pub mod apply;
+pub mod bench;
+pub mod check_compat;
+pub mod clean;
+pub mod config;
+pub mod deps;
 pub mod init;
+pub mod lint;
+pub mod list;
+pub mod man;
+pub mod migrate;
+pub mod normalize;
+pub mod notice;
+pub mod preview;
+pub mod sbom;
+pub mod show;
+pub mod stats;
+pub mod undo;
 pub mod verify;
@@ -0,0 +1,235 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::config::Config;
+use crate::ops::progress::Progress;
+use crate::ops::scan::{get_header_lookup_name, Candidates, Scan, ScanConfig};
+use crate::ops::stats::{WorkTreeRunnerStatistics, WorkTreeRunnerStatus};
+use crate::template::header::{
+    FileTypes, HeadDirectives, SourceHeaders, DEFAULT_BLANK_LINES_AFTER_HEADER,
+};
+use crate::template::notice::{render_notice, NoticeContext};
+use crate::template::parse::parse_header;
+use crate::template::partials::{Partials, DEFAULT_TEMPLATES_DIRNAME};
+use crate::utils::display_path;
+use crate::workspace::lock::WorkspaceLock;
+use crate::workspace::store::Store;
+
+use anyhow::{anyhow, Context, Result};
+use clap::Args;
+use colored::Colorize;
+use rayon::prelude::*;
+use tracing::{debug, error, info, info_span};
+
+use std::env::current_dir;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+#[derive(Args, Debug)]
+pub struct NormalizeArgs {
+    #[command(flatten)]
+    config: Config,
+}
+
+/// Outcome of normalizing a single file.
+enum Outcome {
+    Normalized,
+    Unchanged,
+    Unrecognized,
+    ReadOnly,
+}
+
+pub fn run(args: &mut NormalizeArgs) -> Result<()> {
+    let workspace_root = current_dir()?;
+    let config = &args.config.with_workspace_config(&workspace_root)?;
+    let _lock = WorkspaceLock::acquire(&Store::new(&workspace_root), "normalize")?;
+    let file_types = config.file_types()?;
+    let directives = config.head_directives()?;
+    let templates = config.templates.as_deref();
+    let partials = Arc::new(Partials::new(
+        templates.unwrap_or(Path::new(DEFAULT_TEMPLATES_DIRNAME)),
+    ));
+
+    // ========================================================
+    // Scanning process
+    // ========================================================
+    let progress = Progress::new("normalize", "normalized");
+    progress.start_scan();
+    let scan_span = info_span!("scan").entered();
+
+    let Candidates {
+        files: candidates,
+        skipped,
+    } = Scan::new(ScanConfig::from_config(&workspace_root, config)?)?
+        .with_progress(&progress)
+        .find_candidates()?;
+    scan_span.exit();
+
+    debug!(
+        files = candidates.len(),
+        "found {} candidate file(s)",
+        candidates.len()
+    );
+    progress.start_processing(candidates.len());
+
+    // ========================================================
+    // File processing
+    // ========================================================
+    let mut runner_stats = WorkTreeRunnerStatistics::new("normalize", "normalized");
+    runner_stats.set_items(candidates.len());
+    runner_stats.add_ignored(skipped);
+    let runner_stats = Mutex::new(runner_stats);
+
+    info_span!("process", files = candidates.len()).in_scope(|| {
+        candidates.par_iter().for_each(|entry| {
+            let path = entry.path();
+            let display_path = path.strip_prefix(&workspace_root).unwrap_or(path);
+            match normalize_file(config, &partials, &file_types, &directives, path) {
+                Ok(Outcome::Normalized) => {
+                    runner_stats.lock().unwrap().add_action_count();
+                    progress.inc_processed();
+                    progress.suspend(|| print_task_success(display_path));
+                }
+                Ok(Outcome::Unchanged) => {
+                    debug!(
+                        status = "skipped",
+                        "skip {}: header is normalized",
+                        display_path.display()
+                    );
+                    runner_stats.lock().unwrap().add_ignore();
+                    progress.inc_skipped();
+                }
+                Ok(Outcome::Unrecognized) => {
+                    debug!(
+                        status = "skipped",
+                        "skip {}: no recognized header",
+                        display_path.display()
+                    );
+                    runner_stats.lock().unwrap().add_ignore();
+                    progress.inc_skipped();
+                }
+                Ok(Outcome::ReadOnly) => {
+                    debug!(
+                        status = "read-only",
+                        "skip {}: read-only file",
+                        display_path.display()
+                    );
+                    runner_stats.lock().unwrap().add_read_only();
+                    progress.inc_skipped();
+                }
+                Err(err) => {
+                    runner_stats.lock().unwrap().add_fail();
+                    progress.inc_failed();
+                    progress.suspend(|| print_task_failure(display_path, err));
+                }
+            }
+        })
+    });
+    progress.finish();
+
+    let mut runner_stats = runner_stats.into_inner().unwrap();
+    let num_failed = runner_stats.count_failed();
+    if num_failed > 0 {
+        runner_stats.set_status(WorkTreeRunnerStatus::Failed);
+        runner_stats.print(true);
+        return Err(anyhow!("{num_failed} file(s) could not be normalized"));
+    }
+
+    runner_stats.set_status(WorkTreeRunnerStatus::Ok);
+    runner_stats.print(true);
+
+    Ok(())
+}
+
+/// Rewrites the license header of the file at `path` in the configured format.
+fn normalize_file(
+    config: &Config,
+    partials: &Arc<Partials>,
+    file_types: &FileTypes,
+    directives: &HeadDirectives,
+    path: &Path,
+) -> Result<Outcome> {
+    let content = fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+    let extension = get_header_lookup_name(path, &content, file_types);
+    let Some(prefix) = SourceHeaders::find_header_prefix_for_extension(&extension, file_types)
+    else {
+        return Ok(Outcome::Unrecognized);
+    };
+    let Ok(text) = std::str::from_utf8(&content) else {
+        return Ok(Outcome::Unrecognized);
+    };
+    let Some(header) = parse_header(text, directives) else {
+        return Ok(Outcome::Unrecognized);
+    };
+
+    let context = NoticeContext {
+        // Owners are kept verbatim, so references must not be expanded
+        owner: header.owner.replace("$(", "$$("),
+        // An email address is part of the parsed owner
+        email: None,
+        project: config.project.clone(),
+        project_url: config.project_url.clone(),
+        // Contributors are kept verbatim, like the owner
+        contributors: header
+            .contributors
+            .iter()
+            .map(|contributor| contributor.replace("$(", "$$("))
+            .collect(),
+        year: header.year,
+        variables: config.variables.clone(),
+        compact_sentence: config.compact_sentence.clone(),
+        partials: Some(partials.clone()),
+    };
+    let notice = render_notice(config.format.unwrap_or_default(), &header.license, &context)
+        .with_context(|| format!("failed to render notice of {}", path.display()))?;
+    let blank_lines = config
+        .blank_lines_after_header
+        .unwrap_or(DEFAULT_BLANK_LINES_AFTER_HEADER);
+    let mut replacement = prefix.apply_with_blank_lines(notice, blank_lines)?;
+    if header.range.end == text.len() {
+        // Don't append blank lines to a file consisting of a header only
+        replacement.truncate(replacement.trim_end_matches('\n').len() + 1);
+    }
+
+    let normalized = [
+        &text[..header.range.start],
+        &replacement,
+        &text[header.range.end..],
+    ]
+    .concat();
+    if normalized == text {
+        return Ok(Outcome::Unchanged);
+    }
+
+    let read_only = config.read_only.unwrap_or_default();
+    if !read_only.check(path)? {
+        return Ok(Outcome::ReadOnly);
+    }
+    read_only
+        .write(
+            path,
+            normalized.as_bytes(),
+            config.preserve_mtime.unwrap_or_default(),
+        )
+        .with_context(|| format!("failed to write {}", path.display()))?;
+    Ok(Outcome::Normalized)
+}
+
+fn print_task_success<P>(path: P)
+where
+    P: AsRef<Path>,
+{
+    let result_type = "ok".green();
+    let path = display_path(path);
+    info!(%path, status = "normalized", "normalize {path} ... {result_type}")
+}
+
+fn print_task_failure<P>(path: P, err: anyhow::Error)
+where
+    P: AsRef<Path>,
+{
+    let result_type = "failed".red();
+    let path = display_path(path);
+    error!(%path, status = "failed", "normalize {path} ... {result_type} ({err:#})")
+}
@@ -0,0 +1,127 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::commands::apply::{render_notice, workspace_partials, ApplyArgs};
+use crate::config::Config;
+use crate::ops::notice_file::{extend_notice_year, NoticeFile, NOTICE_FILENAME};
+use crate::schema::LicenseYear;
+use crate::template::notice::{interpolate_with_partials, LicenseNoticeFormat};
+use crate::utils::{current_year, write_atomic};
+
+use anyhow::{anyhow, Context, Result};
+use clap::Args;
+use tracing::info;
+
+use std::env::current_dir;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+#[derive(Args, Debug)]
+pub struct NoticeArgs {
+    #[command(flatten)]
+    config: Config,
+
+    /// Path of the notice file, relative to the workspace root.
+    #[arg(long, value_name = "PATH", default_value = NOTICE_FILENAME)]
+    output: PathBuf,
+
+    /// Fail if the notice file is missing or out of date, without writing it.
+    #[arg(long)]
+    check: bool,
+}
+
+pub fn run(args: &mut NoticeArgs) -> Result<()> {
+    let workspace_root = current_dir()?;
+    let mut config = args.config.with_workspace_config(&workspace_root)?;
+    config.fill_from_metadata(&workspace_root);
+    let mut config = ApplyArgs::to_config(config)?;
+
+    let path = workspace_root.join(&args.output);
+    let existing = match fs::read_to_string(&path) {
+        Ok(content) => Some(content),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+        Err(err) => return Err(err).with_context(|| format!("failed to read {}", path.display())),
+    };
+
+    // The year of an existing notice file is kept, extended to the current year
+    if config.year.is_none() {
+        let year = existing
+            .as_deref()
+            .and_then(|content| extend_notice_year(content, current_year()));
+        config.year =
+            Some(year.unwrap_or_else(|| LicenseYear::single_year(current_year()).unwrap()));
+    }
+    config.format = Some(LicenseNoticeFormat::Spdx);
+
+    let Some(project) = config.project.clone() else {
+        return Err(anyhow!(
+            "the notice file names the project, but `project` is not configured"
+        ));
+    };
+
+    // Owners of subtrees hold the copyright of their files as well
+    let partials = workspace_partials(&config);
+    let mut owners = vec![config.owner.as_str()];
+    for path_owner in config.path_owners.iter() {
+        if !owners.contains(&path_owner.owner.as_str()) {
+            owners.push(&path_owner.owner);
+        }
+    }
+    let copyright = owners
+        .into_iter()
+        .map(|owner| {
+            let notice = render_notice(&config, owner, &partials, &config.license)?;
+            Ok(notice.lines().next().unwrap_or_default().to_string())
+        })
+        .collect::<Result<_>>()?;
+
+    let mut variables = config.variables.clone();
+    variables.insert("owner".into(), config.owner.clone());
+    variables.insert("project".into(), project.clone());
+    variables.insert("license".into(), config.license.to_string());
+    if let Some(year) = config.year.as_ref() {
+        variables.insert("year".into(), year.to_string());
+    }
+    let attributions = config
+        .attributions
+        .iter()
+        .map(|attribution| interpolate_with_partials(attribution, &variables, &partials))
+        .collect::<Result<_>>()
+        .context("failed to render attribution")?;
+
+    let content = NoticeFile {
+        project,
+        copyright,
+        attributions,
+    }
+    .render();
+
+    let display_path = args.output.display();
+    if existing.as_deref() == Some(content.as_str()) {
+        info!("{display_path} is up to date");
+        return Ok(());
+    }
+    if args.check {
+        return match existing {
+            Some(_) => Err(anyhow!(
+                "{display_path} is out of date, run `licensa notice` to update it"
+            )),
+            None => Err(anyhow!(
+                "{display_path} is missing, run `licensa notice` to create it"
+            )),
+        };
+    }
+
+    // New files are created with default permissions rather than those of a temporary file
+    match existing {
+        Some(_) => write_atomic(&path, content.as_bytes()),
+        None => fs::write(&path, content.as_bytes()),
+    }
+    .with_context(|| format!("failed to write {}", path.display()))?;
+    match existing {
+        Some(_) => info!("Updated {display_path}"),
+        None => info!("Created {display_path}"),
+    }
+    Ok(())
+}
@@ -0,0 +1,87 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::commands::apply::{license_header, license_insertion, ApplyArgs, Notices};
+use crate::config::Config;
+use crate::ops::scan::get_header_lookup_name;
+use crate::ops::work_tree::DEFAULT_READ_WINDOW;
+use crate::template::{has_copyright_notice, is_generated};
+
+use anyhow::{anyhow, Context, Result};
+use clap::Args;
+use tracing::{debug, info};
+
+use std::env::current_dir;
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Args, Debug)]
+pub struct PreviewArgs {
+    /// The file to render the license header for.
+    ///
+    /// The file doesn't need to exist, in which case the header is selected by
+    /// the file name alone.
+    #[arg(value_name = "FILE", verbatim_doc_comment)]
+    path: PathBuf,
+
+    #[command(flatten)]
+    config: Config,
+}
+
+pub fn run(args: &mut PreviewArgs) -> Result<()> {
+    let workspace_root = current_dir()?;
+    let mut config = args.config.with_workspace_config(&workspace_root)?;
+    config.fill_from_metadata(&workspace_root);
+    let apply_config = ApplyArgs::to_config(config)?;
+    let notices = Notices::render(&apply_config)?;
+    let notices = notices.for_path(&args.path);
+
+    let path = workspace_root.join(&args.path);
+    let content = match path.exists() {
+        true => fs::read(&path).with_context(|| format!("failed to read {}", path.display()))?,
+        false => vec![],
+    };
+    let lookup_name = get_header_lookup_name(&path, &content, &apply_config.file_types);
+    debug!(
+        "comment style of {} selected by `{lookup_name}`",
+        args.path.display()
+    );
+
+    let Some(header) = license_header(&apply_config, notices, &lookup_name)? else {
+        return Err(anyhow!(
+            "no comment style known for {}, `apply` skips this file",
+            args.path.display()
+        ));
+    };
+
+    let Some((licensed, inserted)) =
+        license_insertion(&apply_config, notices, &lookup_name, &content)?
+    else {
+        // Same window as `apply` searches for existing headers
+        let read_window = apply_config.read_window.unwrap_or(DEFAULT_READ_WINDOW);
+        let head = &content[..content.len().min(read_window)];
+        if has_copyright_notice(head) {
+            info!("{} already has a license header", args.path.display());
+        } else if is_generated(head) {
+            info!(
+                "{} is generated, `apply` skips this file",
+                args.path.display()
+            );
+        } else {
+            return Err(anyhow!("{} is not a text file", args.path.display()));
+        }
+        print!("{header}");
+        return Ok(());
+    };
+    if inserted.start > 0 {
+        let line = licensed[..inserted.start]
+            .iter()
+            .filter(|&&b| b == b'\n')
+            .count();
+        info!("header is inserted after line {line}");
+    }
+
+    // Headers are only inserted into text files
+    print!("{}", String::from_utf8_lossy(&licensed[inserted]));
+    Ok(())
+}
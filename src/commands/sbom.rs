@@ -0,0 +1,139 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::config::Config;
+use crate::ops::progress::Progress;
+use crate::ops::scan::{Scan, ScanConfig};
+use crate::ops::work_tree::DEFAULT_READ_WINDOW;
+use crate::sbom::{FileRecord, Inventory, SbomFormat};
+
+use anyhow::{anyhow, Context, Result};
+use clap::Args;
+use rayon::prelude::*;
+use tracing::{debug, info, info_span, warn};
+
+use std::env::current_dir;
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+#[derive(Args, Debug)]
+pub struct SbomArgs {
+    #[command(flatten)]
+    config: Config,
+
+    /// Format of the generated document, either SPDX or CycloneDX JSON.
+    #[arg(long, value_enum, value_name = "FORMAT", default_value_t)]
+    format: SbomFormat,
+
+    /// Write the document to this file instead of stdout.
+    #[arg(long, value_name = "PATH")]
+    output: Option<PathBuf>,
+}
+
+pub fn run(args: &mut SbomArgs) -> Result<()> {
+    let workspace_root = current_dir()?;
+    let config = &args.config.with_workspace_config(&workspace_root)?;
+
+    // ========================================================
+    // Scanning process
+    // ========================================================
+    let progress = Progress::new("sbom", "recorded");
+    progress.start_scan();
+    let scan_span = info_span!("scan").entered();
+
+    let candidates = Scan::new(ScanConfig::from_config(&workspace_root, config)?)?
+        .with_progress(&progress)
+        .find_candidates()?
+        .files;
+    scan_span.exit();
+
+    debug!(
+        files = candidates.len(),
+        "found {} candidate file(s)",
+        candidates.len()
+    );
+    progress.start_processing(candidates.len());
+
+    // ========================================================
+    // File processing
+    // ========================================================
+    let read_window = config.read_window.unwrap_or(DEFAULT_READ_WINDOW);
+    let files: Vec<FileRecord> = info_span!("inventory", files = candidates.len()).in_scope(|| {
+        candidates
+            .par_iter()
+            .filter_map(|entry| match fs::read(entry.path()) {
+                Ok(content) => {
+                    let head = &content[..content.len().min(read_window)];
+                    progress.inc_processed();
+                    Some(FileRecord::new(
+                        &workspace_root,
+                        entry.path(),
+                        &content,
+                        head,
+                    ))
+                }
+                Err(err) => {
+                    progress.inc_failed();
+                    progress.suspend(|| warn!("failed to read {}: {err}", entry.path().display()));
+                    None
+                }
+            })
+            .collect()
+    });
+    progress.finish();
+
+    let name = workspace_root
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "workspace".into());
+    // Files violating the license policy are reported after the document is written
+    let denied: Vec<&FileRecord> = files
+        .iter()
+        .filter(|file| {
+            let license = file.license.as_deref();
+            license.is_some_and(|license| config.policy.check(license).is_err())
+        })
+        .collect();
+    for file in denied.iter() {
+        let license = file.license.as_deref().unwrap_or_default();
+        warn!(
+            "{}: license `{license}` denied by the license policy",
+            file.path
+        );
+    }
+    let num_denied = denied.len();
+
+    let declared_license = config.license.as_ref().map(|license| license.to_string());
+    let inventory = Inventory::new(name, declared_license, files);
+    let document = inventory.render(args.format)?;
+
+    match args.output.as_ref() {
+        Some(output) => {
+            fs::write(workspace_root.join(output), document)
+                .with_context(|| format!("failed to write {}", output.display()))?;
+            info!(
+                "Recorded {} file(s) in {}",
+                inventory.files.len(),
+                output.display()
+            );
+        }
+        None => {
+            // Stop quietly if the reading end of a pipe is closed, e.g. `licensa sbom | head`
+            if let Err(err) = writeln!(io::stdout().lock(), "{document}") {
+                if err.kind() != io::ErrorKind::BrokenPipe {
+                    return Err(err.into());
+                }
+            }
+        }
+    }
+
+    config.check_policy()?;
+    if num_denied > 0 {
+        return Err(anyhow!(
+            "{num_denied} file(s) with a license denied by the license policy"
+        ));
+    }
+
+    Ok(())
+}
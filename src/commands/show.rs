@@ -0,0 +1,76 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::commands::apply::{license_header, ApplyArgs, Notices};
+use crate::config::Config;
+use crate::schema::LicenseId;
+use crate::spdx::license_texts;
+use crate::template::header::SourceHeaders;
+
+use anyhow::{anyhow, Result};
+use clap::Args;
+use colored::Colorize;
+
+use std::env::current_dir;
+
+/// Copyright owner shown if neither the config file nor a project manifest declares one.
+const PLACEHOLDER_OWNER: &str = "[owner]";
+
+#[derive(Args, Debug)]
+pub struct ShowArgs {
+    /// The SPDX license ID or expression, e.g. `MIT` or `MIT OR Apache-2.0`.
+    #[arg(id = "spdx_id", value_name = "SPDX-ID", conflicts_with = "license")]
+    #[arg(value_parser = crate::parser::parse_license_id)]
+    spdx_id: LicenseId,
+
+    /// The file extension the header is commented for, e.g. `py`.
+    ///
+    /// Names of well-known files such as `Makefile` are accepted as well.
+    #[arg(long, value_name = "EXT", default_value = "rs", verbatim_doc_comment)]
+    extension: String,
+
+    /// Also print the full text of the license.
+    #[arg(long)]
+    text: bool,
+
+    #[command(flatten)]
+    config: Config,
+}
+
+pub fn run(args: &mut ShowArgs) -> Result<()> {
+    let workspace_root = current_dir()?;
+    let mut config = args.config.with_workspace_config(&workspace_root)?;
+    config.fill_from_metadata(&workspace_root);
+    config.license = Some(args.spdx_id.clone());
+    config.owner.get_or_insert_with(|| PLACEHOLDER_OWNER.into());
+    // Only the notice of the license shown is of interest
+    config.file_licenses.clear();
+
+    let lookup_name = lookup_name(&args.extension);
+    let apply_config = ApplyArgs::to_config(config)?;
+    let notices = Notices::render(&apply_config)?;
+    let Some(header) = license_header(&apply_config, &notices, &lookup_name)? else {
+        return Err(anyhow!(
+            "no comment style known for files with extension `{}`",
+            args.extension
+        ));
+    };
+    print!("{header}");
+
+    if args.text {
+        for (id, text) in license_texts(&args.spdx_id)? {
+            println!("{}\n", id.bold());
+            println!("{}\n", text.trim_end());
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the name the header definition of files with `extension` is looked up by.
+fn lookup_name(extension: &str) -> String {
+    if SourceHeaders::well_known_file_extension(extension).is_some() {
+        return extension.to_string();
+    }
+    format!(".{}", extension.trim_start_matches('.'))
+}
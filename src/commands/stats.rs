@@ -0,0 +1,101 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::config::Config;
+use crate::ops::coverage::{CoverageFormat, CoverageReport};
+use crate::ops::progress::Progress;
+use crate::ops::scan::{Candidates, Scan, ScanConfig};
+use crate::ops::work_tree::DEFAULT_READ_WINDOW;
+use crate::template::header::find_interpreter_extension;
+use crate::template::{find_license_id, has_copyright_notice};
+use crate::utils::read_head;
+
+use anyhow::Result;
+use clap::Args;
+use rayon::prelude::*;
+use tracing::{debug, info_span};
+
+use std::env::current_dir;
+use std::sync::Mutex;
+
+#[derive(Args, Debug)]
+pub struct StatsArgs {
+    #[command(flatten)]
+    config: Config,
+
+    /// Format of the report, either human-readable tables, JSON or CSV.
+    #[arg(
+        long,
+        alias = "output",
+        value_enum,
+        value_name = "FORMAT",
+        default_value_t
+    )]
+    format: CoverageFormat,
+}
+
+pub fn run(args: &mut StatsArgs) -> Result<()> {
+    let workspace_root = current_dir()?;
+    let config = &args.config.with_workspace_config(&workspace_root)?;
+
+    // ========================================================
+    // Scanning process
+    // ========================================================
+    let progress = Progress::new("stats", "licensed");
+    progress.start_scan();
+    let scan_span = info_span!("scan").entered();
+
+    let Candidates {
+        files: candidates,
+        skipped,
+    } = Scan::new(ScanConfig::from_config(&workspace_root, config)?)?
+        .with_progress(&progress)
+        .find_candidates()?;
+    scan_span.exit();
+
+    debug!(
+        files = candidates.len(),
+        "found {} candidate file(s)",
+        candidates.len()
+    );
+    progress.start_processing(candidates.len());
+
+    // ========================================================
+    // File processing
+    // ========================================================
+    let report = Mutex::new(CoverageReport::default());
+    let file_types = config.file_types()?;
+    let read_window = config.read_window.unwrap_or(DEFAULT_READ_WINDOW);
+
+    info_span!("check", files = candidates.len()).in_scope(|| {
+        candidates.par_iter().for_each(|entry| {
+            let Ok(head) = read_head(entry.path(), read_window) else {
+                progress.inc_skipped();
+                return;
+            };
+
+            let licensed = has_copyright_notice(&head);
+            let license_id = licensed.then(|| find_license_id(&head)).flatten();
+            report.lock().unwrap().record(
+                &workspace_root,
+                entry.path(),
+                find_interpreter_extension(&head),
+                &file_types,
+                licensed,
+                license_id.as_deref(),
+            );
+
+            if licensed {
+                progress.inc_processed();
+            } else {
+                progress.inc_failed();
+            }
+        })
+    });
+    progress.finish();
+
+    let report = report.into_inner().unwrap();
+    print!("{}", report.render(args.format, skipped));
+
+    Ok(())
+}
@@ -0,0 +1,85 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::ops::journal::Journal;
+use crate::ops::stats::{WorkTreeRunnerStatistics, WorkTreeRunnerStatus};
+use crate::utils::display_path;
+use crate::workspace::lock::WorkspaceLock;
+use crate::workspace::store::Store;
+
+use anyhow::{anyhow, Result};
+use clap::Args;
+use colored::Colorize;
+use tracing::{error, info};
+
+use std::env::current_dir;
+use std::path::Path;
+
+#[derive(Args, Debug, Clone)]
+pub struct UndoArgs {}
+
+pub fn run(_args: &UndoArgs) -> Result<()> {
+    let workspace_root = current_dir()?;
+    let store = Store::new(&workspace_root);
+    let _lock = WorkspaceLock::acquire(&store, "undo")?;
+    let mut journal = Journal::load(&store)?;
+
+    let Some(mut last_run) = journal.pop() else {
+        info!("Nothing to undo");
+        return Ok(());
+    };
+
+    let mut runner_stats = WorkTreeRunnerStatistics::new("undo", "reverted");
+    runner_stats.set_items(last_run.entries.len());
+
+    // Entries that cannot be reverted are kept in the journal, so that
+    // a subsequent `undo` can pick them up once the issue is resolved.
+    let mut remaining = vec![];
+    for entry in last_run.entries.drain(..) {
+        match entry.revert(&workspace_root) {
+            Ok(_) => {
+                runner_stats.add_action_count();
+                print_task_success(&entry.path);
+            }
+            Err(err) => {
+                runner_stats.add_fail();
+                print_task_failure(&entry.path, err);
+                remaining.push(entry);
+            }
+        }
+    }
+
+    let num_failed = remaining.len();
+    last_run.entries = remaining;
+    journal.push(last_run);
+    journal.save(&store)?;
+
+    if num_failed > 0 {
+        runner_stats.set_status(WorkTreeRunnerStatus::Failed);
+        runner_stats.print(true);
+        return Err(anyhow!("{num_failed} file(s) could not be reverted"));
+    }
+
+    runner_stats.set_status(WorkTreeRunnerStatus::Ok);
+    runner_stats.print(true);
+
+    Ok(())
+}
+
+fn print_task_success<P>(path: P)
+where
+    P: AsRef<Path>,
+{
+    let result_type = "ok".green();
+    let path = display_path(path);
+    info!(%path, status = "reverted", "undo {path} ... {result_type}")
+}
+
+fn print_task_failure<P>(path: P, err: anyhow::Error)
+where
+    P: AsRef<Path>,
+{
+    let result_type = "refused".red();
+    let path = display_path(path);
+    error!(%path, status = "refused", "undo {path} ... {result_type} ({err:#})")
+}
@@ -1,89 +1,870 @@
 // Copyright 2024 Nelson Dominguez
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
-use crate::config::Config;
-use crate::ops::scan::is_candidate;
+use crate::commands::apply::{
+    license_content, license_header, license_header_offset, ApplyArgs, Notices,
+};
+use crate::config::{Config, FileLicense};
+use crate::ops::baseline::{Baseline, LICENSA_BASELINE_FILENAME};
+use crate::ops::cache::{FileStamp, FileStatus, ScanCache, VerifyCache};
+use crate::ops::coverage::CoverageReport;
+use crate::ops::diff::{colorize_diff, unified_diff};
+use crate::ops::github::error_annotation;
+use crate::ops::junit::{JunitReport, TestOutcome};
+use crate::ops::progress::Progress;
+use crate::ops::report::{ComplianceReport, ReportFormat, Violation};
+use crate::ops::scan::{
+    get_header_lookup_name, get_header_lookup_name_with_interpreter, Candidates, Scan, ScanConfig,
+};
 use crate::ops::stats::{WorkTreeRunnerStatistics, WorkTreeRunnerStatus};
-use crate::template::has_copyright_notice;
-use crate::workspace::walker::WalkBuilder;
+use crate::ops::work_tree::DEFAULT_READ_WINDOW;
+use crate::template::fingerprint::{find_header_block, fingerprint, matches_ignoring_whitespace};
+use crate::template::header::FileTypes;
+use crate::utils::{self, current_year, read_head, sha256_hex};
+use crate::workspace::git;
+use crate::workspace::store::Store;
+use crate::workspace::LicensaWorkspace;
 
-use anyhow::Result;
-use clap::Args;
+use anyhow::{anyhow, Context, Result};
+use clap::{Args, ValueEnum};
+use colored::Colorize;
 use ignore::DirEntry;
 use rayon::prelude::*;
+use tracing::{debug, error, info, info_span, warn};
 
+use std::collections::{BTreeMap, HashMap};
 use std::env::current_dir;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 
 #[derive(Args, Debug)]
 pub struct VerifyArgs {
     #[command(flatten)]
     config: Config,
+
+    /// Record all files currently missing a license header in a baseline file.
+    ///
+    /// Subsequent `verify` runs ignore violations recorded in the baseline, which
+    /// allows adopting Licensa incrementally while blocking new unlicensed files.
+    #[arg(long, value_name = "PATH", verbatim_doc_comment)]
+    #[arg(num_args = 0..=1, default_missing_value = LICENSA_BASELINE_FILENAME)]
+    write_baseline: Option<PathBuf>,
+
+    /// Path to a baseline file listing grandfathered violations.
+    ///
+    /// Defaults to `.licensa-baseline.json` in the workspace root, if present.
+    #[arg(long, value_name = "PATH", conflicts_with = "write_baseline")]
+    baseline: Option<PathBuf>,
+
+    /// Write a compliance report to this file.
+    ///
+    /// The report format is inferred from the file extension and is either
+    /// HTML (`.html`) or Markdown (`.md`).
+    #[arg(long, value_name = "PATH", conflicts_with = "write_baseline")]
+    report: Option<PathBuf>,
+
+    /// Only process files matching these glob patterns.
+    ///
+    /// Patterns follow `.gitignore` syntax and are matched relative to the
+    /// workspace root. Files matching an include pattern are processed even if
+    /// they match an `--exclude` pattern. Patterns starting with `!` exclude
+    /// files that would be included otherwise.
+    ///
+    ///     licensa verify --include src/**/*.rs '!src/generated/**'
+    #[cfg(not(doctest))]
+    #[arg(long, verbatim_doc_comment)]
+    #[arg(value_name = "GLOB", num_args = 1..)]
+    include: Vec<String>,
+
+    /// Only check files changed in a Git revision range, e.g. `v1.0..HEAD`.
+    ///
+    /// The range is passed to `git diff`: `A...B` selects the files changed on `B`
+    /// since it diverged from `A`, and a single revision selects the files changed
+    /// since that revision, including uncommitted changes. Deleted files and files
+    /// excluded otherwise are skipped.
+    ///
+    ///     licensa verify --rev v1.0..release/2.0
+    #[cfg(not(doctest))]
+    #[arg(long, value_name = "A..B", conflicts_with = "write_baseline")]
+    #[arg(verbatim_doc_comment)]
+    rev: Option<String>,
+
+    /// Only check files changed since HEAD diverged from this revision, e.g. the
+    /// target branch of a pull request.
+    ///
+    /// Files are compared with the merge base of the revision and HEAD, including
+    /// uncommitted changes. In shallow clones, the history since the merge base
+    /// must be fetched.
+    ///
+    ///     licensa verify --diff-base origin/main
+    #[cfg(not(doctest))]
+    #[arg(long, value_name = "REF", conflicts_with_all = ["write_baseline", "rev"])]
+    #[arg(verbatim_doc_comment)]
+    diff_base: Option<String>,
+
+    /// Print a unified diff of the license header `apply` would insert into each
+    /// file missing one.
+    ///
+    /// Only supported with the `text` format, as diffs would break the output of
+    /// the other formats.
+    #[arg(long, conflicts_with = "write_baseline")]
+    diff: bool,
+
+    /// Report license headers that differ from the header `apply` would write.
+    ///
+    /// Headers are compared by fingerprint, ignoring line endings and trailing
+    /// whitespace, so that hand-written headers and edits to headers written by
+    /// `apply` are reported.
+    #[arg(long, conflicts_with = "write_baseline", verbatim_doc_comment)]
+    strict_origin: bool,
+
+    /// Report license headers that don't match the header `apply` would write
+    /// for each file.
+    ///
+    /// Headers are compared word by word, ignoring differences in whitespace and
+    /// line endings. Mismatching headers are reported as being in the wrong format.
+    #[arg(long, conflicts_with = "write_baseline", verbatim_doc_comment)]
+    strict: bool,
+
+    /// Read and check every file instead of reusing the results of unchanged files
+    /// cached in `.licensa` by previous runs.
+    #[arg(long)]
+    no_cache: bool,
+
+    /// Stop at the first violation instead of collecting all violations.
+    ///
+    /// Files are checked while the workspace is scanned and the scan stops as soon
+    /// as a violation is found, which gives fast feedback, e.g. in pre-commit hooks.
+    /// Files checked in parallel may still report further violations.
+    #[arg(long, conflicts_with_all = ["write_baseline", "report"], verbatim_doc_comment)]
+    fail_fast: bool,
+
+    /// Output format of violations.
+    ///
+    /// The `github` format prints GitHub Actions workflow commands, so that
+    /// violations are annotated inline on pull requests. The `junit` format prints
+    /// a JUnit XML report with a test case per checked file, e.g. for CI systems
+    /// that show test results.
+    #[arg(long, value_enum, value_name = "FORMAT", default_value_t)]
+    format: VerifyFormat,
+}
+
+/// Output format of `verify` violations.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum VerifyFormat {
+    /// Human-readable command output.
+    #[default]
+    Text,
+
+    /// GitHub Actions `::error` workflow commands.
+    Github,
+
+    /// A JUnit XML report of all checked files.
+    Junit,
+}
+
+impl VerifyArgs {
+    /// Resolves the baseline used to filter violations, if any.
+    fn resolve_baseline<P>(&self, workspace_root: P) -> Result<Option<Baseline>>
+    where
+        P: AsRef<Path>,
+    {
+        if let Some(path) = self.baseline.as_ref() {
+            return Baseline::load(workspace_root.as_ref().join(path)).map(Some);
+        }
+
+        let default_path = workspace_root.as_ref().join(LICENSA_BASELINE_FILENAME);
+        if default_path.is_file() {
+            return Baseline::load(default_path).map(Some);
+        }
+
+        Ok(None)
+    }
 }
 
 pub fn run(args: &mut VerifyArgs) -> anyhow::Result<()> {
-    let mut runner_stats = WorkTreeRunnerStatistics::new("verify", "found");
+    let runner_stats = WorkTreeRunnerStatistics::new("verify", "found");
 
     let workspace_root = current_dir()?;
     let config = &args.config.with_workspace_config(&workspace_root)?;
+    let baseline = match args.write_baseline {
+        Some(_) => None,
+        None => args.resolve_baseline(&workspace_root)?,
+    };
 
-    // ========================================================
-    // Scanning process
-    // ========================================================
+    config.check_policy()?;
 
-    let mut walk_builder = WalkBuilder::new(&workspace_root);
-    walk_builder.exclude(Some(config.exclude.clone()))?;
+    if args.diff && args.format != VerifyFormat::Text {
+        return Err(anyhow!(
+            "`--diff` can't be used with `--format {}`",
+            args.format.to_possible_value().unwrap().get_name()
+        ));
+    }
 
-    let mut walker = walk_builder.build()?;
-    walker
-        .quit_while(|res| res.is_err())
-        .send_while(|res| is_candidate(res.unwrap()))
-        .max_capacity(None);
+    // Fail early, before scanning the workspace, if the report format is not supported
+    if let Some(report_path) = args.report.as_ref() {
+        ReportFormat::from_path(report_path)?;
+    }
 
-    let candidates: Vec<DirEntry> = walker
-        .run_task()
-        .iter()
-        .par_bridge()
-        .into_par_iter()
-        .filter_map(Result::ok)
-        .collect();
+    // Render notices upfront so that config errors are reported before scanning
+    let managed = if args.diff || args.strict_origin || args.strict {
+        let mut apply_config = config.clone();
+        apply_config.fill_from_metadata(&workspace_root);
+        let apply_config = ApplyArgs::to_config(apply_config)?;
+        let notices = Notices::render(&apply_config)?;
+        Some((apply_config, notices))
+    } else {
+        None
+    };
+
+    // Licenses expected in headers, including those inferred from package manifests
+    let mut expected_licenses = config.clone();
+    if matches!(args.format, VerifyFormat::Github | VerifyFormat::Junit) {
+        expected_licenses.fill_from_metadata(&workspace_root);
+    }
+
+    let range = match args.diff_base.as_deref() {
+        Some(base) => Some(git::merge_base(&workspace_root, base)?),
+        None => args.rev.clone(),
+    };
+    let changed_files = range
+        .map(|range| git::changed_files(&workspace_root, &range).map(Arc::new))
+        .transpose()?;
+
+    // ========================================================
+    // Scanning process
+    // ========================================================
+    let progress = Progress::new("verify", "found");
+    progress.start_scan();
 
-    runner_stats.set_items(candidates.len());
+    let file_types = config.file_types()?;
+    let scan = Scan::new(ScanConfig {
+        include: Some(args.include.clone()),
+        plugin_extensions: config
+            .plugins
+            .iter()
+            .flat_map(|plugin| plugin.extensions.clone())
+            .collect(),
+        paths: changed_files,
+        ..ScanConfig::from_config(&workspace_root, config)?
+    })?
+    .with_progress(&progress);
 
     // ========================================================
     // File processing
     // ========================================================
     let runner_stats = Arc::new(Mutex::new(runner_stats));
+    let missing = Arc::new(Mutex::new(Vec::<PathBuf>::new()));
+    let modified = Mutex::new(Vec::<PathBuf>::new());
+    let wrong_format = Mutex::new(Vec::<PathBuf>::new());
+    let outdated = Mutex::new(Vec::<(PathBuf, u32)>::new());
+    let denied = Mutex::new(Vec::<(PathBuf, String)>::new());
+    // Extensions of the hash-bang interpreters of violating files, for the summary by extension
+    let interpreters = Mutex::new(HashMap::<PathBuf, String>::new());
+    // Only JUnit reports list files without violations
+    let passed = Mutex::new(Vec::<PathBuf>::new());
+    let required_year = config
+        .require_current_year
+        .unwrap_or_default()
+        .then(current_year);
+    let coverage = Mutex::new(CoverageReport::default());
+
+    // With `--fail-fast`, files are checked while scanning, so the progress bar
+    // only counts scanned files
+    let file_progress = match args.fail_fast {
+        true => Progress::hidden(),
+        false => progress.clone(),
+    };
 
-    // Read file as bytes vector and return its content and the patht to it
+    // Detect the header status of a file from its leading bytes and return it along
+    // with the path to the file. The cached status of unchanged files is reused.
+    let store = Store::new(&workspace_root);
+    let read_window = config.read_window.unwrap_or(DEFAULT_READ_WINDOW);
+    let scan_cache = (!args.no_cache).then(|| Mutex::new(ScanCache::load(&store, read_window)));
+    let verify_cache = (!args.no_cache && (args.strict_origin || args.strict))
+        .then(|| Mutex::new(VerifyCache::load(&store)));
     let read_file = |entry: &DirEntry| {
-        fs::read(entry.path())
-            .ok()
-            .map(|content| (content, entry.path().to_path_buf()))
+        let path = entry.path();
+        let relative_path = path.strip_prefix(&workspace_root).unwrap_or(path);
+        let stamp = FileStamp::read(path);
+        let cache = scan_cache.as_ref().zip(stamp);
+        if let Some((cache, stamp)) = cache {
+            if let Some(status) = cache.lock().unwrap().get(relative_path, stamp) {
+                return Some((status.clone(), path.to_path_buf()));
+            }
+        }
+
+        let Ok(head) = read_head(path, read_window) else {
+            file_progress.inc_skipped();
+            return None;
+        };
+        let status = FileStatus::detect(&head);
+        if let Some((cache, stamp)) = cache {
+            cache
+                .lock()
+                .unwrap()
+                .insert(relative_path, stamp, status.clone());
+        }
+        Some((status, path.to_path_buf()))
     };
 
-    // Check existence of copyright notice and update output statistices
-    let check_copyright_notice = |(ref file_contents, ref path): (Vec<u8>, PathBuf)| {
-        let mut runner_stats = runner_stats.lock().unwrap();
-        if has_copyright_notice(file_contents) {
-            runner_stats.add_action_count();
+    // Check existence of copyright notice and update output statistices.
+    // Returns whether the file violates the configuration.
+    let check_copyright_notice = |(ref status, ref path): (FileStatus, PathBuf)| {
+        // Generated files are skipped by `apply`, so they aren't expected to be licensed
+        if !status.candidate {
+            debug!(
+                status = "generated",
+                "skip {}: generated file",
+                path.display()
+            );
+            runner_stats.lock().unwrap().add_generated();
+            file_progress.inc_skipped();
+            return false;
+        }
+
+        let licensed = status.has_header;
+        coverage.lock().unwrap().record(
+            &workspace_root,
+            path,
+            status.interpreter.as_deref(),
+            &file_types,
+            licensed,
+            status.license.as_deref(),
+        );
+
+        let header_matches = |comparison| match managed.as_ref() {
+            Some((apply_config, notices)) => has_managed_header(
+                apply_config,
+                notices.for_path(path.strip_prefix(&workspace_root).unwrap_or(path)),
+                path,
+                read_window,
+                comparison,
+                verify_cache.as_ref(),
+            ),
+            None => true,
+        };
+
+        let record_interpreter = || {
+            if let Some(interpreter) = status.interpreter.as_ref() {
+                interpreters
+                    .lock()
+                    .unwrap()
+                    .insert(path.to_owned(), interpreter.to_owned());
+            }
+        };
+
+        let license = status.license.as_deref();
+        if !licensed {
+            record_interpreter();
+            missing.lock().unwrap().push(path.to_owned());
+            file_progress.inc_failed();
+            // Files listed in the baseline are filtered once all files are checked
+            return !baseline
+                .as_ref()
+                .is_some_and(|b| b.contains(&workspace_root, path));
+        } else if let Some(license) = license.filter(|l| config.policy.check(l).is_err()) {
+            denied
+                .lock()
+                .unwrap()
+                .push((path.to_owned(), license.to_owned()));
+            file_progress.inc_failed();
+        } else if args.strict && !header_matches(HeaderComparison::Words) {
+            wrong_format.lock().unwrap().push(path.to_owned());
+            file_progress.inc_failed();
+        } else if args.strict_origin && !header_matches(HeaderComparison::Fingerprint) {
+            modified.lock().unwrap().push(path.to_owned());
+            file_progress.inc_failed();
+        } else if let Some(year) = status
+            .year
+            .filter(|&year| required_year.is_some_and(|required_year| year < required_year))
+        {
+            outdated.lock().unwrap().push((path.to_owned(), year));
+            file_progress.inc_failed();
         } else {
-            runner_stats.add_ignore();
+            if args.format == VerifyFormat::Junit {
+                passed.lock().unwrap().push(path.to_owned());
+            }
+            runner_stats.lock().unwrap().add_action_count();
+            file_progress.inc_processed();
+            return false;
         }
+        record_interpreter();
+        true
     };
 
-    candidates
-        .par_iter()
-        .filter_map(read_file)
-        .for_each(check_copyright_notice);
+    let stop = Arc::new(AtomicBool::new(false));
+    let check_file = |entry: &DirEntry| {
+        if stop.load(Ordering::Relaxed) {
+            return;
+        }
+        if let Some(file) = read_file(entry) {
+            if check_copyright_notice(file) && args.fail_fast {
+                stop.store(true, Ordering::Relaxed);
+            }
+        }
+    };
+
+    if args.fail_fast {
+        // Check files as they're found, so that the scan stops at the first violation
+        let num_checked = AtomicUsize::new(0);
+        let skipped = info_span!("check").in_scope(|| {
+            scan.with_stop_signal(stop.clone())
+                .for_each_candidate(|entry| {
+                    num_checked.fetch_add(1, Ordering::Relaxed);
+                    check_file(&entry)
+                })
+        })?;
+        runner_stats
+            .lock()
+            .unwrap()
+            .set_items(num_checked.into_inner());
+        runner_stats.lock().unwrap().add_ignored(skipped);
+    } else {
+        let scan_span = info_span!("scan").entered();
+        let Candidates {
+            files: candidates,
+            skipped,
+        } = scan.find_candidates()?;
+        scan_span.exit();
+
+        runner_stats.lock().unwrap().set_items(candidates.len());
+        runner_stats.lock().unwrap().add_ignored(skipped);
+        debug!(
+            files = candidates.len(),
+            "found {} candidate file(s)",
+            candidates.len()
+        );
+        progress.start_processing(candidates.len());
+
+        info_span!("check", files = candidates.len())
+            .in_scope(|| candidates.par_iter().for_each(check_file));
+    }
+    progress.finish();
+
+    if let Some(scan_cache) = scan_cache {
+        let mut scan_cache = scan_cache.into_inner().unwrap();
+        if let Err(err) = scan_cache.save(&store, &workspace_root) {
+            warn!("failed to update scan cache: {err:#}");
+        }
+    }
+    if let Some(verify_cache) = verify_cache {
+        if let Err(err) = verify_cache.into_inner().unwrap().save(&store) {
+            warn!("failed to update verify cache: {err:#}");
+        }
+    }
+
+    let mut missing = missing.lock().unwrap();
+    missing.sort();
+
+    if let Some(baseline_path) = args.write_baseline.as_ref() {
+        let baseline = Baseline::from_paths(&workspace_root, missing.iter());
+        baseline.save(workspace_root.join(baseline_path))?;
+        info!(
+            "Recorded {} file(s) missing a license header in {}",
+            baseline.len(),
+            baseline_path.display()
+        );
+        return Ok(());
+    }
 
     // ========================================================
-    // Print output statistics
+    // Collect violations not covered by the baseline
+    let mut report = ComplianceReport::new(coverage.into_inner().unwrap());
+    let mut junit = JunitReport::new("licensa verify");
+    for path in passed.into_inner().unwrap() {
+        let display_path = path.strip_prefix(&workspace_root).unwrap_or(&path);
+        junit.add(utils::display_path(display_path), TestOutcome::Passed);
+    }
     let mut runner_stats = runner_stats.lock().unwrap();
-    runner_stats.set_status(WorkTreeRunnerStatus::Ok);
-    runner_stats.print(true);
+    let mut violations = Vec::new();
+    for path in missing.iter() {
+        let is_baselined = baseline
+            .as_ref()
+            .is_some_and(|b| b.contains(&workspace_root, path));
+
+        if is_baselined {
+            debug!(
+                status = "baselined",
+                "skip {}: listed in baseline",
+                path.display()
+            );
+            runner_stats.add_ignore();
+            report.baselined += 1;
+            if args.format == VerifyFormat::Junit {
+                let display_path = path.strip_prefix(&workspace_root).unwrap_or(path);
+                junit.add(
+                    utils::display_path(display_path),
+                    TestOutcome::Skipped("Listed in the baseline".into()),
+                );
+            }
+        } else {
+            violations.push((path.clone(), Violation::Missing));
+        }
+    }
+
+    let mut modified = modified.into_inner().unwrap();
+    modified.sort();
+    let mut denied = denied.into_inner().unwrap();
+    denied.sort();
+    let mut wrong_format = wrong_format.into_inner().unwrap();
+    wrong_format.sort();
+    let mut outdated = outdated.into_inner().unwrap();
+    outdated.sort();
+    violations.extend(
+        modified
+            .iter()
+            .map(|path| (path.clone(), Violation::Modified)),
+    );
+    violations.extend(
+        denied
+            .iter()
+            .map(|(path, license)| (path.clone(), Violation::Denied(license.clone()))),
+    );
+    violations.extend(
+        wrong_format
+            .iter()
+            .map(|path| (path.clone(), Violation::WrongFormat)),
+    );
+    violations.extend(
+        outdated
+            .iter()
+            .map(|(path, year)| (path.clone(), Violation::Outdated(*year))),
+    );
+
+    for (path, violation) in violations {
+        runner_stats.add_fail();
+        let display_path = path.strip_prefix(&workspace_root).unwrap_or(&path);
+        emit_violation(
+            args.format,
+            &mut junit,
+            &expected_licenses,
+            &file_types,
+            &path,
+            display_path,
+            &violation,
+        );
+        if violation == Violation::Missing && args.diff {
+            if let Some((apply_config, notices)) = managed.as_ref() {
+                let notices = notices.for_path(display_path);
+                print_diff(apply_config, notices, &path, display_path)?;
+            }
+        }
+        report.add_violation(&workspace_root, &path, violation);
+    }
+
+    // ========================================================
+    // Print output statistics
+    let num_failed = runner_stats.count_failed();
+    if num_failed > 0 {
+        runner_stats.set_status(WorkTreeRunnerStatus::Failed);
+    } else {
+        runner_stats.set_status(WorkTreeRunnerStatus::Ok);
+    }
+    // The report is the only output of the JUnit format, so that it can be redirected to a file
+    match args.format {
+        VerifyFormat::Junit => print!("{}", junit.render()),
+        _ => runner_stats.print(true),
+    }
+
+    if args.format == VerifyFormat::Text {
+        let violations: [(&str, Vec<&PathBuf>); 5] = [
+            (
+                "missing",
+                missing
+                    .iter()
+                    .filter(|path| {
+                        !baseline
+                            .as_ref()
+                            .is_some_and(|b| b.contains(&workspace_root, path))
+                    })
+                    .collect(),
+            ),
+            ("modified", modified.iter().collect()),
+            ("wrong format", wrong_format.iter().collect()),
+            ("denied", denied.iter().map(|(path, _)| path).collect()),
+            ("outdated", outdated.iter().map(|(path, _)| path).collect()),
+        ];
+        print_violations_by_extension(
+            violations
+                .iter()
+                .flat_map(|(_, paths)| paths.iter().copied()),
+            &interpreters.into_inner().unwrap(),
+            &file_types,
+        );
+        print_violations_by_directory(&workspace_root, &violations);
+    }
+
+    if let Some(report_path) = args.report.as_ref() {
+        report.save(workspace_root.join(report_path))?;
+        info!("Wrote compliance report to {}", report_path.display());
+    }
+
+    let num_missing =
+        num_failed - denied.len() - modified.len() - wrong_format.len() - outdated.len();
+    let violations: Vec<String> = [
+        (num_missing, "missing a license header"),
+        (denied.len(), "with a license denied by the license policy"),
+        (modified.len(), "with a modified license header"),
+        (
+            wrong_format.len(),
+            "with a license header in the wrong format",
+        ),
+        (outdated.len(), "with an outdated copyright year"),
+    ]
+    .into_iter()
+    .filter(|(count, _)| *count > 0)
+    .map(|(count, violation)| format!("{count} file(s) {violation}"))
+    .collect();
+
+    if !violations.is_empty() {
+        return Err(anyhow!(violations.join(", ")));
+    }
+
+    Ok(())
+}
+
+/// Prints the number of violating files per file extension, most frequent first.
+///
+/// As in coverage reports, scripts without a known extension are counted by the
+/// extension of their hash-bang interpreter, looked up in `interpreters`.
+fn print_violations_by_extension<'a, I>(
+    paths: I,
+    interpreters: &HashMap<PathBuf, String>,
+    file_types: &FileTypes,
+) where
+    I: Iterator<Item = &'a PathBuf>,
+{
+    let mut counts = BTreeMap::<String, usize>::new();
+    for path in paths {
+        let interpreter = interpreters.get(path).map(String::as_str);
+        let extension = get_header_lookup_name_with_interpreter(path, interpreter, file_types);
+        *counts.entry(extension).or_default() += 1;
+    }
+    if counts.is_empty() {
+        return;
+    }
+
+    let mut counts: Vec<(String, usize)> = counts.into_iter().collect();
+    counts.sort_by(|(_, a), (_, b)| b.cmp(a));
+    let width = counts
+        .iter()
+        .map(|(suffix, _)| suffix.len())
+        .max()
+        .unwrap_or(0);
+    println!("\n{}", "Violations by extension".bold());
+    for (suffix, count) in counts {
+        println!("  {suffix:<width$}  {count:>6}");
+    }
+}
+
+/// Prints the number of violating files per top-level directory of the workspace,
+/// by kind of violation, most violations first.
+///
+/// Files at the root of the workspace are counted as `./`.
+fn print_violations_by_directory(workspace_root: &Path, violations: &[(&str, Vec<&PathBuf>)]) {
+    let mut counts = BTreeMap::<String, Vec<usize>>::new();
+    for (kind, (_, paths)) in violations.iter().enumerate() {
+        for path in paths {
+            let relative = path.strip_prefix(workspace_root).unwrap_or(path);
+            let mut components = relative.components();
+            let dir = match (components.next(), components.next()) {
+                (Some(dir), Some(_)) => format!("{}/", dir.as_os_str().to_string_lossy()),
+                _ => "./".to_string(),
+            };
+            counts
+                .entry(dir)
+                .or_insert_with(|| vec![0; violations.len()])[kind] += 1;
+        }
+    }
+    if counts.is_empty() {
+        return;
+    }
 
+    let mut counts: Vec<(String, Vec<usize>)> = counts.into_iter().collect();
+    counts.sort_by_key(|(_, kinds)| std::cmp::Reverse(kinds.iter().sum::<usize>()));
+    let width = counts.iter().map(|(dir, _)| dir.len()).max().unwrap_or(0);
+    println!("\n{}", "Violations by directory".bold());
+    for (dir, kinds) in counts {
+        let summary: Vec<String> = kinds
+            .iter()
+            .zip(violations)
+            .filter(|(count, _)| **count > 0)
+            .map(|(count, (kind, _))| format!("{count} {kind}"))
+            .collect();
+        println!("  {dir:<width$}  {}", summary.join(", "));
+    }
+}
+
+/// How license headers are compared with the header `apply` would write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HeaderComparison {
+    /// Compare fingerprints, as done by `--strict-origin`.
+    Fingerprint,
+
+    /// Compare words, ignoring all whitespace, as done by `--strict`.
+    Words,
+}
+
+/// Checks whether the license header of the file at `path` is the header `apply` would write.
+///
+/// Only the leading `read_window` bytes of the file, plus the length of the header, are read.
+/// Files that can't be read or have no comment style or UTF-8 content are considered managed.
+/// Results are cached in `verify_cache` by the hash of the read content and the fingerprint
+/// of the header.
+fn has_managed_header(
+    config: &LicensaWorkspace,
+    notices: &Notices,
+    path: &Path,
+    read_window: usize,
+    comparison: HeaderComparison,
+    verify_cache: Option<&Mutex<VerifyCache>>,
+) -> bool {
+    let Ok(head) = read_head(path, read_window) else {
+        return true;
+    };
+    let lookup_name = get_header_lookup_name(path, &head, &config.file_types);
+    let Ok(Some(header)) = license_header(config, notices, &lookup_name) else {
+        return true;
+    };
+
+    // The header starts within the read window, but may extend past it
+    let Ok(content) = read_head(path, read_window + header.len()) else {
+        return true;
+    };
+    let text = match std::str::from_utf8(&content) {
+        Ok(text) => text,
+        // The window may end within a multi-byte character
+        Err(err) if err.error_len().is_none() => {
+            std::str::from_utf8(&content[..err.valid_up_to()]).unwrap_or_default()
+        }
+        Err(_) => return true,
+    };
+
+    // The placement determines where the header is expected
+    let placement = config.placement.unwrap_or_default();
+    let template_fingerprint = fingerprint(&format!("{comparison:?}\n{placement:?}\n{header}"));
+    let content_hash = verify_cache.map(|_| sha256_hex(&content));
+    if let Some((cache, content_hash)) = verify_cache.zip(content_hash.as_ref()) {
+        if let Some(managed) = cache
+            .lock()
+            .unwrap()
+            .get(content_hash, &template_fingerprint)
+        {
+            return managed;
+        }
+    }
+
+    let start = license_header_offset(config, &lookup_name, text);
+    let block = find_header_block(text, start, &header);
+    let managed = match comparison {
+        HeaderComparison::Fingerprint => fingerprint(block) == fingerprint(&header),
+        HeaderComparison::Words => matches_ignoring_whitespace(block, &header),
+    };
+    if let Some((cache, content_hash)) = verify_cache.zip(content_hash.as_ref()) {
+        cache
+            .lock()
+            .unwrap()
+            .insert(content_hash, &template_fingerprint, managed);
+    }
+    managed
+}
+
+/// Prints a diff of the license header `apply` would insert into the file at `path`.
+fn print_diff(
+    config: &LicensaWorkspace,
+    notices: &Notices,
+    path: &Path,
+    display_path: &Path,
+) -> Result<()> {
+    let content = fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+    let lookup_name = get_header_lookup_name(path, &content, &config.file_types);
+    let Some(licensed) = license_content(config, notices, &lookup_name, &content)? else {
+        return Ok(());
+    };
+
+    // Both are valid UTF-8, as headers are only inserted into text files
+    let old = String::from_utf8_lossy(&content);
+    let new = String::from_utf8_lossy(&licensed);
+    let display_path = utils::display_path(display_path);
+    print!(
+        "{}",
+        colorize_diff(&unified_diff(&display_path, &old, &new))
+    );
     Ok(())
 }
+
+/// Prints `violation` of the file at `path` in the output `format`, or adds it to the
+/// `junit` report.
+///
+/// `path` is given along with its display path, relative to the workspace root.
+fn emit_violation(
+    format: VerifyFormat,
+    junit: &mut JunitReport,
+    config: &Config,
+    file_types: &FileTypes,
+    path: &Path,
+    display_path: &Path,
+    violation: &Violation,
+) {
+    match format {
+        VerifyFormat::Text => print_task_violation(display_path, violation),
+        VerifyFormat::Github => println!(
+            "{}",
+            error_annotation(
+                &utils::display_path(display_path),
+                &violation_message(config, file_types, path, violation)
+            )
+        ),
+        VerifyFormat::Junit => junit.add(
+            utils::display_path(display_path),
+            TestOutcome::Failed(violation_message(config, file_types, path, violation)),
+        ),
+    }
+}
+
+/// Returns the message of `violation` of the file at `path`, e.g. `Missing license header (MIT)`.
+///
+/// Messages of missing headers name the license expected for the file, if any. The license
+/// is selected by the same lookup name as the header `apply` writes, so that scripts are
+/// identified by their hash-bang line.
+fn violation_message(
+    config: &Config,
+    file_types: &FileTypes,
+    path: &Path,
+    violation: &Violation,
+) -> String {
+    let mut message = violation.to_string();
+    message[..1].make_ascii_uppercase();
+    if *violation != Violation::Missing {
+        return message;
+    }
+
+    let read_window = config.read_window.unwrap_or(DEFAULT_READ_WINDOW);
+    let head = read_head(path, read_window).unwrap_or_default();
+    let lookup_name = get_header_lookup_name(path, &head, file_types);
+    let license =
+        FileLicense::find(&config.file_licenses, &lookup_name).or(config.license.as_ref());
+    match license {
+        Some(license) => format!("{message} ({license})"),
+        None => message,
+    }
+}
+
+fn print_task_violation<P>(path: P, violation: &Violation)
+where
+    P: AsRef<Path>,
+{
+    let (status, result_type) = match violation {
+        Violation::Missing => ("missing", "missing".to_string()),
+        Violation::Modified => ("modified", "modified".to_string()),
+        Violation::WrongFormat => ("wrong_format", "wrong format".to_string()),
+        Violation::Outdated(year) => ("outdated", format!("outdated ({year})")),
+        Violation::Denied(license) => ("denied", format!("denied ({license})")),
+    };
+    let result_type = result_type.red();
+    let path = utils::display_path(path);
+    error!(%path, status, "verify {path} ... {result_type}")
+}
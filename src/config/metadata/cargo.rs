@@ -0,0 +1,207 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Metadata of Rust packages.
+//!
+//! Reads `package.authors`, `package.license` and `package.name` from
+//! `Cargo.toml`. Fields inherited with `{ workspace = true }` are resolved
+//! from the `[workspace.package]` table of the enclosing Cargo workspace.
+
+use super::{join_authors, MetadataProvider, MetadataSource, ProjectMetadata};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use std::fs;
+use std::path::Path;
+
+const CARGO_TOML: &str = "Cargo.toml";
+
+/// Reads project metadata from `Cargo.toml`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CargoMetadata;
+
+#[derive(Debug, Deserialize)]
+struct Manifest {
+    package: Option<Package>,
+    workspace: Option<Workspace>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Package {
+    name: Option<String>,
+    authors: Option<Field<Vec<String>>>,
+    license: Option<Field<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Workspace {
+    package: Option<Package>,
+}
+
+/// A manifest field that is either set explicitly or inherited from the workspace.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Field<T> {
+    Value(T),
+    Inherited { workspace: bool },
+}
+
+impl<T> Field<T> {
+    fn resolve(
+        self,
+        workspace: Option<&mut Package>,
+        take: fn(&mut Package) -> Option<Field<T>>,
+    ) -> Option<T> {
+        match self {
+            Field::Value(value) => Some(value),
+            Field::Inherited { workspace: true } => match workspace.and_then(take)? {
+                Field::Value(value) => Some(value),
+                Field::Inherited { .. } => None,
+            },
+            Field::Inherited { .. } => None,
+        }
+    }
+}
+
+impl MetadataProvider for CargoMetadata {
+    fn read(&self, dir: &Path) -> Result<Option<MetadataSource>> {
+        let path = dir.join(CARGO_TOML);
+        if !path.is_file() {
+            return Ok(None);
+        }
+
+        let manifest = read_manifest(&path)?;
+        let own_workspace = manifest.workspace.and_then(|ws| ws.package);
+        let Some(package) = manifest.package else {
+            // Virtual manifest of a Cargo workspace
+            let package = own_workspace.unwrap_or_default();
+            return Ok(Some(MetadataSource {
+                metadata: metadata(package, None),
+                path,
+            }));
+        };
+
+        let mut workspace_package = match own_workspace {
+            Some(package) => Some(package),
+            None => find_workspace_package(dir)?,
+        };
+
+        Ok(Some(MetadataSource {
+            metadata: metadata(package, workspace_package.as_mut()),
+            path,
+        }))
+    }
+}
+
+fn read_manifest(path: &Path) -> Result<Manifest> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    toml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// Finds the `[workspace.package]` table of the nearest Cargo workspace above `dir`.
+fn find_workspace_package(dir: &Path) -> Result<Option<Package>> {
+    for ancestor in dir.ancestors().skip(1) {
+        let path = ancestor.join(CARGO_TOML);
+        if !path.is_file() {
+            continue;
+        }
+        if let Some(workspace) = read_manifest(&path)?.workspace {
+            return Ok(workspace.package);
+        }
+    }
+
+    Ok(None)
+}
+
+fn metadata(package: Package, mut workspace: Option<&mut Package>) -> ProjectMetadata {
+    let authors = package
+        .authors
+        .and_then(|field| field.resolve(workspace.as_deref_mut(), |ws| ws.authors.take()));
+    let license = package
+        .license
+        .and_then(|field| field.resolve(workspace, |ws| ws.license.take()));
+
+    ProjectMetadata {
+        owner: authors.and_then(|authors| join_authors(authors.iter().map(String::as_str))),
+        license,
+        name: package.name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    fn read(dir: &Path) -> ProjectMetadata {
+        CargoMetadata.read(dir).unwrap().unwrap().metadata
+    }
+
+    #[test]
+    fn test_read_package_metadata() {
+        let tmp_dir = tempdir().unwrap();
+        fs::write(
+            tmp_dir.path().join(CARGO_TOML),
+            r#"
+[package]
+name = "example"
+authors = ["Jane Doe <jane@example.com>", "John Doe"]
+license = "MIT OR Apache-2.0"
+"#,
+        )
+        .unwrap();
+
+        let metadata = read(tmp_dir.path());
+        assert_eq!(metadata.name.as_deref(), Some("example"));
+        assert_eq!(metadata.owner.as_deref(), Some("Jane Doe, John Doe"));
+        assert_eq!(metadata.license.as_deref(), Some("MIT OR Apache-2.0"));
+    }
+
+    #[test]
+    fn test_read_inherited_workspace_metadata() {
+        let tmp_dir = tempdir().unwrap();
+        let root = tmp_dir.path();
+        let member = root.join("crates/core");
+        fs::create_dir_all(&member).unwrap();
+        fs::write(
+            root.join(CARGO_TOML),
+            r#"
+[workspace]
+members = ["crates/*"]
+
+[workspace.package]
+authors = ["Jane Doe"]
+license = "Apache-2.0"
+"#,
+        )
+        .unwrap();
+        fs::write(
+            member.join(CARGO_TOML),
+            r#"
+[package]
+name = "core"
+authors.workspace = true
+license = { workspace = true }
+"#,
+        )
+        .unwrap();
+
+        let metadata = read(&member);
+        assert_eq!(metadata.owner.as_deref(), Some("Jane Doe"));
+        assert_eq!(metadata.license.as_deref(), Some("Apache-2.0"));
+
+        // Virtual manifest
+        let metadata = read(root);
+        assert_eq!(metadata.owner.as_deref(), Some("Jane Doe"));
+        assert_eq!(metadata.license.as_deref(), Some("Apache-2.0"));
+    }
+
+    #[test]
+    fn test_read_without_manifest() {
+        let tmp_dir = tempdir().unwrap();
+        assert!(CargoMetadata.read(tmp_dir.path()).unwrap().is_none());
+    }
+}
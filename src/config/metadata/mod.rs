@@ -0,0 +1,176 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Project metadata used as fallback for missing config fields.
+//!
+//! When neither the `.licensarc` file nor the command arguments provide an
+//! `owner`, `license` or `project`, these values are inferred from the manifest
+//! of the nearest project, e.g. `package.authors`, `package.license` and
+//! `package.name` of a `Cargo.toml` file, or `author`, `license` and `name` of
+//! a `package.json` file.
+//!
+//! Each manifest format is handled by a [`MetadataProvider`]. Providers are
+//! queried for every directory from the start directory up to the filesystem
+//! root; the first manifest found wins.
+
+pub mod cargo;
+pub mod npm;
+pub mod python;
+
+use anyhow::Result;
+use tracing::debug;
+
+use std::path::{Path, PathBuf};
+
+/// Metadata declared in a project manifest.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProjectMetadata {
+    /// The copyright owner, e.g. the authors of a package.
+    pub owner: Option<String>,
+
+    /// The SPDX license expression of the project.
+    pub license: Option<String>,
+
+    /// The name of the project.
+    pub name: Option<String>,
+}
+
+impl ProjectMetadata {
+    /// Returns `true` if neither an owner, a license nor a name is declared.
+    pub fn is_empty(&self) -> bool {
+        self.owner.is_none() && self.license.is_none() && self.name.is_none()
+    }
+}
+
+/// Project metadata along with the manifest it was read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MetadataSource {
+    pub metadata: ProjectMetadata,
+    pub path: PathBuf,
+}
+
+/// A source of project metadata, e.g. a package manifest.
+pub trait MetadataProvider: Send + Sync {
+    /// Reads the metadata of the project located in `dir`.
+    ///
+    /// Returns `Ok(None)` if `dir` contains no manifest known to this provider.
+    fn read(&self, dir: &Path) -> Result<Option<MetadataSource>>;
+}
+
+/// Returns the built-in metadata providers in order of precedence.
+pub fn default_providers() -> Vec<Box<dyn MetadataProvider>> {
+    vec![
+        Box::new(cargo::CargoMetadata),
+        Box::new(npm::NpmMetadata),
+        Box::new(python::PythonMetadata),
+    ]
+}
+
+/// Finds the metadata of the project nearest to `start`.
+///
+/// Manifests that fail to parse are skipped.
+pub fn find_metadata<P>(start: P, providers: &[Box<dyn MetadataProvider>]) -> Option<MetadataSource>
+where
+    P: AsRef<Path>,
+{
+    for dir in start.as_ref().ancestors() {
+        for provider in providers {
+            match provider.read(dir) {
+                Ok(Some(source)) if !source.metadata.is_empty() => return Some(source),
+                Ok(_) => {}
+                Err(err) => debug!("skip metadata in {}: {err:#}", dir.display()),
+            }
+        }
+    }
+
+    None
+}
+
+/// Returns the name of a person without a trailing `<email>` or `(url)`.
+///
+/// Authors are commonly listed as `Name <email> (url)` in package manifests.
+pub(crate) fn strip_contact(person: &str) -> &str {
+    let end = person.find(['<', '(']).unwrap_or(person.len());
+    person[..end].trim()
+}
+
+/// Joins the names of multiple authors into a single copyright owner.
+pub(crate) fn join_authors<'a, I>(authors: I) -> Option<String>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let names = authors
+        .into_iter()
+        .map(strip_contact)
+        .filter(|name| !name.is_empty())
+        .collect::<Vec<_>>();
+
+    (!names.is_empty()).then(|| names.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_strip_contact() {
+        assert_eq!(strip_contact("Jane Doe <jane@example.com>"), "Jane Doe");
+        assert_eq!(strip_contact("Jane Doe (https://example.com)"), "Jane Doe");
+        assert_eq!(strip_contact("  Jane Doe  "), "Jane Doe");
+        assert_eq!(strip_contact("<jane@example.com>"), "");
+    }
+
+    #[test]
+    fn test_join_authors() {
+        assert_eq!(
+            join_authors(["Jane Doe <jane@example.com>", "John Doe"]),
+            Some("Jane Doe, John Doe".to_string())
+        );
+        assert_eq!(join_authors(["<jane@example.com>"]), None);
+        assert_eq!(join_authors([]), None);
+    }
+
+    #[test]
+    fn test_find_metadata_uses_nearest_manifest() {
+        let tmp_dir = tempdir().unwrap();
+        let root = tmp_dir.path();
+        let nested = root.join("crates/core/src");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(
+            root.join("Cargo.toml"),
+            "[package]\nname = \"root\"\nlicense = \"MIT\"\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("crates/core/Cargo.toml"),
+            "[package]\nname = \"core\"\nlicense = \"Apache-2.0\"\n",
+        )
+        .unwrap();
+
+        let source = find_metadata(&nested, &default_providers()).unwrap();
+        assert_eq!(source.path, root.join("crates/core/Cargo.toml"));
+        assert_eq!(source.metadata.license.as_deref(), Some("Apache-2.0"));
+    }
+
+    #[test]
+    fn test_find_metadata_skips_invalid_manifest() {
+        let tmp_dir = tempdir().unwrap();
+        let root = tmp_dir.path();
+        let nested = root.join("nested");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(root.join("Cargo.toml"), "[package]\nlicense = \"MIT\"\n").unwrap();
+        fs::write(nested.join("Cargo.toml"), "[package\n").unwrap();
+
+        let source = find_metadata(&nested, &default_providers()).unwrap();
+        assert_eq!(source.path, root.join("Cargo.toml"));
+    }
+
+    #[test]
+    fn test_find_metadata_without_manifest() {
+        let tmp_dir = tempdir().unwrap();
+        assert!(find_metadata(tmp_dir.path(), &default_providers()).is_none());
+    }
+}
@@ -0,0 +1,131 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Metadata of Node.js packages.
+//!
+//! Reads `author`, `license` and `name` from `package.json`. The author may be
+//! given as a `Name <email> (url)` string or as an object with a `name` field.
+
+use super::{strip_contact, MetadataProvider, MetadataSource, ProjectMetadata};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use std::fs;
+use std::path::Path;
+
+const PACKAGE_JSON: &str = "package.json";
+
+/// Reads project metadata from `package.json`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NpmMetadata;
+
+#[derive(Debug, Deserialize)]
+struct PackageJson {
+    name: Option<String>,
+    author: Option<Person>,
+    license: Option<License>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Person {
+    Name(String),
+    Object { name: Option<String> },
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum License {
+    Expression(String),
+    /// Deprecated `{ "type": "MIT", "url": "..." }` form
+    Object {
+        #[serde(rename = "type")]
+        kind: Option<String>,
+    },
+}
+
+impl MetadataProvider for NpmMetadata {
+    fn read(&self, dir: &Path) -> Result<Option<MetadataSource>> {
+        let path = dir.join(PACKAGE_JSON);
+        if !path.is_file() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let package: PackageJson = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+
+        let owner = package
+            .author
+            .and_then(|author| match author {
+                Person::Name(name) => Some(strip_contact(&name).to_string()),
+                Person::Object { name } => name.map(|name| name.trim().to_string()),
+            })
+            .filter(|owner| !owner.is_empty());
+        let license = package
+            .license
+            .and_then(|license| match license {
+                License::Expression(expression) => Some(expression),
+                License::Object { kind } => kind,
+            })
+            // `UNLICENSED` marks proprietary packages and is no SPDX expression
+            .filter(|license| license != "UNLICENSED" && !license.starts_with("SEE LICENSE IN"));
+
+        Ok(Some(MetadataSource {
+            metadata: ProjectMetadata {
+                owner,
+                license,
+                name: package.name,
+            },
+            path,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    fn read(content: &str) -> ProjectMetadata {
+        let tmp_dir = tempdir().unwrap();
+        fs::write(tmp_dir.path().join(PACKAGE_JSON), content).unwrap();
+        NpmMetadata.read(tmp_dir.path()).unwrap().unwrap().metadata
+    }
+
+    #[test]
+    fn test_read_author_string() {
+        let metadata = read(
+            r#"{
+                "name": "example",
+                "author": "Jane Doe <jane@example.com> (https://example.com)",
+                "license": "MIT"
+            }"#,
+        );
+        assert_eq!(metadata.name.as_deref(), Some("example"));
+        assert_eq!(metadata.owner.as_deref(), Some("Jane Doe"));
+        assert_eq!(metadata.license.as_deref(), Some("MIT"));
+    }
+
+    #[test]
+    fn test_read_author_object() {
+        let metadata = read(
+            r#"{
+                "author": { "name": "Jane Doe", "email": "jane@example.com" },
+                "license": { "type": "ISC", "url": "https://opensource.org/licenses/ISC" }
+            }"#,
+        );
+        assert_eq!(metadata.owner.as_deref(), Some("Jane Doe"));
+        assert_eq!(metadata.license.as_deref(), Some("ISC"));
+    }
+
+    #[test]
+    fn test_read_unlicensed() {
+        let metadata = read(r#"{ "license": "UNLICENSED" }"#);
+        assert_eq!(metadata.license, None);
+        assert!(metadata.is_empty());
+    }
+}
@@ -0,0 +1,151 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Metadata of Python projects.
+//!
+//! Reads the authors, license and name of a project from `pyproject.toml`,
+//! supporting both PEP 621 (`[project]`) and Poetry (`[tool.poetry]`) layouts.
+//! Fields of the `[project]` table take precedence.
+
+use super::{join_authors, MetadataProvider, MetadataSource, ProjectMetadata};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use std::fs;
+use std::path::Path;
+
+const PYPROJECT_TOML: &str = "pyproject.toml";
+
+/// Reads project metadata from `pyproject.toml`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PythonMetadata;
+
+#[derive(Debug, Deserialize)]
+struct PyProject {
+    project: Option<Project>,
+    tool: Option<Tool>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Tool {
+    poetry: Option<Poetry>,
+}
+
+/// The PEP 621 `[project]` table.
+#[derive(Debug, Deserialize)]
+struct Project {
+    name: Option<String>,
+    #[serde(default)]
+    authors: Vec<Author>,
+    license: Option<License>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Author {
+    name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum License {
+    /// PEP 639 SPDX expression
+    Expression(String),
+    /// Legacy `{ text = "MIT" }` or `{ file = "LICENSE" }` table
+    Table { text: Option<String> },
+}
+
+/// The `[tool.poetry]` table.
+#[derive(Debug, Deserialize)]
+struct Poetry {
+    name: Option<String>,
+    #[serde(default)]
+    authors: Vec<String>,
+    license: Option<String>,
+}
+
+impl MetadataProvider for PythonMetadata {
+    fn read(&self, dir: &Path) -> Result<Option<MetadataSource>> {
+        let path = dir.join(PYPROJECT_TOML);
+        if !path.is_file() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let pyproject: PyProject = toml::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+
+        let mut metadata = ProjectMetadata::default();
+        if let Some(project) = pyproject.project {
+            metadata.owner = join_authors(project.authors.iter().filter_map(|a| a.name.as_deref()));
+            metadata.license = project.license.and_then(|license| match license {
+                License::Expression(expression) => Some(expression),
+                License::Table { text } => text,
+            });
+            metadata.name = project.name;
+        }
+        if let Some(poetry) = pyproject.tool.and_then(|tool| tool.poetry) {
+            metadata.owner = metadata
+                .owner
+                .or_else(|| join_authors(poetry.authors.iter().map(String::as_str)));
+            metadata.license = metadata.license.or(poetry.license);
+            metadata.name = metadata.name.or(poetry.name);
+        }
+
+        Ok(Some(MetadataSource { metadata, path }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tempfile::tempdir;
+
+    fn read(content: &str) -> ProjectMetadata {
+        let tmp_dir = tempdir().unwrap();
+        fs::write(tmp_dir.path().join(PYPROJECT_TOML), content).unwrap();
+        PythonMetadata
+            .read(tmp_dir.path())
+            .unwrap()
+            .unwrap()
+            .metadata
+    }
+
+    #[test]
+    fn test_read_pep621_metadata() {
+        let metadata = read(
+            r#"
+[project]
+name = "example"
+authors = [{ name = "Jane Doe", email = "jane@example.com" }, { email = "bot@example.com" }]
+license = "BSD-3-Clause"
+"#,
+        );
+        assert_eq!(metadata.name.as_deref(), Some("example"));
+        assert_eq!(metadata.owner.as_deref(), Some("Jane Doe"));
+        assert_eq!(metadata.license.as_deref(), Some("BSD-3-Clause"));
+
+        let metadata = read("[project]\nlicense = { text = \"MIT\" }\n");
+        assert_eq!(metadata.license.as_deref(), Some("MIT"));
+
+        let metadata = read("[project]\nlicense = { file = \"LICENSE\" }\n");
+        assert_eq!(metadata.license, None);
+    }
+
+    #[test]
+    fn test_read_poetry_metadata() {
+        let metadata = read(
+            r#"
+[tool.poetry]
+name = "example"
+authors = ["Jane Doe <jane@example.com>"]
+license = "MIT"
+"#,
+        );
+        assert_eq!(metadata.name.as_deref(), Some("example"));
+        assert_eq!(metadata.owner.as_deref(), Some("Jane Doe"));
+        assert_eq!(metadata.license.as_deref(), Some("MIT"));
+    }
+}
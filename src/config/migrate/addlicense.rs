@@ -0,0 +1,168 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Command line arguments of google/addlicense.
+//!
+//! addlicense has no config file; it's configured on the command line, e.g.
+//! `addlicense -c "ACME Inc." -l mit -ignore "vendor/**" .`. Flags follow the
+//! conventions of Go's `flag` package: one or two leading dashes, with values
+//! either following the flag or attached with `=`.
+
+use super::{to_ignore_pattern, Migration};
+use crate::parser::parse_license_year;
+use crate::schema::LicenseId;
+use crate::template::notice::LicenseNoticeFormat;
+
+use anyhow::{anyhow, Result};
+
+use std::str::FromStr;
+
+/// The copyright holder addlicense uses if `-c` is missing.
+const DEFAULT_HOLDER: &str = "Google LLC";
+
+/// Flags that take no value.
+const BOOL_FLAGS: &[&str] = &["s", "check", "v"];
+
+/// Converts the arguments `args` of an addlicense invocation.
+///
+/// The leading `addlicense` program name is optional.
+pub fn migrate<S>(args: &[S]) -> Result<Migration>
+where
+    S: AsRef<str>,
+{
+    let mut args = args.iter().map(AsRef::as_ref).peekable();
+    args.next_if_eq(&"addlicense");
+
+    let mut migration = Migration::default();
+    let mut holder = None;
+    let mut license = None;
+    let mut spdx = None;
+    let mut paths = Vec::new();
+
+    while let Some(arg) = args.next() {
+        let Some(flag) = arg.strip_prefix("--").or_else(|| arg.strip_prefix('-')) else {
+            paths.push(arg);
+            continue;
+        };
+        if flag.is_empty() {
+            // `--` terminates the flags
+            paths.extend(args.by_ref());
+            break;
+        }
+
+        let (name, value) = match flag.split_once('=') {
+            Some((name, value)) => (name, Some(value)),
+            None if BOOL_FLAGS.contains(&flag) => (flag, None),
+            None => {
+                let value = args
+                    .next()
+                    .ok_or_else(|| anyhow!("flag needs an argument: -{flag}"))?;
+                (flag, Some(value))
+            }
+        };
+        let value = value.unwrap_or_default();
+
+        match name {
+            "c" => holder = Some(value.to_string()),
+            "l" => license = Some(value),
+            "y" => match parse_license_year(value) {
+                Ok(year) => migration.config.year = Some(year),
+                Err(_) => migration
+                    .warnings
+                    .push(format!("copyright year `{value}` is not a year or range of years")),
+            },
+            "s" => spdx = Some(value),
+            "ignore" => migration.ignore.push(to_ignore_pattern(value)),
+            "skip" => migration
+                .ignore
+                .push(format!("*.{}", value.trim_start_matches('.'))),
+            "f" => migration.warnings.push(format!(
+                "custom license file `{value}` is not supported, use `format` and `compactSentence` instead"
+            )),
+            // Mode and verbosity flags have no config equivalent
+            "check" | "v" => {}
+            _ => return Err(anyhow!("unknown addlicense flag: -{name}")),
+        }
+    }
+
+    migration.config.owner = Some(holder.unwrap_or_else(|| DEFAULT_HOLDER.to_string()));
+    migration.config.license = Some(license_id(license.unwrap_or("apache"))?);
+    migration.config.format = Some(match spdx {
+        // `-s=only` writes the SPDX identifier without the license notice
+        Some("" | "true" | "only") => LicenseNoticeFormat::Spdx,
+        _ => LicenseNoticeFormat::Full,
+    });
+
+    let paths: Vec<&str> = paths.into_iter().filter(|path| *path != ".").collect();
+    if !paths.is_empty() {
+        migration.warnings.push(format!(
+            "paths {} are not migrated, Licensa checks the entire workspace",
+            paths.join(", ")
+        ));
+    }
+
+    Ok(migration)
+}
+
+/// Returns the SPDX license ID of an addlicense `-l` value.
+///
+/// addlicense accepts a few short names besides SPDX license IDs.
+fn license_id(license: &str) -> Result<LicenseId> {
+    let id = match license.to_lowercase().as_str() {
+        "apache" => "Apache-2.0",
+        "bsd" => "BSD-3-Clause",
+        "mit" => "MIT",
+        "mpl" => "MPL-2.0",
+        _ => license,
+    };
+    LicenseId::from_str(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrate() {
+        let migration = migrate(&[
+            "addlicense",
+            "-c",
+            "ACME Inc.",
+            "-l=mit",
+            "-y",
+            "2020-2024",
+            "-s=only",
+            "--ignore",
+            "vendor/**",
+            "-ignore=**/*.pb.go",
+            "-check",
+            ".",
+        ])
+        .unwrap();
+
+        let config = migration.config;
+        assert_eq!(config.owner.as_deref(), Some("ACME Inc."));
+        assert_eq!(config.license.as_deref(), Some("MIT"));
+        assert_eq!(config.year.unwrap().to_string(), "2020-2024");
+        assert_eq!(config.format, Some(LicenseNoticeFormat::Spdx));
+        assert_eq!(migration.ignore, vec!["/vendor/**", "**/*.pb.go"]);
+        assert!(migration.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_migrate_defaults() {
+        let migration = migrate(&["-f", "header.tpl", "src"]).unwrap();
+
+        let config = migration.config;
+        assert_eq!(config.owner.as_deref(), Some(DEFAULT_HOLDER));
+        assert_eq!(config.license.as_deref(), Some("Apache-2.0"));
+        assert_eq!(config.format, Some(LicenseNoticeFormat::Full));
+        assert_eq!(migration.warnings.len(), 2);
+    }
+
+    #[test]
+    fn test_migrate_invalid_flags() {
+        assert!(migrate(&["-unknown"]).is_err());
+        assert!(migrate(&["-c"]).is_err());
+    }
+}
@@ -0,0 +1,350 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! `.licenserc.yaml` files of Apache SkyWalking Eyes (`license-eye`).
+//!
+//! Only the `header` section is converted. The `header` section may be a single
+//! header config or a list of them; of a list, only the first entry is converted.
+//!
+//! Custom header `content` consisting of a copyright line followed by a single
+//! paragraph is converted into the `compact` notice format, with the license-eye
+//! placeholders `[year]`, `[owner]` and `[software-name]` replaced by variables.
+//! Custom comment styles are checked against the built-in comment styles of Licensa.
+
+use super::{to_ignore_pattern, Migration};
+use crate::parser::parse_license_year;
+use crate::schema::LicenseId;
+use crate::template::header::{FileTypes, SourceHeaders};
+use crate::template::notice::LicenseNoticeFormat;
+
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+/// The default filename of license-eye config files.
+pub const LICENSE_EYE_CONFIG_FILENAME: &str = ".licenserc.yaml";
+
+#[derive(Debug, Default, Deserialize)]
+struct LicenseEyeConfig {
+    header: Option<Headers>,
+    dependency: Option<serde_yaml::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Headers {
+    One(Box<Header>),
+    Many(Vec<Header>),
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+struct Header {
+    license: HeaderLicense,
+    paths: Vec<String>,
+    paths_ignore: Vec<String>,
+    language: BTreeMap<String, Language>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case", default)]
+struct HeaderLicense {
+    spdx_id: Option<String>,
+    copyright_owner: Option<String>,
+    copyright_year: Option<serde_yaml::Value>,
+    software_name: Option<String>,
+    content: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Language {
+    extensions: Vec<String>,
+    filenames: Vec<String>,
+    comment_style_id: Option<String>,
+}
+
+/// Comment styles of license-eye along with the comment token they start with.
+const COMMENT_STYLES: &[(&str, &str)] = &[
+    ("AngleBracket", "<!--"),
+    ("CurlyBracketHashtag", "{#"),
+    ("DoubleDash", "--"),
+    ("DoubleSlash", "//"),
+    ("Hashtag", "#"),
+    ("PercentSign", "%"),
+    ("RoundBracketAsterisk", "(*"),
+    ("SemiColon", ";"),
+    ("SlashAsterisk", "/*"),
+];
+
+/// Variable the `[software-name]` placeholder of header content is converted into.
+const SOFTWARE_NAME_VARIABLE: &str = "softwareName";
+
+/// Reads and converts the license-eye config file at `path`.
+pub fn migrate_file<P>(path: P) -> Result<Migration>
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("failed to read license-eye config file {}", path.display()))?;
+    migrate(&content)
+        .with_context(|| format!("failed to parse license-eye config file {}", path.display()))
+}
+
+/// Converts the content of a license-eye config file.
+pub fn migrate(content: &str) -> Result<Migration> {
+    let config: LicenseEyeConfig = serde_yaml::from_str(content)?;
+    let mut migration = Migration::default();
+
+    if config.dependency.is_some() {
+        migration.warnings.push(
+            "the `dependency` section is not migrated, use `licensa deps` and the `policy` config instead"
+                .into(),
+        );
+    }
+
+    let header = match config.header {
+        Some(Headers::One(header)) => *header,
+        Some(Headers::Many(headers)) => {
+            if headers.len() > 1 {
+                migration.warnings.push(format!(
+                    "only the first of {} `header` sections is migrated",
+                    headers.len()
+                ));
+            }
+            headers.into_iter().next().unwrap_or_default()
+        }
+        None => return Err(anyhow!("missing `header` section")),
+    };
+
+    let license = header.license;
+    if let Some(spdx_id) = license.spdx_id.as_deref() {
+        migration.config.license = Some(LicenseId::from_str(spdx_id)?);
+    }
+    migration.config.owner = license.copyright_owner;
+    if let Some(year) = license.copyright_year.as_ref() {
+        let year = match year {
+            serde_yaml::Value::Number(year) => year.to_string(),
+            serde_yaml::Value::String(year) => year.to_owned(),
+            _ => return Err(anyhow!("invalid `copyright-year`, expected a year")),
+        };
+        match parse_license_year(&year) {
+            Ok(year) => migration.config.year = Some(year),
+            Err(_) => migration.warnings.push(format!(
+                "copyright year `{year}` is not a year or range of years"
+            )),
+        }
+    }
+    match license.content.as_deref() {
+        Some(content) => match convert_content(content) {
+            Some((format, sentence)) => {
+                migration.config.format = Some(format);
+                migration.config.compact_sentence = sentence;
+                if content.contains("[software-name]") {
+                    migration.config.variables.insert(
+                        SOFTWARE_NAME_VARIABLE.into(),
+                        license.software_name.unwrap_or_default(),
+                    );
+                }
+            }
+            None => {
+                migration.config.format = Some(LicenseNoticeFormat::Full);
+                migration.warnings.push(
+                    "custom header `content` is not supported, the `full` notice of the license is used instead"
+                        .into(),
+                );
+            }
+        },
+        // Without custom content, license-eye writes the standard license header
+        None => migration.config.format = Some(LicenseNoticeFormat::Full),
+    }
+
+    if header.paths.iter().any(|path| path != "**") {
+        migration.warnings.push(format!(
+            "`paths` {} are not migrated, Licensa checks the entire workspace",
+            header.paths.join(", ")
+        ));
+    }
+    migration.ignore = header
+        .paths_ignore
+        .iter()
+        .map(|path| to_ignore_pattern(path))
+        .collect();
+
+    for (name, language) in header.language.iter() {
+        if let Some(warning) = check_comment_style(name, language) {
+            migration.warnings.push(warning);
+        }
+    }
+
+    Ok(migration)
+}
+
+/// Converts custom header `content` into the notice format rendering it, along
+/// with the sentence of the `compact` format, if needed.
+///
+/// Returns `None` if no notice format renders the content.
+fn convert_content(content: &str) -> Option<(LicenseNoticeFormat, Option<String>)> {
+    match (
+        content.contains("SPDX-FileCopyrightText"),
+        content.contains("SPDX-License-Identifier"),
+    ) {
+        (true, true) => return Some((LicenseNoticeFormat::Reuse, None)),
+        (false, true) => return Some((LicenseNoticeFormat::Spdx, None)),
+        _ => {}
+    }
+
+    let content = content.trim();
+    let (copyright, sentence) = content.split_once('\n')?;
+    let sentence = sentence.trim();
+    if !copyright.trim_start().starts_with("Copyright")
+        || sentence.is_empty()
+        || sentence.lines().any(|line| line.trim().is_empty())
+    {
+        return None;
+    }
+
+    let sentence = sentence
+        .lines()
+        .map(str::trim)
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace("$(", "$$(")
+        .replace("[year]", "$(year)")
+        .replace("[owner]", "$(owner)")
+        .replace("[software-name]", &format!("$({SOFTWARE_NAME_VARIABLE})"));
+    Some((LicenseNoticeFormat::Compact, Some(sentence)))
+}
+
+/// Checks whether Licensa comments the files of `language` in its comment style.
+///
+/// Returns a warning describing the differences, if any.
+fn check_comment_style(name: &str, language: &Language) -> Option<String> {
+    let style = language.comment_style_id.as_deref()?;
+    let Some((_, token)) = COMMENT_STYLES.iter().find(|(id, _)| *id == style) else {
+        return Some(format!(
+            "comment style `{style}` of {name} is not supported"
+        ));
+    };
+
+    let extensions = language.extensions.iter().map(|extension| {
+        let extension = extension.to_lowercase();
+        match extension.starts_with('.') {
+            true => extension,
+            false => format!(".{extension}"),
+        }
+    });
+    let file_names = language.filenames.iter().map(|name| name.to_lowercase());
+    let mismatches: Vec<String> = extensions
+        .chain(file_names)
+        .filter(|lookup_name| {
+            SourceHeaders::find_header_prefix_for_extension(lookup_name, &FileTypes::default())
+                .map_or(true, |prefix| {
+                    !prefix.top.starts_with(token) && !prefix.mid.starts_with(token)
+                })
+        })
+        .collect();
+    if mismatches.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "{} files of {name} are not commented in the `{style}` style, Licensa uses built-in comment styles",
+        mismatches.join(", ")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrate() {
+        let migration = migrate(
+            r#"
+header:
+  license:
+    spdx-id: Apache-2.0
+    copyright-owner: Apache Software Foundation
+    copyright-year: 2020
+  paths:
+    - "**"
+  paths-ignore:
+    - dist
+    - "**/*.md"
+  comment: on-failure
+"#,
+        )
+        .unwrap();
+
+        let config = migration.config;
+        assert_eq!(config.license.as_deref(), Some("Apache-2.0"));
+        assert_eq!(config.owner.as_deref(), Some("Apache Software Foundation"));
+        assert_eq!(config.year.unwrap().to_string(), "2020");
+        assert_eq!(config.format, Some(LicenseNoticeFormat::Full));
+        assert_eq!(migration.ignore, vec!["/dist", "**/*.md"]);
+        assert!(migration.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_migrate_header_list() {
+        let migration = migrate(
+            r#"
+header:
+  - license:
+      spdx-id: MIT
+      content: |
+        Copyright [year] [owner]
+        SPDX-License-Identifier: MIT
+    paths: ["src/**"]
+    language:
+      Go:
+        extensions: [".go"]
+        comment_style_id: DoubleSlash
+      Foo:
+        extensions: [".foo", ".rs"]
+        comment_style_id: Hashtag
+  - license:
+      spdx-id: Apache-2.0
+dependency:
+  files: [go.mod]
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(migration.config.license.as_deref(), Some("MIT"));
+        assert_eq!(migration.config.format, Some(LicenseNoticeFormat::Spdx));
+        assert_eq!(migration.warnings.len(), 4);
+    }
+
+    #[test]
+    fn test_convert_content() {
+        let (format, sentence) = convert_content(
+            "Copyright [year] [owner]\n\nLicensed under the MIT license,\nsee LICENSE in [software-name].\n",
+        )
+        .unwrap();
+        assert_eq!(format, LicenseNoticeFormat::Compact);
+        assert_eq!(
+            sentence.as_deref(),
+            Some("Licensed under the MIT license, see LICENSE in $(softwareName).")
+        );
+
+        assert_eq!(
+            convert_content("SPDX-FileCopyrightText: [owner]\nSPDX-License-Identifier: MIT"),
+            Some((LicenseNoticeFormat::Reuse, None))
+        );
+        assert_eq!(
+            convert_content("Licensed to the ASF.\n\nSee the NOTICE file.\n\nMore text."),
+            None
+        );
+    }
+
+    #[test]
+    fn test_migrate_without_header() {
+        assert!(migrate("dependency:\n  files: [go.mod]\n").is_err());
+    }
+}
@@ -0,0 +1,99 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Conversion of the configuration of other license header tools.
+//!
+//! Teams switching to Licensa can convert the settings of google/addlicense,
+//! given as its command line arguments, and of Apache SkyWalking Eyes
+//! (`license-eye`), read from its `.licenserc.yaml` file, into a `.licensarc`
+//! config and `.licensaignore` patterns. Settings without a Licensa equivalent
+//! are reported as warnings instead of failing the migration.
+
+pub mod addlicense;
+pub mod license_eye;
+
+use crate::config::Config;
+
+use clap::ValueEnum;
+
+/// A license header tool whose configuration can be migrated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum MigrationSource {
+    /// google/addlicense, configured with command line arguments.
+    Addlicense,
+
+    /// Apache SkyWalking Eyes, configured with a `.licenserc.yaml` file.
+    LicenseEye,
+}
+
+impl MigrationSource {
+    /// Returns the name of the tool.
+    pub fn title(&self) -> &'static str {
+        match self {
+            MigrationSource::Addlicense => "addlicense",
+            MigrationSource::LicenseEye => "license-eye",
+        }
+    }
+}
+
+/// The result of converting the configuration of another tool.
+#[derive(Debug, Clone, Default)]
+pub struct Migration {
+    /// The equivalent Licensa config.
+    pub config: Config,
+
+    /// Patterns of files excluded from license checks, in `.licensaignore` syntax.
+    pub ignore: Vec<String>,
+
+    /// Settings that couldn't be converted.
+    pub warnings: Vec<String>,
+}
+
+impl Migration {
+    /// Returns the ignore file section listing the migrated ignore patterns of `source`.
+    pub fn ignore_section(&self, source: MigrationSource) -> String {
+        let mut section = format!("# Migrated from {}\n", source.title());
+        for pattern in self.ignore.iter() {
+            section.push_str(pattern);
+            section.push('\n');
+        }
+        section
+    }
+}
+
+/// Converts a glob pattern matched against paths relative to the workspace root,
+/// e.g. `vendor/**`, into an equivalent `.licensaignore` pattern.
+///
+/// Unlike gitignore patterns, such globs only match from the workspace root, so
+/// patterns not starting with `**` are anchored with a leading `/`.
+fn to_ignore_pattern(glob: &str) -> String {
+    let glob = glob.trim_start_matches("./");
+    if glob.starts_with("**") || glob.starts_with('/') {
+        return glob.to_string();
+    }
+    format!("/{glob}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_ignore_pattern() {
+        assert_eq!(to_ignore_pattern("**/*.md"), "**/*.md");
+        assert_eq!(to_ignore_pattern("vendor/**"), "/vendor/**");
+        assert_eq!(to_ignore_pattern("./dist"), "/dist");
+    }
+
+    #[test]
+    fn test_ignore_section() {
+        let migration = Migration {
+            ignore: vec!["/dist".into(), "**/*.md".into()],
+            ..Default::default()
+        };
+        assert_eq!(
+            migration.ignore_section(MigrationSource::LicenseEye),
+            "# Migrated from license-eye\n/dist\n**/*.md\n"
+        );
+    }
+}
@@ -0,0 +1,1296 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+pub mod metadata;
+pub mod migrate;
+pub mod policy;
+pub mod remote;
+
+use crate::config::migrate::license_eye::{self, LICENSE_EYE_CONFIG_FILENAME};
+use crate::config::policy::{LicensePolicy, PolicyViolation};
+use crate::config::remote::RemoteConfig;
+use crate::diagnostic::Diagnostic;
+use crate::ops::permissions::ReadOnlyPolicy;
+use crate::ops::workspace::find_workspace_config_path;
+use crate::schema::{LicenseId, LicenseYear};
+use crate::template::header::{
+    FileTypes, HeadDirectives, HeaderPlacement, MAX_BLANK_LINES_AFTER_HEADER,
+};
+use crate::template::notice::LicenseNoticeFormat;
+use crate::workspace::git;
+use crate::workspace::ops::{interpolate_env, parse_config_json, strip_json_comments};
+use crate::workspace::store::Store;
+use crate::workspace::walker::SymlinkPolicy;
+
+use anyhow::{anyhow, Context, Result};
+use clap::Args;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+/// The filename used for Licensa's ignore file, which contains patterns
+/// for files or directories to be excluded from license scanning or other
+/// workspace operations.
+pub const LICENSA_IGNORE_FILENAME: &str = ".licensaignore";
+
+/// The filename used for Licensa's configuration file, which stores
+/// workspace-specific settings and preferences.F
+pub const LICENSA_CONFIG_FILENAME: &str = ".licensarc";
+
+static CONFIG_FILE: OnceLock<PathBuf> = OnceLock::new();
+static PROFILE: OnceLock<String> = OnceLock::new();
+
+/// Makes all commands load the config file at `path` instead of discovering
+/// one in the workspace.
+///
+/// Does nothing if `path` is `None`. Calling this function more than once has no effect.
+pub fn init_config_file(path: Option<PathBuf>) {
+    if let Some(path) = path {
+        let _ = CONFIG_FILE.set(path);
+    }
+}
+
+/// Returns the config file selected with `--config`, if any.
+pub fn config_file() -> Option<&'static Path> {
+    CONFIG_FILE.get().map(PathBuf::as_path)
+}
+
+/// Makes all commands merge the config profile `name` over the base config.
+///
+/// Does nothing if `name` is `None`. Calling this function more than once has no effect.
+pub fn init_profile(name: Option<String>) {
+    if let Some(name) = name {
+        let _ = PROFILE.set(name);
+    }
+}
+
+/// Returns the config profile selected with `--profile`, if any.
+pub fn profile() -> Option<&'static str> {
+    PROFILE.get().map(String::as_str)
+}
+
+/// Represents the container for a Licensa config file that may be
+/// included in root directory of a software project.
+///
+/// A Licensa config file contains workspace-wide config presets.
+/// If a config file is present in the same directory a Licensa command
+/// is executed in, the provided config fields will be merged into
+/// the command arguments, replacing the specific command's default
+/// argument settings.
+///
+/// CLI arguments **always** take precedence over options provided
+/// in the config file. An exeception to that rule is when a command
+/// accepts a `--config` flag, which, when present, explicitly requests
+/// the usage of a specific Licensa config file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Args, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields, default)]
+pub struct Config {
+    /// The JSON Schema of this file, e.g. the output of `licensa config schema`.
+    ///
+    /// Lets editors provide completion and validation. Ignored by Licensa.
+    #[arg(skip)]
+    #[serde(rename = "$schema", skip_serializing)]
+    pub schema: Option<String>,
+
+    /// The SPDX license ID or expression (case-insensitive).
+    ///
+    /// If a value partially matches a SPDX license ID, it is automatically transformed
+    /// into the most likely SPDX license expression match. For example, "apache" would
+    /// become "Apache-2.0", "mits" is transformed into "MIT" and so on.
+    /// However, an error is thrown if no match is found for the imprecise expression.
+    ///
+    /// For a comprehensive list of the available SPDX refer to https://spdx.org/licenses/.
+    #[arg(short = 't', long = "type", verbatim_doc_comment)]
+    #[arg(value_name = "ID")]
+    #[arg(value_parser = crate::parser::parse_license_id)]
+    pub license: Option<LicenseId>,
+
+    /// The copyright owner.
+    #[arg(short, long, verbatim_doc_comment, value_name = "NAME")]
+    pub owner: Option<String>,
+
+    /// The email address of the copyright owner.
+    ///
+    /// Rendered after the owner in copyright notices, e.g. `Jane Doe <jane@acme.io>`,
+    /// and available as `$(email)` in template variables.
+    #[arg(long, verbatim_doc_comment, value_name = "EMAIL")]
+    pub email: Option<String>,
+
+    /// The name of the project.
+    ///
+    /// Available as `$(project)` in template variables and required by the `full`
+    /// notice of licenses naming the project, i.e. BSD-4-Clause, NCSA and Vim.
+    #[arg(long, verbatim_doc_comment, value_name = "NAME")]
+    pub project: Option<String>,
+
+    /// The URL of the project homepage.
+    ///
+    /// Available as `$(projectUrl)` in template variables and required by the
+    /// `full` notice of the NCSA license.
+    #[arg(long, verbatim_doc_comment, value_name = "URL")]
+    pub project_url: Option<String>,
+
+    /// Contributors of the files, rendered as `SPDX-FileContributor` lines beneath
+    /// the copyright line of copyright notices.
+    ///
+    /// Provide multiple contributors by repeating the option, e.g.
+    /// `--contributor "Jane Doe" --contributor "John Doe"`.
+    #[arg(long = "contributor", verbatim_doc_comment, value_name = "NAME")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub contributors: Vec<String>,
+
+    /// Represents the copyright year or a range of years.
+    ///
+    /// This field is used to define the copyright duration when applying license headers.
+    /// When providing a range, it signifies the inclusive span of years.
+    ///
+    /// The special keyword `present` indicates the current year, e.g. "2022-present".
+    /// Discrete years and ranges are listed in ascending order, separated by commas.
+    ///
+    /// === EXAMPLE USAGE ================================================
+    ///     
+    ///     licensa <COMMAND> --year 2020
+    ///     licensa <COMMAND> --year 2020-2023
+    ///     licensa <COMMAND> --year 2020-present
+    ///     licensa <COMMAND> --year "2019, 2021-2023, 2024"
+    #[cfg(not(doctest))]
+    #[arg(long, verbatim_doc_comment)]
+    #[arg(value_name = "YEAR | PERIOD | LIST")]
+    #[arg(value_parser = crate::parser::parse_license_year)]
+    pub year: Option<LicenseYear>,
+
+    /// The format of the copyright notice: `spdx`, `full`, `reuse` or `compact`.
+    ///
+    /// The `spdx` format writes a copyright line followed by an SPDX license identifier.
+    /// The `full` format writes the notice recommended by the license authors instead,
+    /// e.g. the Apache-2.0 boilerplate. It's available for Apache-2.0, MIT, MPL-2.0 and
+    /// the GNU GPL, LGPL and AGPL licenses; other licenses fall back to `spdx`.
+    /// The `reuse` format writes `SPDX-FileCopyrightText` and `SPDX-License-Identifier`
+    /// tags as required by the REUSE specification.
+    /// The `compact` format writes a copyright line followed by a single sentence
+    /// pointing to the license, see `compactSentence`.
+    ///
+    /// Defaults to `spdx`.
+    #[arg(id = "notice_format", long = "notice-format")]
+    #[arg(value_enum, value_name = "FORMAT", verbatim_doc_comment)]
+    pub format: Option<LicenseNoticeFormat>,
+
+    /// Where license headers are inserted: `top` or `after-docs`.
+    ///
+    /// With `after-docs`, headers are inserted below the module-level documentation
+    /// of Rust (`//!` and `/*! */` comments), Python (module docstrings) and Go files
+    /// (package docs, after the `package` clause). Other files use `top`.
+    ///
+    /// Defaults to `top`.
+    #[arg(long, value_enum, value_name = "PLACEMENT", verbatim_doc_comment)]
+    pub placement: Option<HeaderPlacement>,
+
+    /// The number of blank lines following an inserted header, from 0 to 2.
+    ///
+    /// Defaults to 1.
+    #[arg(long, value_name = "LINES", verbatim_doc_comment)]
+    #[arg(value_parser = clap::value_parser!(u8).range(0..=MAX_BLANK_LINES_AFTER_HEADER as i64))]
+    #[serde(default, deserialize_with = "deserialize_blank_lines")]
+    #[schemars(range(max = 2))]
+    pub blank_lines_after_header: Option<u8>,
+
+    /// A list of glob patterns to exclude specific files or directories from the licensing process.
+    ///
+    /// Using this field, you can prevent the application of license headers or other licensing-related
+    /// actions to files that match the specified patterns. This is useful for excluding things like:
+    ///
+    /// - Generated files or third-party code that shouldn't be modified.
+    /// - Files already containing appropriate license information.
+    /// - Files irrelevant to the licensing process.
+    ///
+    ///
+    /// === EXAMPLE USAGE ================================================
+    ///
+    ///     licensa apply --exclude *.txt **/target
+    ///
+    /// === IMPORTANT NOTES ==============================================
+    ///
+    /// - Glob patterns follow standard `.gitignore` patterns.
+    /// - Patterns are case-sensitive.
+    /// - Exclusion applies to files within the workspace or project directory.
+    /// - If a file matches multiple patterns, it's still excluded.
+    /// - Provide multiple patterns as separate space-delimited arguments when using command-line options.
+    #[cfg(not(doctest))]
+    #[arg(long, verbatim_doc_comment)]
+    #[arg(value_name = "GLOB[,...]", value_delimiter = ' ', num_args = 1..)]
+    #[arg(default_values_t = Vec::<String>::new())]
+    #[serde(default)]
+    pub exclude: Vec<String>,
+
+    /// The number of leading bytes read from each file when looking for a license header.
+    ///
+    /// License headers are expected at the beginning of a file, so there is no need to
+    /// load entire files into memory. Increase this value if headers are preceded by
+    /// large prologues.
+    ///
+    /// Defaults to 1024 bytes.
+    #[arg(long, value_name = "BYTES", verbatim_doc_comment)]
+    pub read_window: Option<usize>,
+
+    /// Skip files larger than this number of bytes.
+    ///
+    /// Generated bundles and data files may have a source code file extension, but
+    /// usually don't need a license header. Skipped files are counted as ignored.
+    ///
+    /// By default, files of any size are processed.
+    #[arg(long, value_name = "BYTES", verbatim_doc_comment)]
+    pub max_file_size: Option<u64>,
+
+    /// How symbolic links are treated: `skip`, `follow` or `error`.
+    ///
+    /// Symbolic links are skipped by default. When following links, link cycles
+    /// and files resolving to a path outside of the workspace root are skipped.
+    #[arg(long, value_enum, value_name = "POLICY", verbatim_doc_comment)]
+    pub symlinks: Option<SymlinkPolicy>,
+
+    /// How read-only files are treated when they'd be modified: `error`, `skip` or `chmod`.
+    ///
+    /// By default, modifying a read-only file fails. Skipped files are counted
+    /// separately in the result. With `chmod`, files are made writable for the
+    /// time of writing and read-only again afterwards.
+    #[arg(long, value_enum, value_name = "POLICY", verbatim_doc_comment)]
+    pub read_only: Option<ReadOnlyPolicy>,
+
+    /// Restore the modification time of files after modifying them.
+    ///
+    /// Keeps build systems relying on timestamps from rebuilding files
+    /// because of a changed license header.
+    #[arg(long, num_args = 0..=1, default_missing_value = "true", require_equals = true)]
+    #[arg(value_name = "BOOL", verbatim_doc_comment)]
+    pub preserve_mtime: Option<bool>,
+
+    /// Include hidden files and directories, e.g. `.github/workflows/ci.yml`.
+    ///
+    /// Hidden files are skipped by default. The `.git` directory is always skipped.
+    #[arg(long, num_args = 0..=1, default_missing_value = "true", require_equals = true)]
+    #[arg(value_name = "BOOL", verbatim_doc_comment)]
+    pub hidden: Option<bool>,
+
+    /// Include Git submodules and nested repositories.
+    ///
+    /// Vendored code in submodules usually carries its own license headers, so
+    /// submodules declared in `.gitmodules` and directories containing a `.git`
+    /// entry are skipped by default.
+    #[arg(long, num_args = 0..=1, default_missing_value = "true", require_equals = true)]
+    #[arg(value_name = "BOOL", verbatim_doc_comment)]
+    pub include_submodules: Option<bool>,
+
+    /// Include directories containing vendored third-party code.
+    ///
+    /// Directories named like `vendor`, `third_party` or `node_modules`, and
+    /// directories containing a license file that differs from those in the
+    /// workspace root, are skipped by default, unless they contain a Licensa
+    /// config file.
+    #[arg(long, num_args = 0..=1, default_missing_value = "true", require_equals = true)]
+    #[arg(value_name = "BOOL", verbatim_doc_comment)]
+    pub include_vendored: Option<bool>,
+
+    /// Ignore `linguist-generated` and `export-ignore` attributes in `.gitattributes`.
+    ///
+    /// Paths marked as generated or export-ignored in the `.gitattributes` file of
+    /// the workspace root are skipped by default.
+    #[arg(long, num_args = 0..=1, default_missing_value = "true", require_equals = true)]
+    #[arg(value_name = "BOOL", verbatim_doc_comment)]
+    pub ignore_gitattributes: Option<bool>,
+
+    /// Require the copyright year of license headers to include the current year.
+    ///
+    /// `verify` reports headers whose year, or the end of a year range, is older
+    /// than the current year. Headers without a year are not reported.
+    #[arg(long, num_args = 0..=1, default_missing_value = "true", require_equals = true)]
+    #[arg(value_name = "BOOL", verbatim_doc_comment)]
+    pub require_current_year: Option<bool>,
+
+    /// Member packages of a monorepo, as directories or glob patterns relative to
+    /// the workspace root, e.g. `packages/*`.
+    ///
+    /// Each member is processed with its own configuration: settings in a member's
+    /// `.licensarc` take precedence over the root config. Files outside of all members
+    /// are processed with the root config. Only configurable in the config file.
+    #[arg(skip)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub workspaces: Vec<String>,
+
+    /// The maximum number of scanned files buffered before they are processed.
+    ///
+    /// Defaults to 64 files per scan thread. Only configurable in the config file.
+    #[arg(skip)]
+    pub scan_limit: Option<usize>,
+
+    /// The number of threads used to scan the workspace.
+    ///
+    /// Defaults to the number of available CPUs. Only configurable in the config file.
+    #[arg(skip)]
+    pub scan_threads: Option<usize>,
+
+    /// Named sets of config fields selectable with `--profile`, e.g. `oss` and `internal`.
+    ///
+    /// The fields of the selected profile are merged over the base config, while
+    /// command arguments still take precedence. Only configurable in the config file.
+    #[arg(skip)]
+    #[serde(skip_serializing)]
+    pub profiles: BTreeMap<String, Config>,
+
+    /// Licenses of specific file types that differ from the workspace license.
+    ///
+    /// For example, documentation may be licensed under `CC-BY-4.0` while source code
+    /// is licensed under `Apache-2.0`. The first entry matching the extension of a file
+    /// is used. Only configurable in the config file.
+    #[arg(skip)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub file_licenses: Vec<FileLicense>,
+
+    /// Copyright owners of files in specific directories that differ from the
+    /// workspace owner, e.g. of plugins maintained by other teams.
+    ///
+    /// The first entry with a pattern matching the path of a file relative to the
+    /// workspace root is used. Only configurable in the config file.
+    #[arg(skip)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub path_owners: Vec<PathOwner>,
+
+    /// Licenses allowed or denied in the workspace.
+    ///
+    /// `apply` and `verify` fail if the configured license or the license found in a
+    /// file header violates the policy, `deps` and `sbom` if the license of a
+    /// dependency or file does. Only configurable in the config file.
+    #[arg(skip)]
+    #[serde(default, skip_serializing_if = "LicensePolicy::is_empty")]
+    pub policy: LicensePolicy,
+
+    /// Custom variables that may be referenced as `$(name)` in copyright notices.
+    ///
+    /// For example, with `"variables": { "division": "Research" }`, an owner of
+    /// `ACME Inc. $(division)` renders as `ACME Inc. Research`. The variables `owner`,
+    /// `license` and `year` are always available. Only configurable in the config file.
+    #[arg(skip)]
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub variables: BTreeMap<String, String>,
+
+    /// The sentence following the copyright line of the `compact` notice format.
+    ///
+    /// Besides the usual variables, `$(determiner)` expands to the indefinite article
+    /// preceding the license, i.e. `a` or `an`, and `$(location)` to where the full
+    /// license text can be found. Only configurable in the config file.
+    #[arg(skip)]
+    pub compact_sentence: Option<String>,
+
+    /// The directory of partials included in copyright notices as `$(> name)`,
+    /// relative to the workspace root. Defaults to `.licensa-templates`.
+    ///
+    /// The partial `name` is read from `name.txt`, e.g. a legal disclaimer shared by
+    /// the compact sentence of several profiles. Only configurable in the config file.
+    #[arg(skip)]
+    pub templates: Option<PathBuf>,
+
+    /// Attribution notices of bundled third-party works, written to the `NOTICE`
+    /// file by `notice` beneath the copyright lines of the project.
+    ///
+    /// Each entry is a paragraph that may reference variables and partials, e.g.
+    /// `$(> foo-notice)` to include the NOTICE of a bundled library kept in the
+    /// templates directory. Only configurable in the config file.
+    #[arg(skip)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attributions: Vec<String>,
+
+    /// External commands adding license headers to files of formats without a built-in
+    /// comment style, e.g. Jupyter notebooks.
+    ///
+    /// A plugin reads a line of JSON context with the `path`, `license` and rendered
+    /// `notice` of a file from stdin, followed by the content of the file, and writes the
+    /// new content of the file to stdout. Only configurable in the config file.
+    #[arg(skip)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub plugins: Vec<FilePlugin>,
+
+    /// File suffixes mapped to the extension whose comment style applies to them,
+    /// e.g. `{ ".html.tpl": ".html", ".envrc": ".sh" }`.
+    ///
+    /// Of compound suffixes such as `.html.tpl`, the longest configured or built-in
+    /// suffix wins. Suffixes are matched case-insensitively. Configs of workspace
+    /// members extend the file types of the root config. Only configurable in the
+    /// config file.
+    #[arg(skip)]
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub file_types: BTreeMap<String, String>,
+
+    /// Prefixes of first lines that license headers are inserted after, in addition
+    /// to hash-bang lines and built-in directives such as `<?php` or `# syntax`,
+    /// e.g. `["// dart format off", "#cloud-config"]`.
+    ///
+    /// Prefixes are matched case-insensitively. Configs of workspace members extend
+    /// the directives of the root config. Only configurable in the config file.
+    #[arg(skip)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub head_directives: Vec<String>,
+
+    /// A config file shared across repositories, downloaded from an HTTPS `url` and
+    /// pinned to the `sha256` checksum of its content.
+    ///
+    /// Fields of the workspace config take precedence over the extended config, e.g. a
+    /// license policy and notice format maintained by a compliance team. Downloaded
+    /// files are cached in the `.licensa` directory. Only configurable in the config file.
+    #[arg(skip)]
+    pub extends: Option<RemoteConfig>,
+}
+
+/// A license applied to files with specific extensions.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct FileLicense {
+    /// File extensions with or without a leading dot, e.g. `html` or `.css` (case-insensitive).
+    pub extensions: Vec<String>,
+
+    /// The SPDX license ID or expression used for matching files.
+    pub license: LicenseId,
+}
+
+impl FileLicense {
+    /// Checks whether this entry applies to files with the given extension.
+    pub fn matches(&self, extension: &str) -> bool {
+        let extension = normalize_extension(extension);
+        self.extensions
+            .iter()
+            .any(|candidate| normalize_extension(candidate) == extension)
+    }
+
+    /// Returns the license of the first entry in `file_licenses` matching `extension`.
+    pub fn find<'a>(file_licenses: &'a [FileLicense], extension: &str) -> Option<&'a LicenseId> {
+        file_licenses
+            .iter()
+            .find(|file_license| file_license.matches(extension))
+            .map(|file_license| &file_license.license)
+    }
+}
+
+/// A copyright owner of the files in specific directories.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct PathOwner {
+    /// Glob patterns of paths relative to the workspace root, e.g. `plugins/foo/**`.
+    ///
+    /// A pattern matching a directory matches all files beneath it, so that
+    /// `plugins/foo` is equivalent to `plugins/foo/**`.
+    pub paths: Vec<String>,
+
+    /// The copyright owner of matching files.
+    pub owner: String,
+}
+
+impl PathOwner {
+    /// Returns a matcher for the paths of this entry.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a pattern is not a valid glob.
+    pub fn matcher(&self) -> Result<GlobSet> {
+        let mut globs = GlobSetBuilder::new();
+        for pattern in self.paths.iter() {
+            let pattern = pattern
+                .trim()
+                .trim_start_matches("./")
+                .trim_end_matches('/');
+            for pattern in [pattern.to_string(), format!("{pattern}/**")] {
+                let glob = GlobBuilder::new(&pattern)
+                    .literal_separator(true)
+                    .build()
+                    .map_err(|err| {
+                        anyhow!(
+                            "invalid path pattern {pattern:?} of owner {}: {err}",
+                            self.owner
+                        )
+                    })?;
+                globs.add(glob);
+            }
+        }
+        Ok(globs.build()?)
+    }
+}
+
+/// An external command handling files with specific extensions.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct FilePlugin {
+    /// File extensions with or without a leading dot, e.g. `ipynb` (case-insensitive).
+    pub extensions: Vec<String>,
+
+    /// The program to run, followed by its arguments, e.g. `["python3", "tools/notebook.py"]`.
+    ///
+    /// The command runs in the workspace root.
+    pub command: Vec<String>,
+}
+
+impl FilePlugin {
+    /// Checks whether this plugin handles files with the given extension.
+    pub fn matches(&self, extension: &str) -> bool {
+        let extension = normalize_extension(extension);
+        self.extensions
+            .iter()
+            .any(|candidate| normalize_extension(candidate) == extension)
+    }
+
+    /// Returns the first plugin in `plugins` handling files with the given extension.
+    pub fn find<'a>(plugins: &'a [FilePlugin], extension: &str) -> Option<&'a FilePlugin> {
+        plugins.iter().find(|plugin| plugin.matches(extension))
+    }
+}
+
+fn normalize_extension(extension: &str) -> String {
+    extension.trim_start_matches('.').to_lowercase()
+}
+
+fn deserialize_blank_lines<'de, D>(deserializer: D) -> Result<Option<u8>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let blank_lines = Option::<u8>::deserialize(deserializer)?;
+    match blank_lines {
+        Some(n) if n > MAX_BLANK_LINES_AFTER_HEADER => Err(serde::de::Error::custom(format!(
+            "expected at most {MAX_BLANK_LINES_AFTER_HEADER} blank lines after header, got {n}"
+        ))),
+        _ => Ok(blank_lines),
+    }
+}
+
+impl Config {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn from_defaults() -> Self {
+        let empty = Config::new();
+        Config {
+            license: empty.license().map(|s| s.into()),
+            owner: empty.holder().map(|s| s.to_owned()),
+            year: empty.year().map(|s| s.to_owned()),
+            format: empty.format,
+            placement: empty.placement,
+            blank_lines_after_header: empty.blank_lines_after_header,
+            exclude: empty.exclude().to_vec(),
+            read_window: empty.read_window,
+            max_file_size: empty.max_file_size,
+            symlinks: empty.symlinks,
+            read_only: empty.read_only,
+            preserve_mtime: empty.preserve_mtime,
+            hidden: empty.hidden,
+            include_submodules: empty.include_submodules,
+            include_vendored: empty.include_vendored,
+            ignore_gitattributes: empty.ignore_gitattributes,
+            require_current_year: empty.require_current_year,
+            workspaces: empty.workspaces,
+            scan_limit: empty.scan_limit,
+            scan_threads: empty.scan_threads,
+            schema: empty.schema,
+            profiles: empty.profiles,
+            file_licenses: empty.file_licenses,
+            policy: empty.policy,
+            variables: empty.variables,
+            compact_sentence: empty.compact_sentence,
+            templates: empty.templates,
+            plugins: empty.plugins,
+            extends: empty.extends,
+            email: empty.email,
+            project: empty.project,
+            project_url: empty.project_url,
+            contributors: empty.contributors,
+            file_types: empty.file_types,
+            path_owners: empty.path_owners,
+            attributions: empty.attributions,
+            head_directives: empty.head_directives,
+        }
+    }
+
+    pub fn update(&mut self, source: Config) {
+        if !source.exclude.is_empty() {
+            let mut patterns = source.exclude;
+            self.exclude.append(&mut patterns);
+        }
+        if let Some(holder) = source.owner.as_deref() {
+            self.owner = Some(holder.to_owned())
+        }
+        if let Some(email) = source.email {
+            self.email = Some(email)
+        }
+        if let Some(project) = source.project {
+            self.project = Some(project)
+        }
+        if let Some(project_url) = source.project_url {
+            self.project_url = Some(project_url)
+        }
+        if !source.contributors.is_empty() {
+            self.contributors = source.contributors
+        }
+        if let Some(license) = source.license.as_deref() {
+            self.license = Some(LicenseId(license.to_string()))
+        }
+        if let Some(year) = source.year.as_ref() {
+            self.year = Some(year.to_owned())
+        }
+        if let Some(format) = source.format {
+            self.format = Some(format)
+        }
+        if let Some(placement) = source.placement {
+            self.placement = Some(placement)
+        }
+        if let Some(blank_lines) = source.blank_lines_after_header {
+            self.blank_lines_after_header = Some(blank_lines)
+        }
+        if let Some(read_window) = source.read_window {
+            self.read_window = Some(read_window)
+        }
+        if let Some(max_file_size) = source.max_file_size {
+            self.max_file_size = Some(max_file_size)
+        }
+        if let Some(symlinks) = source.symlinks {
+            self.symlinks = Some(symlinks)
+        }
+        if let Some(read_only) = source.read_only {
+            self.read_only = Some(read_only)
+        }
+        if let Some(preserve_mtime) = source.preserve_mtime {
+            self.preserve_mtime = Some(preserve_mtime)
+        }
+        if let Some(hidden) = source.hidden {
+            self.hidden = Some(hidden)
+        }
+        if let Some(include_submodules) = source.include_submodules {
+            self.include_submodules = Some(include_submodules)
+        }
+        if let Some(include_vendored) = source.include_vendored {
+            self.include_vendored = Some(include_vendored)
+        }
+        if let Some(ignore_gitattributes) = source.ignore_gitattributes {
+            self.ignore_gitattributes = Some(ignore_gitattributes)
+        }
+        if let Some(require_current_year) = source.require_current_year {
+            self.require_current_year = Some(require_current_year)
+        }
+        if !source.workspaces.is_empty() {
+            self.workspaces = source.workspaces
+        }
+        if let Some(scan_limit) = source.scan_limit {
+            self.scan_limit = Some(scan_limit)
+        }
+        if let Some(scan_threads) = source.scan_threads {
+            self.scan_threads = Some(scan_threads)
+        }
+        if !source.file_licenses.is_empty() {
+            self.file_licenses = source.file_licenses
+        }
+        if !source.path_owners.is_empty() {
+            self.path_owners = source.path_owners
+        }
+        if !source.policy.is_empty() {
+            self.policy = source.policy
+        }
+        self.variables.extend(source.variables);
+        if let Some(compact_sentence) = source.compact_sentence {
+            self.compact_sentence = Some(compact_sentence)
+        }
+        if let Some(templates) = source.templates {
+            self.templates = Some(templates)
+        }
+        if !source.attributions.is_empty() {
+            self.attributions = source.attributions
+        }
+        if !source.plugins.is_empty() {
+            self.plugins = source.plugins
+        }
+        self.file_types.extend(source.file_types);
+        for directive in source.head_directives {
+            if !self.head_directives.contains(&directive) {
+                self.head_directives.push(directive)
+            }
+        }
+    }
+
+    pub fn exclude(&self) -> &[String] {
+        self.exclude.as_ref()
+    }
+
+    pub fn holder(&self) -> Option<&str> {
+        self.owner.as_deref()
+    }
+
+    pub fn license(&self) -> Option<&str> {
+        self.license.as_deref()
+    }
+
+    pub fn year(&self) -> Option<&LicenseYear> {
+        self.year.as_ref()
+    }
+
+    /// Parses the configured `fileTypes`, see [`FileTypes::parse`].
+    pub fn file_types(&self) -> Result<FileTypes> {
+        FileTypes::parse(&self.file_types)
+    }
+
+    /// Parses the configured `headDirectives`, see [`HeadDirectives::parse`].
+    pub fn head_directives(&self) -> Result<HeadDirectives> {
+        HeadDirectives::parse(&self.head_directives)
+    }
+
+    /// Try to resolve workspace configuration and merge those with self.
+    pub fn with_workspace_config<T>(&mut self, workspace_root: T) -> Result<Config>
+    where
+        T: AsRef<Path>,
+    {
+        if let Some(mut ws_config) = Config::from_workspace(workspace_root)? {
+            ws_config.update(self.to_owned());
+            return Ok(ws_config);
+        }
+
+        Ok(self.to_owned())
+    }
+
+    /// Checks whether the configured license and file-specific licenses comply with
+    /// the license `policy`.
+    pub fn check_policy(&self) -> Result<(), PolicyViolation> {
+        let file_licenses = self.file_licenses.iter().map(|f| &f.license);
+        for license in self.license.iter().chain(file_licenses) {
+            self.policy.check(license)?;
+        }
+        Ok(())
+    }
+
+    /// Fills in a missing `owner`, `license` or `project` from the metadata of the
+    /// project nearest to `dir`, e.g. the authors, license and name in `Cargo.toml`.
+    ///
+    /// If no manifest declares an owner, the Git user configured for `dir` is used.
+    pub fn fill_from_metadata<T>(&mut self, dir: T)
+    where
+        T: AsRef<Path>,
+    {
+        if self.owner.is_some() && self.license.is_some() && self.project.is_some() {
+            return;
+        }
+
+        let providers = metadata::default_providers();
+        if let Some(source) = metadata::find_metadata(dir.as_ref(), &providers) {
+            let path = source.path.display();
+            if self.owner.is_none() {
+                if let Some(owner) = source.metadata.owner {
+                    debug!("using owner {owner:?} from {path}");
+                    self.owner = Some(owner);
+                }
+            }
+            if self.license.is_none() {
+                if let Some(license) = source.metadata.license {
+                    match LicenseId::from_str(&license) {
+                        Ok(license_id) => {
+                            debug!("using license {license:?} from {path}");
+                            self.license = Some(license_id);
+                        }
+                        Err(_) => warn!("ignore invalid license {license:?} in {path}"),
+                    }
+                }
+            }
+            if self.project.is_none() {
+                if let Some(name) = source.metadata.name {
+                    debug!("using project {name:?} from {path}");
+                    self.project = Some(name);
+                }
+            }
+        }
+
+        if self.owner.is_none() {
+            if let Some((owner, key)) = git::user_identity(dir) {
+                debug!("using owner {owner:?} from git config {key}");
+                self.owner = Some(owner);
+            }
+        }
+    }
+
+    /// Reads the config file located in `workspace_root`, if any.
+    ///
+    /// If a config file was selected with `--config`, that file is read instead.
+    /// The profile selected with `--profile` is merged over the base config, and
+    /// its `fileTypes` are registered for all lookups of comment styles.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the selected profile isn't defined in the config file,
+    /// or if a file type is invalid.
+    pub fn from_workspace<T>(workspace_root: T) -> Result<Option<Config>>
+    where
+        T: AsRef<Path>,
+    {
+        let workspace_root = workspace_root.as_ref();
+        let config = match config_file() {
+            Some(path) => Some(Config::from_file(path)?),
+            None => Config::read_workspace(workspace_root)?,
+        };
+        let config = config
+            .map(|config| config.with_extends(workspace_root))
+            .transpose()?;
+
+        let config = match (config, profile()) {
+            (Some(config), Some(name)) => config.with_profile(name).map(Some)?,
+            (None, Some(name)) => {
+                return Err(anyhow!(
+                    "Profile `{name}` selected, but no Licensa config file found"
+                ))
+            }
+            (config, None) => config.map(Config::without_profiles),
+        };
+        if let Some(config) = config.as_ref() {
+            config.check_file_types()?;
+        }
+        Ok(config)
+    }
+
+    /// Reads the config file of a monorepo member located in `package_root`, if any.
+    ///
+    /// Unlike [`Config::from_workspace`], the profile selected with `--profile` is
+    /// only merged if the member config defines it.
+    pub fn from_member<T>(package_root: T) -> Result<Option<Config>>
+    where
+        T: AsRef<Path>,
+    {
+        let package_root = package_root.as_ref();
+        let Some(config) = Config::read_workspace(package_root)? else {
+            return Ok(None);
+        };
+        let config = config.with_extends(package_root)?;
+        let config = match profile() {
+            Some(name) if config.profiles.contains_key(name) => config.with_profile(name)?,
+            _ => config.without_profiles(),
+        };
+        config.check_file_types()?;
+        Ok(Some(config))
+    }
+
+    /// Checks that the `fileTypes` and `headDirectives` of a config file are valid,
+    /// so that errors are reported when the file is read.
+    fn check_file_types(&self) -> Result<()> {
+        self.file_types()?;
+        self.head_directives()?;
+        Ok(())
+    }
+
+    /// Merges the fields of the profile `name` over self.
+    pub fn with_profile(mut self, name: &str) -> Result<Config> {
+        let Some(profile) = self.profiles.remove(name) else {
+            let available = self.profiles.keys().cloned().collect::<Vec<_>>();
+            return Err(match available.is_empty() {
+                true => anyhow!("Unknown profile `{name}`. The config file defines no profiles"),
+                false => anyhow!(
+                    "Unknown profile `{name}`. Available profiles: {}",
+                    available.join(", ")
+                ),
+            });
+        };
+        debug!("using config profile {name:?}");
+
+        self.update(profile);
+        Ok(self.without_profiles())
+    }
+
+    /// Merges self over the config file referenced by `extends`, if any.
+    ///
+    /// The extended config is cached in the store of `workspace_root`. Profiles of
+    /// both configs are kept, with those of self taking precedence.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the extended config cannot be loaded, doesn't match its
+    /// checksum, or extends another config itself.
+    pub fn with_extends<T>(mut self, workspace_root: T) -> Result<Config>
+    where
+        T: AsRef<Path>,
+    {
+        let Some(remote) = self.extends.take() else {
+            return Ok(self);
+        };
+        let content = remote.load(&Store::new(workspace_root))?;
+        let mut base = Config::parse(&content, &remote.url)
+            .with_context(|| format!("Invalid extended config {}", remote.url))?;
+        if base.extends.is_some() {
+            return Err(anyhow!(
+                "Extended config {} must not extend another config",
+                remote.url
+            ));
+        }
+
+        base.profiles.append(&mut self.profiles);
+        base.update(self);
+        Ok(base)
+    }
+
+    fn without_profiles(mut self) -> Config {
+        self.profiles.clear();
+        self
+    }
+
+    /// Reads the config file located in `workspace_root`.
+    ///
+    /// Without a Licensa config file, the `.licenserc.yaml` file of license-eye is
+    /// read instead, so repositories checked by both tools share one config.
+    fn read_workspace(workspace_root: &Path) -> Result<Option<Config>> {
+        if let Some(path) = find_workspace_config_path(workspace_root) {
+            let content = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read config file {}", path.display()))?;
+            let origin = path.strip_prefix(workspace_root).unwrap_or(&path);
+            return Config::parse(&content, &origin.to_string_lossy()).map(Some);
+        }
+        let license_eye_config = workspace_root.join(LICENSE_EYE_CONFIG_FILENAME);
+        if license_eye_config.is_file() {
+            return Config::from_license_eye(&license_eye_config).map(Some);
+        }
+        Ok(None)
+    }
+
+    /// Reads the config file at `path`.
+    ///
+    /// YAML files are read as license-eye config files.
+    pub fn from_file<T>(path: T) -> Result<Config>
+    where
+        T: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let extension = path.extension().and_then(|extension| extension.to_str());
+        if matches!(extension, Some("yaml" | "yml")) {
+            return Config::from_license_eye(path);
+        }
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config file {}", path.display()))?;
+        Config::parse(&content, &path.to_string_lossy())
+    }
+
+    /// Reads the license-eye config file at `path`, ignoring unsupported settings.
+    fn from_license_eye(path: &Path) -> Result<Config> {
+        let migration = license_eye::migrate_file(path)?;
+        for warning in migration.warnings.iter() {
+            debug!("ignore setting of {}: {warning}", path.display());
+        }
+        Ok(Config {
+            exclude: migration.ignore,
+            ..migration.config
+        })
+    }
+
+    /// Parses the config file `content`, read from `origin`.
+    ///
+    /// Errors point to the offending line of the config file, where possible.
+    fn parse(content: &str, origin: &str) -> Result<Config> {
+        let parse_error = |err: serde_json::Error| {
+            let context = format!("Failed to parse Licensa config file {origin}");
+            match Diagnostic::from_json_error(&err, content) {
+                Some(diagnostic) => anyhow::Error::new(diagnostic.with_origin(origin)),
+                None => anyhow::Error::new(err),
+            }
+            .context(context)
+        };
+        let config = parse_config_json(content).map_err(parse_error)?;
+        serde_json::from_value::<Config>(interpolate_env(config)?).map_err(|err| {
+            // Parsed values carry no position, so the error is located by deserializing
+            // the text again. Comments are blanked, keeping positions as they are.
+            let located = serde_json::from_str::<Config>(&strip_json_comments(content));
+            match located {
+                Err(located) if located.to_string().starts_with(&err.to_string()) => {
+                    parse_error(located)
+                }
+                _ => parse_error(err),
+            }
+        })
+    }
+}
+
+pub struct Copyright {
+    pub license: LicenseId,
+    pub owner: String,
+    pub year: Option<LicenseYear>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Args)]
+pub struct CopyrightArgs {
+    /// The SPDX identifier of the license (case-insensitive).
+    ///
+    /// SPDX (Software Package Data Exchange) is a standard format for communicating the components,
+    /// licenses, and copyrights associated with software.
+    ///
+    /// See https://spdx.org/licenses/
+    #[arg(short = 't', long = "type", value_parser = crate::parser::parse_license_id)]
+    pub license: Option<LicenseId>,
+
+    /// The copyright owner.
+    #[arg(short, long, value_name = "NAME")]
+    pub owner: Option<String>,
+
+    /// Represents the copyright year or a range of years.
+    ///
+    /// This field is used to define the copyright duration when applying license headers.
+    /// When providing a range, it signifies the inclusive span of years.
+    ///
+    /// The special keyword `present` indicates the current year, e.g. `2022-present`.
+    #[arg(long, value_name = "YYYY | YYYY-YYYY | YYYY-present", value_parser = crate::parser::parse_license_year)]
+    pub year: Option<LicenseYear>,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_config_invalid_license_year() {
+        let config = serde_json::from_value::<Config>(json!({
+            "year": 20033,
+        }));
+        assert!(config.is_err());
+
+        let config = serde_json::from_value::<Config>(json!({
+            "year": null,
+        }));
+        assert!(config.is_ok());
+
+        let config = serde_json::from_value::<Config>(json!({
+            "year": "2025-2024",
+        }));
+        assert!(config.is_err());
+    }
+
+    #[test]
+    fn test_config_fill_from_metadata() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            tmp_dir.path().join("Cargo.toml"),
+            "[package]\nname = \"example\"\nauthors = [\"Jane Doe <jane@example.com>\"]\nlicense = \"MIT\"\n",
+        )
+        .unwrap();
+
+        let mut config = Config::new();
+        config.fill_from_metadata(tmp_dir.path());
+        assert_eq!(config.holder(), Some("Jane Doe"));
+        assert_eq!(config.license(), Some("MIT"));
+        assert_eq!(config.project.as_deref(), Some("example"));
+
+        // Values already set are kept
+        let mut config = Config {
+            owner: Some("John Doe".into()),
+            ..Config::new()
+        };
+        config.fill_from_metadata(tmp_dir.path());
+        assert_eq!(config.holder(), Some("John Doe"));
+        assert_eq!(config.license(), Some("MIT"));
+
+        // The project is filled even if the owner and license are set
+        let mut config = Config {
+            owner: Some("John Doe".into()),
+            license: Some(LicenseId::from_str("Apache-2.0").unwrap()),
+            ..Config::new()
+        };
+        config.fill_from_metadata(tmp_dir.path());
+        assert_eq!(config.license(), Some("Apache-2.0"));
+        assert_eq!(config.project.as_deref(), Some("example"));
+    }
+
+    #[test]
+    fn test_config_fill_from_metadata_ignores_invalid_license() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            tmp_dir.path().join("Cargo.toml"),
+            "[package]\nlicense = \"NOT-A-LICENSE\"\n",
+        )
+        .unwrap();
+
+        let mut config = Config::new();
+        config.fill_from_metadata(tmp_dir.path());
+        assert_eq!(config.license(), None);
+    }
+
+    #[test]
+    fn test_config_from_file() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let path = tmp_dir.path().join("licensa.json");
+        std::fs::write(&path, r#"{ "owner": "Jane Doe", "license": "MIT" }"#).unwrap();
+
+        let config = Config::from_file(&path).unwrap();
+        assert_eq!(config.holder(), Some("Jane Doe"));
+        assert_eq!(config.license(), Some("MIT"));
+
+        std::fs::write(&path, r#"{ "unknown": true }"#).unwrap();
+        assert!(Config::from_file(&path).is_err());
+        assert!(Config::from_file(tmp_dir.path().join("missing.json")).is_err());
+    }
+
+    #[test]
+    fn test_config_read_license_eye_config() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            tmp_dir.path().join(LICENSE_EYE_CONFIG_FILENAME),
+            "header:\n  license:\n    spdx-id: MIT\n    copyright-owner: Jane Doe\n  paths-ignore:\n    - dist\n",
+        )
+        .unwrap();
+
+        let config = Config::read_workspace(tmp_dir.path()).unwrap().unwrap();
+        assert_eq!(config.holder(), Some("Jane Doe"));
+        assert_eq!(config.license(), Some("MIT"));
+        assert_eq!(config.exclude(), ["/dist"]);
+
+        // A Licensa config file takes precedence
+        std::fs::write(
+            tmp_dir.path().join(LICENSA_CONFIG_FILENAME),
+            r#"{ "owner": "John Doe" }"#,
+        )
+        .unwrap();
+        let config = Config::read_workspace(tmp_dir.path()).unwrap().unwrap();
+        assert_eq!(config.holder(), Some("John Doe"));
+        assert_eq!(config.license(), None);
+    }
+
+    #[test]
+    fn test_config_schema() {
+        let config = serde_json::from_value::<Config>(json!({
+            "$schema": "./licensarc.schema.json",
+            "owner": "Jane Doe",
+        }))
+        .unwrap();
+        assert_eq!(config.schema.as_deref(), Some("./licensarc.schema.json"));
+        // The schema reference is not forwarded to commands
+        assert!(serde_json::to_value(&config)
+            .unwrap()
+            .get("$schema")
+            .is_none());
+
+        let schema = serde_json::to_value(schemars::schema_for!(Config)).unwrap();
+        let properties = schema["properties"].as_object().unwrap();
+        for field in [
+            "$schema",
+            "license",
+            "owner",
+            "year",
+            "exclude",
+            "workspaces",
+        ] {
+            assert!(properties.contains_key(field), "missing property {field}");
+        }
+        assert_eq!(schema["additionalProperties"], json!(false));
+    }
+
+    #[test]
+    fn test_config_with_profile() {
+        let config = serde_json::from_value::<Config>(json!({
+            "license": "Apache-2.0",
+            "owner": "Jane Doe",
+            "exclude": ["dist/"],
+            "profiles": {
+                "internal": {
+                    "license": "MIT",
+                    "owner": "ACME Inc.",
+                    "exclude": ["vendor/"]
+                },
+                "oss": {}
+            }
+        }))
+        .unwrap();
+
+        let internal = config.clone().with_profile("internal").unwrap();
+        assert_eq!(internal.license(), Some("MIT"));
+        assert_eq!(internal.holder(), Some("ACME Inc."));
+        assert_eq!(internal.exclude(), ["dist/", "vendor/"]);
+        assert!(internal.profiles.is_empty());
+
+        let oss = config.clone().with_profile("oss").unwrap();
+        assert_eq!(oss.license(), Some("Apache-2.0"));
+
+        let err = config.with_profile("unknown").unwrap_err();
+        assert!(err.to_string().contains("internal, oss"));
+    }
+
+    #[test]
+    fn test_config_with_extends() {
+        let dir = tempfile::tempdir().unwrap();
+        let remote = r#"{ "license": "Apache-2.0", "owner": "ACME Inc.", "format": "compact",
+            "policy": { "deny": ["GPL-3.0-only"] } }"#;
+        let checksum = crate::utils::sha256_hex(remote);
+        let store = Store::new(dir.path());
+        store.ensure().unwrap();
+        fs::write(store.path(format!("extends-{checksum}.json")), remote).unwrap();
+
+        let config = serde_json::from_value::<Config>(json!({
+            "owner": "Jane Doe",
+            "extends": { "url": "https://licensa.invalid/licensarc", "sha256": checksum }
+        }))
+        .unwrap()
+        .with_extends(dir.path())
+        .unwrap();
+        assert_eq!(config.license(), Some("Apache-2.0"));
+        assert_eq!(config.holder(), Some("Jane Doe"));
+        assert_eq!(config.format, Some(LicenseNoticeFormat::Compact));
+        assert_eq!(config.policy.deny, ["GPL-3.0-only"]);
+        assert!(config.extends.is_none());
+
+        dir.close().unwrap();
+    }
+
+    #[test]
+    fn test_file_license_find() {
+        let file_licenses = serde_json::from_value::<Vec<FileLicense>>(json!([
+            { "extensions": ["html", ".CSS"], "license": "CC-BY-4.0" },
+            { "extensions": ["css", "sh"], "license": "MIT" }
+        ]))
+        .unwrap();
+
+        let find = |ext| FileLicense::find(&file_licenses, ext).map(|id| id.to_string());
+        assert_eq!(find(".html").as_deref(), Some("CC-BY-4.0"));
+        assert_eq!(find(".css").as_deref(), Some("CC-BY-4.0"));
+        assert_eq!(find("SH").as_deref(), Some("MIT"));
+        assert_eq!(find(".rs"), None);
+
+        let invalid = serde_json::from_value::<Vec<FileLicense>>(json!([
+            { "extensions": ["md"], "license": "NOT-A-LICENSE" }
+        ]));
+        assert!(invalid.is_err());
+    }
+
+    #[test]
+    fn test_path_owner_matcher() {
+        let path_owner = serde_json::from_value::<PathOwner>(json!({
+            "paths": ["plugins/foo", "./tools/*/", "docs/**/*.md"],
+            "owner": "Foo Contributors"
+        }))
+        .unwrap();
+        let matcher = path_owner.matcher().unwrap();
+        assert!(matcher.is_match("plugins/foo/src/lib.rs"));
+        assert!(matcher.is_match("tools/gen/main.rs"));
+        assert!(matcher.is_match("docs/guide/intro.md"));
+        assert!(!matcher.is_match("plugins/foobar/lib.rs"));
+        assert!(!matcher.is_match("src/plugins/foo/lib.rs"));
+        assert!(!matcher.is_match("docs/guide/intro.rs"));
+
+        let invalid = PathOwner {
+            paths: vec!["plugins/[foo".into()],
+            owner: "Foo Contributors".into(),
+        };
+        assert!(invalid.matcher().is_err());
+    }
+
+    #[test]
+    fn test_config_blank_lines_after_header() {
+        let config =
+            serde_json::from_value::<Config>(json!({ "blankLinesAfterHeader": 0 })).unwrap();
+        assert_eq!(config.blank_lines_after_header, Some(0));
+
+        let config = serde_json::from_value::<Config>(json!({})).unwrap();
+        assert_eq!(config.blank_lines_after_header, None);
+
+        let err =
+            serde_json::from_value::<Config>(json!({ "blankLinesAfterHeader": 3 })).unwrap_err();
+        assert!(err.to_string().contains("at most 2 blank lines"));
+    }
+}
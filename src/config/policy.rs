@@ -0,0 +1,166 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! License policies restricting the licenses used in a workspace.
+//!
+//! A policy lists allowed and denied SPDX license IDs. License expressions are
+//! evaluated as a whole: an `OR` expression complies if one of its alternatives
+//! does, an `AND` expression only if all of its licenses do.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use spdx::{Expression, ParseMode};
+use thiserror::Error;
+
+/// Allowed and denied licenses, configured in the `policy` section of the config file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct LicensePolicy {
+    /// SPDX license IDs that may be used, e.g. `MIT` (case-insensitive).
+    ///
+    /// If empty, all licenses that aren't denied are allowed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<String>,
+
+    /// SPDX license IDs that must not be used, e.g. `GPL-3.0-only` (case-insensitive).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deny: Vec<String>,
+}
+
+/// Error returned when a license expression violates the license policy.
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("license `{license}` violates the license policy: {} not allowed", .denied.join(", "))]
+pub struct PolicyViolation {
+    /// The violating license expression.
+    pub license: String,
+
+    /// The license IDs of the expression that aren't allowed.
+    pub denied: Vec<String>,
+}
+
+impl LicensePolicy {
+    /// Checks whether the policy neither allows nor denies any license.
+    pub fn is_empty(&self) -> bool {
+        self.allow.is_empty() && self.deny.is_empty()
+    }
+
+    /// Checks whether the license expression `license` complies with the policy.
+    ///
+    /// Values that aren't valid SPDX expressions are compared as a whole.
+    pub fn check(&self, license: &str) -> Result<(), PolicyViolation> {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        // IDs are compared in their canonical form, e.g. `GPL-3.0-only` as `GPL-3.0`
+        let allow: Vec<String> = self.allow.iter().map(|id| canonical_id(id)).collect();
+        let deny: Vec<String> = self.deny.iter().map(|id| canonical_id(id)).collect();
+        let permits = |id: &str| {
+            let listed = |ids: &[String]| ids.iter().any(|listed| listed.eq_ignore_ascii_case(id));
+            !listed(&deny) && (allow.is_empty() || listed(&allow))
+        };
+
+        let denied = match parse_expression(license) {
+            Some(expression) => {
+                let result =
+                    expression.evaluate_with_failures(|req| permits(&req.license.to_string()));
+                let Err(failures) = result else {
+                    return Ok(());
+                };
+                // Spans refer to the canonicalized expression
+                let expr: &str = expression.as_ref();
+                let mut denied: Vec<String> = failures
+                    .iter()
+                    .map(|failure| {
+                        expr[failure.span.start as usize..failure.span.end as usize].to_string()
+                    })
+                    .collect();
+                denied.sort_unstable();
+                denied.dedup();
+                denied
+            }
+            None if permits(license) => return Ok(()),
+            None => vec![license.to_string()],
+        };
+
+        Err(PolicyViolation {
+            license: license.to_string(),
+            denied,
+        })
+    }
+}
+
+/// Parses the license expression `license`, canonicalizing it if needed, e.g. `mit or apache-2.0`.
+pub(crate) fn parse_expression(license: &str) -> Option<Expression> {
+    if let Ok(expression) = Expression::parse_mode(license, ParseMode::LAX) {
+        return Some(expression);
+    }
+    let canonical = Expression::canonicalize(license).ok()??;
+    Expression::parse(&canonical).ok()
+}
+
+/// Returns the canonical form of the license ID `id`, or `id` itself if it's not a
+/// valid SPDX license ID.
+fn canonical_id(id: &str) -> String {
+    let Some(expression) = parse_expression(id) else {
+        return id.to_string();
+    };
+    let mut requirements = expression.requirements();
+    match (requirements.next(), requirements.next()) {
+        (Some(req), None) => req.req.license.to_string(),
+        _ => id.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(allow: &[&str], deny: &[&str]) -> LicensePolicy {
+        LicensePolicy {
+            allow: allow.iter().map(|id| id.to_string()).collect(),
+            deny: deny.iter().map(|id| id.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_deny_list() {
+        let policy = policy(&[], &["GPL-3.0-only"]);
+        assert!(policy.check("MIT").is_ok());
+        assert_eq!(
+            policy.check("MIT AND GPL-3.0-only"),
+            Err(PolicyViolation {
+                license: "MIT AND GPL-3.0-only".into(),
+                denied: vec!["GPL-3.0-only".into()],
+            })
+        );
+
+        // One allowed alternative is sufficient
+        assert!(policy.check("MIT OR GPL-3.0-only").is_ok());
+
+        // Deprecated and current IDs of the same license are equivalent
+        assert!(policy.check("GPL-3.0").is_err());
+        assert!(policy.check("GPL-3.0-or-later").is_ok());
+    }
+
+    #[test]
+    fn test_allow_list() {
+        let policy = policy(&["MIT", "Apache-2.0"], &[]);
+        assert!(policy.check("MIT OR Apache-2.0").is_ok());
+        assert!(policy.check("(MIT OR BSD-3-Clause) AND Apache-2.0").is_ok());
+        assert!(policy.check("MPL-2.0").is_err());
+
+        // Licenses denied several times are reported once
+        let violation = policy.check("GPL-3.0-only AND MPL-2.0 AND GPL-3.0-only");
+        assert_eq!(violation.unwrap_err().denied, ["GPL-3.0-only", "MPL-2.0"]);
+
+        // Invalid expressions are compared as a whole
+        assert!(policy.check("mit").is_ok());
+        assert!(policy.check("Proprietary License").is_err());
+    }
+
+    #[test]
+    fn test_empty_policy() {
+        assert!(LicensePolicy::default().check("Anything goes").is_ok());
+    }
+}
@@ -0,0 +1,157 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Config files shared across repositories, referenced by URL in the `extends` field.
+//!
+//! A remote config is pinned to the SHA-256 checksum of its content, so that a change
+//! on the server never silently changes the config of a workspace. Downloaded files
+//! are cached in the `.licensa` store under their checksum and only downloaded again
+//! if the cached copy is missing. Downloads are performed by `curl`.
+
+use crate::utils::sha256_hex;
+use crate::workspace::store::Store;
+
+use anyhow::{anyhow, Context, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use std::fs;
+use std::process::Command;
+
+/// Maximum number of seconds a download may take.
+const DOWNLOAD_TIMEOUT_SECS: u32 = 30;
+
+/// A config file extended by the workspace config, e.g. the license policy and
+/// header wording maintained by a compliance team.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct RemoteConfig {
+    /// HTTPS URL of the config file.
+    pub url: String,
+
+    /// Hex-encoded SHA-256 checksum of the config file.
+    pub sha256: String,
+}
+
+impl RemoteConfig {
+    /// Checks whether the URL uses HTTPS and the checksum is a valid SHA-256 hash.
+    pub fn validate(&self) -> Result<()> {
+        if !self.url.starts_with("https://") {
+            return Err(anyhow!("Extended config URL `{}` must use HTTPS", self.url));
+        }
+        if self.sha256.len() != 64 || !self.sha256.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(anyhow!(
+                "Checksum `{}` of extended config is not a SHA-256 hash",
+                self.sha256
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns the content of the config file, reading the copy cached in `store` if
+    /// present and downloading the file otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the download fails or the content doesn't match the checksum.
+    pub fn load(&self, store: &Store) -> Result<String> {
+        self.validate()?;
+        let checksum = self.sha256.to_ascii_lowercase();
+        let path = store.path(format!("extends-{checksum}.json"));
+
+        if let Ok(content) = fs::read(&path) {
+            if sha256_hex(&content) == checksum {
+                debug!("using cached config {}", path.display());
+                return decode(content, &self.url);
+            }
+            debug!("ignore modified cached config {}", path.display());
+        }
+
+        debug!("downloading config {}", self.url);
+        let content = download(&self.url)?;
+        let actual = sha256_hex(&content);
+        if actual != checksum {
+            return Err(anyhow!(
+                "Checksum mismatch for extended config {}: expected {checksum}, got {actual}",
+                self.url
+            ));
+        }
+
+        store.ensure()?;
+        fs::write(&path, &content)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        decode(content, &self.url)
+    }
+}
+
+/// Downloads the file at `url`, refusing to follow redirects to other protocols than HTTPS.
+fn download(url: &str) -> Result<Vec<u8>> {
+    let timeout = DOWNLOAD_TIMEOUT_SECS.to_string();
+    let output = Command::new("curl")
+        .args(["--fail", "--silent", "--show-error", "--location"])
+        .args(["--proto", "=https", "--proto-redir", "=https"])
+        .args(["--max-time", &timeout, "--", url])
+        .output()
+        .context("Failed to run `curl` to download the extended config")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!(
+            "Failed to download extended config {url}: {}",
+            stderr.trim()
+        ));
+    }
+    Ok(output.stdout)
+}
+
+fn decode(content: Vec<u8>, url: &str) -> Result<String> {
+    String::from_utf8(content).with_context(|| format!("Extended config {url} is not valid UTF-8"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const CONTENT: &str = r#"{ "policy": { "deny": ["GPL-3.0-only"] } }"#;
+
+    fn remote(url: &str, sha256: &str) -> RemoteConfig {
+        RemoteConfig {
+            url: url.into(),
+            sha256: sha256.into(),
+        }
+    }
+
+    #[test]
+    fn test_remote_config_validate() {
+        let checksum = sha256_hex(CONTENT);
+        assert!(remote("https://example.com/licensarc", &checksum)
+            .validate()
+            .is_ok());
+        assert!(remote("http://example.com/licensarc", &checksum)
+            .validate()
+            .is_err());
+        assert!(remote("https://example.com/licensarc", "abc")
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn test_remote_config_load_cached() {
+        let dir = tempdir().unwrap();
+        let store = Store::new(dir.path());
+        let checksum = sha256_hex(CONTENT);
+        store.ensure().unwrap();
+        fs::write(store.path(format!("extends-{checksum}.json")), CONTENT).unwrap();
+
+        // The URL is never requested if the cached copy matches the checksum
+        let config = remote(
+            "https://licensa.invalid/licensarc",
+            &checksum.to_uppercase(),
+        );
+        assert_eq!(config.load(&store).unwrap(), CONTENT);
+
+        dir.close().unwrap();
+    }
+}
@@ -0,0 +1,216 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Dependencies of Rust packages.
+//!
+//! Locked dependencies are read from `Cargo.lock`. If no lockfile exists, the
+//! direct dependencies declared in `Cargo.toml` are used instead. Licenses are
+//! looked up in the Cargo registry cache (`$CARGO_HOME/registry/src`).
+
+use super::{Dependency, Ecosystem};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const CARGO_LOCK: &str = "Cargo.lock";
+const CARGO_TOML: &str = "Cargo.toml";
+
+#[derive(Debug, Deserialize)]
+struct Lockfile {
+    #[serde(default)]
+    package: Vec<LockedPackage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LockedPackage {
+    name: String,
+    version: String,
+    source: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Manifest {
+    package: Option<ManifestPackage>,
+    #[serde(default)]
+    dependencies: toml::Table,
+    #[serde(default, rename = "dev-dependencies")]
+    dev_dependencies: toml::Table,
+    #[serde(default, rename = "build-dependencies")]
+    build_dependencies: toml::Table,
+}
+
+#[derive(Debug, Deserialize)]
+struct ManifestPackage {
+    license: Option<toml::Value>,
+}
+
+/// Collects the Cargo dependencies of the workspace at `root`.
+pub fn collect(root: &Path) -> Result<Vec<Dependency>> {
+    let lockfile = root.join(CARGO_LOCK);
+    if lockfile.is_file() {
+        return collect_locked(&lockfile);
+    }
+
+    let manifest = root.join(CARGO_TOML);
+    if manifest.is_file() {
+        return collect_declared(&manifest);
+    }
+
+    Ok(vec![])
+}
+
+fn collect_locked(lockfile: &Path) -> Result<Vec<Dependency>> {
+    let content = fs::read_to_string(lockfile)
+        .with_context(|| format!("failed to read {}", lockfile.display()))?;
+    let parsed: Lockfile = toml::from_str(&content)
+        .with_context(|| format!("failed to parse {}", lockfile.display()))?;
+
+    let registry = registry_src_dirs();
+    let dependencies = parsed
+        .package
+        .into_iter()
+        // Workspace members don't have a source
+        .filter(|package| package.source.is_some())
+        .map(|package| Dependency {
+            license: find_registry_license(&registry, &package.name, &package.version),
+            name: package.name,
+            version: package.version,
+            ecosystem: Ecosystem::Cargo,
+            manifest: lockfile.to_path_buf(),
+        })
+        .collect();
+
+    Ok(dependencies)
+}
+
+fn collect_declared(manifest_path: &Path) -> Result<Vec<Dependency>> {
+    let content = fs::read_to_string(manifest_path)
+        .with_context(|| format!("failed to read {}", manifest_path.display()))?;
+    let manifest: Manifest = toml::from_str(&content)
+        .with_context(|| format!("failed to parse {}", manifest_path.display()))?;
+
+    let tables = [
+        manifest.dependencies,
+        manifest.dev_dependencies,
+        manifest.build_dependencies,
+    ];
+
+    let dependencies = tables
+        .into_iter()
+        .flatten()
+        // Path dependencies are part of the workspace
+        .filter(|(_, spec)| spec.get("path").is_none())
+        .map(|(name, spec)| {
+            let version = match &spec {
+                toml::Value::String(version) => version.to_owned(),
+                spec => spec
+                    .get("version")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("*")
+                    .to_owned(),
+            };
+            Dependency {
+                name,
+                version,
+                ecosystem: Ecosystem::Cargo,
+                license: None,
+                manifest: manifest_path.to_path_buf(),
+            }
+        })
+        .collect();
+
+    Ok(dependencies)
+}
+
+/// Returns the source directories of all registries in the Cargo home.
+fn registry_src_dirs() -> Vec<PathBuf> {
+    let cargo_home = env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cargo")));
+
+    let Some(src) = cargo_home.map(|home| home.join("registry").join("src")) else {
+        return vec![];
+    };
+    fs::read_dir(src)
+        .map(|entries| entries.filter_map(Result::ok).map(|e| e.path()).collect())
+        .unwrap_or_default()
+}
+
+/// Reads the declared license of a package from the registry cache.
+fn find_registry_license(registry: &[PathBuf], name: &str, version: &str) -> Option<String> {
+    registry
+        .iter()
+        .map(|dir| dir.join(format!("{name}-{version}")).join(CARGO_TOML))
+        .find(|path| path.is_file())
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| toml::from_str::<Manifest>(&content).ok())
+        .and_then(|manifest| manifest.package?.license)
+        .and_then(|license| license.as_str().map(str::to_owned))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_collect_locked() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join(CARGO_LOCK),
+            r#"
+version = 3
+
+[[package]]
+name = "licensa"
+version = "0.1.0"
+
+[[package]]
+name = "anyhow"
+version = "1.0.81"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#,
+        )
+        .unwrap();
+
+        let dependencies = collect(dir.path()).unwrap();
+        assert_eq!(dependencies.len(), 1);
+        assert_eq!(dependencies[0].name, "anyhow");
+        assert_eq!(dependencies[0].version, "1.0.81");
+
+        dir.close().unwrap();
+    }
+
+    #[test]
+    fn test_collect_declared() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join(CARGO_TOML),
+            r#"
+[package]
+name = "licensa"
+
+[dependencies]
+anyhow = "1.0"
+serde = { version = "1", features = ["derive"] }
+local = { path = "../local" }
+
+[dev-dependencies]
+tempfile = "3"
+"#,
+        )
+        .unwrap();
+
+        let mut dependencies = collect(dir.path()).unwrap();
+        dependencies.sort_by(|a, b| a.name.cmp(&b.name));
+        let names: Vec<_> = dependencies.iter().map(|d| d.name.as_str()).collect();
+        assert_eq!(names, ["anyhow", "serde", "tempfile"]);
+        assert_eq!(dependencies[1].version, "1");
+
+        dir.close().unwrap();
+    }
+}
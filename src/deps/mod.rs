@@ -0,0 +1,78 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Third-party dependency license discovery.
+//!
+//! Dependencies are read from the manifests and lockfiles of supported package
+//! ecosystems. Licenses are resolved offline from locally installed packages,
+//! e.g. the Cargo registry cache, `node_modules` or a Python virtual environment.
+
+pub mod cargo;
+pub mod npm;
+pub mod python;
+
+use anyhow::Result;
+use serde::Serialize;
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Package ecosystem a dependency belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Ecosystem {
+    Cargo,
+    Npm,
+    Python,
+}
+
+impl fmt::Display for Ecosystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Ecosystem::Cargo => "cargo",
+            Ecosystem::Npm => "npm",
+            Ecosystem::Python => "python",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Represents a third-party dependency.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Dependency {
+    /// Name of the package.
+    pub name: String,
+
+    /// Resolved version, or the version requirement if the dependency isn't locked.
+    pub version: String,
+
+    /// Ecosystem the package belongs to.
+    pub ecosystem: Ecosystem,
+
+    /// Declared license expression of the package, if it could be resolved.
+    pub license: Option<String>,
+
+    /// Path to the manifest the dependency was found in.
+    pub manifest: PathBuf,
+}
+
+/// Collects the dependencies of all supported manifests in `workspace_root`.
+///
+/// Dependencies are sorted by ecosystem and name.
+pub fn collect<P>(workspace_root: P) -> Result<Vec<Dependency>>
+where
+    P: AsRef<Path>,
+{
+    let root = workspace_root.as_ref();
+    let mut dependencies = cargo::collect(root)?;
+    dependencies.append(&mut npm::collect(root)?);
+    dependencies.append(&mut python::collect(root)?);
+
+    dependencies.sort_by(|a, b| {
+        (a.ecosystem, &a.name, &a.version).cmp(&(b.ecosystem, &b.name, &b.version))
+    });
+    dependencies
+        .dedup_by(|a, b| a.ecosystem == b.ecosystem && a.name == b.name && a.version == b.version);
+
+    Ok(dependencies)
+}
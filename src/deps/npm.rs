@@ -0,0 +1,153 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Dependencies of Node.js packages.
+//!
+//! Direct dependencies are read from `package.json`. Installed versions and
+//! licenses are resolved from the corresponding package in `node_modules`.
+
+use super::{Dependency, Ecosystem};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::Value;
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+const PACKAGE_JSON: &str = "package.json";
+const NODE_MODULES: &str = "node_modules";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PackageJson {
+    version: Option<String>,
+    license: Option<Value>,
+    licenses: Option<Vec<Value>>,
+    #[serde(default)]
+    dependencies: BTreeMap<String, String>,
+    #[serde(default)]
+    dev_dependencies: BTreeMap<String, String>,
+    #[serde(default)]
+    optional_dependencies: BTreeMap<String, String>,
+}
+
+impl PackageJson {
+    /// Returns the declared license, supporting the deprecated object and array forms.
+    fn license(&self) -> Option<String> {
+        let license_type = |value: &Value| match value {
+            Value::String(s) => Some(s.to_owned()),
+            Value::Object(obj) => obj.get("type").and_then(Value::as_str).map(str::to_owned),
+            _ => None,
+        };
+
+        if let Some(license) = self.license.as_ref().and_then(license_type) {
+            return Some(license);
+        }
+
+        let licenses: Vec<String> = self
+            .licenses
+            .iter()
+            .flatten()
+            .filter_map(license_type)
+            .collect();
+        match licenses.len() {
+            0 => None,
+            1 => licenses.into_iter().next(),
+            _ => Some(format!("({})", licenses.join(" OR "))),
+        }
+    }
+}
+
+/// Collects the npm dependencies of the workspace at `root`.
+pub fn collect(root: &Path) -> Result<Vec<Dependency>> {
+    let manifest_path = root.join(PACKAGE_JSON);
+    if !manifest_path.is_file() {
+        return Ok(vec![]);
+    }
+
+    let content = fs::read_to_string(&manifest_path)
+        .with_context(|| format!("failed to read {}", manifest_path.display()))?;
+    let manifest: PackageJson = serde_json::from_str(&content)
+        .with_context(|| format!("failed to parse {}", manifest_path.display()))?;
+
+    let declared = manifest
+        .dependencies
+        .into_iter()
+        .chain(manifest.dev_dependencies)
+        .chain(manifest.optional_dependencies);
+
+    let dependencies = declared
+        .map(|(name, requirement)| {
+            let installed = read_installed(root, &name).unwrap_or_default();
+            Dependency {
+                version: installed.version.clone().unwrap_or(requirement),
+                license: installed.license(),
+                name,
+                ecosystem: Ecosystem::Npm,
+                manifest: manifest_path.clone(),
+            }
+        })
+        .collect();
+
+    Ok(dependencies)
+}
+
+fn read_installed(root: &Path, name: &str) -> Option<PackageJson> {
+    let path = root.join(NODE_MODULES).join(name).join(PACKAGE_JSON);
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_collect_with_installed_packages() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::write(
+            root.join(PACKAGE_JSON),
+            r#"{
+                "name": "lotr-map",
+                "dependencies": { "leaflet": "^1.9.0", "@scope/pkg": "2.0.0" },
+                "devDependencies": { "typescript": "^5.0.0" }
+            }"#,
+        )
+        .unwrap();
+
+        let leaflet = root.join(NODE_MODULES).join("leaflet");
+        fs::create_dir_all(&leaflet).unwrap();
+        fs::write(
+            leaflet.join(PACKAGE_JSON),
+            r#"{ "version": "1.9.4", "license": "BSD-2-Clause" }"#,
+        )
+        .unwrap();
+
+        let scoped = root.join(NODE_MODULES).join("@scope").join("pkg");
+        fs::create_dir_all(&scoped).unwrap();
+        fs::write(
+            scoped.join(PACKAGE_JSON),
+            r#"{ "licenses": [{ "type": "MIT" }, { "type": "Apache-2.0" }] }"#,
+        )
+        .unwrap();
+
+        let dependencies = collect(root).unwrap();
+        assert_eq!(dependencies.len(), 3);
+
+        let find = |name: &str| dependencies.iter().find(|d| d.name == name).unwrap();
+        assert_eq!(find("leaflet").version, "1.9.4");
+        assert_eq!(find("leaflet").license.as_deref(), Some("BSD-2-Clause"));
+        assert_eq!(
+            find("@scope/pkg").license.as_deref(),
+            Some("(MIT OR Apache-2.0)")
+        );
+        assert_eq!(find("typescript").version, "^5.0.0");
+        assert_eq!(find("typescript").license, None);
+
+        dir.close().unwrap();
+    }
+}
@@ -0,0 +1,243 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Dependencies of Python projects.
+//!
+//! Direct dependencies are read from `pyproject.toml`, supporting both PEP 621
+//! (`[project]`) and Poetry (`[tool.poetry]`) layouts. Installed versions and
+//! licenses are resolved from the package metadata of a virtual environment
+//! in `.venv` or `venv`.
+
+use super::{Dependency, Ecosystem};
+
+use anyhow::{Context, Result};
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const PYPROJECT_TOML: &str = "pyproject.toml";
+const VIRTUAL_ENVS: &[&str] = &[".venv", "venv"];
+
+/// Collects the Python dependencies of the workspace at `root`.
+pub fn collect(root: &Path) -> Result<Vec<Dependency>> {
+    let manifest_path = root.join(PYPROJECT_TOML);
+    if !manifest_path.is_file() {
+        return Ok(vec![]);
+    }
+
+    let content = fs::read_to_string(&manifest_path)
+        .with_context(|| format!("failed to read {}", manifest_path.display()))?;
+    let manifest: toml::Table = toml::from_str(&content)
+        .with_context(|| format!("failed to parse {}", manifest_path.display()))?;
+
+    let site_packages = site_packages_dirs(root);
+    let dependencies = declared_requirements(&manifest)
+        .into_iter()
+        .map(|(name, requirement)| {
+            let metadata = read_metadata(&site_packages, &name);
+            let version = metadata
+                .as_ref()
+                .and_then(|m| metadata_field(m, "Version"))
+                .unwrap_or(requirement);
+            let license = metadata.as_ref().and_then(|m| {
+                metadata_field(m, "License-Expression").or_else(|| metadata_field(m, "License"))
+            });
+            Dependency {
+                name,
+                version,
+                ecosystem: Ecosystem::Python,
+                license,
+                manifest: manifest_path.clone(),
+            }
+        })
+        .collect();
+
+    Ok(dependencies)
+}
+
+/// Returns the names and version requirements of all declared dependencies.
+fn declared_requirements(manifest: &toml::Table) -> Vec<(String, String)> {
+    let mut requirements = vec![];
+
+    // PEP 621: `dependencies = ["requests>=2.0", ...]`
+    let project = manifest.get("project");
+    let pep621 = project
+        .and_then(|p| p.get("dependencies"))
+        .and_then(|d| d.as_array());
+    for requirement in pep621.into_iter().flatten().filter_map(|r| r.as_str()) {
+        requirements.push(parse_requirement(requirement));
+    }
+
+    // Poetry: `[tool.poetry.dependencies] requests = "^2.0"`
+    let poetry = manifest
+        .get("tool")
+        .and_then(|t| t.get("poetry"))
+        .and_then(|p| p.get("dependencies"))
+        .and_then(|d| d.as_table());
+    for (name, spec) in poetry.into_iter().flatten() {
+        if name == "python" {
+            continue;
+        }
+        let version = match spec {
+            toml::Value::String(version) => version.to_owned(),
+            spec => spec
+                .get("version")
+                .and_then(|v| v.as_str())
+                .unwrap_or("*")
+                .to_owned(),
+        };
+        requirements.push((name.to_owned(), version));
+    }
+
+    requirements
+}
+
+/// Splits a PEP 508 requirement into the package name and its version specifier.
+fn parse_requirement(requirement: &str) -> (String, String) {
+    let requirement = requirement.split(';').next().unwrap_or_default().trim();
+    let end = requirement
+        .find(|c: char| !(c.is_alphanumeric() || matches!(c, '-' | '_' | '.')))
+        .unwrap_or(requirement.len());
+
+    let name = requirement[..end].to_owned();
+    let mut specifier = requirement[end..].trim();
+    // Skip extras, e.g. `requests[socks]>=2.0`
+    if specifier.starts_with('[') {
+        specifier = specifier
+            .split_once(']')
+            .map(|(_, rest)| rest.trim())
+            .unwrap_or_default();
+    }
+
+    let version = match specifier
+        .trim_start_matches(['(', ' '])
+        .trim_end_matches(')')
+    {
+        "" => "*".to_owned(),
+        specifier => specifier.to_owned(),
+    };
+    (name, version)
+}
+
+/// Returns the `site-packages` directories of local virtual environments.
+fn site_packages_dirs(root: &Path) -> Vec<PathBuf> {
+    let mut dirs = vec![];
+    for venv in VIRTUAL_ENVS {
+        let venv = root.join(venv);
+        // Windows layout
+        let lib = venv.join("Lib").join("site-packages");
+        if lib.is_dir() {
+            dirs.push(lib);
+        }
+        // Unix layout: lib/pythonX.Y/site-packages
+        let Ok(entries) = fs::read_dir(venv.join("lib")) else {
+            continue;
+        };
+        for entry in entries.filter_map(Result::ok) {
+            let site_packages = entry.path().join("site-packages");
+            if site_packages.is_dir() {
+                dirs.push(site_packages);
+            }
+        }
+    }
+    dirs
+}
+
+/// Normalizes a package name for comparison as described in PEP 503.
+fn normalize_name(name: &str) -> String {
+    name.to_lowercase().replace(['-', '.'], "_")
+}
+
+/// Reads the `METADATA` file of the installed distribution named `name`.
+fn read_metadata(site_packages: &[PathBuf], name: &str) -> Option<String> {
+    let name = normalize_name(name);
+    site_packages
+        .iter()
+        .filter_map(|dir| fs::read_dir(dir).ok())
+        .flat_map(|entries| entries.filter_map(Result::ok))
+        .map(|entry| entry.path())
+        .find(|path| {
+            let dirname = path.file_name().unwrap_or_default().to_string_lossy();
+            dirname
+                .strip_suffix(".dist-info")
+                .and_then(|stem| stem.rsplit_once('-'))
+                .is_some_and(|(dist, _)| normalize_name(dist) == name)
+        })
+        .and_then(|path| fs::read_to_string(path.join("METADATA")).ok())
+}
+
+/// Returns the value of the core metadata header `field`.
+fn metadata_field(metadata: &str, field: &str) -> Option<String> {
+    metadata
+        .lines()
+        // Headers end at the first blank line, followed by the description
+        .take_while(|line| !line.is_empty())
+        .find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            let value = value.trim();
+            (key.eq_ignore_ascii_case(field) && !value.is_empty() && value != "UNKNOWN")
+                .then(|| value.to_owned())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_parse_requirement() {
+        assert_eq!(
+            parse_requirement("requests>=2.0"),
+            ("requests".into(), ">=2.0".into())
+        );
+        assert_eq!(
+            parse_requirement("requests[socks] (>=2.0); python_version<'3.8'"),
+            ("requests".into(), ">=2.0".into())
+        );
+        assert_eq!(parse_requirement("rich"), ("rich".into(), "*".into()));
+    }
+
+    #[test]
+    fn test_collect_with_virtual_env() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::write(
+            root.join(PYPROJECT_TOML),
+            r#"
+[project]
+name = "lotr-map"
+dependencies = ["Typing.Extensions>=4", "rich"]
+
+[tool.poetry.dependencies]
+python = "^3.10"
+click = "^8.0"
+"#,
+        )
+        .unwrap();
+
+        let dist_info = root
+            .join(".venv/lib/python3.12/site-packages")
+            .join("typing_extensions-4.11.0.dist-info");
+        fs::create_dir_all(&dist_info).unwrap();
+        fs::write(
+            dist_info.join("METADATA"),
+            "Metadata-Version: 2.1\nName: typing_extensions\nVersion: 4.11.0\nLicense-Expression: PSF-2.0\n\nLicense: ignored\n",
+        )
+        .unwrap();
+
+        let dependencies = collect(root).unwrap();
+        assert_eq!(dependencies.len(), 3);
+
+        let find = |name: &str| dependencies.iter().find(|d| d.name == name).unwrap();
+        assert_eq!(find("Typing.Extensions").version, "4.11.0");
+        assert_eq!(
+            find("Typing.Extensions").license.as_deref(),
+            Some("PSF-2.0")
+        );
+        assert_eq!(find("rich").license, None);
+        assert_eq!(find("click").version, "^8.0");
+
+        dir.close().unwrap();
+    }
+}
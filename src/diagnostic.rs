@@ -0,0 +1,254 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Errors pointing to the offending part of a config file or command line value.
+//!
+//! A [`Diagnostic`] is rendered with the line it refers to, underlining the
+//! offending span:
+//!
+//! ```text
+//! expected `,` or `}`
+//!  --> .licensarc:3:3
+//!   |
+//! 3 |   "license": "MIT"
+//!   |   ^
+//! ```
+
+use std::fmt;
+use std::ops::Range;
+
+/// An error about a span of source text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// What's wrong with the span.
+    message: String,
+
+    /// The source text the span refers to.
+    source: String,
+
+    /// The byte range of the offending text in `source`.
+    span: Range<usize>,
+
+    /// The name of the source, e.g. the path of a config file.
+    origin: Option<String>,
+
+    /// A short description of the span, written beneath it.
+    label: Option<String>,
+}
+
+impl Diagnostic {
+    /// Creates a diagnostic about the bytes `span` of `source`.
+    ///
+    /// The span is clamped to `source` and to character boundaries.
+    pub fn new<M, S>(message: M, source: S, span: Range<usize>) -> Self
+    where
+        M: Into<String>,
+        S: Into<String>,
+    {
+        let source = source.into();
+        let start = floor_char_boundary(&source, span.start);
+        let end = floor_char_boundary(&source, span.end).max(start);
+        Self {
+            message: message.into(),
+            source,
+            span: start..end,
+            origin: None,
+            label: None,
+        }
+    }
+
+    /// Creates a diagnostic about the text of `source` starting at the one-based
+    /// `line` and `column`, as reported by `serde_json`.
+    ///
+    /// The span extends over the JSON token ending at the position, if any, e.g. the
+    /// string whose value is invalid.
+    pub fn at<M, S>(message: M, source: S, line: usize, column: usize) -> Self
+    where
+        M: Into<String>,
+        S: Into<String>,
+    {
+        let source = source.into();
+        let line_start: usize = source
+            .split_inclusive('\n')
+            .take(line.saturating_sub(1))
+            .map(str::len)
+            .sum();
+        let line_text = source[line_start..].lines().next().unwrap_or_default();
+        let column = column.clamp(1, line_text.len().max(1));
+        let token = token_before(&line_text[..column.min(line_text.len())]);
+        let start = line_start + column - token.max(1);
+        Self::new(message, source, start..line_start + column)
+    }
+
+    /// Creates a diagnostic from a `serde_json` error about `source`.
+    ///
+    /// Returns `None` if the error has no position, e.g. if it occurred deserializing
+    /// an already parsed value.
+    pub fn from_json_error<S>(err: &serde_json::Error, source: S) -> Option<Self>
+    where
+        S: Into<String>,
+    {
+        if err.line() == 0 {
+            return None;
+        }
+        let message = err.to_string();
+        let position = format!(" at line {} column {}", err.line(), err.column());
+        let message = message.strip_suffix(&position).unwrap_or(&message);
+        Some(Self::at(message, source, err.line(), err.column()))
+    }
+
+    /// Sets the name of the source, e.g. the path of a config file.
+    pub fn with_origin<S>(mut self, origin: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.origin = Some(origin.into());
+        self
+    }
+
+    /// Sets a short description written beneath the span.
+    pub fn with_label<S>(mut self, label: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Returns the one-based line and column of the start of the span.
+    pub fn line_column(&self) -> (usize, usize) {
+        let before = &self.source[..self.span.start];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        (line, before[line_start..].chars().count() + 1)
+    }
+}
+
+impl std::error::Error for Diagnostic {}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (line, column) = self.line_column();
+        let line_start = self.source[..self.span.start]
+            .rfind('\n')
+            .map_or(0, |i| i + 1);
+        let line_end = self.source[line_start..]
+            .find('\n')
+            .map_or(self.source.len(), |i| line_start + i);
+        let text = self.source[line_start..line_end].trim_end_matches('\r');
+
+        // Line numbers are only meaningful for sources of several lines
+        let number = match self.origin.is_some() || self.source.contains('\n') {
+            true => line.to_string(),
+            false => String::new(),
+        };
+        let gutter = " ".repeat(number.len());
+
+        write!(f, "{}", self.message)?;
+        if let Some(origin) = self.origin.as_ref() {
+            write!(f, "\n{gutter}--> {origin}:{line}:{column}")?;
+        }
+        write!(f, "\n{gutter} |\n{number} | {text}\n{gutter} | ")?;
+
+        // Tabs are kept so that the underline lines up with the text
+        let prefix = &text[..(self.span.start - line_start).min(text.len())];
+        for c in prefix.chars() {
+            f.write_str(if c == '\t' { "\t" } else { " " })?;
+        }
+        let span_end = self.span.end.min(line_start + text.len());
+        let width = self.source[self.span.start..span_end.max(self.span.start)]
+            .chars()
+            .count()
+            .max(1);
+        write!(f, "{}", "^".repeat(width))?;
+        if let Some(label) = self.label.as_ref() {
+            write!(f, " {label}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the length of the JSON token at the end of `text`, i.e. a string
+/// including its quotes, or a literal such as a number.
+fn token_before(text: &str) -> usize {
+    if let Some(body) = text.strip_suffix('"') {
+        // The opening quote is the last one not escaped by a backslash
+        let mut end = body.len();
+        while let Some(quote) = body[..end].rfind('"') {
+            let backslashes = body[..quote]
+                .chars()
+                .rev()
+                .take_while(|&c| c == '\\')
+                .count();
+            if backslashes % 2 == 0 {
+                return text.len() - quote;
+            }
+            end = quote;
+        }
+        return 1;
+    }
+    text.chars()
+        .rev()
+        .take_while(|c| c.is_alphanumeric() || matches!(c, '.' | '-' | '+'))
+        .map(char::len_utf8)
+        .sum()
+}
+
+fn floor_char_boundary(text: &str, index: usize) -> usize {
+    let mut index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_diagnostic() {
+        let source = "{\n  \"owner\": \"ACME\"\n  \"license\": \"MIT\"\n}\n";
+        let diagnostic = Diagnostic::at("expected `,` or `}`", source, 3, 3)
+            .with_origin(".licensarc")
+            .with_label("expected `,` or `}`");
+        assert_eq!(diagnostic.line_column(), (3, 3));
+        assert_eq!(
+            diagnostic.to_string(),
+            "expected `,` or `}`\n --> .licensarc:3:3\n  |\n3 |   \"license\": \"MIT\"\n  |   ^ expected `,` or `}`"
+        );
+
+        // Invalid values are underlined entirely
+        let source = "{\n  \"year\": \"20x4\"\n}";
+        let diagnostic = Diagnostic::at("invalid year", source, 2, 16);
+        assert!(diagnostic
+            .to_string()
+            .ends_with("2 |   \"year\": \"20x4\"\n  |           ^^^^^^"));
+        let diagnostic = Diagnostic::at("invalid type", "{\"hidden\": 12}", 1, 13);
+        assert!(diagnostic
+            .to_string()
+            .ends_with(" | {\"hidden\": 12}\n |            ^^"));
+    }
+
+    #[test]
+    fn test_diagnostic_from_json_error() {
+        let source = "{\n  \"owner\": \"ACME\",\n}";
+        let err = serde_json::from_str::<serde_json::Value>(source).unwrap_err();
+        let diagnostic = Diagnostic::from_json_error(&err, source).unwrap();
+        assert_eq!(diagnostic.to_string(), "trailing comma\n  |\n3 | }\n  | ^");
+
+        let value = serde_json::json!({ "owner": 1 });
+        let err = serde_json::from_value::<BTreeMap<String, String>>(value).unwrap_err();
+        assert!(Diagnostic::from_json_error(&err, source).is_none());
+    }
+
+    #[test]
+    fn test_diagnostic_single_line() {
+        let diagnostic = Diagnostic::new("unknown term", "MIT OR Apch", 7..11);
+        assert_eq!(
+            diagnostic.to_string(),
+            "unknown term\n |\n | MIT OR Apch\n |        ^^^^"
+        );
+    }
+}
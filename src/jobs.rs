@@ -0,0 +1,32 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Process-wide parallelism settings.
+//!
+//! The number of jobs selected with `--jobs` sizes the global [rayon] thread pool
+//! used for file processing, as well as the number of threads walking the workspace.
+//! Without `--jobs`, all available CPUs are used.
+
+use std::sync::OnceLock;
+
+static JOBS: OnceLock<usize> = OnceLock::new();
+
+/// Limits all commands to `jobs` parallel threads.
+///
+/// Does nothing if `jobs` is `None`. Calling this function more than once has no effect.
+pub fn init(jobs: Option<usize>) {
+    let Some(jobs) = jobs else {
+        return;
+    };
+    if JOBS.set(jobs).is_err() {
+        return;
+    }
+    let _ = rayon::ThreadPoolBuilder::new()
+        .num_threads(jobs)
+        .build_global();
+}
+
+/// Returns the number of jobs selected with `--jobs`, if any.
+pub fn jobs() -> Option<usize> {
+    JOBS.get().copied()
+}
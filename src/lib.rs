@@ -4,14 +4,20 @@
 #![allow(dead_code, unused_variables)]
 
 pub mod cli;
+pub mod color;
 pub mod commands;
 pub mod config;
+pub mod jobs;
+pub mod logger;
 pub mod template;
 pub mod workspace;
 
+mod deps;
+mod diagnostic;
 mod error;
 mod ops;
 mod parser;
+mod sbom;
 mod schema;
 mod spdx;
 mod utils;
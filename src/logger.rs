@@ -0,0 +1,131 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Leveled logging for command output.
+//!
+//! Command output is emitted as [tracing] events and written to stderr. Events at
+//! the `info` level are printed as-is, while all other levels are prefixed with
+//! their severity. The maximum level is controlled by the `-v` and `-q` flags.
+//!
+//! With `--log-format json`, every event is written as a single JSON object,
+//! including its structured fields and the spans it was emitted in. Closed spans
+//! are reported along with their timings, which helps to identify slow phases.
+
+use clap::ValueEnum;
+use colored::Colorize;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::fmt::format::{FmtSpan, Writer};
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields};
+use tracing_subscriber::registry::LookupSpan;
+
+pub use tracing_subscriber::filter::LevelFilter;
+
+use std::fmt;
+use std::io;
+
+/// Output format of log events.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum LogFormat {
+    /// Human-readable command output.
+    #[default]
+    Text,
+
+    /// Newline-delimited JSON objects.
+    Json,
+}
+
+/// Returns the maximum log level for the given verbosity flags.
+///
+/// `quiet` restricts output to errors. Otherwise, each occurrence of `-v`
+/// increases the level, starting at `info`.
+pub fn level_filter(verbose: u8, quiet: bool) -> LevelFilter {
+    if quiet {
+        return LevelFilter::ERROR;
+    }
+    match verbose {
+        0 => LevelFilter::INFO,
+        1 => LevelFilter::DEBUG,
+        _ => LevelFilter::TRACE,
+    }
+}
+
+/// Installs the global logger with the given maximum level and output format.
+///
+/// Calling this function more than once has no effect.
+pub fn init(level: LevelFilter, format: LogFormat) {
+    let builder = tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_writer(io::stderr);
+
+    let _ = match format {
+        LogFormat::Text => builder.event_format(CliFormatter).try_init(),
+        LogFormat::Json => {
+            // Prevent terminal color codes from leaking into JSON messages
+            colored::control::set_override(false);
+            builder
+                .json()
+                .with_ansi(false)
+                .with_current_span(true)
+                .with_span_list(true)
+                .with_span_events(FmtSpan::CLOSE)
+                .try_init()
+        }
+    };
+}
+
+/// Formats events as plain command output lines.
+///
+/// Only the message of an event is printed; structured fields are reserved
+/// for machine-readable output formats.
+struct CliFormatter;
+
+impl<S, N> FormatEvent<S, N> for CliFormatter
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        match *event.metadata().level() {
+            Level::ERROR => write!(writer, "{}: ", "error".red().bold())?,
+            Level::WARN => write!(writer, "{}: ", "warning".yellow().bold())?,
+            Level::INFO => {}
+            Level::DEBUG => write!(writer, "{}: ", "debug".blue())?,
+            Level::TRACE => write!(writer, "{}: ", "trace".dimmed())?,
+        }
+        let mut visitor = MessageVisitor(writer.by_ref(), Ok(()));
+        event.record(&mut visitor);
+        visitor.1?;
+        writeln!(writer)
+    }
+}
+
+/// Writes the `message` field of an event.
+struct MessageVisitor<'a>(Writer<'a>, fmt::Result);
+
+impl Visit for MessageVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.1 = write!(self.0, "{value:?}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_filter() {
+        assert_eq!(level_filter(0, false), LevelFilter::INFO);
+        assert_eq!(level_filter(1, false), LevelFilter::DEBUG);
+        assert_eq!(level_filter(2, false), LevelFilter::TRACE);
+        assert_eq!(level_filter(5, false), LevelFilter::TRACE);
+        assert_eq!(level_filter(0, true), LevelFilter::ERROR);
+    }
+}
@@ -0,0 +1,177 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Backup copies of files created before they are modified.
+//!
+//! Backups are either stored next to the original file or mirrored into a
+//! dedicated backup directory. In both cases the backup filename is suffixed
+//! with [BACKUP_SUFFIX], which prevents backups from being picked up as
+//! candidates by subsequent runs.
+
+use anyhow::Result;
+use ignore::WalkBuilder;
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Suffix appended to the filename of backup copies.
+pub const BACKUP_SUFFIX: &str = ".licensa.bak";
+
+/// Determines where backup copies are stored.
+#[derive(Debug, Clone, Default)]
+pub struct Backup {
+    /// Optional directory mirroring the workspace structure.
+    ///
+    /// If `None`, backups are stored next to the original file.
+    pub dir: Option<PathBuf>,
+}
+
+impl Backup {
+    /// Creates a new backup policy storing backups in `dir`, if provided.
+    pub fn new(dir: Option<PathBuf>) -> Self {
+        Self { dir }
+    }
+
+    /// Returns the path of the backup for the file at `path`.
+    pub fn backup_path<R, P>(&self, workspace_root: R, path: P) -> PathBuf
+    where
+        R: AsRef<Path>,
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let mut backup_path = match self.dir.as_ref() {
+            None => path.to_path_buf(),
+            Some(dir) => {
+                let relative = path.strip_prefix(workspace_root.as_ref()).unwrap_or(path);
+                workspace_root.as_ref().join(dir).join(relative)
+            }
+        }
+        .into_os_string();
+
+        backup_path.push(BACKUP_SUFFIX);
+        backup_path.into()
+    }
+
+    /// Copies the file at `path` to its backup location and returns the backup path.
+    pub fn create<R, P>(&self, workspace_root: R, path: P) -> io::Result<PathBuf>
+    where
+        R: AsRef<Path>,
+        P: AsRef<Path>,
+    {
+        let backup_path = self.backup_path(workspace_root, &path);
+        if let Some(parent) = backup_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(path, &backup_path)?;
+        Ok(backup_path)
+    }
+}
+
+/// Checks whether `path` refers to a backup copy.
+pub fn is_backup<P>(path: P) -> bool
+where
+    P: AsRef<Path>,
+{
+    path.as_ref()
+        .file_name()
+        .is_some_and(|name| name.to_string_lossy().ends_with(BACKUP_SUFFIX))
+}
+
+/// Finds all backup copies within `root`.
+///
+/// Hidden directories and paths ignored by `.gitignore` or `.licensaignore` files
+/// are searched as well, since backups are commonly ignored, e.g. by `*.bak`.
+pub fn find_backups<P>(root: P) -> Result<Vec<PathBuf>>
+where
+    P: AsRef<Path>,
+{
+    let walker = WalkBuilder::new(root)
+        .standard_filters(false)
+        .filter_entry(|entry| entry.file_name() != ".git")
+        .build();
+
+    let mut backups = Vec::new();
+    for entry in walker {
+        let entry = entry?;
+        if entry.file_type().is_some_and(|t| t.is_file()) && is_backup(entry.path()) {
+            backups.push(entry.into_path());
+        }
+    }
+    backups.sort();
+
+    Ok(backups)
+}
+
+/// Removes all backup copies within `root` and returns the removed paths.
+pub fn remove_backups<P>(root: P) -> Result<Vec<PathBuf>>
+where
+    P: AsRef<Path>,
+{
+    let backups = find_backups(root)?;
+    for path in backups.iter() {
+        fs::remove_file(path)?;
+    }
+    Ok(backups)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_backup_path_next_to_file() {
+        let backup = Backup::default();
+        let root = Path::new("/workspace");
+        let path = root.join("src").join("main.rs");
+
+        let expected = root.join("src").join("main.rs.licensa.bak");
+        assert_eq!(backup.backup_path(root, path), expected);
+    }
+
+    #[test]
+    fn test_backup_path_in_backup_dir() {
+        let backup = Backup::new(Some(PathBuf::from(".backups")));
+        let root = Path::new("/workspace");
+        let path = root.join("src").join("main.rs");
+
+        let expected = root
+            .join(".backups")
+            .join("src")
+            .join("main.rs.licensa.bak");
+        assert_eq!(backup.backup_path(root, path), expected);
+    }
+
+    #[test]
+    fn test_create_and_remove_backups() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir(root.join("src")).unwrap();
+        let file_path = root.join("src").join("main.rs");
+        fs::write(&file_path, "fn main() {}").unwrap();
+
+        let backup_path = Backup::default().create(root, &file_path).unwrap();
+        assert!(is_backup(&backup_path));
+        assert_eq!(fs::read_to_string(&backup_path).unwrap(), "fn main() {}");
+
+        let backup_path = Backup::new(Some(PathBuf::from("backups")))
+            .create(root, &file_path)
+            .unwrap();
+        assert!(backup_path.starts_with(root.join("backups")));
+
+        // Backups ignored by Git or in hidden directories are found as well
+        fs::write(root.join(".gitignore"), "*.bak\n").unwrap();
+        fs::create_dir(root.join(".hidden")).unwrap();
+        let hidden_path = root.join(".hidden").join("main.rs");
+        fs::write(&hidden_path, "fn main() {}").unwrap();
+        Backup::default().create(root, &hidden_path).unwrap();
+
+        let removed = remove_backups(root).unwrap();
+        assert_eq!(removed.len(), 3);
+        assert!(find_backups(root).unwrap().is_empty());
+        assert!(file_path.exists());
+
+        dir.close().unwrap();
+    }
+}
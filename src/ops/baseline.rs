@@ -0,0 +1,156 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Baseline files allow a workspace to adopt Licensa incrementally.
+//!
+//! A baseline records the set of files that were missing a license header at the
+//! time it was written. Subsequent `verify` runs treat those files as known
+//! violations and only fail for files that are not part of the baseline.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+/// Default filename of a Licensa baseline file.
+pub const LICENSA_BASELINE_FILENAME: &str = ".licensa-baseline.json";
+
+/// Represents the set of grandfathered files of a workspace.
+///
+/// Paths are stored relative to the workspace root, using `/` as separator,
+/// so that a baseline can be shared across platforms.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Baseline {
+    files: BTreeSet<String>,
+}
+
+impl Baseline {
+    /// Creates a baseline from paths relative to `workspace_root`.
+    ///
+    /// Paths outside the workspace root are recorded as provided.
+    pub fn from_paths<R, I, P>(workspace_root: R, paths: I) -> Self
+    where
+        R: AsRef<Path>,
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        let files = paths
+            .into_iter()
+            .map(|path| baseline_key(&workspace_root, path))
+            .collect();
+        Self { files }
+    }
+
+    /// Reads a baseline from the file at `path`.
+    pub fn load<P>(path: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("failed to read baseline file {}", path.display()))?;
+        let baseline = serde_json::from_str::<Baseline>(&content)
+            .with_context(|| format!("failed to parse baseline file {}", path.display()))?;
+        Ok(baseline)
+    }
+
+    /// Writes the baseline as pretty-formatted JSON to the file at `path`.
+    pub fn save<P>(&self, path: P) -> Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(path, content)
+            .with_context(|| format!("failed to write baseline file {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Checks whether the file at `path` is recorded in the baseline.
+    pub fn contains<R, P>(&self, workspace_root: R, path: P) -> bool
+    where
+        R: AsRef<Path>,
+        P: AsRef<Path>,
+    {
+        self.files.contains(&baseline_key(workspace_root, path))
+    }
+
+    /// Returns the number of files recorded in the baseline.
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Checks if the baseline is empty.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
+/// Converts `path` into a workspace-relative, `/`-separated baseline entry.
+fn baseline_key<R, P>(workspace_root: R, path: P) -> String
+where
+    R: AsRef<Path>,
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let path = path.strip_prefix(workspace_root.as_ref()).unwrap_or(path);
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_baseline_contains_relative_paths() {
+        let root = PathBuf::from("/workspace");
+        let baseline = Baseline::from_paths(
+            &root,
+            [root.join("src").join("main.rs"), root.join("build.sh")],
+        );
+
+        assert_eq!(baseline.len(), 2);
+        assert!(baseline.contains(&root, root.join("src").join("main.rs")));
+        assert!(baseline.contains(&root, "build.sh"));
+        assert!(!baseline.contains(&root, root.join("src").join("lib.rs")));
+    }
+
+    #[test]
+    fn test_baseline_save_and_load() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let baseline_path = root.join(LICENSA_BASELINE_FILENAME);
+
+        let baseline = Baseline::from_paths(root, [root.join("a.rs"), root.join("b").join("c.py")]);
+        baseline.save(&baseline_path).unwrap();
+
+        let content = fs::read_to_string(&baseline_path).unwrap();
+        assert!(content.contains("\"b/c.py\""));
+
+        let loaded = Baseline::load(&baseline_path).unwrap();
+        assert_eq!(loaded, baseline);
+
+        dir.close().unwrap();
+    }
+
+    #[test]
+    fn test_baseline_load_invalid() {
+        let dir = tempdir().unwrap();
+        let baseline_path = dir.path().join(LICENSA_BASELINE_FILENAME);
+
+        assert!(Baseline::load(&baseline_path).is_err());
+
+        fs::write(&baseline_path, r#"{ "paths": [] }"#).unwrap();
+        assert!(Baseline::load(&baseline_path).is_err());
+
+        dir.close().unwrap();
+    }
+}
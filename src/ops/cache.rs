@@ -0,0 +1,312 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Caches of file checks, stored in the workspace's `.licensa` directory.
+//!
+//! The scan cache records the license header status of files between runs, so
+//! repeated `verify` and `apply` runs skip reading files whose modification time
+//! and size haven't changed since the previous run. It is discarded if it was
+//! written with a different read window or by a different version of the cache format.
+//!
+//! The verify cache records whether license headers are the headers `apply` would
+//! write, as checked by `verify --strict-origin`. Results are keyed by the hash of
+//! the file content and the fingerprint of the header template, so they're
+//! invalidated when either the file or the configured notice changes.
+
+use crate::template::header::find_interpreter_extension;
+use crate::template::{find_copyright_year, find_license_id, has_copyright_notice, is_generated};
+use crate::workspace::store::Store;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+/// Name of the scan cache entry within the workspace store.
+pub const SCAN_CACHE_FILENAME: &str = "cache";
+
+/// Name of the verify cache entry within the workspace store.
+pub const VERIFY_CACHE_FILENAME: &str = "verify-cache";
+
+/// Version of the cache format, bumped whenever cached results become stale,
+/// e.g. because header detection changed.
+const SCAN_CACHE_VERSION: u32 = 3;
+
+/// Modification time and size of a file, used to detect changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileStamp {
+    /// Modification time in nanoseconds since the Unix epoch.
+    pub mtime: u64,
+
+    /// Size of the file in bytes.
+    pub size: u64,
+}
+
+impl FileStamp {
+    /// Reads the stamp of the file at `path`.
+    ///
+    /// Returns `None` if the file's metadata or modification time is not available.
+    pub fn read<P>(path: P) -> Option<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let metadata = fs::metadata(path).ok()?;
+        let mtime = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+        Some(Self {
+            mtime: u64::try_from(mtime.as_nanos()).ok()?,
+            size: metadata.len(),
+        })
+    }
+}
+
+/// The license header status of a file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileStatus {
+    /// Whether the file is expected to carry a license header.
+    ///
+    /// Files generated by tools are not, unless they already contain one.
+    pub candidate: bool,
+
+    /// Whether the file contains a license header.
+    pub has_header: bool,
+
+    /// The SPDX license expression found in the header, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
+
+    /// The latest copyright year found in the header, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub year: Option<u32>,
+
+    /// The extension of the interpreter of the hash-bang line, if any, e.g. `.sh`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interpreter: Option<String>,
+}
+
+impl FileStatus {
+    /// Detects the status of a file from its leading bytes `head`.
+    pub fn detect(head: &[u8]) -> Self {
+        let has_header = has_copyright_notice(head);
+        Self {
+            candidate: has_header || !is_generated(head),
+            has_header,
+            license: has_header.then(|| find_license_id(head)).flatten(),
+            year: has_header.then(|| find_copyright_year(head)).flatten(),
+            interpreter: find_interpreter_extension(head).map(str::to_owned),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct CacheEntry {
+    stamp: FileStamp,
+    status: FileStatus,
+}
+
+/// Cached file statuses, keyed by `/`-separated paths relative to the workspace root.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanCache {
+    version: u32,
+    read_window: usize,
+    entries: BTreeMap<String, CacheEntry>,
+}
+
+impl ScanCache {
+    /// Creates an empty cache for statuses detected within the first `read_window` bytes.
+    pub fn new(read_window: usize) -> Self {
+        Self {
+            version: SCAN_CACHE_VERSION,
+            read_window,
+            entries: BTreeMap::new(),
+        }
+    }
+
+    /// Loads the cache from `store`.
+    ///
+    /// Returns an empty cache if none exists, or if the stored cache can't be read or
+    /// was written with a different read window or format version.
+    pub fn load(store: &Store, read_window: usize) -> Self {
+        match store.read::<ScanCache, _>(SCAN_CACHE_FILENAME) {
+            Ok(Some(cache))
+                if cache.version == SCAN_CACHE_VERSION && cache.read_window == read_window =>
+            {
+                cache
+            }
+            Ok(_) => Self::new(read_window),
+            Err(err) => {
+                debug!("discard scan cache: {err:#}");
+                Self::new(read_window)
+            }
+        }
+    }
+
+    /// Writes the cache to `store`, dropping entries of files that no longer exist.
+    pub fn save(&mut self, store: &Store, workspace_root: &Path) -> Result<()> {
+        self.entries
+            .retain(|path, _| workspace_root.join(path).is_file());
+        store.write(SCAN_CACHE_FILENAME, self)
+    }
+
+    /// Returns the read window the cached statuses were detected with.
+    pub fn read_window(&self) -> usize {
+        self.read_window
+    }
+
+    /// Returns the cached status of the file at `path`, relative to the workspace root,
+    /// if the file hasn't changed since it was cached.
+    pub fn get(&self, path: &Path, stamp: FileStamp) -> Option<&FileStatus> {
+        self.entries
+            .get(&cache_key(path))
+            .filter(|entry| entry.stamp == stamp)
+            .map(|entry| &entry.status)
+    }
+
+    /// Caches the status of the file at `path`, relative to the workspace root.
+    pub fn insert(&mut self, path: &Path, stamp: FileStamp, status: FileStatus) {
+        self.entries
+            .insert(cache_key(path), CacheEntry { stamp, status });
+    }
+
+    /// Returns the number of cached statuses.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if no statuses are cached.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Cached results of `verify --strict-origin` checks.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VerifyCache {
+    version: u32,
+
+    /// Whether the header is managed, keyed by `<content hash>:<template fingerprint>`.
+    entries: BTreeMap<String, bool>,
+
+    /// Keys looked up or inserted since the cache was loaded.
+    #[serde(skip)]
+    used: BTreeSet<String>,
+}
+
+impl VerifyCache {
+    /// Loads the cache from `store`.
+    ///
+    /// Returns an empty cache if none exists, or if the stored cache can't be read or
+    /// was written by a different version of the cache format.
+    pub fn load(store: &Store) -> Self {
+        match store.read::<VerifyCache, _>(VERIFY_CACHE_FILENAME) {
+            Ok(Some(cache)) if cache.version == SCAN_CACHE_VERSION => cache,
+            Ok(_) => Self::default(),
+            Err(err) => {
+                debug!("discard verify cache: {err:#}");
+                Self::default()
+            }
+        }
+    }
+
+    /// Writes the cache to `store`, dropping entries that weren't used since it was loaded.
+    pub fn save(&mut self, store: &Store) -> Result<()> {
+        let used = std::mem::take(&mut self.used);
+        self.entries.retain(|key, _| used.contains(key));
+        self.version = SCAN_CACHE_VERSION;
+        store.write(VERIFY_CACHE_FILENAME, self)
+    }
+
+    /// Returns the cached result for a file with `content_hash` checked against the
+    /// header template with `template_fingerprint`.
+    pub fn get(&mut self, content_hash: &str, template_fingerprint: &str) -> Option<bool> {
+        let key = format!("{content_hash}:{template_fingerprint}");
+        let managed = self.entries.get(&key).copied();
+        if managed.is_some() {
+            self.used.insert(key);
+        }
+        managed
+    }
+
+    /// Caches the result for a file with `content_hash` checked against the header
+    /// template with `template_fingerprint`.
+    pub fn insert(&mut self, content_hash: &str, template_fingerprint: &str, managed: bool) {
+        let key = format!("{content_hash}:{template_fingerprint}");
+        self.used.insert(key.clone());
+        self.entries.insert(key, managed);
+    }
+}
+
+fn cache_key(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_file_status_detect() {
+        let status = FileStatus::detect(b"// Copyright Bilbo\n// SPDX-License-Identifier: MIT\n");
+        assert!(status.candidate && status.has_header);
+        assert_eq!(status.license.as_deref(), Some("MIT"));
+        assert_eq!(status.year, None);
+
+        let status = FileStatus::detect(b"// Code generated by protoc-gen-go. DO NOT EDIT.\n");
+        assert!(!status.candidate && !status.has_header);
+    }
+
+    #[test]
+    fn test_scan_cache_invalidation() {
+        let dir = tempdir().unwrap();
+        let store = Store::new(dir.path());
+        std::fs::write(dir.path().join("main.rs"), "fn main() {}\n").unwrap();
+        let path = Path::new("main.rs");
+        let stamp = FileStamp::read(dir.path().join(path)).unwrap();
+        let status = FileStatus::detect(b"fn main() {}\n");
+
+        let mut cache = ScanCache::new(1024);
+        cache.insert(path, stamp, status.clone());
+        cache.insert(Path::new("removed.rs"), stamp, status.clone());
+        cache.save(&store, dir.path()).unwrap();
+
+        let cache = ScanCache::load(&store, 1024);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.get(path, stamp), Some(&status));
+
+        let changed = FileStamp {
+            size: stamp.size + 1,
+            ..stamp
+        };
+        assert_eq!(cache.get(path, changed), None);
+
+        // A different read window may change detection results
+        assert!(ScanCache::load(&store, 512).is_empty());
+    }
+
+    #[test]
+    fn test_verify_cache() {
+        let dir = tempdir().unwrap();
+        let store = Store::new(dir.path());
+
+        let mut cache = VerifyCache::load(&store);
+        cache.insert("content", "template", true);
+        cache.insert("stale", "template", false);
+        cache.save(&store).unwrap();
+
+        let mut cache = VerifyCache::load(&store);
+        assert_eq!(cache.get("content", "template"), Some(true));
+        assert_eq!(cache.get("content", "changed"), None);
+        cache.save(&store).unwrap();
+
+        // Entries unused by the previous run are dropped
+        let mut cache = VerifyCache::load(&store);
+        assert_eq!(cache.get("stale", "template"), None);
+        assert_eq!(cache.get("content", "template"), Some(true));
+    }
+}
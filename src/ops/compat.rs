@@ -0,0 +1,266 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Built-in compatibility matrix of common licenses.
+//!
+//! A license is compatible with the license of a project if code under that license
+//! may be included in the project without relicensing it. Licenses are grouped by
+//! the reach of their copyleft: permissive licenses may be included in any project,
+//! weak copyleft licenses only apply to the covered files or libraries, while strong
+//! and network copyleft licenses require the entire project to be licensed under
+//! compatible terms. Known exceptions, e.g. Apache-2.0 code in a GPL-2.0-only project,
+//! are listed separately.
+
+use crate::config::policy::parse_expression;
+
+use spdx::{LicenseItem, LicenseReq};
+
+/// Whether a license is compatible with the license of a project.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compatibility {
+    Compatible,
+    Incompatible,
+
+    /// One of the licenses is not covered by the built-in matrix.
+    Unknown,
+}
+
+/// The reach of the copyleft of a license.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Copyleft {
+    /// No copyleft, e.g. MIT.
+    None,
+
+    /// Copyleft of the covered files or library, e.g. MPL-2.0 or LGPL-3.0.
+    Weak,
+
+    /// Copyleft of the entire work, e.g. GPL-3.0.
+    Strong,
+
+    /// Copyleft of the entire work, including its use over a network, e.g. AGPL-3.0.
+    Network,
+}
+
+/// Licenses covered by the matrix and the reach of their copyleft.
+const LICENSES: &[(&str, Copyleft)] = &[
+    ("0BSD", Copyleft::None),
+    ("Apache-2.0", Copyleft::None),
+    ("BSD-2-Clause", Copyleft::None),
+    ("BSD-3-Clause", Copyleft::None),
+    ("BSL-1.0", Copyleft::None),
+    ("CC0-1.0", Copyleft::None),
+    ("ISC", Copyleft::None),
+    ("MIT", Copyleft::None),
+    ("MIT-0", Copyleft::None),
+    ("Unicode-3.0", Copyleft::None),
+    ("Unicode-DFS-2016", Copyleft::None),
+    ("Unlicense", Copyleft::None),
+    ("Zlib", Copyleft::None),
+    ("EPL-2.0", Copyleft::Weak),
+    ("LGPL-2.1-only", Copyleft::Weak),
+    ("LGPL-2.1-or-later", Copyleft::Weak),
+    ("LGPL-3.0-only", Copyleft::Weak),
+    ("LGPL-3.0-or-later", Copyleft::Weak),
+    ("MPL-2.0", Copyleft::Weak),
+    ("GPL-2.0-only", Copyleft::Strong),
+    ("GPL-2.0-or-later", Copyleft::Strong),
+    ("GPL-3.0-only", Copyleft::Strong),
+    ("GPL-3.0-or-later", Copyleft::Strong),
+    ("AGPL-3.0-only", Copyleft::Network),
+    ("AGPL-3.0-or-later", Copyleft::Network),
+];
+
+/// Pairs of licenses whose copyleft would allow combining them, but whose terms conflict.
+///
+/// The first license is the license of the included code, the second the project license.
+const INCOMPATIBLE: &[(&str, &str)] = &[
+    // Apache-2.0 has patent terms that GPLv2 considers additional restrictions
+    ("Apache-2.0", "GPL-2.0-only"),
+    // GPLv2-only code can't be relicensed under version 3
+    ("GPL-2.0-only", "GPL-3.0-only"),
+    ("GPL-2.0-only", "GPL-3.0-or-later"),
+    ("GPL-2.0-only", "AGPL-3.0-only"),
+    ("GPL-2.0-only", "AGPL-3.0-or-later"),
+    ("LGPL-3.0-only", "GPL-2.0-only"),
+    ("LGPL-3.0-or-later", "GPL-2.0-only"),
+    ("GPL-3.0-only", "GPL-2.0-only"),
+    ("GPL-3.0-or-later", "GPL-2.0-only"),
+    // EPL-2.0 is only GPL-compatible with a secondary license designation
+    ("EPL-2.0", "GPL-2.0-only"),
+    ("EPL-2.0", "GPL-2.0-or-later"),
+    ("EPL-2.0", "GPL-3.0-only"),
+    ("EPL-2.0", "GPL-3.0-or-later"),
+];
+
+/// Checks whether code licensed under the expression `license` may be included in a
+/// project licensed under the expression `project_license`.
+///
+/// If the project is offered under multiple licenses, e.g. `MIT OR Apache-2.0`, the
+/// license must be compatible with each of them. Of a license expression, e.g.
+/// `MIT OR GPL-3.0-only`, one alternative must be compatible.
+pub fn check_compatibility(license: &str, project_license: &str) -> Compatibility {
+    let (Some(license), Some(project_license)) =
+        (parse_expression(license), parse_expression(project_license))
+    else {
+        return Compatibility::Unknown;
+    };
+    let project_licenses: Vec<String> = project_license
+        .requirements()
+        .map(|req| license_id(&req.req))
+        .collect();
+
+    let check = |req: &LicenseReq| {
+        let id = license_id(req);
+        let results = project_licenses
+            .iter()
+            .map(|project| check_license(&id, project));
+        results.fold(Compatibility::Compatible, |result, next| {
+            match (result, next) {
+                (Compatibility::Incompatible, _) | (_, Compatibility::Incompatible) => {
+                    Compatibility::Incompatible
+                }
+                (Compatibility::Unknown, _) | (_, Compatibility::Unknown) => Compatibility::Unknown,
+                _ => Compatibility::Compatible,
+            }
+        })
+    };
+
+    if license.evaluate(|req| check(req) == Compatibility::Compatible) {
+        Compatibility::Compatible
+    } else if license.evaluate(|req| check(req) != Compatibility::Incompatible) {
+        Compatibility::Unknown
+    } else {
+        Compatibility::Incompatible
+    }
+}
+
+/// Checks whether code licensed under the license ID `id` may be included in a project
+/// licensed under the license ID `project_id`.
+fn check_license(id: &str, project_id: &str) -> Compatibility {
+    let (Some(copyleft), Some(project_copyleft)) = (copyleft(id), copyleft(project_id)) else {
+        return Compatibility::Unknown;
+    };
+    if id == project_id {
+        return Compatibility::Compatible;
+    }
+    if INCOMPATIBLE.contains(&(id, project_id)) {
+        return Compatibility::Incompatible;
+    }
+
+    let compatible = match copyleft {
+        Copyleft::None | Copyleft::Weak => true,
+        Copyleft::Strong => project_copyleft >= Copyleft::Strong,
+        Copyleft::Network => project_copyleft == Copyleft::Network,
+    };
+    match compatible {
+        true => Compatibility::Compatible,
+        false => Compatibility::Incompatible,
+    }
+}
+
+fn copyleft(id: &str) -> Option<Copyleft> {
+    LICENSES
+        .iter()
+        .find(|(license, _)| *license == id)
+        .map(|(_, copyleft)| *copyleft)
+}
+
+/// Returns the current SPDX ID of the license of `req`, e.g. `GPL-3.0-or-later` for
+/// the deprecated `GPL-3.0+`.
+fn license_id(req: &LicenseReq) -> String {
+    let LicenseItem::Spdx { id, or_later } = &req.license else {
+        return req.license.to_string();
+    };
+    if !id.is_gnu() {
+        return id.name.to_string();
+    }
+
+    let name = id.name;
+    let or_later = *or_later || name.ends_with("-or-later") || name.ends_with('+');
+    let version = name
+        .trim_end_matches('+')
+        .trim_end_matches("-or-later")
+        .trim_end_matches("-only");
+    match or_later {
+        true => format!("{version}-or-later"),
+        false => format!("{version}-only"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_permissive_licenses() {
+        use Compatibility::*;
+        assert_eq!(check_compatibility("MIT", "Apache-2.0"), Compatible);
+        assert_eq!(
+            check_compatibility("BSD-3-Clause", "GPL-3.0-only"),
+            Compatible
+        );
+        assert_eq!(
+            check_compatibility("Apache-2.0", "GPL-3.0-or-later"),
+            Compatible
+        );
+        assert_eq!(
+            check_compatibility("Apache-2.0", "GPL-2.0-only"),
+            Incompatible
+        );
+    }
+
+    #[test]
+    fn test_copyleft_licenses() {
+        use Compatibility::*;
+        assert_eq!(check_compatibility("GPL-3.0-only", "MIT"), Incompatible);
+        assert_eq!(check_compatibility("GPL-3.0", "GPL-3.0-only"), Compatible);
+        assert_eq!(check_compatibility("GPL-2.0+", "GPL-3.0-only"), Compatible);
+        assert_eq!(
+            check_compatibility("GPL-2.0-only", "GPL-3.0-only"),
+            Incompatible
+        );
+        assert_eq!(
+            check_compatibility("GPL-3.0-only", "AGPL-3.0-only"),
+            Compatible
+        );
+        assert_eq!(
+            check_compatibility("AGPL-3.0-only", "GPL-3.0-only"),
+            Incompatible
+        );
+        assert_eq!(check_compatibility("MPL-2.0", "MIT"), Compatible);
+        assert_eq!(
+            check_compatibility("LGPL-2.1-or-later", "Apache-2.0"),
+            Compatible
+        );
+    }
+
+    #[test]
+    fn test_expressions() {
+        use Compatibility::*;
+        assert_eq!(
+            check_compatibility("MIT OR GPL-3.0-only", "MIT"),
+            Compatible
+        );
+        assert_eq!(
+            check_compatibility("MIT AND GPL-3.0-only", "MIT"),
+            Incompatible
+        );
+        assert_eq!(
+            check_compatibility("GPL-3.0-only", "MIT OR GPL-3.0-only"),
+            Incompatible
+        );
+        assert_eq!(check_compatibility("MIT", "MIT OR Apache-2.0"), Compatible);
+    }
+
+    #[test]
+    fn test_unknown_licenses() {
+        use Compatibility::*;
+        assert_eq!(check_compatibility("Proprietary", "MIT"), Unknown);
+        assert_eq!(check_compatibility("CDDL-1.0", "MIT"), Unknown);
+        assert_eq!(check_compatibility("CDDL-1.0 OR MIT", "MIT"), Compatible);
+        assert_eq!(
+            check_compatibility("CDDL-1.0 OR GPL-3.0-only", "MIT"),
+            Unknown
+        );
+    }
+}
@@ -0,0 +1,432 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! License header coverage of a workspace.
+//!
+//! A [CoverageReport] aggregates the license header state of candidate files,
+//! both for the entire workspace and grouped by directory, file extension and language.
+//! Reports are rendered in one of the [CoverageFormat]s.
+
+use crate::ops::language::language_name;
+use crate::ops::scan::get_header_lookup_name_with_interpreter;
+use crate::template::header::FileTypes;
+
+use clap::ValueEnum;
+use colored::Colorize;
+use serde::Serialize;
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::Path;
+
+/// Key used for files located directly in the workspace root.
+pub const ROOT_DIR_KEY: &str = ".";
+
+/// License header coverage of a set of files.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Coverage {
+    /// Number of candidate files.
+    pub total: usize,
+
+    /// Number of files containing a license header.
+    pub licensed: usize,
+
+    /// Number of files missing a license header.
+    pub missing: usize,
+
+    /// Number of files per detected SPDX license expression.
+    pub licenses: BTreeMap<String, usize>,
+}
+
+impl Coverage {
+    /// Records a single file.
+    ///
+    /// `license_id` is the SPDX license expression found in a licensed file, if any.
+    pub fn add(&mut self, licensed: bool, license_id: Option<&str>) {
+        self.total += 1;
+        if !licensed {
+            self.missing += 1;
+            return;
+        }
+        self.licensed += 1;
+        if let Some(license_id) = license_id {
+            *self.licenses.entry(license_id.to_string()).or_default() += 1;
+        }
+    }
+
+    /// Returns the share of licensed files in percent.
+    ///
+    /// An empty set of files is considered fully covered.
+    pub fn percentage(&self) -> f64 {
+        if self.total == 0 {
+            return 100.0;
+        }
+        self.licensed as f64 * 100.0 / self.total as f64
+    }
+}
+
+/// License header coverage of a workspace.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoverageReport {
+    /// Coverage of all candidate files.
+    pub summary: Coverage,
+
+    /// Coverage by workspace-relative, `/`-separated parent directory.
+    pub directories: BTreeMap<String, Coverage>,
+
+    /// Coverage by file extension (or filename for files without extension).
+    ///
+    /// Scripts without a known extension are grouped by the extension of the
+    /// interpreter of their hash-bang line, e.g. `.sh`, as for license headers.
+    pub extensions: BTreeMap<String, Coverage>,
+
+    /// Coverage by language, e.g. `Python`, derived from the file extension.
+    pub languages: BTreeMap<String, Coverage>,
+}
+
+impl CoverageReport {
+    /// Records the file at `path` in the summary and all groupings.
+    ///
+    /// `interpreter` is the extension of the interpreter of the file's hash-bang
+    /// line, if any, see [`find_interpreter_extension`]. `file_types` maps the
+    /// file's extension to the one its extension and language are grouped by.
+    ///
+    /// [`find_interpreter_extension`]: crate::template::header::find_interpreter_extension
+    pub fn record<R, P>(
+        &mut self,
+        workspace_root: R,
+        path: P,
+        interpreter: Option<&str>,
+        file_types: &FileTypes,
+        licensed: bool,
+        license_id: Option<&str>,
+    ) where
+        R: AsRef<Path>,
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let directory = directory_key(workspace_root, path);
+        let extension = get_header_lookup_name_with_interpreter(path, interpreter, file_types);
+        let language = language_name(&extension, file_types);
+
+        self.summary.add(licensed, license_id);
+        self.directories
+            .entry(directory)
+            .or_default()
+            .add(licensed, license_id);
+        self.extensions
+            .entry(extension)
+            .or_default()
+            .add(licensed, license_id);
+        self.languages
+            .entry(language.to_string())
+            .or_default()
+            .add(licensed, license_id);
+    }
+}
+
+/// Output format of a coverage report.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum CoverageFormat {
+    /// Human-readable tables of the coverage by language, directory and extension.
+    #[default]
+    Table,
+
+    /// A JSON document of the report, e.g. for dashboards.
+    Json,
+
+    /// Comma-separated values with one row per group, e.g. for spreadsheets.
+    Csv,
+}
+
+/// Columns of the CSV format.
+const CSV_HEADER: &str = "group,name,total,licensed,missing,coverage";
+
+impl CoverageReport {
+    /// Renders the report in `format`.
+    ///
+    /// `skipped` is the number of candidate files that couldn't be checked.
+    pub fn render(&self, format: CoverageFormat, skipped: usize) -> String {
+        match format {
+            CoverageFormat::Table => self.render_table(skipped),
+            CoverageFormat::Json => self.render_json(skipped),
+            CoverageFormat::Csv => self.render_csv(),
+        }
+    }
+
+    fn render_table(&self, skipped: usize) -> String {
+        let summary = &self.summary;
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "License coverage: {}/{} files ({})",
+            summary.licensed,
+            summary.total,
+            format_percentage(summary)
+        );
+        if skipped > 0 {
+            let _ = writeln!(out, "Skipped {skipped} file(s), run with `-v` for details");
+        }
+
+        if summary.licensed > 0 {
+            let identified: usize = summary.licenses.values().sum();
+            let mut licenses: Vec<(String, usize)> = summary
+                .licenses
+                .iter()
+                .map(|(id, count)| (id.to_owned(), *count))
+                .collect();
+            if summary.licensed > identified {
+                licenses.push(("(no SPDX identifier)".into(), summary.licensed - identified));
+            }
+
+            let _ = writeln!(out, "\n{}", "Licenses".bold());
+            let width = licenses.iter().map(|(id, _)| id.len()).max().unwrap_or(0);
+            for (id, count) in licenses {
+                let _ = writeln!(out, "  {id:<width$}  {count:>6}");
+            }
+        }
+
+        write_coverage_table(&mut out, "Languages", &self.languages);
+        write_coverage_table(&mut out, "Directories", &self.directories);
+        write_coverage_table(&mut out, "Extensions", &self.extensions);
+        out
+    }
+
+    fn render_json(&self, skipped: usize) -> String {
+        #[derive(Serialize)]
+        struct Output<'a> {
+            #[serde(flatten)]
+            report: &'a CoverageReport,
+            skipped: usize,
+        }
+
+        let output = Output {
+            report: self,
+            skipped,
+        };
+        // Serializing maps with string keys and numbers can't fail
+        serde_json::to_string_pretty(&output).unwrap() + "\n"
+    }
+
+    fn render_csv(&self) -> String {
+        let mut out = format!("{CSV_HEADER}\n");
+        let groups = [
+            (
+                "summary",
+                &BTreeMap::from([(String::new(), self.summary.clone())]),
+            ),
+            ("language", &self.languages),
+            ("directory", &self.directories),
+            ("extension", &self.extensions),
+        ];
+        for (group, rows) in groups {
+            for (name, coverage) in rows {
+                let _ = writeln!(
+                    out,
+                    "{group},{},{},{},{},{:.1}",
+                    csv_field(name),
+                    coverage.total,
+                    coverage.licensed,
+                    coverage.missing,
+                    coverage.percentage()
+                );
+            }
+        }
+        out
+    }
+}
+
+fn write_coverage_table(out: &mut String, title: &str, rows: &BTreeMap<String, Coverage>) {
+    if rows.is_empty() {
+        return;
+    }
+
+    let width = rows
+        .keys()
+        .map(String::len)
+        .max()
+        .unwrap_or(0)
+        .max(title.len());
+    let _ = writeln!(
+        out,
+        "\n{}  {:>6}  {:>8}  {:>7}  {:>8}",
+        format!("{title:<width$}").bold(),
+        "total",
+        "licensed",
+        "missing",
+        "coverage"
+    );
+    for (key, coverage) in rows {
+        let _ = writeln!(
+            out,
+            "{key:<width$}  {:>6}  {:>8}  {:>7}  {:>8}",
+            coverage.total,
+            coverage.licensed,
+            coverage.missing,
+            format_percentage(coverage)
+        );
+    }
+}
+
+fn format_percentage(coverage: &Coverage) -> String {
+    format!("{:.1}%", coverage.percentage())
+}
+
+/// Quotes `value` for a CSV file if it contains a separator, quote or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        return format!("\"{}\"", value.replace('"', "\"\""));
+    }
+    value.to_string()
+}
+
+/// Returns the workspace-relative, `/`-separated parent directory of `path`.
+pub fn directory_key<R, P>(workspace_root: R, path: P) -> String
+where
+    R: AsRef<Path>,
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let relative = path.strip_prefix(workspace_root.as_ref()).unwrap_or(path);
+    let key = relative
+        .parent()
+        .map(|parent| {
+            parent
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/")
+        })
+        .unwrap_or_default();
+
+    if key.is_empty() {
+        return ROOT_DIR_KEY.to_string();
+    }
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::language::OTHER_LANGUAGE;
+    use crate::template::header::find_interpreter_extension;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_coverage_add() {
+        let mut coverage = Coverage::default();
+        assert_eq!(coverage.percentage(), 100.0);
+
+        coverage.add(true, Some("MIT"));
+        coverage.add(true, Some("MIT"));
+        coverage.add(true, None);
+        coverage.add(false, None);
+
+        assert_eq!(coverage.total, 4);
+        assert_eq!(coverage.licensed, 3);
+        assert_eq!(coverage.missing, 1);
+        assert_eq!(coverage.licenses.get("MIT"), Some(&2));
+        assert_eq!(coverage.percentage(), 75.0);
+    }
+
+    #[test]
+    fn test_coverage_report_record() {
+        let root = PathBuf::from("/workspace");
+        let file_types = FileTypes::default();
+        let mut report = CoverageReport::default();
+        report.record(&root, root.join("build.sh"), None, &file_types, false, None);
+        report.record(
+            &root,
+            root.join("src").join("main.rs"),
+            None,
+            &file_types,
+            true,
+            Some("MIT"),
+        );
+        report.record(
+            &root,
+            root.join("src").join("lib.rs"),
+            None,
+            &file_types,
+            false,
+            None,
+        );
+        report.record(
+            &root,
+            root.join("src").join("a").join("b.rs"),
+            None,
+            &file_types,
+            true,
+            None,
+        );
+        // Scripts without an extension are grouped by their interpreter
+        let interpreter = find_interpreter_extension(b"#!/usr/bin/env python3\nprint()\n");
+        report.record(
+            &root,
+            root.join("deploy"),
+            interpreter,
+            &file_types,
+            false,
+            None,
+        );
+        report.record(&root, root.join("LICENSE"), None, &file_types, false, None);
+
+        assert_eq!(report.summary.total, 6);
+        assert_eq!(report.summary.missing, 4);
+
+        assert_eq!(report.directories[ROOT_DIR_KEY].missing, 3);
+        assert_eq!(report.directories["src"].total, 2);
+        assert_eq!(report.directories["src/a"].licensed, 1);
+
+        assert_eq!(report.extensions[".rs"].total, 3);
+        assert_eq!(report.extensions[".sh"].missing, 1);
+
+        assert_eq!(report.languages["Rust"].licensed, 2);
+        assert_eq!(report.languages["Shell"].percentage(), 0.0);
+
+        assert_eq!(report.extensions[".py"].missing, 1);
+        assert_eq!(report.languages["Python"].total, 1);
+        assert_eq!(report.extensions["LICENSE"].total, 1);
+        assert_eq!(report.languages[OTHER_LANGUAGE].total, 1);
+    }
+
+    #[test]
+    fn test_coverage_report_render() {
+        let root = PathBuf::from("/workspace");
+        let file_types = FileTypes::default();
+        let mut report = CoverageReport::default();
+        report.record(&root, root.join("build.sh"), None, &file_types, false, None);
+        report.record(
+            &root,
+            root.join("a,b").join("main.rs"),
+            None,
+            &file_types,
+            true,
+            Some("MIT"),
+        );
+
+        assert_eq!(
+            report.render(CoverageFormat::Csv, 0),
+            "group,name,total,licensed,missing,coverage\n\
+            summary,,2,1,1,50.0\n\
+            language,Rust,1,1,0,100.0\n\
+            language,Shell,1,0,1,0.0\n\
+            directory,.,1,0,1,0.0\n\
+            directory,\"a,b\",1,1,0,100.0\n\
+            extension,.rs,1,1,0,100.0\n\
+            extension,.sh,1,0,1,0.0\n"
+        );
+
+        let json: serde_json::Value =
+            serde_json::from_str(&report.render(CoverageFormat::Json, 3)).unwrap();
+        assert_eq!(json["summary"]["licensed"], 1);
+        assert_eq!(json["summary"]["licenses"]["MIT"], 1);
+        assert_eq!(json["directories"]["a,b"]["total"], 1);
+        assert_eq!(json["skipped"], 3);
+
+        let table = report.render(CoverageFormat::Table, 3);
+        assert!(table.starts_with("License coverage: 1/2 files (50.0%)\nSkipped 3 file(s)"));
+    }
+}
@@ -0,0 +1,190 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Unified diffs of file modifications, e.g. to preview the license header
+//! that `apply` would insert into a file.
+
+use colored::Colorize;
+
+use std::ops::Range;
+
+/// Number of unchanged lines shown around a change.
+const CONTEXT_LINES: usize = 3;
+
+/// Number of leading lines of a file shown by [`insertion_preview`].
+pub const PREVIEW_LINES: usize = 20;
+
+/// Returns a unified diff of `old` and `new`, the content of the file at `path`
+/// before and after a modification.
+///
+/// Changes are expected to be contiguous, as is the case for inserted headers, so
+/// the diff consists of a single hunk. Returns an empty string if both are equal.
+pub fn unified_diff(path: &str, old: &str, new: &str) -> String {
+    let old_lines: Vec<&str> = old.split_inclusive('\n').collect();
+    let new_lines: Vec<&str> = new.split_inclusive('\n').collect();
+
+    let prefix = old_lines
+        .iter()
+        .zip(&new_lines)
+        .take_while(|(a, b)| a == b)
+        .count();
+    if prefix == old_lines.len() && prefix == new_lines.len() {
+        return String::new();
+    }
+    let suffix = old_lines[prefix..]
+        .iter()
+        .rev()
+        .zip(new_lines[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let start = prefix.saturating_sub(CONTEXT_LINES);
+    let context_after = suffix.min(CONTEXT_LINES);
+    let old_end = old_lines.len() - suffix + context_after;
+    let new_end = new_lines.len() - suffix + context_after;
+
+    let mut diff = format!("--- a/{path}\n+++ b/{path}\n");
+    diff.push_str(&format!(
+        "@@ -{} +{} @@\n",
+        hunk_range(start, old_end - start),
+        hunk_range(start, new_end - start)
+    ));
+    for line in &old_lines[start..prefix] {
+        push_line(&mut diff, ' ', line);
+    }
+    for line in &old_lines[prefix..old_lines.len() - suffix] {
+        push_line(&mut diff, '-', line);
+    }
+    for line in &new_lines[prefix..new_lines.len() - suffix] {
+        push_line(&mut diff, '+', line);
+    }
+    for line in &new_lines[new_lines.len() - suffix..new_end] {
+        push_line(&mut diff, ' ', line);
+    }
+    diff
+}
+
+/// Colorizes a unified diff returned by [`unified_diff`] for terminal output.
+///
+/// Colors are omitted if disabled, e.g. with `--color never` or the `NO_COLOR` environment variable.
+pub fn colorize_diff(diff: &str) -> String {
+    diff.split_inclusive('\n')
+        .map(|line| {
+            let (text, newline) = match line.strip_suffix('\n') {
+                Some(text) => (text, "\n"),
+                None => (line, ""),
+            };
+            let text = if text.starts_with("---") || text.starts_with("+++") {
+                text.bold()
+            } else if text.starts_with("@@") {
+                text.cyan()
+            } else if text.starts_with('+') {
+                text.green()
+            } else if text.starts_with('-') {
+                text.red()
+            } else {
+                text.normal()
+            };
+            format!("{text}{newline}")
+        })
+        .collect()
+}
+
+/// Returns the first `max_lines` lines of `content`, each along with whether it
+/// overlaps the byte range `inserted`, e.g. of a license header inserted into the file.
+///
+/// Lines don't include their line break.
+pub fn insertion_preview(
+    content: &str,
+    inserted: Range<usize>,
+    max_lines: usize,
+) -> Vec<(&str, bool)> {
+    let mut start = 0;
+    content
+        .split_inclusive('\n')
+        .take(max_lines)
+        .map(|line| {
+            let end = start + line.len();
+            let highlighted = start < inserted.end && end > inserted.start;
+            start = end;
+            (line.trim_end_matches(['\r', '\n']), highlighted)
+        })
+        .collect()
+}
+
+/// Colorizes the preview returned by [`insertion_preview`] of the file at `path`
+/// for terminal output, highlighting inserted lines in green.
+///
+/// Omitted lines are indicated by a trailing `...` line if `truncated` is set.
+pub fn colorize_preview(path: &str, lines: &[(&str, bool)], truncated: bool) -> String {
+    let mut preview = format!("{}\n", path.bold());
+    for (line, highlighted) in lines {
+        let line = match highlighted {
+            true => line.green(),
+            false => line.normal(),
+        };
+        preview.push_str(&format!("{line}\n"));
+    }
+    if truncated {
+        preview.push_str(&format!("{}\n", "...".dimmed()));
+    }
+    preview
+}
+
+/// Formats the line range of a hunk, where `start` is the zero-based index of
+/// its first line.
+fn hunk_range(start: usize, count: usize) -> String {
+    match count {
+        0 => format!("{start},0"),
+        count => format!("{},{count}", start + 1),
+    }
+}
+
+fn push_line(diff: &mut String, marker: char, line: &str) {
+    diff.push(marker);
+    diff.push_str(line);
+    if !line.ends_with('\n') {
+        diff.push_str("\n\\ No newline at end of file\n");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unified_diff_insertion() {
+        let old = "fn a() {}\nfn b() {}\nfn c() {}\nfn d() {}\n";
+        let new = "// Copyright Bilbo\n\nfn a() {}\nfn b() {}\nfn c() {}\nfn d() {}\n";
+        let expected = "--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1,3 +1,5 @@\n+// Copyright Bilbo\n+\n fn a() {}\n fn b() {}\n fn c() {}\n";
+        assert_eq!(unified_diff("src/lib.rs", old, new), expected);
+    }
+
+    #[test]
+    fn test_unified_diff_after_prologue() {
+        let old = "#!/bin/sh\necho";
+        let new = "#!/bin/sh\n# Copyright Bilbo\n\necho";
+        let expected = "--- a/run\n+++ b/run\n@@ -1,2 +1,4 @@\n #!/bin/sh\n+# Copyright Bilbo\n+\n echo\n\\ No newline at end of file\n";
+        assert_eq!(unified_diff("run", old, new), expected);
+    }
+
+    #[test]
+    fn test_unified_diff_empty_file() {
+        let expected = "--- a/x.py\n+++ b/x.py\n@@ -0,0 +1,1 @@\n+# Copyright Bilbo\n";
+        assert_eq!(unified_diff("x.py", "", "# Copyright Bilbo\n"), expected);
+        assert_eq!(unified_diff("x.py", "a\n", "a\n"), "");
+    }
+
+    #[test]
+    fn test_insertion_preview() {
+        let content = "#!/bin/sh\r\n# Copyright Bilbo\n\necho\nexit\n";
+        let expected = vec![
+            ("#!/bin/sh", false),
+            ("# Copyright Bilbo", true),
+            ("", true),
+            ("echo", false),
+        ];
+        assert_eq!(insertion_preview(content, 11..30, 4), expected);
+        assert!(insertion_preview("", 0..0, 4).is_empty());
+    }
+}
@@ -0,0 +1,45 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! GitHub Actions workflow commands, printed by `verify --format github` so that
+//! violations are shown inline on pull requests.
+
+/// Returns an `error` workflow command annotating the first line of the file at `path`.
+///
+/// `path` is expected to be relative to the repository root and `/`-separated.
+pub fn error_annotation(path: &str, message: &str) -> String {
+    format!(
+        "::error file={},line=1::{}",
+        escape_property(path),
+        escape_data(message)
+    )
+}
+
+/// Escapes the message of a workflow command.
+fn escape_data(s: &str) -> String {
+    s.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escapes a property value of a workflow command.
+fn escape_property(s: &str) -> String {
+    escape_data(s).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_annotation() {
+        assert_eq!(
+            error_annotation("src/main.rs", "Missing license header (MIT)"),
+            "::error file=src/main.rs,line=1::Missing license header (MIT)"
+        );
+        assert_eq!(
+            error_annotation("a,b:c%.rs", "100%\nmissing"),
+            "::error file=a%2Cb%3Ac%25.rs,line=1::100%25%0Amissing"
+        );
+    }
+}
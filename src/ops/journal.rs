@@ -0,0 +1,241 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! The modification journal records changes applied to files, so they can be reverted.
+//!
+//! Each `apply` run that modifies files appends a [JournalRun] to the journal,
+//! which is stored in the workspace's `.licensa` directory. An entry captures the
+//! byte range that was inserted into a file, the bytes it replaced, if any, and
+//! the hash of the resulting content. Reverting an entry is refused if the file has
+//! been modified since.
+
+use crate::utils::{sha256_hex, write_atomic};
+use crate::workspace::store::Store;
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+/// Name of the journal entry within the workspace store.
+pub const JOURNAL_FILENAME: &str = "journal";
+
+/// Represents a single file modification.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// Path to the modified file, relative to the workspace root.
+    pub path: PathBuf,
+
+    /// Start offset of the inserted bytes.
+    pub start: usize,
+
+    /// End offset (exclusive) of the inserted bytes.
+    pub end: usize,
+
+    /// SHA-256 hash of the file content after the modification.
+    pub hash: String,
+
+    /// The bytes replaced by the inserted bytes, restored when reverting.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub replaced: String,
+}
+
+impl JournalEntry {
+    /// Creates a new entry for a file modified by inserting `range` into its content.
+    pub fn new<P>(path: P, range: Range<usize>, content: &[u8]) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            start: range.start,
+            end: range.end,
+            hash: sha256_hex(content),
+            replaced: String::new(),
+        }
+    }
+
+    /// Creates a new entry for a file modified by replacing `replaced` with the bytes
+    /// in `range` of its content.
+    pub fn replacement<P>(path: P, range: Range<usize>, replaced: &str, content: &[u8]) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        Self {
+            replaced: replaced.to_owned(),
+            ..Self::new(path, range, content)
+        }
+    }
+
+    /// Reverts the modification captured by this entry.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or written, or if it has been
+    /// modified since the entry was recorded.
+    pub fn revert<R>(&self, workspace_root: R) -> Result<()>
+    where
+        R: AsRef<Path>,
+    {
+        let path = workspace_root.as_ref().join(&self.path);
+        let content =
+            fs::read(&path).with_context(|| format!("failed to read {}", path.display()))?;
+
+        if sha256_hex(&content) != self.hash || self.end > content.len() {
+            return Err(anyhow!(
+                "{} has been modified since it was last applied",
+                self.path.display()
+            ));
+        }
+
+        let content = [
+            &content[..self.start],
+            self.replaced.as_bytes(),
+            &content[self.end..],
+        ]
+        .concat();
+        write_atomic(&path, content)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+        Ok(())
+    }
+}
+
+/// Represents all file modifications of a single run.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct JournalRun {
+    /// The command that performed the modifications.
+    pub command: String,
+
+    /// The recorded modifications.
+    pub entries: Vec<JournalEntry>,
+}
+
+/// Represents the history of runs that modified workspace files.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Journal {
+    runs: Vec<JournalRun>,
+}
+
+impl Journal {
+    /// Loads the journal from the workspace store, or returns an empty journal.
+    pub fn load(store: &Store) -> Result<Self> {
+        let journal = store.read::<Journal, _>(JOURNAL_FILENAME)?;
+        Ok(journal.unwrap_or_default())
+    }
+
+    /// Saves the journal to the workspace store.
+    ///
+    /// The journal entry is removed from the store if no runs are recorded.
+    pub fn save(&self, store: &Store) -> Result<()> {
+        if self.runs.is_empty() {
+            return store.remove(JOURNAL_FILENAME);
+        }
+        store.write(JOURNAL_FILENAME, self)
+    }
+
+    /// Records a run. Runs without any entries are not recorded.
+    pub fn push(&mut self, run: JournalRun) {
+        if !run.entries.is_empty() {
+            self.runs.push(run);
+        }
+    }
+
+    /// Removes and returns the most recent run.
+    pub fn pop(&mut self) -> Option<JournalRun> {
+        self.runs.pop()
+    }
+
+    /// Returns the number of recorded runs.
+    pub fn len(&self) -> usize {
+        self.runs.len()
+    }
+
+    /// Checks if the journal has no recorded runs.
+    pub fn is_empty(&self) -> bool {
+        self.runs.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_journal_entry_revert() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let content = b"#!/bin/sh\n# Copyright Me\n\necho\n";
+        fs::write(root.join("run.sh"), content).unwrap();
+
+        let entry = JournalEntry::new("run.sh", 10..26, content);
+        entry.revert(root).unwrap();
+
+        let reverted = fs::read_to_string(root.join("run.sh")).unwrap();
+        assert_eq!(reverted, "#!/bin/sh\necho\n");
+
+        dir.close().unwrap();
+    }
+
+    #[test]
+    fn test_journal_entry_revert_replacement() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let content = b"// Copyright 2020-2024 Me\n";
+        fs::write(root.join("main.rs"), content).unwrap();
+
+        let entry = JournalEntry::replacement("main.rs", 13..22, "2020", content);
+        entry.revert(root).unwrap();
+
+        let reverted = fs::read_to_string(root.join("main.rs")).unwrap();
+        assert_eq!(reverted, "// Copyright 2020 Me\n");
+
+        dir.close().unwrap();
+    }
+
+    #[test]
+    fn test_journal_entry_revert_modified_file() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let content = b"// Copyright Me\n\nfn main() {}\n";
+        let entry = JournalEntry::new("main.rs", 0..17, content);
+
+        fs::write(
+            root.join("main.rs"),
+            b"// Copyright Me\n\nfn main() { todo!() }\n",
+        )
+        .unwrap();
+        assert!(entry.revert(root).is_err());
+
+        dir.close().unwrap();
+    }
+
+    #[test]
+    fn test_journal_push_pop_persist() {
+        let dir = tempdir().unwrap();
+        let store = Store::new(dir.path());
+
+        let mut journal = Journal::load(&store).unwrap();
+        assert!(journal.is_empty());
+
+        journal.push(JournalRun::default());
+        assert!(journal.is_empty());
+
+        journal.push(JournalRun {
+            command: "apply".into(),
+            entries: vec![JournalEntry::new("main.rs", 0..3, b"abc")],
+        });
+        journal.save(&store).unwrap();
+
+        let mut journal = Journal::load(&store).unwrap();
+        assert_eq!(journal.len(), 1);
+        assert_eq!(journal.pop().unwrap().entries.len(), 1);
+
+        journal.save(&store).unwrap();
+        assert!(!store.path(JOURNAL_FILENAME).exists());
+
+        dir.close().unwrap();
+    }
+}
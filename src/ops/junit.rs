@@ -0,0 +1,143 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! JUnit XML reports, printed by `verify --format junit` so that CI systems show
+//! checked files as test cases.
+//!
+//! Each checked file is a test case named after its workspace-relative path,
+//! which fails with the reason of its violation.
+
+use std::fmt::Write;
+
+/// Outcome of checking a single file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TestOutcome {
+    Passed,
+
+    /// The file violates the configuration for the given reason.
+    Failed(String),
+
+    /// The file wasn't checked for the given reason, e.g. as it's listed in the baseline.
+    Skipped(String),
+}
+
+/// A JUnit test suite of checked files.
+#[derive(Debug, Clone, Default)]
+pub struct JunitReport {
+    /// Name of the test suite.
+    name: String,
+
+    /// Workspace-relative, `/`-separated paths of the checked files and their outcome.
+    cases: Vec<(String, TestOutcome)>,
+}
+
+impl JunitReport {
+    /// Creates an empty test suite called `name`.
+    pub fn new<S>(name: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            name: name.into(),
+            cases: Vec::new(),
+        }
+    }
+
+    /// Adds a test case for the file at `path`.
+    pub fn add<S>(&mut self, path: S, outcome: TestOutcome)
+    where
+        S: Into<String>,
+    {
+        self.cases.push((path.into(), outcome));
+    }
+
+    /// Renders the report as a JUnit XML document, with test cases sorted by path.
+    pub fn render(&self) -> String {
+        let mut cases: Vec<_> = self.cases.iter().collect();
+        cases.sort_by(|(a, _), (b, _)| a.cmp(b));
+        let count = |f: fn(&TestOutcome) -> bool| cases.iter().filter(|(_, o)| f(o)).count();
+        let failures = count(|o| matches!(o, TestOutcome::Failed(_)));
+        let skipped = count(|o| matches!(o, TestOutcome::Skipped(_)));
+
+        let name = escape_xml(&self.name);
+        let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        let _ = writeln!(
+            out,
+            "<testsuites name=\"{name}\" tests=\"{}\" failures=\"{failures}\" skipped=\"{skipped}\">",
+            cases.len()
+        );
+        let _ = writeln!(
+            out,
+            "  <testsuite name=\"{name}\" tests=\"{}\" failures=\"{failures}\" errors=\"0\" skipped=\"{skipped}\">",
+            cases.len()
+        );
+        for (path, outcome) in cases {
+            let path = escape_xml(path);
+            let _ = write!(out, "    <testcase name=\"{path}\" classname=\"{name}\"");
+            match outcome {
+                TestOutcome::Passed => out.push_str("/>\n"),
+                TestOutcome::Failed(reason) => {
+                    let reason = escape_xml(reason);
+                    let _ = writeln!(
+                        out,
+                        ">\n      <failure message=\"{reason}\">{path}: {reason}</failure>\n    </testcase>"
+                    );
+                }
+                TestOutcome::Skipped(reason) => {
+                    let _ = writeln!(
+                        out,
+                        ">\n      <skipped message=\"{}\"/>\n    </testcase>",
+                        escape_xml(reason)
+                    );
+                }
+            }
+        }
+        out.push_str("  </testsuite>\n</testsuites>\n");
+        out
+    }
+}
+
+/// Escapes text and attribute values of an XML document.
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_junit_report() {
+        let mut report = JunitReport::new("licensa");
+        report.add("src/main.rs", TestOutcome::Passed);
+        report.add(
+            "src/<gen>.rs",
+            TestOutcome::Failed("Missing license header (MIT & \"Apache\")".into()),
+        );
+        report.add(
+            "legacy.sh",
+            TestOutcome::Skipped("Listed in baseline".into()),
+        );
+
+        assert_eq!(
+            report.render(),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="licensa" tests="3" failures="1" skipped="1">
+  <testsuite name="licensa" tests="3" failures="1" errors="0" skipped="1">
+    <testcase name="legacy.sh" classname="licensa">
+      <skipped message="Listed in baseline"/>
+    </testcase>
+    <testcase name="src/&lt;gen&gt;.rs" classname="licensa">
+      <failure message="Missing license header (MIT &amp; &quot;Apache&quot;)">src/&lt;gen&gt;.rs: Missing license header (MIT &amp; &quot;Apache&quot;)</failure>
+    </testcase>
+    <testcase name="src/main.rs" classname="licensa"/>
+  </testsuite>
+</testsuites>
+"#
+        );
+    }
+}
@@ -0,0 +1,113 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Names of the programming and markup languages of candidate files.
+
+use crate::template::header::FileTypes;
+
+/// Name of the language group of files without a known language.
+pub const OTHER_LANGUAGE: &str = "Other";
+
+/// Languages along with the file extensions (or extensionless file names) they're
+/// detected by, matched case-insensitively.
+const LANGUAGES: &[(&str, &[&str])] = &[
+    ("Ada", &[".adb", ".ads"]),
+    ("Batch", &[".bat", ".cmd"]),
+    ("C", &[".c", ".h"]),
+    ("C#", &[".cs"]),
+    ("C++", &[".cc", ".cpp", ".hh", ".hpp"]),
+    ("CMake", &[".cmake"]),
+    ("CSS", &[".css", ".less", ".sass", ".scss"]),
+    ("Clojure", &[".clj", ".cljc", ".cljs", ".edn"]),
+    ("Dart", &[".dart"]),
+    ("Dockerfile", &[".dockerfile", "dockerfile"]),
+    ("Elixir", &[".ex", ".exs"]),
+    ("Elm", &[".elm"]),
+    ("Emacs Lisp", &[".el"]),
+    ("Erlang", &[".erl"]),
+    ("Fortran", &[".f", ".f03", ".f08", ".f90", ".f95", ".for"]),
+    ("Go", &[".go"]),
+    ("GraphQL", &[".sdl"]),
+    ("Graphviz", &[".gv"]),
+    ("Groovy", &[".groovy"]),
+    ("HCL", &[".hcl", ".tf"]),
+    ("HTML", &[".html"]),
+    ("Haskell", &[".hs"]),
+    ("Java", &[".java"]),
+    ("JavaScript", &[".cjs", ".js", ".jsx", ".mjs"]),
+    ("Jinja", &[".j2"]),
+    ("Julia", &[".jl"]),
+    ("Jupyter Notebook", &[".ipynb"]),
+    ("Kotlin", &[".kt", ".kts"]),
+    ("Lisp", &[".lisp"]),
+    ("Lua", &[".lua"]),
+    ("Makefile", &[".mk"]),
+    ("Markdown", &[".markdown", ".md"]),
+    ("Nim", &[".nim", ".nimble", ".nims"]),
+    ("Nix", &[".nix"]),
+    ("OCaml", &[".ml", ".mli", ".mll", ".mly"]),
+    ("Objective-C", &[".m", ".mm"]),
+    ("PHP", &[".php"]),
+    ("Perl", &[".pl", ".pm"]),
+    ("PowerShell", &[".ps1", ".psd1", ".psm1"]),
+    ("Protocol Buffers", &[".proto"]),
+    ("Puppet", &[".pp"]),
+    ("Python", &[".py"]),
+    ("R", &[".r"]),
+    ("Ruby", &[".rb", "gemfile"]),
+    ("Rust", &[".rs"]),
+    ("SQL", &[".sql"]),
+    ("SVG", &[".svg"]),
+    ("Scala", &[".scala"]),
+    ("Shell", &[".sh"]),
+    ("Starlark", &[".bzl", ".build", "build"]),
+    ("Swift", &[".swift"]),
+    ("TOML", &[".toml"]),
+    ("Tcl", &[".tcl"]),
+    ("TeX", &[".cls", ".sty", ".tex"]),
+    ("TypeScript", &[".ts", ".tsx"]),
+    ("VHDL", &[".vhd", ".vhdl"]),
+    ("Verilog", &[".sv", ".v"]),
+    ("Vue", &[".vue"]),
+    ("XML", &[".wxi", ".wxl", ".wxs", ".xml"]),
+    ("YAML", &[".yaml", ".yml"]),
+    ("Zig", &[".zig"]),
+];
+
+/// Returns the name of the language of files with the path suffix `suffix`, e.g.
+/// `Python` for `.py`.
+///
+/// `suffix` is either an extension with a leading dot or, for well-known files such
+/// as `Makefile`, the file name. Suffixes of `file_types` are resolved to the
+/// extension they're mapped to. Returns [`OTHER_LANGUAGE`] for unknown suffixes.
+pub fn language_name(suffix: &str, file_types: &FileTypes) -> &'static str {
+    let suffix = file_types.resolve_extension(suffix);
+    LANGUAGES
+        .iter()
+        .find(|(_, suffixes)| suffixes.iter().any(|s| s.eq_ignore_ascii_case(&suffix)))
+        .map_or(OTHER_LANGUAGE, |(name, _)| *name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_language_name() {
+        let file_types = FileTypes::default();
+        assert_eq!(language_name(".py", &file_types), "Python");
+        assert_eq!(language_name(".R", &file_types), "R");
+        assert_eq!(language_name(".tsx", &file_types), "TypeScript");
+        assert_eq!(language_name("Makefile", &file_types), "Makefile");
+        assert_eq!(language_name("CMakeLists.txt", &file_types), "CMake");
+        assert_eq!(language_name(".txt", &file_types), OTHER_LANGUAGE);
+        assert_eq!(language_name("LICENSE", &file_types), OTHER_LANGUAGE);
+
+        // Configured file types are named after the extension they're mapped to
+        let file_types = BTreeMap::from([(".html.tpl".to_string(), ".html".to_string())]);
+        let file_types = FileTypes::parse(&file_types).unwrap();
+        assert_eq!(language_name(".html.tpl", &file_types), "HTML");
+    }
+}
@@ -1,6 +1,21 @@
 // Copyright 2024 Nelson Dominguez
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
+pub mod backup;
+pub mod baseline;
+pub mod cache;
+pub mod compat;
+pub mod coverage;
+pub mod diff;
+pub mod github;
+pub mod journal;
+pub mod junit;
+pub mod language;
+pub mod notice_file;
+pub mod permissions;
+pub mod plugin;
+pub mod progress;
+pub mod report;
 pub mod scan;
 pub mod stats;
 pub mod work_tree;
@@ -0,0 +1,122 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Generation of `NOTICE` files, as required by the Apache License 2.0 for
+//! attribution notices that must be redistributed with a work.
+//!
+//! A notice file names the project, followed by its copyright lines and the
+//! attribution notices of bundled third-party works:
+//!
+//! ```text
+//! Rocket
+//! Copyright 2020-2024 ACME Inc.
+//!
+//! This product bundles Foo, which is available under the MIT license.
+//! ```
+
+use crate::schema::LicenseYear;
+
+/// The name of the notice file at the root of a workspace.
+pub const NOTICE_FILENAME: &str = "NOTICE";
+
+/// Content of a notice file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NoticeFile {
+    /// The name of the project.
+    pub project: String,
+
+    /// Copyright lines of the project, e.g. `Copyright 2024 ACME Inc.`.
+    pub copyright: Vec<String>,
+
+    /// Attribution notices of bundled third-party works, one paragraph each.
+    pub attributions: Vec<String>,
+}
+
+impl NoticeFile {
+    /// Renders the notice file, with paragraphs separated by a blank line.
+    pub fn render(&self) -> String {
+        let mut content = format!("{}\n", self.project.trim());
+        for line in self.copyright.iter() {
+            content.push_str(line.trim());
+            content.push('\n');
+        }
+        for attribution in self.attributions.iter() {
+            let attribution = attribution.trim();
+            if !attribution.is_empty() {
+                content.push('\n');
+                content.push_str(attribution);
+                content.push('\n');
+            }
+        }
+        content
+    }
+}
+
+/// Returns the copyright year of the first copyright line of the notice file
+/// `content`, extended to `year`, e.g. `2020-2024` for `Copyright 2020 ACME Inc.`.
+///
+/// The last period of a list such as `2019, 2021-2023` is extended. Years ending
+/// in `present` or `year` are returned as is. Returns `None` if the notice file
+/// has no copyright line with a year.
+pub fn extend_notice_year(content: &str, year: u32) -> Option<LicenseYear> {
+    let line = content
+        .lines()
+        .find(|line| line.to_ascii_lowercase().contains("copyright"))?;
+    let mut periods: Vec<String> = line
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|token| !token.is_empty())
+        .skip_while(|token| token.parse::<LicenseYear>().is_err())
+        .take_while(|token| token.parse::<LicenseYear>().is_ok())
+        .map(str::to_string)
+        .collect();
+
+    let last = periods.last()?.parse::<LicenseYear>().ok()?;
+    if last.last_year().is_some_and(|last_year| last_year < year) {
+        let extended = LicenseYear::year_range(last.first_year(), year).ok()?;
+        *periods.last_mut()? = extended.to_string();
+    }
+    periods.join(", ").parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_notice_file() {
+        let notice = NoticeFile {
+            project: "Rocket".into(),
+            copyright: vec![
+                "Copyright 2024 ACME Inc.".into(),
+                "Copyright 2024 Foo Contributors".into(),
+            ],
+            attributions: vec![
+                "This product bundles Foo.\n".into(),
+                "".into(),
+                "This product includes software\ndeveloped by Bar.".into(),
+            ],
+        };
+        assert_eq!(
+            notice.render(),
+            "Rocket\nCopyright 2024 ACME Inc.\nCopyright 2024 Foo Contributors\n\nThis product bundles Foo.\n\nThis product includes software\ndeveloped by Bar.\n"
+        );
+    }
+
+    #[test]
+    fn test_extend_notice_year() {
+        let year = |content| extend_notice_year(content, 2024).map(|year| year.to_string());
+        assert_eq!(year("Rocket\nCopyright 2020 ACME\n").unwrap(), "2020-2024");
+        assert_eq!(
+            year("Rocket\nCopyright 2020-2022 ACME\n").unwrap(),
+            "2020-2024"
+        );
+        assert_eq!(year("Rocket\nCopyright 2024 ACME\n").unwrap(), "2024");
+        assert_eq!(year("Copyright 2020-present ACME").unwrap(), "2020-present");
+        assert_eq!(
+            year("Copyright 2019, 2021-2023 ACME").unwrap(),
+            "2019, 2021-2024"
+        );
+        assert_eq!(year("Rocket\nCopyright ACME\n"), None);
+        assert_eq!(year("Rocket\n"), None);
+    }
+}
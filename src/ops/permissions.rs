@@ -0,0 +1,136 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Writing of modified files, and handling of files without write permission, e.g.
+//! files checked out read-only.
+
+use crate::utils::write_atomic_with_mtime;
+
+use anyhow::{anyhow, Result};
+use clap::ValueEnum;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use std::fs::{self, Permissions};
+use std::io;
+use std::path::Path;
+
+/// Determines how read-only files are treated when they'd be modified.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum, JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum ReadOnlyPolicy {
+    /// Fail for read-only files, without attempting to write them.
+    #[default]
+    Error,
+
+    /// Leave read-only files unmodified.
+    Skip,
+
+    /// Make read-only files writable for the time of writing.
+    Chmod,
+}
+
+impl ReadOnlyPolicy {
+    /// Checks whether the file at `path` may be modified.
+    ///
+    /// Returns `Ok(false)` if the file is read-only and skipped by the policy.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file is read-only and the policy is [`ReadOnlyPolicy::Error`].
+    pub fn check(self, path: &Path) -> Result<bool> {
+        if !is_read_only(path) {
+            return Ok(true);
+        }
+        match self {
+            ReadOnlyPolicy::Error => Err(anyhow!(
+                "{} is read-only (pass `--read-only skip` or `--read-only chmod`)",
+                path.display()
+            )),
+            ReadOnlyPolicy::Skip => Ok(false),
+            ReadOnlyPolicy::Chmod => Ok(true),
+        }
+    }
+
+    /// Replaces the content of the file at `path` with `content`.
+    ///
+    /// With [`ReadOnlyPolicy::Chmod`], read-only files are made writable for the
+    /// time of writing, and read-only again afterwards. With `preserve_mtime`, the
+    /// file keeps its modification time.
+    pub fn write(self, path: &Path, content: &[u8], preserve_mtime: bool) -> io::Result<()> {
+        let mtime = match preserve_mtime {
+            true => Some(fs::metadata(path)?.modified()?),
+            false => None,
+        };
+        if self != ReadOnlyPolicy::Chmod || !is_read_only(path) {
+            return write_atomic_with_mtime(path, content, mtime);
+        }
+
+        let permissions = fs::metadata(path)?.permissions();
+        fs::set_permissions(path, writable(permissions.clone()))?;
+        let result = write_atomic_with_mtime(path, content, mtime);
+        // The original permissions are restored even if writing failed
+        fs::set_permissions(path, permissions)?;
+        result
+    }
+}
+
+/// Checks whether the file at `path` exists and is read-only.
+pub fn is_read_only(path: &Path) -> bool {
+    fs::metadata(path).is_ok_and(|metadata| metadata.permissions().readonly())
+}
+
+/// Returns `permissions` with write permission for the owner.
+#[cfg(unix)]
+fn writable(permissions: Permissions) -> Permissions {
+    use std::os::unix::fs::PermissionsExt;
+    Permissions::from_mode(permissions.mode() | 0o200)
+}
+
+/// Returns `permissions` without the read-only attribute.
+#[cfg(not(unix))]
+#[allow(clippy::permissions_set_readonly_false)]
+fn writable(mut permissions: Permissions) -> Permissions {
+    permissions.set_readonly(false);
+    permissions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn set_read_only(path: &Path) {
+        let mut permissions = fs::metadata(path).unwrap().permissions();
+        permissions.set_readonly(true);
+        fs::set_permissions(path, permissions).unwrap();
+    }
+
+    #[test]
+    fn test_read_only_policy() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("main.rs");
+        fs::write(&path, "fn main() {}\n").unwrap();
+        assert!(ReadOnlyPolicy::Error.check(&path).unwrap());
+
+        set_read_only(&path);
+        assert!(ReadOnlyPolicy::Error.check(&path).is_err());
+        assert!(!ReadOnlyPolicy::Skip.check(&path).unwrap());
+        assert!(ReadOnlyPolicy::Chmod.check(&path).unwrap());
+
+        ReadOnlyPolicy::Chmod
+            .write(&path, b"// Header\nfn main() {}\n", false)
+            .unwrap();
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "// Header\nfn main() {}\n"
+        );
+        assert!(is_read_only(&path));
+
+        // Make the file removable on all platforms
+        fs::set_permissions(&path, writable(fs::metadata(&path).unwrap().permissions())).unwrap();
+        dir.close().unwrap();
+    }
+}
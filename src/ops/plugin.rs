@@ -0,0 +1,129 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! External commands adding license headers to files without a built-in comment style.
+//!
+//! A plugin is run once per file. It reads a single line of JSON context from stdin,
+//! followed by the content of the file, and writes the new content of the file to
+//! stdout. Writing the content unchanged leaves the file untouched, e.g. if the plugin
+//! finds a license header that Licensa doesn't recognize.
+
+use anyhow::{anyhow, Context, Result};
+use serde::Serialize;
+
+use std::io::Write;
+use std::ops::Range;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// The context of a file passed to a plugin.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginContext<'a> {
+    /// Path of the file relative to the workspace root, with `/` separators.
+    pub path: &'a str,
+
+    /// The SPDX license expression of the file.
+    pub license: &'a str,
+
+    /// The rendered copyright notice, without comment markers.
+    pub notice: &'a str,
+}
+
+/// Runs the plugin `command` in `dir` for a file with `content` and returns the new
+/// content of the file.
+///
+/// # Errors
+///
+/// Returns an error if the command cannot be started or exits with a failure status.
+pub fn run_plugin(
+    command: &[String],
+    dir: &Path,
+    context: &PluginContext,
+    content: &[u8],
+) -> Result<Vec<u8>> {
+    let Some((program, args)) = command.split_first() else {
+        return Err(anyhow!("plugin command is empty"));
+    };
+
+    let mut input = serde_json::to_vec(context)?;
+    input.push(b'\n');
+    input.extend_from_slice(content);
+
+    let mut child = Command::new(program)
+        .args(args)
+        .current_dir(dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("failed to run plugin `{program}`"))?;
+
+    // Write stdin on a separate thread, so that a plugin writing its output before
+    // reading all of its input doesn't block on a full stdout pipe
+    let mut stdin = child.stdin.take().unwrap();
+    let writer = std::thread::spawn(move || stdin.write_all(&input));
+    let output = child
+        .wait_with_output()
+        .with_context(|| format!("failed to run plugin `{program}`"))?;
+    // Plugins may exit without reading their entire input
+    let _ = writer.join();
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!(
+            "plugin `{program}` failed with {}: {}",
+            output.status,
+            stderr.trim()
+        ));
+    }
+    Ok(output.stdout)
+}
+
+/// Returns the byte range in `old` replaced by a plugin, along with the byte range
+/// of its replacement in `new`.
+///
+/// Ranges are empty if the content is unchanged.
+pub fn changed_ranges(old: &[u8], new: &[u8]) -> (Range<usize>, Range<usize>) {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    (prefix..old.len() - suffix, prefix..new.len() - suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changed_ranges() {
+        assert_eq!(changed_ranges(b"abc", b"abc"), (3..3, 3..3));
+        assert_eq!(changed_ranges(b"{}", b"{\"a\"}"), (1..1, 1..4));
+        assert_eq!(changed_ranges(b"aXc", b"aYYc"), (1..2, 1..3));
+        assert_eq!(changed_ranges(b"", b"abc"), (0..0, 0..3));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_plugin() {
+        let context = PluginContext {
+            path: "notebook.ipynb",
+            license: "MIT",
+            notice: "Copyright Bilbo",
+        };
+        let command = ["sh", "-c", "read -r context; echo \"$context\"; cat"].map(String::from);
+        let output = run_plugin(&command, Path::new("."), &context, b"{}").unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "{\"path\":\"notebook.ipynb\",\"license\":\"MIT\",\"notice\":\"Copyright Bilbo\"}\n{}"
+        );
+
+        let command = ["sh", "-c", "echo broken >&2; exit 3"].map(String::from);
+        let err = run_plugin(&command, Path::new("."), &context, b"{}").unwrap_err();
+        assert!(err.to_string().contains("broken"));
+    }
+}
@@ -0,0 +1,171 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Progress reporting for long-running scans and file processing.
+//!
+//! The progress bar is drawn to stdout and is only visible if stdout is a
+//! terminal and informational output isn't disabled. When output is redirected
+//! or piped, all progress updates are silently discarded, so that logs and CI
+//! output remain clean.
+
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+use tracing::Level;
+
+use std::io::{stdout, IsTerminal};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+const SCAN_TEMPLATE: &str = "{spinner:.green} {prefix} scanning ... {pos} files";
+const PROCESS_TEMPLATE: &str =
+    "{prefix} [{bar:30.cyan/blue}] {pos}/{len} files ({msg}) [{elapsed_precise}, ETA {eta}]";
+
+/// Reports the progress of a command processing workspace files.
+///
+/// Cloning a `Progress` yields a handle to the same progress bar, which allows
+/// sharing it across worker threads.
+#[derive(Debug, Clone)]
+pub struct Progress {
+    bar: ProgressBar,
+    action: String,
+    counters: Arc<ProgressCounters>,
+}
+
+#[derive(Debug, Default)]
+struct ProgressCounters {
+    processed: AtomicUsize,
+    skipped: AtomicUsize,
+    failed: AtomicUsize,
+}
+
+impl Progress {
+    /// Creates a new progress bar labeled with `namespace`.
+    ///
+    /// `action` describes processed files in the progress message, e.g. `modified`.
+    /// The progress bar is hidden if stdout is not a terminal or if informational
+    /// output is disabled, e.g. by passing `--quiet`.
+    pub fn new<N>(namespace: N, action: N) -> Self
+    where
+        N: AsRef<str>,
+    {
+        let bar = if stdout().is_terminal() && tracing::enabled!(Level::INFO) {
+            ProgressBar::with_draw_target(None, ProgressDrawTarget::stdout())
+        } else {
+            ProgressBar::hidden()
+        };
+        bar.set_prefix(namespace.as_ref().to_string());
+
+        Self {
+            bar,
+            action: action.as_ref().to_string(),
+            counters: Arc::default(),
+        }
+    }
+
+    /// Creates a progress bar that never draws anything.
+    pub fn hidden() -> Self {
+        Self {
+            bar: ProgressBar::hidden(),
+            action: String::new(),
+            counters: Arc::default(),
+        }
+    }
+
+    /// Checks whether progress updates are drawn.
+    pub fn is_hidden(&self) -> bool {
+        self.bar.is_hidden()
+    }
+
+    /// Switches to the scanning phase, in which the number of files is unknown.
+    pub fn start_scan(&self) {
+        self.bar
+            .set_style(ProgressStyle::with_template(SCAN_TEMPLATE).unwrap());
+        self.bar.enable_steady_tick(Duration::from_millis(100));
+    }
+
+    /// Records a file found while scanning the workspace.
+    pub fn inc_scanned(&self) {
+        self.bar.inc(1);
+    }
+
+    /// Switches to the processing phase for `num_items` files.
+    pub fn start_processing(&self, num_items: usize) {
+        self.bar.disable_steady_tick();
+        self.bar.set_style(
+            ProgressStyle::with_template(PROCESS_TEMPLATE)
+                .unwrap()
+                .progress_chars("=> "),
+        );
+        self.bar.set_length(num_items as u64);
+        self.bar.set_position(0);
+        self.update_message();
+    }
+
+    /// Records a file the command acted upon.
+    pub fn inc_processed(&self) {
+        self.inc(&self.counters.processed);
+    }
+
+    /// Records a file that was skipped.
+    pub fn inc_skipped(&self) {
+        self.inc(&self.counters.skipped);
+    }
+
+    /// Records a file that failed to process.
+    pub fn inc_failed(&self) {
+        self.inc(&self.counters.failed);
+    }
+
+    /// Hides the progress bar while `f` runs, e.g. to print a line to stdout.
+    pub fn suspend<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        self.bar.suspend(f)
+    }
+
+    /// Removes the progress bar from the terminal.
+    pub fn finish(&self) {
+        self.bar.finish_and_clear();
+    }
+
+    fn inc(&self, counter: &AtomicUsize) {
+        counter.fetch_add(1, Ordering::Relaxed);
+        self.bar.inc(1);
+        self.update_message();
+    }
+
+    fn update_message(&self) {
+        let processed = self.counters.processed.load(Ordering::Relaxed);
+        let skipped = self.counters.skipped.load(Ordering::Relaxed);
+        let failed = self.counters.failed.load(Ordering::Relaxed);
+        self.bar.set_message(format!(
+            "{processed} {}, {skipped} skipped, {failed} failed",
+            self.action
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_counts_files() {
+        let progress = Progress::hidden();
+        progress.start_scan();
+        progress.inc_scanned();
+        progress.inc_scanned();
+
+        progress.start_processing(3);
+        progress.inc_processed();
+        progress.clone().inc_skipped();
+        progress.inc_failed();
+
+        assert!(progress.is_hidden());
+        assert_eq!(progress.bar.position(), 3);
+        assert_eq!(progress.counters.processed.load(Ordering::Relaxed), 1);
+        assert_eq!(progress.counters.skipped.load(Ordering::Relaxed), 1);
+        assert_eq!(progress.counters.failed.load(Ordering::Relaxed), 1);
+    }
+}
@@ -0,0 +1,391 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Shareable compliance reports generated by `verify --report`.
+//!
+//! A report summarizes license header coverage, breaks it down by directory and
+//! lists all offending files along with their violation. It is rendered as a self-contained HTML document
+//! or as Markdown, depending on the extension of the output file.
+
+use crate::ops::coverage::{directory_key, Coverage, CoverageReport};
+
+use anyhow::{anyhow, Context, Result};
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Write};
+use std::fs;
+use std::path::Path;
+
+/// Output format of a compliance report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Html,
+    Markdown,
+}
+
+impl ReportFormat {
+    /// Infers the report format from the extension of `path`.
+    pub fn from_path<P>(path: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let extension = path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+
+        match extension.as_str() {
+            "html" | "htm" => Ok(Self::Html),
+            "md" | "markdown" => Ok(Self::Markdown),
+            _ => Err(anyhow!(
+                "unsupported report format for {}, expected a .html or .md file",
+                path.display()
+            )),
+        }
+    }
+}
+
+/// Reason of a file offending the configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    /// The file has no license header.
+    Missing,
+
+    /// The license header differs from the one written by `apply`.
+    Modified,
+
+    /// The license header is not in the configured format.
+    WrongFormat,
+
+    /// The copyright year of the license header is older than the current year.
+    Outdated(u32),
+
+    /// The license of the header is denied by the license policy.
+    Denied(String),
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Missing => f.write_str("missing license header"),
+            Self::Modified => f.write_str("modified license header"),
+            Self::WrongFormat => f.write_str("license header in the wrong format"),
+            Self::Outdated(year) => write!(f, "outdated copyright year ({year})"),
+            Self::Denied(license) => write!(f, "license denied by the license policy ({license})"),
+        }
+    }
+}
+
+/// Represents the outcome of a `verify` run.
+#[derive(Debug, Clone, Default)]
+pub struct ComplianceReport {
+    /// License header coverage of all candidate files.
+    pub coverage: CoverageReport,
+
+    /// Offending files and their violation, grouped by parent directory.
+    pub violations: BTreeMap<String, Vec<(String, Violation)>>,
+
+    /// Number of files missing a license header that are listed in the baseline.
+    pub baselined: usize,
+}
+
+impl ComplianceReport {
+    /// Creates a new report for the given coverage.
+    pub fn new(coverage: CoverageReport) -> Self {
+        Self {
+            coverage,
+            ..Default::default()
+        }
+    }
+
+    /// Records the file at `path` as offending with `violation`.
+    pub fn add_violation<R, P>(&mut self, workspace_root: R, path: P, violation: Violation)
+    where
+        R: AsRef<Path>,
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let relative = path.strip_prefix(workspace_root.as_ref()).unwrap_or(path);
+        let file = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        self.violations
+            .entry(directory_key(workspace_root, path))
+            .or_default()
+            .push((file, violation));
+    }
+
+    /// Returns the total number of offending files.
+    pub fn num_violations(&self) -> usize {
+        self.violations.values().map(Vec::len).sum()
+    }
+
+    /// Renders the report in the given format.
+    pub fn render(&self, format: ReportFormat) -> String {
+        match format {
+            ReportFormat::Html => self.render_html(),
+            ReportFormat::Markdown => self.render_markdown(),
+        }
+    }
+
+    /// Renders the report and writes it to the file at `path`.
+    ///
+    /// The format is inferred from the extension of `path`.
+    pub fn save<P>(&self, path: P) -> Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let content = self.render(ReportFormat::from_path(path)?);
+        fs::write(path, content)
+            .with_context(|| format!("failed to write report {}", path.display()))?;
+        Ok(())
+    }
+
+    fn render_markdown(&self) -> String {
+        let mut out = String::new();
+        let summary = &self.coverage.summary;
+
+        out.push_str("# License Compliance Report\n\n");
+        out.push_str("## Summary\n\n");
+        out.push_str("| Files | Licensed | Violations | Baselined | Coverage |\n");
+        out.push_str("| ---: | ---: | ---: | ---: | ---: |\n");
+        let _ = writeln!(
+            out,
+            "| {} | {} | {} | {} | {:.1}% |",
+            summary.total,
+            summary.licensed,
+            self.num_violations(),
+            self.baselined,
+            summary.percentage()
+        );
+
+        if !summary.licenses.is_empty() {
+            out.push_str("\n## Licenses\n\n| License | Files |\n| --- | ---: |\n");
+            for (license, count) in summary.licenses.iter() {
+                let _ = writeln!(out, "| `{license}` | {count} |");
+            }
+        }
+
+        out.push_str("\n## Directories\n\n");
+        out.push_str("| Directory | Files | Licensed | Missing | Coverage |\n");
+        out.push_str("| --- | ---: | ---: | ---: | ---: |\n");
+        for (dir, coverage) in self.coverage.directories.iter() {
+            let _ = writeln!(
+                out,
+                "| `{dir}` | {} | {} | {} | {:.1}% |",
+                coverage.total,
+                coverage.licensed,
+                coverage.missing,
+                coverage.percentage()
+            );
+        }
+
+        out.push_str("\n## Offending Files\n");
+        if self.violations.is_empty() {
+            out.push_str("\nNo files violate the configuration.\n");
+        }
+        for (dir, files) in self.violations.iter() {
+            let _ = writeln!(out, "\n### `{dir}` ({})\n", files.len());
+            for (file, violation) in files {
+                let _ = writeln!(out, "- `{file}`: {violation}");
+            }
+        }
+
+        out
+    }
+
+    fn render_html(&self) -> String {
+        let mut out = String::new();
+        let summary = &self.coverage.summary;
+
+        out.push_str(HTML_HEAD);
+        out.push_str("<h1>License Compliance Report</h1>\n");
+
+        out.push_str("<h2>Summary</h2>\n<table>\n");
+        out.push_str("<tr><th>Files</th><th>Licensed</th><th>Violations</th><th>Baselined</th><th>Coverage</th></tr>\n");
+        let _ = writeln!(
+            out,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{:.1}%</td></tr>",
+            summary.total,
+            summary.licensed,
+            self.num_violations(),
+            self.baselined,
+            summary.percentage()
+        );
+        out.push_str("</table>\n");
+
+        if !summary.licenses.is_empty() {
+            out.push_str("<h2>Licenses</h2>\n<table>\n<tr><th>License</th><th>Files</th></tr>\n");
+            for (license, count) in summary.licenses.iter() {
+                let _ = writeln!(
+                    out,
+                    "<tr><td><code>{}</code></td><td>{count}</td></tr>",
+                    escape_html(license)
+                );
+            }
+            out.push_str("</table>\n");
+        }
+
+        out.push_str("<h2>Directories</h2>\n");
+        for (dir, coverage) in self.coverage.directories.iter() {
+            let files = self.violations.get(dir);
+            let _ = writeln!(
+                out,
+                "<details{}><summary><code>{}</code> {}</summary>",
+                if files.is_some() { " open" } else { "" },
+                escape_html(dir),
+                html_coverage(coverage)
+            );
+            if let Some(files) = files {
+                out.push_str("<ul>\n");
+                for (file, violation) in files {
+                    out.push_str(&html_violation(file, violation));
+                }
+                out.push_str("</ul>\n");
+            }
+            out.push_str("</details>\n");
+        }
+
+        out.push_str("<h2>Offending Files</h2>\n");
+        if self.violations.is_empty() {
+            out.push_str("<p>No files violate the configuration.</p>\n");
+        } else {
+            out.push_str("<ul>\n");
+            for (file, violation) in self.violations.values().flatten() {
+                out.push_str(&html_violation(file, violation));
+            }
+            out.push_str("</ul>\n");
+        }
+
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+}
+
+const HTML_HEAD: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>License Compliance Report</title>
+<style>
+body { font-family: sans-serif; margin: 2rem; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #ccc; padding: 0.25rem 0.75rem; text-align: right; }
+th:first-child, td:first-child { text-align: left; }
+.missing { color: #b00020; }
+</style>
+</head>
+<body>
+"#;
+
+fn html_coverage(coverage: &Coverage) -> String {
+    let missing = match coverage.missing {
+        0 => String::new(),
+        n => format!(", <span class=\"missing\">{n} missing</span>"),
+    };
+    format!(
+        "{}/{} files licensed ({:.1}%){missing}",
+        coverage.licensed,
+        coverage.total,
+        coverage.percentage()
+    )
+}
+
+fn html_violation(file: &str, violation: &Violation) -> String {
+    format!(
+        "<li><code>{}</code> <span class=\"missing\">{}</span></li>\n",
+        escape_html(file),
+        escape_html(&violation.to_string())
+    )
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::template::header::FileTypes;
+    use std::path::PathBuf;
+
+    fn example_report() -> ComplianceReport {
+        let root = PathBuf::from("/workspace");
+        let file_types = FileTypes::default();
+        let mut coverage = CoverageReport::default();
+        coverage.record(
+            &root,
+            root.join("src").join("main.rs"),
+            None,
+            &file_types,
+            true,
+            Some("MIT"),
+        );
+        coverage.record(
+            &root,
+            root.join("src").join("<lib>.rs"),
+            None,
+            &file_types,
+            false,
+            None,
+        );
+        coverage.record(&root, root.join("build.sh"), None, &file_types, false, None);
+
+        let mut report = ComplianceReport::new(coverage);
+        report.add_violation(&root, root.join("src").join("<lib>.rs"), Violation::Missing);
+        report.add_violation(&root, root.join("build.sh"), Violation::Modified);
+        report.add_violation(
+            &root,
+            root.join("src").join("main.rs"),
+            Violation::Denied("<GPL>".into()),
+        );
+        report.baselined = 1;
+        report
+    }
+
+    #[test]
+    fn test_report_format_from_path() {
+        assert_eq!(
+            ReportFormat::from_path("out.html").unwrap(),
+            ReportFormat::Html
+        );
+        assert_eq!(
+            ReportFormat::from_path("out.MD").unwrap(),
+            ReportFormat::Markdown
+        );
+        assert!(ReportFormat::from_path("out.pdf").is_err());
+        assert!(ReportFormat::from_path("out").is_err());
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let report = example_report();
+        assert_eq!(report.num_violations(), 3);
+
+        let md = report.render(ReportFormat::Markdown);
+        assert!(md.contains("| 3 | 1 | 3 | 1 | 33.3% |"));
+        assert!(md.contains("| `MIT` | 1 |"));
+        assert!(md.contains("### `src` (2)"));
+        assert!(md.contains("- `src/<lib>.rs`: missing license header"));
+        assert!(md.contains("- `build.sh`: modified license header"));
+    }
+
+    #[test]
+    fn test_render_html_escapes_paths() {
+        let html = example_report().render(ReportFormat::Html);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains(
+            "<li><code>src/&lt;lib&gt;.rs</code> <span class=\"missing\">missing license header</span></li>"
+        ));
+        assert!(html.contains("license denied by the license policy (&lt;GPL&gt;)"));
+        assert!(!html.contains("<lib>"));
+    }
+}
@@ -1,19 +1,27 @@
 // Copyright 2024 Nelson Dominguez
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
-use crate::template::header::SourceHeaders;
+use crate::config::Config;
+use crate::ops::progress::Progress;
+use crate::template::header::{
+    find_interpreter_extension, FileTypes, SourceHeaders, HASH_BANG_READ_WINDOW,
+};
+use crate::utils::{read_head, strip_verbatim_prefix};
+use anyhow::{anyhow, Result};
 use rayon::prelude::*;
+use tracing::{debug, warn};
 
 use crossbeam_channel::Receiver;
+use ignore::overrides::OverrideBuilder;
 use ignore::{DirEntry, WalkState};
 
 use std::borrow::Borrow;
-use std::path::{Path, PathBuf};
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 
-use crate::workspace::walker::{Walk, WalkBuilder};
-
-/// Default filename for the `Licensa` CLI ignore patterns.
-const LICENSA_IGNORE_FILE: &str = ".licensaignore";
+use crate::workspace::walker::{SymlinkPolicy, Walk, WalkBuilder, WalkResult};
 
 /// Configuration for a scan operation.
 #[derive(Debug, Clone)]
@@ -21,44 +29,244 @@ pub struct ScanConfig {
     /// Root directory to start scanning from.
     pub root: PathBuf,
 
-    /// Optional list of paths to exclude from the scan.
-    pub exclude: Option<Vec<&'static str>>,
+    /// Optional list of glob patterns of paths to exclude from the scan.
+    pub exclude: Option<Vec<String>>,
+
+    /// Optional list of glob patterns the scan is restricted to.
+    ///
+    /// Include patterns take precedence over exclude patterns.
+    pub include: Option<Vec<String>>,
 
-    /// Limit on the number of parallel scan operations.
+    /// Capacity of the channel candidate files are sent through.
+    ///
+    /// Walker threads block while the channel is full, which bounds the number of
+    /// file entries held in memory before they're processed.
     pub limit: usize,
+
+    /// Number of threads used to walk the workspace.
+    pub threads: usize,
+
+    /// Optional size limit in bytes. Larger files are skipped.
+    pub max_file_size: Option<u64>,
+
+    /// How symbolic links are treated.
+    pub symlinks: SymlinkPolicy,
+
+    /// Whether to scan hidden files and directories.
+    pub hidden: bool,
+
+    /// Whether to scan Git submodules and nested repositories.
+    pub submodules: bool,
+
+    /// Whether to scan directories containing vendored third-party code.
+    pub vendored: bool,
+
+    /// Whether to skip paths marked `linguist-generated` or `export-ignore` in `.gitattributes`.
+    pub git_attributes: bool,
+
+    /// Extensions of files handled by plugins, which are candidates even without a
+    /// built-in comment style.
+    pub plugin_extensions: Vec<String>,
+
+    /// File types whose files are candidates in addition to those with a built-in
+    /// comment style.
+    pub file_types: FileTypes,
+
+    /// Optional set of absolute file paths the scan is restricted to, e.g. the files
+    /// changed in a Git revision range.
+    ///
+    /// Files in the set are only candidates if they aren't excluded otherwise.
+    pub paths: Option<Arc<HashSet<PathBuf>>>,
+}
+
+/// Number of buffered file entries per walker thread, if no scan limit is configured.
+const DEFAULT_LIMIT_PER_THREAD: usize = 64;
+
+impl ScanConfig {
+    /// Creates a new configuration scanning `root` without any exclude or include patterns.
+    ///
+    /// The number of jobs selected with `--jobs` takes precedence over `threads`.
+    /// Unless set explicitly, `threads` defaults to the available parallelism of the
+    /// host and `limit` scales with the number of threads.
+    pub fn new<P>(root: P, threads: Option<usize>, limit: Option<usize>) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        let threads = crate::jobs::jobs()
+            .or(threads)
+            .filter(|threads| *threads > 0)
+            .unwrap_or_else(default_threads);
+        let limit = limit
+            .filter(|limit| *limit > 0)
+            .unwrap_or(threads * DEFAULT_LIMIT_PER_THREAD);
+
+        Self {
+            root: root.as_ref().to_path_buf(),
+            exclude: None,
+            include: None,
+            limit,
+            threads,
+            max_file_size: None,
+            symlinks: SymlinkPolicy::default(),
+            hidden: false,
+            submodules: false,
+            vendored: false,
+            git_attributes: true,
+            plugin_extensions: vec![],
+            file_types: FileTypes::default(),
+            paths: None,
+        }
+    }
+
+    /// Creates a new configuration scanning `root` with the exclude patterns, file
+    /// types and scan options of `config`.
+    ///
+    /// Include patterns, plugin extensions and paths are left to the caller.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file types of `config` are invalid.
+    pub fn from_config<P>(root: P, config: &Config) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        Ok(Self {
+            exclude: Some(config.exclude.clone()),
+            max_file_size: config.max_file_size,
+            symlinks: config.symlinks.unwrap_or_default(),
+            hidden: config.hidden.unwrap_or_default(),
+            submodules: config.include_submodules.unwrap_or_default(),
+            vendored: config.include_vendored.unwrap_or_default(),
+            git_attributes: !config.ignore_gitattributes.unwrap_or_default(),
+            file_types: config.file_types()?,
+            ..Self::new(root, config.scan_threads, config.scan_limit)
+        })
+    }
+}
+
+/// Returns the number of threads the host can run in parallel.
+pub fn default_threads() -> usize {
+    std::thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// Candidate files found by a scan.
+#[derive(Debug, Default)]
+pub struct Candidates {
+    /// Candidate files, in no particular order.
+    pub files: Vec<DirEntry>,
+
+    /// Number of candidate files skipped, e.g. for exceeding the maximum file size.
+    pub skipped: usize,
 }
 
 /// Represents a scanning operation.
 pub struct Scan {
     config: ScanConfig,
     walker: Walk,
+    progress: Progress,
+    stop: Option<Arc<AtomicBool>>,
 }
 
 impl Scan {
     /// Creates a new [Scan] instance of with the given configuration.
-    pub fn new(config: ScanConfig) -> Self {
-        let exclude = config.exclude.clone().unwrap_or_default();
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the exclude or include patterns is not a valid glob.
+    pub fn new(config: ScanConfig) -> Result<Self> {
         let mut walk_builder = WalkBuilder::new(&config.root);
-        walk_builder.add_ignore(LICENSA_IGNORE_FILE);
+        walk_builder.threads(config.threads);
+        walk_builder.follow_links(config.symlinks == SymlinkPolicy::Follow);
+        walk_builder.include_hidden(config.hidden);
+        walk_builder.include_submodules(config.submodules);
+        walk_builder.include_vendored(config.vendored);
+        walk_builder.respect_git_attributes(config.git_attributes);
+        walk_builder.exclude(config.exclude.clone())?;
+        walk_builder.include(config.include.clone())?;
+        let walker = walk_builder.build()?;
+
+        Ok(Self {
+            config,
+            walker,
+            progress: Progress::hidden(),
+            stop: None,
+        })
+    }
 
-        walk_builder.exclude(Some(exclude)).unwrap();
-        let walker = walk_builder.build().unwrap();
+    /// Reports each file found while scanning to `progress`.
+    pub fn with_progress(mut self, progress: &Progress) -> Self {
+        self.progress = progress.clone();
+        self
+    }
 
-        Self { config, walker }
+    /// Stops the scan once `stop` is set, e.g. after the first failure of a command.
+    pub fn with_stop_signal(mut self, stop: Arc<AtomicBool>) -> Self {
+        self.stop = Some(stop);
+        self
     }
 
-    pub fn find_candidates(mut self) -> Vec<DirEntry> {
-        self.walker.quit_while(|res| res.is_err());
-        self.walker
-            .send_while(|res| res.is_ok() && is_candidate(res.unwrap()));
-        self.walker.max_capacity(None);
-        self.walker
-            .run_task()
+    /// Collects all candidate files of the workspace.
+    ///
+    /// Files exceeding the configured maximum file size are left out and only counted.
+    /// When following symbolic links, files resolving to a path outside of the workspace
+    /// root and files reachable through multiple paths are skipped as well.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a symbolic link is found and the symlink policy is
+    /// [SymlinkPolicy::Error].
+    pub fn find_candidates(self) -> Result<Candidates> {
+        let progress = self.progress.clone();
+        let (rx, filter) = self.start();
+        let files = rx
             .iter()
+            .inspect(|_| progress.inc_scanned())
             .par_bridge()
             .into_par_iter()
             .filter_map(Result::ok)
-            .collect()
+            .collect();
+
+        filter.finish().map(|skipped| Candidates { files, skipped })
+    }
+
+    /// Calls `visit` for each candidate file while the workspace is still being scanned.
+    ///
+    /// Candidate files are selected as by [Scan::find_candidates]. Returns the number
+    /// of skipped candidate files.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a symbolic link is found and the symlink policy is
+    /// [SymlinkPolicy::Error].
+    pub fn for_each_candidate<F>(self, visit: F) -> Result<usize>
+    where
+        F: Fn(DirEntry) + Sync + Send,
+    {
+        let progress = self.progress.clone();
+        let (rx, filter) = self.start();
+        rx.iter()
+            .inspect(|_| progress.inc_scanned())
+            .par_bridge()
+            .into_par_iter()
+            .filter_map(Result::ok)
+            .for_each(visit);
+
+        filter.finish()
+    }
+
+    /// Starts walking the workspace and returns a receiver for candidate files.
+    fn start(mut self) -> (Receiver<WalkResult>, Arc<CandidateFilter>) {
+        let filter = Arc::new(CandidateFilter::new(&self.config));
+
+        let quit_filter = filter.clone();
+        let send_filter = filter.clone();
+        let stop = self.stop;
+        self.walker.quit_while(move |res| {
+            quit_filter.quit(&res) || stop.as_ref().is_some_and(|s| s.load(Ordering::Relaxed))
+        });
+        self.walker.send_while(move |res| send_filter.accept(res));
+        self.walker.max_capacity(Some(self.config.limit));
+        (self.walker.run_task(), filter)
     }
 
     /// Runs the scan in parallel and returns a receiver for receiving file entries.
@@ -70,20 +278,23 @@ impl Scan {
     /// Returns an error if there are issues with building or running the parallel walker.
     pub fn run(self) -> Receiver<FileEntry> {
         let (tx, rx) = crossbeam_channel::bounded::<FileEntry>(self.config.limit);
-        self.walker.run(|| {
-            let tx = tx.clone();
-            Box::new(move |result| {
-                if result.is_err() {
-                    return WalkState::Quit;
-                }
-
-                let entry = result.unwrap();
-                if is_candidate(&entry) {
-                    let entry = FileEntry::from(entry);
-                    tx.send(entry).unwrap();
-                }
-
-                WalkState::Continue
+        std::thread::spawn(move || {
+            let file_types = &self.config.file_types;
+            self.walker.run(|| {
+                let tx = tx.clone();
+                Box::new(move |result| {
+                    if result.is_err() {
+                        return WalkState::Quit;
+                    }
+
+                    let entry = result.unwrap();
+                    if is_candidate(&entry, file_types) && tx.send(FileEntry::from(entry)).is_err()
+                    {
+                        return WalkState::Quit;
+                    }
+
+                    WalkState::Continue
+                })
             })
         });
 
@@ -131,15 +342,189 @@ impl From<&DirEntry> for FileEntry {
     }
 }
 
-/// Checks if a directory entry is a candidate for applying a license.
-pub fn is_candidate<E>(entry: E) -> bool
+/// Decides which walk results are candidates and keeps track of skipped files.
+struct CandidateFilter {
+    root: PathBuf,
+    canonical_root: PathBuf,
+    max_file_size: Option<u64>,
+    symlinks: SymlinkPolicy,
+    plugin_extensions: Vec<String>,
+    file_types: FileTypes,
+    paths: Option<Arc<HashSet<PathBuf>>>,
+    skipped: AtomicUsize,
+    visited: Mutex<HashSet<PathBuf>>,
+    error: Mutex<Option<anyhow::Error>>,
+}
+
+impl CandidateFilter {
+    fn new(config: &ScanConfig) -> Self {
+        Self {
+            root: config.root.clone(),
+            canonical_root: config
+                .root
+                .canonicalize()
+                .map_or_else(|_| config.root.clone(), strip_verbatim_prefix),
+            max_file_size: config.max_file_size,
+            symlinks: config.symlinks,
+            plugin_extensions: config
+                .plugin_extensions
+                .iter()
+                .map(|extension| extension.trim_start_matches('.').to_lowercase())
+                .collect(),
+            file_types: config.file_types.clone(),
+            paths: config.paths.clone(),
+            skipped: AtomicUsize::new(0),
+            visited: Mutex::default(),
+            error: Mutex::default(),
+        }
+    }
+
+    /// Returns the number of skipped candidate files, or the error the scan was stopped with.
+    fn finish(&self) -> Result<usize> {
+        if let Some(err) = self.error.lock().unwrap().take() {
+            return Err(err);
+        }
+        Ok(self.skipped.load(Ordering::Relaxed))
+    }
+
+    /// Checks whether a plugin handles the file of `entry`.
+    fn has_plugin(&self, entry: &DirEntry) -> bool {
+        if !entry.file_type().is_some_and(|ftype| ftype.is_file()) {
+            return false;
+        }
+        let Some(extension) = entry.path().extension() else {
+            return false;
+        };
+        let extension = extension.to_string_lossy().to_lowercase();
+        self.plugin_extensions.contains(&extension)
+    }
+
+    /// Checks whether the walk should stop at `result`.
+    ///
+    /// Symbolic link cycles don't stop the walk.
+    fn quit(&self, result: &WalkResult) -> bool {
+        match result {
+            Ok(entry) => {
+                let is_forbidden_link = self.symlinks == SymlinkPolicy::Error
+                    && entry.depth() > 0
+                    && entry.path_is_symlink();
+                if is_forbidden_link {
+                    *self.error.lock().unwrap() = Some(anyhow!(
+                        "symbolic link found at {}, use `--symlinks follow` or `--symlinks skip` to process workspaces containing symbolic links",
+                        self.relative(entry.path()).display()
+                    ));
+                }
+                is_forbidden_link
+            }
+            Err(err) => loop_error_path(err).is_none(),
+        }
+    }
+
+    /// Checks whether `result` is a candidate file.
+    fn accept(&self, result: WalkResult) -> bool {
+        let entry = match result {
+            Ok(entry) => entry,
+            Err(err) => {
+                if let Some(path) = loop_error_path(&err) {
+                    warn!(
+                        path = %self.relative(path).display(),
+                        status = "skipped",
+                        "skip {}: symbolic link cycle",
+                        self.relative(path).display()
+                    );
+                }
+                return false;
+            }
+        };
+
+        if let Some(paths) = self.paths.as_ref() {
+            if !paths.contains(entry.path()) {
+                return false;
+            }
+        }
+        if !is_candidate(&entry, &self.file_types) && !self.has_plugin(&entry) {
+            return false;
+        }
+        if exceeds_file_size(&entry, self.max_file_size) {
+            self.skip(entry.path(), "exceeds maximum file size");
+            return false;
+        }
+        if self.symlinks != SymlinkPolicy::Follow {
+            return true;
+        }
+
+        // Guard against writes to files outside of the workspace, and against
+        // processing a file twice when it is reachable through multiple links.
+        let Ok(real_path) = entry.path().canonicalize().map(strip_verbatim_prefix) else {
+            self.skip(entry.path(), "failed to resolve symbolic links");
+            return false;
+        };
+        if !real_path.starts_with(&self.canonical_root) {
+            warn!(
+                path = %self.relative(entry.path()).display(),
+                status = "skipped",
+                "skip {}: symbolic link points outside of the workspace",
+                self.relative(entry.path()).display()
+            );
+            self.skipped.fetch_add(1, Ordering::Relaxed);
+            return false;
+        }
+        if !self.visited.lock().unwrap().insert(real_path) {
+            self.skip(entry.path(), "file already visited through another path");
+            return false;
+        }
+        true
+    }
+
+    fn skip(&self, path: &Path, reason: &str) {
+        let path = self.relative(path);
+        debug!(
+            path = %path.display(),
+            status = "skipped",
+            "skip {}: {reason}",
+            path.display()
+        );
+        self.skipped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn relative<'a>(&self, path: &'a Path) -> &'a Path {
+        path.strip_prefix(&self.root).unwrap_or(path)
+    }
+}
+
+/// Returns the path of the link that caused a symbolic link cycle, if `err` is one.
+fn loop_error_path(err: &ignore::Error) -> Option<&Path> {
+    match err {
+        ignore::Error::Loop { child, .. } => Some(child),
+        ignore::Error::WithPath { err, .. }
+        | ignore::Error::WithDepth { err, .. }
+        | ignore::Error::WithLineNumber { err, .. } => loop_error_path(err),
+        _ => None,
+    }
+}
+
+/// Checks if the file of a directory entry is larger than `max_file_size` bytes.
+///
+/// Files whose size can't be determined are never considered oversized.
+fn exceeds_file_size(entry: &DirEntry, max_file_size: Option<u64>) -> bool {
+    let Some(max_file_size) = max_file_size else {
+        return false;
+    };
+    entry
+        .metadata()
+        .is_ok_and(|metadata| metadata.len() > max_file_size)
+}
+
+/// Checks if a directory entry is a candidate for applying a license, i.e. a file
+/// with a built-in comment style or one of `file_types`.
+pub fn is_candidate<E>(entry: E, file_types: &FileTypes) -> bool
 where
     E: Borrow<DirEntry>,
 {
     let entry = entry.borrow();
 
     // Only consider entry if it is a regular file
-    if !entry.file_type().map_or(false, |ftype| ftype.is_file()) {
+    if !entry.file_type().is_some_and(|ftype| ftype.is_file()) {
         return false;
     }
 
@@ -148,38 +533,133 @@ where
         return false;
     }
 
-    let lookup_name = get_path_suffix(path);
-    SourceHeaders::find_header_definition_by_extension(&lookup_name).is_some()
+    let lookup_name = get_path_suffix(path, file_types);
+    if SourceHeaders::find_header_definition_by_extension(&lookup_name, file_types).is_some() {
+        return true;
+    }
+
+    // Extensionless scripts are identified by their hash-bang line
+    path.extension().is_none()
+        && read_head(path, HASH_BANG_READ_WINDOW)
+            .is_ok_and(|head| find_interpreter_extension(&head).is_some())
+}
+
+/// Returns the name used to look up the header definition of `path`, whose content
+/// starts with `head`.
+///
+/// Same as [`get_path_suffix`], except that scripts without a known suffix are
+/// identified by the interpreter of their hash-bang line.
+pub fn get_header_lookup_name<P>(path: P, head: &[u8], file_types: &FileTypes) -> String
+where
+    P: AsRef<Path>,
+{
+    get_header_lookup_name_with_interpreter(path, find_interpreter_extension(head), file_types)
+}
+
+/// Same as [`get_header_lookup_name`], with the extension of the hash-bang
+/// `interpreter` already resolved, e.g. from a cached file status.
+pub fn get_header_lookup_name_with_interpreter<P>(
+    path: P,
+    interpreter: Option<&str>,
+    file_types: &FileTypes,
+) -> String
+where
+    P: AsRef<Path>,
+{
+    let suffix = get_path_suffix(&path, file_types);
+    if SourceHeaders::find_header_definition_by_extension(&suffix, file_types).is_some() {
+        return suffix;
+    }
+    interpreter.map_or(suffix, str::to_owned)
+}
+
+/// Returns the name used to look up the header definition of `path`.
+///
+/// This is the name of well-known files such as `Makefile` or `CMakeLists.txt`,
+/// otherwise the lowercase suffix including the leading dot, e.g. `.rs` for
+/// `main.RS`, or the file name of files without an extension.
+///
+/// Of compound suffixes such as `.html.tpl`, the longest one with a known comment
+/// style wins, including suffixes of `file_types`. Without a known comment style,
+/// the last extension is returned, e.g. `.gz` for `logs.tar.gz`.
+pub fn get_path_suffix<P>(path: P, file_types: &FileTypes) -> String
+where
+    P: AsRef<Path>,
+{
+    let file_name = path
+        .as_ref()
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    if SourceHeaders::well_known_file_extension(&file_name).is_some() {
+        return file_name.into_owned();
+    }
+
+    // Suffixes from the longest to the shortest, ignoring the leading dot of hidden files
+    let lowercase = file_name.to_lowercase();
+    let suffixes: Vec<&str> = lowercase
+        .char_indices()
+        .skip(1)
+        .filter(|(_, c)| *c == '.')
+        .map(|(i, _)| &lowercase[i..])
+        .collect();
+    let Some(extension) = suffixes.last() else {
+        return file_name.into_owned();
+    };
+    suffixes
+        .iter()
+        .find(|suffix| {
+            file_types.contains(suffix) || file_types.find_header_definition(suffix).is_some()
+        })
+        .unwrap_or(extension)
+        .to_string()
+}
+
+/// Checks whether the glob `pattern`, matched relative to the scan root, points
+/// outside of it, e.g. `../shared/**`.
+pub fn points_outside_root(pattern: &str) -> bool {
+    let pattern = pattern.strip_prefix('!').unwrap_or(pattern);
+    Path::new(pattern)
+        .components()
+        .any(|component| component == Component::ParentDir)
 }
 
-#[inline]
-pub fn get_path_suffix<P>(path: P) -> String
+/// Returns the glob `patterns` matching none of `paths`, where patterns are matched
+/// relative to `root` as include patterns of a scan.
+///
+/// Negated patterns, e.g. `!src/generated/**`, are matched like their positive form.
+pub fn find_unmatched_patterns<'a, P>(
+    root: &Path,
+    patterns: &[&'a str],
+    paths: &[P],
+) -> Result<Vec<&'a str>>
 where
     P: AsRef<Path>,
 {
-    path.as_ref().extension().map_or_else(
-        || {
-            path.as_ref()
-                .file_name()
-                .and_then(|name| name.to_str())
-                .map_or(String::new(), |s| s.to_owned())
-        },
-        |extension| {
-            let mut lookup_name = String::with_capacity(extension.len() + 1);
-            lookup_name.push('.');
-            lookup_name.push_str(extension.to_str().unwrap_or_default());
-            lookup_name
-        },
-    )
+    let mut unmatched = Vec::new();
+    for pattern in patterns.iter().copied() {
+        let mut builder = OverrideBuilder::new(root);
+        builder.add(pattern.strip_prefix('!').unwrap_or(pattern))?;
+        let overrides = builder.build()?;
+        if !paths
+            .iter()
+            .any(|path| overrides.matched(path.as_ref(), false).is_whitelist())
+        {
+            unmatched.push(pattern);
+        }
+    }
+    Ok(unmatched)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::LICENSA_IGNORE_FILENAME as LICENSA_IGNORE_FILE;
     use crate::workspace::walker::WalkBuilder;
 
     #[allow(unused_imports)]
     use rayon::prelude::*;
+    use std::collections::BTreeMap;
     use std::env::current_dir;
     use std::fs::File;
     use std::io::Write;
@@ -192,19 +672,17 @@ mod tests {
     #[test]
     fn test_example_scan() {
         let config = ScanConfig {
-            exclude: Some(vec!["!**/target/*.py"]), // "!**/*.py", "!**/*.sh"
-            limit: 200,
-            root: current_dir().unwrap(),
+            exclude: Some(vec!["!**/target/*.py".into()]), // "!**/*.py", "!**/*.sh"
+            ..ScanConfig::new(current_dir().unwrap(), Some(2), Some(200))
         };
 
         let exclude = config.exclude.clone().unwrap_or_default();
         let mut walk_builder = WalkBuilder::new(&config.root);
-        walk_builder.add_ignore(LICENSA_IGNORE_FILE);
         walk_builder.exclude(Some(exclude)).unwrap();
 
         let mut walker = walk_builder.build().unwrap();
         walker.quit_while(|res| res.is_err());
-        walker.send_while(|res| res.is_ok() && is_candidate(res.unwrap()));
+        walker.send_while(|res| res.is_ok() && is_candidate(res.unwrap(), &FileTypes::default()));
         walker.max_capacity(None);
 
         let result = walker.run_task();
@@ -238,15 +716,14 @@ mod tests {
             .write_all(b"ignored.txt")
             .expect("Failed to write to .licensaignore file");
 
-        let scan_config = ScanConfig {
-            limit: 100,
-            exclude: None,
-            root: root_path.to_path_buf(),
-        };
-        let scan = Scan::new(scan_config);
-        let result = scan.run(); //.expect("Failed to execute scan");
+        let licensed_file_path = root_path.join("main.rs");
+        File::create(&licensed_file_path).expect("Failed to create source file");
 
-        // TODO: Implement tests
+        let scan_config = ScanConfig::new(root_path, Some(2), Some(100));
+        let scan = Scan::new(scan_config).expect("Failed to create scan");
+        let result: Vec<PathBuf> = scan.run().iter().map(|entry| entry.abspath).collect();
+
+        assert_eq!(result, [licensed_file_path]);
 
         drop(ignored_file_path);
         drop(licensaignore_path);
@@ -257,15 +734,250 @@ mod tests {
     fn test_scan_without_license_candidates() {
         let temp_dir = create_temp_dir();
         let root_path = temp_dir.path();
+        let scan_config = ScanConfig::new(root_path, Some(2), Some(100));
+        let scan = Scan::new(scan_config).expect("Failed to create scan");
+        let result = scan.run();
+
+        assert!(result.iter().next().is_none());
+    }
+
+    #[test]
+    fn test_scan_skips_excluded_paths() {
+        let temp_dir = create_temp_dir();
+        let root_path = temp_dir.path();
+        for dir in ["src", "target/debug", "scripts"] {
+            std::fs::create_dir_all(root_path.join(dir)).unwrap();
+        }
+        for file in [
+            "src/lib.rs",
+            "target/debug/build.rs",
+            "scripts/run.sh",
+            "setup.py",
+        ] {
+            File::create(root_path.join(file)).unwrap();
+        }
+
         let scan_config = ScanConfig {
-            limit: 100,
-            exclude: None,
-            root: root_path.to_path_buf(),
+            exclude: Some(vec!["target".into(), "*.py".into(), "/scripts/".into()]),
+            ..ScanConfig::new(root_path, Some(2), Some(100))
         };
-        let scan = Scan::new(scan_config);
-        let result = scan.run();
+        let progress = Progress::hidden();
+        let scan = Scan::new(scan_config)
+            .expect("Failed to create scan")
+            .with_progress(&progress);
+        let candidates: Vec<PathBuf> = scan
+            .find_candidates()
+            .unwrap()
+            .files
+            .into_iter()
+            .map(|entry| entry.into_path())
+            .collect();
+
+        assert_eq!(candidates, [root_path.join("src").join("lib.rs")]);
+    }
+
+    #[test]
+    fn test_scan_rejects_invalid_exclude_pattern() {
+        let temp_dir = create_temp_dir();
+        let scan_config = ScanConfig {
+            exclude: Some(vec!["src/[".into()]),
+            ..ScanConfig::new(temp_dir.path(), Some(2), Some(100))
+        };
+        assert!(Scan::new(scan_config).is_err());
+    }
 
-        // TODO: Assert that the result is Ok and the candidates list is empty
+    #[test]
+    fn test_scan_config_defaults() {
+        let config = ScanConfig::new("root", None, None);
+        assert_eq!(config.threads, default_threads());
+        assert_eq!(config.limit, default_threads() * DEFAULT_LIMIT_PER_THREAD);
+
+        let config = ScanConfig::new("root", Some(3), Some(10));
+        assert_eq!((config.threads, config.limit), (3, 10));
+
+        let config = ScanConfig::new("root", Some(0), Some(0));
+        assert_eq!(config.threads, default_threads());
+        assert!(config.limit > 0);
+    }
+
+    #[test]
+    fn test_scan_with_more_candidates_than_limit() {
+        let temp_dir = create_temp_dir();
+        for i in 0..20 {
+            File::create(temp_dir.path().join(format!("{i}.rs"))).unwrap();
+        }
+
+        let scan_config = ScanConfig::new(temp_dir.path(), Some(2), Some(1));
+        let scan = Scan::new(scan_config.clone()).expect("Failed to create scan");
+        assert_eq!(scan.find_candidates().unwrap().files.len(), 20);
+
+        let scan = Scan::new(scan_config).expect("Failed to create scan");
+        assert_eq!(scan.run().iter().count(), 20);
+    }
+
+    #[test]
+    fn test_scan_skips_oversized_files() {
+        let temp_dir = create_temp_dir();
+        let root_path = temp_dir.path();
+        std::fs::write(root_path.join("small.rs"), "fn main() {}").unwrap();
+        std::fs::write(root_path.join("bundle.js"), "x".repeat(1024)).unwrap();
+
+        let scan_config = ScanConfig {
+            max_file_size: Some(512),
+            ..ScanConfig::new(root_path, None, None)
+        };
+        let candidates = Scan::new(scan_config)
+            .expect("Failed to create scan")
+            .find_candidates()
+            .unwrap();
+
+        assert_eq!(candidates.skipped, 1);
+        assert_eq!(candidates.files.len(), 1);
+        assert_eq!(candidates.files[0].file_name(), "small.rs");
+    }
+
+    #[cfg(unix)]
+    fn create_symlink_workspace() -> (tempfile::TempDir, tempfile::TempDir) {
+        use std::os::unix::fs::symlink;
+
+        let outside = create_temp_dir();
+        std::fs::write(outside.path().join("outside.rs"), "").unwrap();
+
+        let temp_dir = create_temp_dir();
+        let root = temp_dir.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src").join("lib.rs"), "").unwrap();
+        symlink(root.join("src"), root.join("alias")).unwrap();
+        symlink(root, root.join("src").join("cycle")).unwrap();
+        symlink(outside.path().join("outside.rs"), root.join("outside.rs")).unwrap();
+
+        (temp_dir, outside)
+    }
+
+    #[cfg(unix)]
+    fn find_with_symlinks(root: &Path, symlinks: SymlinkPolicy) -> Result<Candidates> {
+        let scan_config = ScanConfig {
+            symlinks,
+            ..ScanConfig::new(root, None, None)
+        };
+        Scan::new(scan_config).unwrap().find_candidates()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_scan_skips_symlinks_by_default() {
+        let (temp_dir, _outside) = create_symlink_workspace();
+        let candidates = find_with_symlinks(temp_dir.path(), SymlinkPolicy::Skip).unwrap();
+
+        assert_eq!(candidates.files.len(), 1);
+        assert_eq!(
+            candidates.files[0].path(),
+            temp_dir.path().join("src/lib.rs")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_scan_follows_symlinks_within_workspace() {
+        let (temp_dir, _outside) = create_symlink_workspace();
+        let candidates = find_with_symlinks(temp_dir.path(), SymlinkPolicy::Follow).unwrap();
+
+        // `src/lib.rs` is reachable as `alias/lib.rs` too, and `outside.rs` escapes the root
+        assert_eq!(candidates.files.len(), 1);
+        assert_eq!(candidates.skipped, 2);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_scan_fails_on_symlinks() {
+        let (temp_dir, _outside) = create_symlink_workspace();
+        let result = find_with_symlinks(temp_dir.path(), SymlinkPolicy::Error);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_get_path_suffix() {
+        assert_eq!(get_path_suffix("src/main.rs", &FileTypes::default()), ".rs");
+        assert_eq!(
+            get_path_suffix("docs/CMakeLists.txt", &FileTypes::default()),
+            "CMakeLists.txt"
+        );
+        assert_eq!(get_path_suffix("notes.txt", &FileTypes::default()), ".txt");
+        assert_eq!(get_path_suffix("src/MAIN.RS", &FileTypes::default()), ".rs");
+        assert_eq!(
+            get_path_suffix("app.Dockerfile", &FileTypes::default()),
+            ".dockerfile"
+        );
+        assert_eq!(
+            get_path_suffix("src/app.test.ts", &FileTypes::default()),
+            ".ts"
+        );
+        assert_eq!(get_path_suffix("logs.tar.gz", &FileTypes::default()), ".gz");
+        assert_eq!(get_path_suffix(".bashrc", &FileTypes::default()), ".bashrc");
+        assert_eq!(
+            get_path_suffix("Makefile", &FileTypes::default()),
+            "Makefile"
+        );
+        assert_eq!(get_path_suffix("LICENSE", &FileTypes::default()), "LICENSE");
+    }
+
+    #[test]
+    fn test_get_path_suffix_file_types() {
+        let file_types = BTreeMap::from([(".tpl".to_string(), ".unknown".to_string())]);
+        assert!(FileTypes::parse(&file_types).is_err());
+        let file_types = BTreeMap::from([("html.tpl".to_string(), ".html".to_string())]);
+        assert!(FileTypes::parse(&file_types).is_err());
+
+        let file_types = BTreeMap::from([(".HTML.tpl".to_string(), "html".to_string())]);
+        let file_types = FileTypes::parse(&file_types).unwrap();
+        assert_eq!(
+            get_path_suffix("views/index.html.tpl", &file_types),
+            ".html.tpl"
+        );
+        assert_eq!(get_path_suffix("views/index.tpl", &file_types), ".tpl");
+        let definition = file_types.find_header_definition(".html.TPL").unwrap();
+        assert_eq!(definition.header_prefix.top, "<!--");
+    }
+
+    #[test]
+    fn test_find_unmatched_patterns() {
+        let root = Path::new("/workspace");
+        let paths = [root.join("src/main.rs"), root.join("src/gen/api.rs")];
+        let patterns = ["src/**/*.rs", "!src/gen/**", "docs/**", "!vendor/**"];
+        let unmatched = find_unmatched_patterns(root, &patterns, &paths).unwrap();
+        assert_eq!(unmatched, ["docs/**", "!vendor/**"]);
+
+        assert!(points_outside_root("../shared/**"));
+        assert!(points_outside_root("!src/../../shared"));
+        assert!(!points_outside_root("/src/**"));
+    }
+
+    #[test]
+    fn test_scan_includes_extensionless_scripts() {
+        let temp_dir = create_temp_dir();
+        std::fs::write(
+            temp_dir.path().join("deploy"),
+            "#!/usr/bin/env bash\necho\n",
+        )
+        .unwrap();
+        std::fs::write(temp_dir.path().join("notes"), "echo\n").unwrap();
+        std::fs::write(temp_dir.path().join("data.csv"), "#!/bin/sh\n").unwrap();
+
+        let scan = Scan::new(ScanConfig::new(temp_dir.path(), None, None)).unwrap();
+        let files = scan.find_candidates().unwrap().files;
+        let names: Vec<_> = files.iter().map(|entry| entry.file_name()).collect();
+        assert_eq!(names, ["deploy"]);
+
+        let path = temp_dir.path().join("deploy");
+        assert_eq!(
+            get_header_lookup_name(&path, b"#!/bin/bash\n", &FileTypes::default()),
+            ".sh"
+        );
+        assert_eq!(
+            get_header_lookup_name("main.rs", b"#!/bin/bash\n", &FileTypes::default()),
+            ".rs"
+        );
     }
 
     #[test]
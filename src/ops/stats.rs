@@ -5,8 +5,11 @@ use colored::Colorize;
 
 use std::{fmt, time::Instant};
 
+#[derive(Clone)]
 pub struct WorkTreeRunnerStatistics {
     ignored: usize,
+    generated: usize,
+    read_only: usize,
     action_count: usize,
     action: String,
     failed: usize,
@@ -24,6 +27,8 @@ impl WorkTreeRunnerStatistics {
         Self {
             failed: 0,
             ignored: 0,
+            generated: 0,
+            read_only: 0,
             num_items: 0,
             action_count: 0,
             action: action.as_ref().to_string(),
@@ -37,6 +42,20 @@ impl WorkTreeRunnerStatistics {
         self.ignored += 1;
         self
     }
+    pub fn add_ignored(&mut self, count: usize) -> &Self {
+        self.ignored += count;
+        self
+    }
+    /// Counts a file skipped because it's generated by a tool.
+    pub fn add_generated(&mut self) -> &Self {
+        self.generated += 1;
+        self
+    }
+    /// Counts a file skipped because it's read-only.
+    pub fn add_read_only(&mut self) -> &Self {
+        self.read_only += 1;
+        self
+    }
     pub fn add_action_count(&mut self) -> &Self {
         self.action_count += 1;
         self
@@ -45,6 +64,16 @@ impl WorkTreeRunnerStatistics {
         self.failed += 1;
         self
     }
+    /// Adds the counters of `other`, e.g. to aggregate the statistics of multiple runs.
+    pub fn merge(&mut self, other: &Self) -> &Self {
+        self.ignored += other.ignored;
+        self.generated += other.generated;
+        self.read_only += other.read_only;
+        self.action_count += other.action_count;
+        self.failed += other.failed;
+        self.num_items += other.num_items;
+        self
+    }
     pub fn set_items(&mut self, num_items: usize) -> &Self {
         self.num_items = num_items;
         self
@@ -54,13 +83,19 @@ impl WorkTreeRunnerStatistics {
         self
     }
 
-    pub fn count_ignored(self) -> usize {
+    pub fn count_ignored(&self) -> usize {
         self.ignored
     }
-    pub fn count_passed(self) -> usize {
+    pub fn count_generated(&self) -> usize {
+        self.generated
+    }
+    pub fn count_read_only(&self) -> usize {
+        self.read_only
+    }
+    pub fn count_passed(&self) -> usize {
         self.action_count
     }
-    pub fn count_failed(&mut self) -> usize {
+    pub fn count_failed(&self) -> usize {
         self.failed
     }
     pub fn num_items(&self) -> usize {
@@ -94,7 +129,14 @@ impl fmt::Display for WorkTreeRunnerStatistics {
         let failed = format!("{} failed", self.failed);
         let ignored = format!("{} ignored", self.ignored);
         let duration = format!("finished in {}", self.elapsed_time());
-        write!(f, "{status}. {action}; {failed}; {ignored}; {duration}")
+        write!(f, "{status}. {action}; {failed}; {ignored}; ")?;
+        if self.generated > 0 {
+            write!(f, "{} generated; ", self.generated)?;
+        }
+        if self.read_only > 0 {
+            write!(f, "{} read-only; ", self.read_only)?;
+        }
+        write!(f, "{duration}")
     }
 }
 
@@ -6,9 +6,14 @@
 #![allow(dead_code)]
 #![deny(bare_trait_objects)]
 
+use crate::utils::read_head;
+
 use crossbeam_channel::{Receiver, Sender};
 use rayon::prelude::*;
-use std::{fs, path::PathBuf, sync::Arc};
+use std::{fs, io, path::PathBuf, sync::Arc};
+
+/// Default number of leading bytes read from each file in the work tree.
+pub const DEFAULT_READ_WINDOW: usize = 1024;
 
 /// Macro for defining trait aliases with optional type parameters and where clauses.
 macro_rules! trait_aliases {(
@@ -50,9 +55,36 @@ macro_rules! trait_aliases {(
     )*
 )}
 
+/// Represents a file handed to the tasks of a work tree.
+///
+/// Only the leading bytes of the file are read eagerly. Tasks that need the
+/// entire content (e.g. to rewrite the file) can load it on demand, which keeps
+/// memory usage bounded when processing large files in parallel.
 pub struct FileTaskResponse {
-    pub content: String,
+    /// The leading bytes of the file, limited by the work tree's read window.
+    pub head: Vec<u8>,
+
+    /// Path to the file.
     pub path: PathBuf,
+
+    /// Size of the file in bytes.
+    pub size: u64,
+}
+
+impl FileTaskResponse {
+    /// Returns `true` if `head` holds the entire file content.
+    #[inline]
+    pub fn is_complete(&self) -> bool {
+        self.head.len() as u64 >= self.size
+    }
+
+    /// Returns the entire file content, reading it from disk if `head` is incomplete.
+    pub fn read_content(&self) -> io::Result<Vec<u8>> {
+        if self.is_complete() {
+            return Ok(self.head.clone());
+        }
+        fs::read(&self.path)
+    }
 }
 
 /// A trait representing a generic file processor.
@@ -183,6 +215,7 @@ where
 /// to run file processing on multiple paths concurrently.
 pub struct WorkTree {
     tasks: Vec<Box<dyn FileTask>>,
+    read_window: usize,
 }
 
 impl WorkTree {
@@ -193,9 +226,12 @@ impl WorkTree {
     /// * `context` - The context for file processing.
     /// * `function` - The function used to process file contents.
     ///
-    /// # Outputurns
+    /// # Returns
     ///
     /// A receiver for receiving results from the file processor.
+    ///
+    /// The receiver must be kept alive for as long as the work tree runs,
+    /// otherwise the task stops processing files after its first result.
     pub fn add_task<Context, Output, F>(
         &mut self,
         context: Context,
@@ -206,7 +242,9 @@ impl WorkTree {
         Output: Send + 'static,
         F: Function<Context, Output>,
     {
-        let (sender, receiver) = crossbeam_channel::bounded(100);
+        // Results are usually collected after the work tree has finished running,
+        // so the channel must not block the workers once a given capacity is reached.
+        let (sender, receiver) = crossbeam_channel::unbounded();
         let task = FunctionFileTask::new(sender, context, function);
         self.tasks.push(Box::new(task));
 
@@ -220,10 +258,12 @@ impl WorkTree {
     /// * `tree_paths` - A vector of `PathBuf` representing the work tree paths.
     pub fn run(&self, tree_paths: Vec<PathBuf>) {
         let initial_tasks = self.tasks.clone();
+        let read_window = self.read_window;
 
         let read_file = |path: PathBuf| {
-            let content = fs::read_to_string(&path).ok();
-            content.map(move |c| FileTaskResponse { content: c, path })
+            let size = fs::metadata(&path).ok()?.len();
+            let head = read_head(&path, read_window).ok()?;
+            Some(FileTaskResponse { head, path, size })
         };
 
         tree_paths
@@ -236,8 +276,18 @@ impl WorkTree {
             });
     }
 
+    /// Sets the number of leading bytes read from each file before running tasks.
+    #[inline]
+    pub fn read_window(&mut self, limit: usize) -> &mut Self {
+        self.read_window = limit;
+        self
+    }
+
     pub fn new() -> Self {
-        Self { tasks: vec![] }
+        Self {
+            tasks: vec![],
+            read_window: DEFAULT_READ_WINDOW,
+        }
     }
 }
 
@@ -271,6 +321,7 @@ mod tests {
 
         let processor = WorkTree {
             tasks: vec![Box::new(MockFileTask)],
+            read_window: DEFAULT_READ_WINDOW,
         };
 
         // Run with an empty work tree path vector
@@ -290,8 +341,9 @@ mod tests {
         let mut cloned_processor = function_processor.clone();
 
         let response = &FileTaskResponse {
-            content: "example test content".into(),
+            head: "example test content".into(),
             path: PathBuf::new(),
+            size: 20,
         };
 
         // Process file contents with the cloned processor
@@ -302,7 +354,7 @@ mod tests {
 
     #[test]
     fn test_work_tree_processor() {
-        let mut work_tree_processor = WorkTree { tasks: vec![] };
+        let mut work_tree_processor = WorkTree::new();
         let receiver = work_tree_processor.add_task(MockContext, mock_function);
         let (tmp_dir, tmp_file) = create_temp_file("work_tree_processor.txt");
 
@@ -316,4 +368,46 @@ mod tests {
 
         let _ = tmp_dir.close();
     }
+
+    #[test]
+    fn test_work_tree_bounded_read_window() {
+        #[derive(Clone)]
+        struct HeadContext;
+
+        fn read_window(_context: &mut HeadContext, response: &FileTaskResponse) -> usize {
+            assert!(!response.is_complete());
+            assert_eq!(response.read_content().unwrap().len() as u64, response.size);
+            response.head.len()
+        }
+
+        let (tmp_dir, tmp_file) = create_temp_file("large_file.txt");
+        fs::write(&tmp_file, "x".repeat(64)).unwrap();
+
+        let mut work_tree = WorkTree::new();
+        work_tree.read_window(16);
+        let receiver = work_tree.add_task(HeadContext, read_window);
+        work_tree.run(vec![tmp_file]);
+        assert_eq!(receiver.try_recv(), Ok(16));
+
+        let _ = tmp_dir.close();
+    }
+
+    #[test]
+    fn test_work_tree_processes_all_files() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let paths: Vec<PathBuf> = (0..256)
+            .map(|i| {
+                let path = tmp_dir.path().join(format!("file_{i}.txt"));
+                fs::write(&path, "content").unwrap();
+                path
+            })
+            .collect();
+
+        let mut work_tree = WorkTree::new();
+        let receiver = work_tree.add_task(MockContext, mock_function);
+        work_tree.run(paths);
+        assert_eq!(receiver.try_iter().count(), 256);
+
+        let _ = tmp_dir.close();
+    }
 }
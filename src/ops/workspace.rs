@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
 use crate::utils::{resolve_any_path, verify_dir, write_json};
+use crate::workspace::ops::parse_config_json;
 
 use anyhow::{anyhow, Result};
 use lazy_static::lazy_static;
@@ -10,7 +11,7 @@ use serde_json::{Map, Value};
 
 use std::borrow::Borrow;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 lazy_static! {
     static ref LICENSA_IGNORE: &'static str = std::include_str!("../../.licensaignore");
@@ -21,6 +22,14 @@ const LICENSA_IGNORE_FILENAME: &str = ".licensaignore";
 const DEFAULT_CONFIG_FILENAME: &str = ".licensarc";
 const POSSIBLE_CONFIG_FILENAMES: &[&str] = &[".licensarc", ".licensarc.json"];
 
+/// Returns the path of the Licensa configuration file in `workspace_root`, if any.
+pub fn find_workspace_config_path<P>(workspace_root: P) -> Option<PathBuf>
+where
+    P: AsRef<Path>,
+{
+    resolve_any_path(workspace_root, POSSIBLE_CONFIG_FILENAMES)
+}
+
 /// Find a Licensa configuration file in the directory specified by `workspace_root`.
 /// If a config file is found, read it and return it's contents.
 ///
@@ -71,7 +80,7 @@ where
 
     if let Some(path) = config_path {
         let content = fs::read_to_string(path)?;
-        let content = serde_json::from_str::<T>(&content)?;
+        let content = serde_json::from_value::<T>(parse_config_json(&content)?)?;
         return Ok(content);
     }
 
@@ -91,7 +100,7 @@ where
 
     if let Some(path) = resolve_any_path(workspace_root, POSSIBLE_CONFIG_FILENAMES) {
         let content = fs::read_to_string(path)?;
-        let content = serde_json::from_str::<Value>(&content)?;
+        let content = parse_config_json(&content)?;
         return Ok(Some(content));
     }
 
@@ -166,7 +175,7 @@ pub fn workspace_config_exists<P>(workspace_root: P) -> bool
 where
     P: AsRef<Path>,
 {
-    resolve_any_path(workspace_root, POSSIBLE_CONFIG_FILENAMES).map_or(false, |p| true)
+    resolve_any_path(workspace_root, POSSIBLE_CONFIG_FILENAMES).is_some()
 }
 
 /// Save `.licensaignore` file to provided directory.
@@ -1,22 +1,93 @@
 // Copyright 2024 Nelson Dominguez
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
+use std::ops::Range;
 use std::str::FromStr;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 
+use crate::diagnostic::Diagnostic;
 use crate::schema::{LicenseId, LicenseYear, LicenseYearError};
 
+/// Parses the SPDX license ID or expression `input` of a command line argument.
+///
+/// Errors point to the offending term of the expression.
 pub fn parse_license_id(input: &str) -> Result<LicenseId> {
     // We trim leading and trailing `"` in case an user provides a single license ID
     // as `--type "MIT"`, whereas it should be provided as `--type MIT`.
     let typ = input.trim_matches('"');
-    LicenseId::from_str(input)
+    LicenseId::from_str(typ).map_err(|err| {
+        let (message, span) = match err.downcast_ref::<spdx::ParseError>() {
+            Some(err) => {
+                let offset = input.find(&err.original).unwrap_or_default();
+                let span = offset + err.span.start..offset + err.span.end;
+                (err.reason.to_string(), span)
+            }
+            None => (err.to_string(), trimmed_span(input)),
+        };
+        anyhow!(Diagnostic::new(message, input, span))
+    })
 }
 
-pub fn parse_license_year(input: &str) -> Result<LicenseYear, LicenseYearError> {
+/// Parses the copyright year `input` of a command line argument.
+///
+/// Errors point to the offending year or period of `input`.
+pub fn parse_license_year(input: &str) -> Result<LicenseYear> {
     // Trim leading and trailing `"` in case an user provides a single license year
     // as `--year "2003"`, where it should be provided as `--type 2003`.
-    let input = input.trim_matches('"');
-    LicenseYear::from_str(input)
+    let year = input.trim_matches('"');
+    LicenseYear::from_str(year).map_err(|err| {
+        let span = year_error_span(input, &err);
+        anyhow!(Diagnostic::new(err.to_string(), input, span))
+    })
+}
+
+/// Returns the span of `input` that `err` refers to.
+fn year_error_span(input: &str, err: &LicenseYearError) -> Range<usize> {
+    let text = match err {
+        LicenseYearError::InvalidFormat(text)
+        | LicenseYearError::InvalidYear(text)
+        | LicenseYearError::UnorderedList(text) => text.clone(),
+        LicenseYearError::InvalidPeriod(start, end) => format!("{start}-{end}"),
+        LicenseYearError::EmptyString => String::new(),
+    };
+    match input.find(&text).filter(|_| !text.is_empty()) {
+        Some(start) => start..start + text.len(),
+        None => trimmed_span(input),
+    }
+}
+
+/// Returns the span of `input` without surrounding whitespace and quotes.
+fn trimmed_span(input: &str) -> Range<usize> {
+    let trimmed = input.trim_matches(|c: char| c.is_whitespace() || c == '"');
+    let start = trimmed.as_ptr() as usize - input.as_ptr() as usize;
+    start..start + trimmed.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_license_id_error() {
+        let err = parse_license_id("MIT OR Apch").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unknown term\n |\n | MIT OR Apch\n |        ^^^^"
+        );
+        assert_eq!(parse_license_id("\"mit\"").unwrap().to_string(), "MIT");
+    }
+
+    #[test]
+    fn test_parse_license_year_error() {
+        let err = parse_license_year("2020-20x4").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "20x4 does not represent a calendar year\n |\n | 2020-20x4\n |      ^^^^"
+        );
+        let err = parse_license_year("2019, 2023-2021").unwrap_err();
+        assert!(err
+            .to_string()
+            .ends_with("| 2019, 2023-2021\n |       ^^^^^^^^^"));
+    }
 }
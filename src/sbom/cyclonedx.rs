@@ -0,0 +1,229 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! CycloneDX 1.5 BOMs.
+//!
+//! The workspace is described as the metadata component and each scanned file
+//! as a component of type `file`. See <https://cyclonedx.org/docs/1.5/json/>
+//! for the specification.
+
+use super::{FileRecord, Inventory};
+use crate::utils::{sha256_hex, utc_timestamp};
+
+use serde::Serialize;
+
+/// Version of the CycloneDX specification BOMs conform to.
+pub const SPEC_VERSION: &str = "1.5";
+
+const WORKSPACE_REF: &str = "workspace";
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Bom {
+    bom_format: String,
+    spec_version: String,
+    serial_number: String,
+    version: u32,
+    metadata: Metadata,
+    components: Vec<Component>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Metadata {
+    timestamp: String,
+    tools: Tools,
+    component: Component,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Tools {
+    components: Vec<Component>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Component {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(rename = "bom-ref", skip_serializing_if = "Option::is_none")]
+    bom_ref: Option<String>,
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    hashes: Vec<Hash>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    licenses: Vec<LicenseChoice>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    copyright: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Hash {
+    alg: String,
+    content: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LicenseChoice {
+    expression: String,
+}
+
+impl Bom {
+    /// Creates a CycloneDX BOM describing `inventory`.
+    pub fn new(inventory: &Inventory) -> Self {
+        let timestamp = utc_timestamp();
+        let components: Vec<Component> = inventory.files.iter().map(Component::file).collect();
+
+        let seed = inventory
+            .files
+            .iter()
+            .fold(format!("{}{timestamp}", inventory.name), |seed, file| {
+                seed + &file.sha256
+            });
+
+        Self {
+            bom_format: "CycloneDX".into(),
+            spec_version: SPEC_VERSION.into(),
+            serial_number: format!("urn:uuid:{}", uuid_from_hash(&sha256_hex(seed))),
+            version: 1,
+            metadata: Metadata {
+                timestamp,
+                tools: Tools {
+                    components: vec![Component {
+                        kind: "application".into(),
+                        bom_ref: None,
+                        name: "licensa".into(),
+                        version: Some(env!("CARGO_PKG_VERSION").into()),
+                        hashes: vec![],
+                        licenses: vec![],
+                        copyright: None,
+                    }],
+                },
+                component: Component {
+                    kind: "application".into(),
+                    bom_ref: Some(WORKSPACE_REF.into()),
+                    name: inventory.name.clone(),
+                    version: None,
+                    hashes: vec![],
+                    licenses: licenses(inventory.declared_license.as_deref()),
+                    copyright: None,
+                },
+            },
+            components,
+        }
+    }
+}
+
+impl Component {
+    fn file(record: &FileRecord) -> Self {
+        Self {
+            kind: "file".into(),
+            bom_ref: Some(format!("file:{}", record.path)),
+            name: record.path.clone(),
+            version: None,
+            hashes: vec![
+                Hash {
+                    alg: "SHA-1".into(),
+                    content: record.sha1.clone(),
+                },
+                Hash {
+                    alg: "SHA-256".into(),
+                    content: record.sha256.clone(),
+                },
+            ],
+            licenses: licenses(record.license.as_deref()),
+            copyright: record.copyright.clone(),
+        }
+    }
+}
+
+fn licenses(expr: Option<&str>) -> Vec<LicenseChoice> {
+    expr.map(|expr| LicenseChoice {
+        expression: expr.to_string(),
+    })
+    .into_iter()
+    .collect()
+}
+
+/// Formats the leading bytes of a hex-encoded hash as a version 4 UUID.
+fn uuid_from_hash(hash: &str) -> String {
+    let hex = hash.as_bytes();
+    let variant = match hex[16] {
+        b'0'..=b'3' | b'c' => '8',
+        b'4'..=b'7' | b'd' => '9',
+        b'8' | b'9' | b'e' => 'a',
+        _ => 'b',
+    };
+    format!(
+        "{}-{}-4{}-{variant}{}-{}",
+        &hash[0..8],
+        &hash[8..12],
+        &hash[13..16],
+        &hash[17..20],
+        &hash[20..32]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_cyclonedx_bom() {
+        let root = Path::new("/workspace");
+        let licensed =
+            b"// Copyright 2024 Bilbo Baggins\n// SPDX-License-Identifier: MIT OR Apache-2.0\n";
+        let unlicensed = b"fn main() {}\n";
+        let inventory = Inventory::new(
+            "shire",
+            Some("MIT".into()),
+            vec![
+                FileRecord::new(root, root.join("src/main.rs"), unlicensed, unlicensed),
+                FileRecord::new(root, root.join("src/lib.rs"), licensed, licensed),
+            ],
+        );
+
+        let bom = serde_json::to_value(Bom::new(&inventory)).unwrap();
+        assert_eq!(bom["bomFormat"], "CycloneDX");
+        assert_eq!(bom["specVersion"], SPEC_VERSION);
+        assert_eq!(bom["metadata"]["component"]["name"], "shire");
+        assert_eq!(
+            bom["metadata"]["component"]["licenses"][0]["expression"],
+            "MIT"
+        );
+
+        let components = bom["components"].as_array().unwrap();
+        assert_eq!(components.len(), 2);
+        assert_eq!(components[0]["type"], "file");
+        assert_eq!(components[0]["name"], "src/lib.rs");
+        assert_eq!(
+            components[0]["licenses"][0]["expression"],
+            "MIT OR Apache-2.0"
+        );
+        assert_eq!(components[0]["copyright"], "Copyright 2024 Bilbo Baggins");
+        assert_eq!(components[0]["hashes"].as_array().unwrap().len(), 2);
+        assert!(components[1].get("licenses").is_none());
+        assert!(components[1].get("copyright").is_none());
+    }
+
+    #[test]
+    fn test_uuid_from_hash() {
+        let uuid = uuid_from_hash(&sha256_hex(b"licensa"));
+        let parts: Vec<&str> = uuid.split('-').collect();
+        assert_eq!(
+            parts.iter().map(|p| p.len()).collect::<Vec<_>>(),
+            [8, 4, 4, 4, 12]
+        );
+        assert!(parts[2].starts_with('4'));
+        assert!(matches!(
+            parts[3].chars().next(),
+            Some('8' | '9' | 'a' | 'b')
+        ));
+    }
+}
@@ -0,0 +1,116 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Software bill of materials (SBOM) listing the license information of
+//! workspace files.
+//!
+//! An [Inventory] captures the license and copyright information detected in
+//! each scanned file. It can be rendered in any of the supported [SbomFormat]s.
+
+pub mod cyclonedx;
+pub mod spdx;
+
+use crate::template::{find_copyright_text, find_license_id};
+use crate::utils::{sha1_hex, sha256_hex};
+
+use anyhow::Result;
+use clap::ValueEnum;
+
+use std::path::Path;
+
+/// Output format of a software bill of materials.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum SbomFormat {
+    /// SPDX 2.3 document in JSON format.
+    #[default]
+    #[value(name = "spdx-json")]
+    SpdxJson,
+
+    /// CycloneDX 1.5 BOM in JSON format.
+    #[value(name = "cyclonedx-json")]
+    CycloneDxJson,
+}
+
+/// License information of a single file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileRecord {
+    /// Workspace-relative, `/`-separated path to the file.
+    pub path: String,
+
+    /// Hex-encoded SHA-1 hash of the file content.
+    pub sha1: String,
+
+    /// Hex-encoded SHA-256 hash of the file content.
+    pub sha256: String,
+
+    /// SPDX license expression found in the file header, if any.
+    pub license: Option<String>,
+
+    /// Copyright notice found in the file header, if any.
+    pub copyright: Option<String>,
+}
+
+impl FileRecord {
+    /// Creates a record for the file at `path`.
+    ///
+    /// License and copyright information is only searched for in `head`, the
+    /// leading bytes of `content`.
+    pub fn new<R, P>(workspace_root: R, path: P, content: &[u8], head: &[u8]) -> Self
+    where
+        R: AsRef<Path>,
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let relative = path.strip_prefix(workspace_root.as_ref()).unwrap_or(path);
+        let path = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        Self {
+            path,
+            sha1: sha1_hex(content),
+            sha256: sha256_hex(content),
+            license: find_license_id(head),
+            copyright: find_copyright_text(head),
+        }
+    }
+}
+
+/// License inventory of a workspace.
+#[derive(Debug, Clone, Default)]
+pub struct Inventory {
+    /// Name of the workspace, usually the name of its root directory.
+    pub name: String,
+
+    /// License declared in the workspace configuration, if any.
+    pub declared_license: Option<String>,
+
+    /// Records of all scanned files, sorted by path.
+    pub files: Vec<FileRecord>,
+}
+
+impl Inventory {
+    /// Creates a new inventory, sorting `files` by path.
+    pub fn new<N>(name: N, declared_license: Option<String>, mut files: Vec<FileRecord>) -> Self
+    where
+        N: AsRef<str>,
+    {
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        Self {
+            name: name.as_ref().to_string(),
+            declared_license,
+            files,
+        }
+    }
+
+    /// Renders the inventory as a pretty-printed document in the given format.
+    pub fn render(&self, format: SbomFormat) -> Result<String> {
+        let content = match format {
+            SbomFormat::SpdxJson => serde_json::to_string_pretty(&spdx::Document::new(self))?,
+            SbomFormat::CycloneDxJson => serde_json::to_string_pretty(&cyclonedx::Bom::new(self))?,
+        };
+        Ok(content)
+    }
+}
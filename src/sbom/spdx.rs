@@ -0,0 +1,290 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! SPDX 2.3 documents.
+//!
+//! The workspace is described as a single package containing all scanned files.
+//! See <https://spdx.github.io/spdx-spec/v2.3/> for the specification.
+
+use super::{FileRecord, Inventory};
+use crate::utils::{sha1_hex, sha256_hex, utc_timestamp};
+
+use ::spdx::{Expression, ParseMode};
+use serde::Serialize;
+
+/// Version of the SPDX specification documents conform to.
+pub const SPDX_VERSION: &str = "SPDX-2.3";
+
+/// Value used for fields whose value has not been determined.
+const NOASSERTION: &str = "NOASSERTION";
+
+const DOCUMENT_ID: &str = "SPDXRef-DOCUMENT";
+const PACKAGE_ID: &str = "SPDXRef-Package";
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Document {
+    spdx_version: String,
+    data_license: String,
+    #[serde(rename = "SPDXID")]
+    spdx_id: String,
+    name: String,
+    document_namespace: String,
+    creation_info: CreationInfo,
+    packages: Vec<Package>,
+    files: Vec<File>,
+    relationships: Vec<Relationship>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CreationInfo {
+    created: String,
+    creators: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Package {
+    #[serde(rename = "SPDXID")]
+    spdx_id: String,
+    name: String,
+    download_location: String,
+    files_analyzed: bool,
+    package_verification_code: PackageVerificationCode,
+    license_concluded: String,
+    license_declared: String,
+    license_info_from_files: Vec<String>,
+    copyright_text: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PackageVerificationCode {
+    package_verification_code_value: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct File {
+    #[serde(rename = "SPDXID")]
+    spdx_id: String,
+    file_name: String,
+    checksums: Vec<Checksum>,
+    license_concluded: String,
+    license_info_in_files: Vec<String>,
+    copyright_text: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Checksum {
+    algorithm: String,
+    checksum_value: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Relationship {
+    spdx_element_id: String,
+    relationship_type: String,
+    related_spdx_element: String,
+}
+
+impl Document {
+    /// Creates an SPDX document describing `inventory`.
+    pub fn new(inventory: &Inventory) -> Self {
+        let created = utc_timestamp();
+        let verification_code = package_verification_code(&inventory.files);
+
+        let files: Vec<File> = inventory
+            .files
+            .iter()
+            .enumerate()
+            .map(|(index, record)| File::new(index, record))
+            .collect();
+
+        let mut license_info_from_files: Vec<String> = files
+            .iter()
+            .flat_map(|file| file.license_info_in_files.iter().cloned())
+            .filter(|license| license != NOASSERTION)
+            .collect();
+        license_info_from_files.sort();
+        license_info_from_files.dedup();
+        if license_info_from_files.is_empty() {
+            license_info_from_files.push(NOASSERTION.into());
+        }
+
+        let mut relationships = vec![Relationship {
+            spdx_element_id: DOCUMENT_ID.into(),
+            relationship_type: "DESCRIBES".into(),
+            related_spdx_element: PACKAGE_ID.into(),
+        }];
+        relationships.extend(files.iter().map(|file| Relationship {
+            spdx_element_id: PACKAGE_ID.into(),
+            relationship_type: "CONTAINS".into(),
+            related_spdx_element: file.spdx_id.clone(),
+        }));
+
+        // The namespace must be unique for each document
+        let namespace_hash = sha256_hex(format!("{}{created}{verification_code}", inventory.name));
+
+        Self {
+            spdx_version: SPDX_VERSION.into(),
+            data_license: "CC0-1.0".into(),
+            spdx_id: DOCUMENT_ID.into(),
+            name: inventory.name.clone(),
+            document_namespace: format!(
+                "https://spdx.org/spdxdocs/{}-{}",
+                sanitize_id(&inventory.name),
+                &namespace_hash[..32]
+            ),
+            creation_info: CreationInfo {
+                created,
+                creators: vec![format!("Tool: licensa-{}", env!("CARGO_PKG_VERSION"))],
+            },
+            packages: vec![Package {
+                spdx_id: PACKAGE_ID.into(),
+                name: inventory.name.clone(),
+                download_location: NOASSERTION.into(),
+                files_analyzed: true,
+                package_verification_code: PackageVerificationCode {
+                    package_verification_code_value: verification_code,
+                },
+                license_concluded: NOASSERTION.into(),
+                license_declared: inventory
+                    .declared_license
+                    .clone()
+                    .unwrap_or_else(|| NOASSERTION.into()),
+                license_info_from_files,
+                copyright_text: NOASSERTION.into(),
+            }],
+            files,
+            relationships,
+        }
+    }
+}
+
+impl File {
+    fn new(index: usize, record: &FileRecord) -> Self {
+        Self {
+            spdx_id: format!("SPDXRef-File-{}-{}", index + 1, sanitize_id(&record.path)),
+            file_name: format!("./{}", record.path),
+            checksums: vec![
+                Checksum {
+                    algorithm: "SHA1".into(),
+                    checksum_value: record.sha1.clone(),
+                },
+                Checksum {
+                    algorithm: "SHA256".into(),
+                    checksum_value: record.sha256.clone(),
+                },
+            ],
+            license_concluded: NOASSERTION.into(),
+            license_info_in_files: license_info(record.license.as_deref()),
+            copyright_text: record
+                .copyright
+                .clone()
+                .unwrap_or_else(|| NOASSERTION.into()),
+        }
+    }
+}
+
+/// Returns the individual licenses referenced by the license expression `expr`.
+fn license_info(expr: Option<&str>) -> Vec<String> {
+    let licenses = expr
+        .and_then(|expr| Expression::parse_mode(expr, ParseMode::LAX).ok())
+        .map(|expr| {
+            let mut licenses: Vec<String> = expr
+                .requirements()
+                .map(|req| req.req.license.to_string())
+                .collect();
+            licenses.sort();
+            licenses.dedup();
+            licenses
+        })
+        .unwrap_or_default();
+
+    if licenses.is_empty() {
+        return vec![NOASSERTION.into()];
+    }
+    licenses
+}
+
+/// Computes the package verification code as described in section 7.9 of the
+/// SPDX specification.
+fn package_verification_code(files: &[FileRecord]) -> String {
+    let mut checksums: Vec<&str> = files.iter().map(|file| file.sha1.as_str()).collect();
+    checksums.sort();
+    sha1_hex(checksums.concat())
+}
+
+/// Replaces characters not allowed in SPDX identifiers.
+fn sanitize_id(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '-' => c,
+            _ => '-',
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn example_inventory() -> Inventory {
+        let root = Path::new("/workspace");
+        let licensed =
+            b"// Copyright 2024 Bilbo Baggins\n// SPDX-License-Identifier: MIT OR Apache-2.0\n";
+        let unlicensed = b"fn main() {}\n";
+        Inventory::new(
+            "shire",
+            Some("MIT".into()),
+            vec![
+                FileRecord::new(root, root.join("src/main.rs"), unlicensed, unlicensed),
+                FileRecord::new(root, root.join("src/lib.rs"), licensed, licensed),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_spdx_document() {
+        let inventory = example_inventory();
+        let document = serde_json::to_value(Document::new(&inventory)).unwrap();
+
+        assert_eq!(document["spdxVersion"], SPDX_VERSION);
+        assert_eq!(document["SPDXID"], DOCUMENT_ID);
+        assert_eq!(document["packages"][0]["licenseDeclared"], "MIT");
+        assert_eq!(
+            document["packages"][0]["licenseInfoFromFiles"],
+            serde_json::json!(["Apache-2.0", "MIT"])
+        );
+
+        let files = document["files"].as_array().unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0]["fileName"], "./src/lib.rs");
+        assert_eq!(files[0]["copyrightText"], "Copyright 2024 Bilbo Baggins");
+        assert_eq!(files[1]["fileName"], "./src/main.rs");
+        assert_eq!(
+            files[1]["licenseInfoInFiles"],
+            serde_json::json!([NOASSERTION])
+        );
+        assert_eq!(files[1]["copyrightText"], NOASSERTION);
+
+        let relationships = document["relationships"].as_array().unwrap();
+        assert_eq!(relationships.len(), 3);
+    }
+
+    #[test]
+    fn test_package_verification_code() {
+        let files = example_inventory().files;
+        let mut checksums = [files[0].sha1.clone(), files[1].sha1.clone()];
+        checksums.sort();
+        assert_eq!(
+            package_verification_code(&files),
+            sha1_hex(checksums.concat())
+        );
+    }
+}
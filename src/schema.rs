@@ -5,6 +5,12 @@ use crate::spdx::try_find_by_id;
 use crate::utils::validate::is_valid_year;
 
 use anyhow::{anyhow, Result};
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{
+    InstanceType, Metadata, NumberValidation, Schema, SchemaObject, StringValidation,
+    SubschemaValidation,
+};
+use schemars::JsonSchema;
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use thiserror::Error;
 
@@ -94,6 +100,26 @@ impl<'de> Deserialize<'de> for LicenseId {
     }
 }
 
+impl JsonSchema for LicenseId {
+    fn schema_name() -> String {
+        "LicenseId".to_string()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            metadata: Some(Box::new(Metadata {
+                description: Some(
+                    "A SPDX license ID or expression, e.g. `MIT OR Apache-2.0`".into(),
+                ),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
 // =========================================================
 // =========================================================
 // License year
@@ -102,12 +128,12 @@ impl<'de> Deserialize<'de> for LicenseId {
 #[derive(Debug, Error)]
 pub enum LicenseYearError {
     #[error(
-        "license year must be a non-empty string in one of the following formats: YYYY, YYYY-YYYY, or YYYY-present"
+        "license year must be a non-empty string in one of the following formats: YYYY, YYYY-YYYY, YYYY-present, or a comma-separated list of these"
     )]
     EmptyString,
 
     #[error(
-        "invalid license year format {0}. Expected value in one of the following formats: YYYY, YYYY-YYYY, or YYYY-present"
+        "invalid license year format {0}. Expected value in one of the following formats: YYYY, YYYY-YYYY, YYYY-present, or a comma-separated list of these"
     )]
     InvalidFormat(String),
 
@@ -116,13 +142,22 @@ pub enum LicenseYearError {
 
     #[error("the starting year {0} of a license period must be less than the ending year {1} of the period")]
     InvalidPeriod(u32, u32),
+
+    #[error("the years of the list {0} must be in ascending order and must not overlap")]
+    UnorderedList(String),
 }
 
+/// A copyright year, a period such as `2020-2024` or `2020-present`, or a list of
+/// these such as `2019, 2021-2023, 2024`.
+///
+/// The fields describe the last period of a list.
 #[derive(Debug, Clone, PartialEq)]
 pub struct LicenseYear {
     start: u32,
     end: Option<u32>,
     is_present: bool,
+    /// The first and optional last year of the periods preceding the last one in a list.
+    preceding: Vec<(u32, Option<u32>)>,
 }
 
 impl LicenseYear {
@@ -136,6 +171,7 @@ impl LicenseYear {
             start: year,
             end: None,
             is_present: false,
+            preceding: vec![],
         })
     }
 
@@ -162,64 +198,120 @@ impl LicenseYear {
 
         Ok(license_year)
     }
+
+    /// Returns the first year of the period, or of the first period of a list.
+    pub fn first_year(&self) -> u32 {
+        self.preceding
+            .first()
+            .map_or(self.start, |(start, _)| *start)
+    }
+
+    /// Returns the last year of the period, or of the last period of a list, or `None`
+    /// if the period extends to the present.
+    pub fn last_year(&self) -> Option<u32> {
+        if self.is_present {
+            return None;
+        }
+        Some(self.end.unwrap_or(self.start))
+    }
 }
 
 impl FromStr for LicenseYear {
     type Err = LicenseYearError;
 
     fn from_str(value: &str) -> Result<Self, Self::Err> {
-        let parts: Vec<&str> = value.split('-').collect();
-
-        if parts.is_empty() {
-            return Err(LicenseYearError::EmptyString);
+        if !value.contains(',') {
+            return parse_period(value);
         }
 
-        let num_parts = parts.len();
-        if num_parts > 2 {
+        let mut periods = value
+            .split(',')
+            .map(|period| parse_period(period.trim()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut license_year = periods.pop().unwrap();
+
+        // Only the last period of a list may extend to the present
+        if periods.iter().any(|period| period.is_present) {
             return Err(LicenseYearError::InvalidFormat(value.to_string()));
         }
-
-        let start = parts[0];
-        if !is_valid_year(start) {
-            return Err(LicenseYearError::InvalidYear(value.to_string()));
+        let mut last_year = 0;
+        for period in periods.iter().chain([&license_year]) {
+            if period.start <= last_year {
+                return Err(LicenseYearError::UnorderedList(value.to_string()));
+            }
+            last_year = period.end.unwrap_or(period.start);
         }
-        let start: u32 = start.parse().unwrap();
 
-        if num_parts == 1 {
-            return Ok(LicenseYear {
-                end: None,
-                is_present: false,
-                start,
-            });
-        }
+        license_year.preceding = periods
+            .into_iter()
+            .map(|period| (period.start, period.end))
+            .collect();
+        Ok(license_year)
+    }
+}
 
-        let end = parts[1];
-        if end == "present" {
-            return Ok(LicenseYear {
-                end: None,
-                is_present: true,
-                start,
-            });
-        } else if !is_valid_year(end) {
-            return Err(LicenseYearError::InvalidYear(end.to_string()));
-        }
+/// Parses a single year or period, i.e. `YYYY`, `YYYY-YYYY` or `YYYY-present`.
+fn parse_period(value: &str) -> Result<LicenseYear, LicenseYearError> {
+    let parts: Vec<&str> = value.split('-').collect();
 
-        let end: u32 = end.parse().unwrap();
+    if parts.is_empty() {
+        return Err(LicenseYearError::EmptyString);
+    }
 
-        if start >= end {
-            return Err(LicenseYearError::InvalidPeriod(start, end));
-        }
+    let num_parts = parts.len();
+    if num_parts > 2 {
+        return Err(LicenseYearError::InvalidFormat(value.to_string()));
+    }
 
-        Ok(LicenseYear {
-            end: Some(end),
+    let start = parts[0];
+    if !is_valid_year(start) {
+        return Err(LicenseYearError::InvalidYear(value.to_string()));
+    }
+    let start: u32 = start.parse().unwrap();
+
+    if num_parts == 1 {
+        return Ok(LicenseYear {
+            end: None,
             is_present: false,
             start,
-        })
+            preceding: vec![],
+        });
+    }
+
+    let end = parts[1];
+    if end == "present" {
+        return Ok(LicenseYear {
+            end: None,
+            is_present: true,
+            start,
+            preceding: vec![],
+        });
+    } else if !is_valid_year(end) {
+        return Err(LicenseYearError::InvalidYear(end.to_string()));
     }
+
+    let end: u32 = end.parse().unwrap();
+
+    if start >= end {
+        return Err(LicenseYearError::InvalidPeriod(start, end));
+    }
+
+    Ok(LicenseYear {
+        end: Some(end),
+        is_present: false,
+        start,
+        preceding: vec![],
+    })
 }
 
 impl fmt::Display for LicenseYear {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (start, end) in self.preceding.iter() {
+            match end {
+                Some(end) => write!(f, "{start}-{end}, ")?,
+                None => write!(f, "{start}, ")?,
+            }
+        }
         if self.is_present {
             write!(f, "{}-present", self.start)
         } else if let Some(end) = self.end {
@@ -250,7 +342,7 @@ impl<'de> Deserialize<'de> for LicenseYear {
             type Value = LicenseYear;
 
             fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-                formatter.write_str("a string or an integer in one of the following formats: YYYY, YYYY-YYYY, or YYYY-present")
+                formatter.write_str("a string or an integer in one of the following formats: YYYY, YYYY-YYYY, YYYY-present, or a comma-separated list of these")
             }
 
             fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
@@ -272,6 +364,49 @@ impl<'de> Deserialize<'de> for LicenseYear {
     }
 }
 
+impl JsonSchema for LicenseYear {
+    fn schema_name() -> String {
+        "LicenseYear".to_string()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        let year = SchemaObject {
+            instance_type: Some(InstanceType::Integer.into()),
+            number: Some(Box::new(NumberValidation {
+                minimum: Some(1000.0),
+                maximum: Some(9999.0),
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+        let period = SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            string: Some(Box::new(StringValidation {
+                pattern: Some(
+                    r"^\d{4}(-\d{4})?(, *\d{4}(-\d{4})?)*(, *\d{4}-present|-present)?$".into(),
+                ),
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+
+        SchemaObject {
+            metadata: Some(Box::new(Metadata {
+                description: Some(
+                    "A copyright year or period: YYYY, YYYY-YYYY or YYYY-present, or a comma-separated list of these, e.g. `2019, 2021-2023`".into(),
+                ),
+                ..Default::default()
+            })),
+            subschemas: Some(Box::new(SubschemaValidation {
+                one_of: Some(vec![year.into(), period.into()]),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
 fn visit_string<E>(value: &str) -> Result<LicenseYear, E>
 where
     E: de::Error,
@@ -293,6 +428,7 @@ where
         start: value as u32,
         end: None,
         is_present: false,
+        preceding: vec![],
     })
 }
 
@@ -307,6 +443,7 @@ mod tests {
             end: None,
             is_present: false,
             start: 2024,
+            preceding: vec![],
         };
 
         let parsed = visit_int::<de::value::Error>(u64::from(year));
@@ -352,6 +489,7 @@ mod tests {
             end: None,
             is_present: false,
             start: 2024,
+            preceding: vec![],
         };
 
         let parsed = visit_string::<de::value::Error>(year);
@@ -366,6 +504,7 @@ mod tests {
             end: Some(2014),
             is_present: false,
             start: 2011,
+            preceding: vec![],
         };
 
         let parsed = visit_string::<de::value::Error>(period);
@@ -380,6 +519,7 @@ mod tests {
             end: None,
             is_present: true,
             start: 2022,
+            preceding: vec![],
         };
 
         let parsed = visit_string::<de::value::Error>(year_range);
@@ -387,4 +527,30 @@ mod tests {
         assert!(parsed.is_ok());
         assert_eq!(parsed.unwrap(), expected)
     }
+
+    #[test]
+    fn test_parse_license_year_list() {
+        let year: LicenseYear = "2019,2021-2023, 2024-present".parse().unwrap();
+        assert_eq!(
+            year,
+            LicenseYear {
+                end: None,
+                is_present: true,
+                start: 2024,
+                preceding: vec![(2019, None), (2021, Some(2023))],
+            }
+        );
+        assert_eq!(year.to_string(), "2019, 2021-2023, 2024-present");
+        assert_eq!(year.first_year(), 2019);
+        assert_eq!(year.last_year(), None);
+
+        let year: LicenseYear = "2019, 2021, 2024".parse().unwrap();
+        assert_eq!(year.to_string(), "2019, 2021, 2024");
+        assert_eq!(year.last_year(), Some(2024));
+
+        assert!("2021, 2019".parse::<LicenseYear>().is_err());
+        assert!("2019-2022, 2021".parse::<LicenseYear>().is_err());
+        assert!("2019-present, 2024".parse::<LicenseYear>().is_err());
+        assert!("2019, , 2024".parse::<LicenseYear>().is_err());
+    }
 }
@@ -0,0 +1,224 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+pub mod notices;
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use spdx::identifiers::{
+    EXCEPTIONS, IS_COPYLEFT, IS_DEPRECATED, IS_FSF_LIBRE, IS_OSI_APPROVED, LICENSES,
+};
+use spdx::{imprecise_license_id, license_id, Expression, ParseMode};
+
+/// Tries to find a SPDX license identifier based on the provided expression.
+///
+/// This function accepts SPDX license expressions in various forms, such as
+/// single license IDs ("MIT", "mit"), complex expressions ("MIT OR Apache-2.0"),
+/// or a combination. It aims to provide a normalized and canonicalized SPDX
+/// license identifier that can be used for further processing.
+///
+/// # Arguments
+///
+/// - `expr`: The SPDX license expression to analyze and process.
+///
+/// # Returns
+///
+/// Returns a `Result` with an `Option<String>`:
+///
+/// - `Ok(Some(license_id))`: If a SPDX license identifier is successfully found.
+/// - `Ok(None)`: If the provided expression is not in its valid form but can be
+///   canonicalized and parsed.
+/// - `Err(_)`: If the expression cannot be canonicalized or other errors occur.
+pub fn try_find_by_id<I>(expr: I) -> Result<Option<String>>
+where
+    I: AsRef<str>,
+{
+    let expr = expr.as_ref();
+
+    if is_single_expr(expr) {
+        // The casing doesn't matter here. We try to find the license id based
+        // on a single SPDX expression such as "MIT", "mit", "apache2" etc.
+        let license_id = partially_find_license(expr);
+        return Ok(license_id);
+    }
+
+    if let Ok(license) = Expression::parse_mode(expr, ParseMode::LAX) {
+        // At this point we just parse the expression in a non-strict mode.
+        // We don't care about errors. In cases where the provided expression
+        // is already in it's valid form (e.g "MIT OR Apache-2.0") the parser
+        // will be happy.
+        let license_id = Some(license.to_string());
+        return Ok(license_id);
+    }
+
+    // If we reach the next line, the provided expression is not in it's valid form yet.
+    // The `canonicalize` method converts the input expression to one that can be parsed
+    // in strict mode.
+    let expr = Expression::canonicalize(expr)?;
+    Ok(expr)
+}
+
+fn partially_find_license(expr: &str) -> Option<String> {
+    license_id(expr)
+        .or_else(|| imprecise_license_id(expr).map(|(license_id, _)| license_id))
+        .map(|l| l.name.to_string())
+}
+
+fn is_single_expr(expr: &str) -> bool {
+    expr.split(' ').collect::<Vec<&str>>().len() == 1
+}
+
+/// A license of the SPDX license list.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LicenseEntry {
+    /// The SPDX license identifier, e.g. `MIT`.
+    pub id: &'static str,
+
+    /// The full name of the license, e.g. `MIT License`.
+    pub name: &'static str,
+
+    /// Whether the license is approved by the Open Source Initiative.
+    pub osi_approved: bool,
+
+    /// Whether the license is considered free/libre by the Free Software Foundation.
+    pub fsf_libre: bool,
+
+    /// Whether the license is a copyleft license.
+    pub copyleft: bool,
+
+    /// Whether the license identifier is deprecated.
+    pub deprecated: bool,
+}
+
+/// Returns all licenses of the SPDX license list, ordered by license identifier.
+pub fn list_licenses() -> Vec<LicenseEntry> {
+    LICENSES
+        .iter()
+        .map(|&(id, name, flags)| LicenseEntry {
+            id,
+            name,
+            osi_approved: flags & IS_OSI_APPROVED != 0,
+            fsf_libre: flags & IS_FSF_LIBRE != 0,
+            copyleft: flags & IS_COPYLEFT != 0,
+            deprecated: flags & IS_DEPRECATED != 0,
+        })
+        .collect()
+}
+
+/// Returns the full text of each license and exception of the license expression `expr`,
+/// along with its SPDX identifier.
+///
+/// # Errors
+///
+/// Returns an error if `expr` is not a valid SPDX license expression or refers to a
+/// license that is not on the SPDX license list, e.g. a `LicenseRef-`.
+pub fn license_texts(expr: &str) -> Result<Vec<(&'static str, &'static str)>> {
+    let expression = Expression::parse_mode(expr, ParseMode::LAX)?;
+    let mut texts = vec![];
+    for req in expression.requirements() {
+        let Some(license) = req.req.license.id() else {
+            return Err(anyhow!(
+                "no license text available for '{}'",
+                req.req.license
+            ));
+        };
+        texts.push((license.name, license.text()));
+        if let Some(exception) = req.req.exception {
+            texts.push((exception.name, exception.text()));
+        }
+    }
+    texts.dedup_by_key(|(id, _)| *id);
+    Ok(texts)
+}
+
+/// Returns the SPDX license or exception identifier matching `id` case-insensitively,
+/// in its correct casing, e.g. `Apache-2.0` for `apache-2.0`.
+pub fn find_id_ignore_case(id: &str) -> Option<&'static str> {
+    let licenses = LICENSES.iter().map(|(id, _, _)| *id);
+    let exceptions = EXCEPTIONS.iter().map(|(id, _)| *id);
+    licenses
+        .chain(exceptions)
+        .find(|candidate| candidate.eq_ignore_ascii_case(id))
+}
+
+pub fn list_spdx_license_names() -> Vec<String> {
+    LICENSES
+        .iter()
+        .map(|(_, fullname, _)| fullname.to_string())
+        .collect()
+}
+
+pub fn id_from_license_fullname(name: &str) -> Result<String> {
+    let item = LICENSES
+        .iter()
+        .find(|(_, fullname, _)| name == *fullname)
+        .map(|(id, fullname, _)| *id);
+
+    if item.is_none() {
+        return Err(anyhow!("no SPDX ID found for name: '{}'", name));
+    }
+
+    Ok(item.unwrap().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_try_find_by_id_exact() {
+        let expr = "BSD-3-Clause-No-Nuclear-License";
+        let license_id = try_find_by_id(expr);
+        println!("{:?}", license_id);
+        assert!(&license_id.is_ok());
+    }
+
+    #[test]
+    fn test_try_find_by_id_invalid() {
+        let expr = "BSE";
+        let license_id = try_find_by_id(expr);
+        assert!(&license_id.unwrap().is_none());
+    }
+
+    #[test]
+    fn test_list_licenses() {
+        let licenses = list_licenses();
+        assert_eq!(licenses.len(), LICENSES.len());
+
+        let gpl = licenses.iter().find(|l| l.id == "GPL-3.0-only").unwrap();
+        assert_eq!(gpl.name, "GNU General Public License v3.0 only");
+        assert!(gpl.osi_approved && gpl.fsf_libre && gpl.copyleft && !gpl.deprecated);
+
+        let gpl = licenses.iter().find(|l| l.id == "GPL-3.0").unwrap();
+        assert!(gpl.deprecated);
+    }
+
+    #[test]
+    fn test_find_id_ignore_case() {
+        assert_eq!(find_id_ignore_case("apache-2.0"), Some("Apache-2.0"));
+        assert_eq!(find_id_ignore_case("MIT"), Some("MIT"));
+        assert_eq!(
+            find_id_ignore_case("llvm-exception"),
+            Some("LLVM-exception")
+        );
+        assert_eq!(find_id_ignore_case("apache2"), None);
+    }
+
+    #[test]
+    fn test_license_texts() {
+        let texts = license_texts("MIT OR Apache-2.0 WITH LLVM-exception").unwrap();
+        let ids: Vec<&str> = texts.iter().map(|(id, _)| *id).collect();
+        assert_eq!(ids, vec!["MIT", "Apache-2.0", "LLVM-exception"]);
+        assert!(texts[0].1.contains("Permission is hereby granted"));
+
+        assert!(license_texts("LicenseRef-Proprietary").is_err());
+    }
+
+    #[test]
+    fn test_try_find_by_id_combined() {
+        let expr = "mit or apache";
+        let license_id = try_find_by_id(expr);
+        assert!(&license_id.is_ok());
+    }
+}
@@ -0,0 +1,439 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Full license notices recommended by the authors of a license.
+//!
+//! Notices are handlebars templates rendered with the same data as the SPDX
+//! notice, i.e. `owner` and an optional `year`. Notices of licenses naming the
+//! project additionally reference `project` and `projectUrl`. The notice of a compound license
+//! expression such as `MIT OR Apache-2.0` consists of a sentence stating how the
+//! licenses combine, followed by the notice of each license.
+
+/// The copyright line preceding every full notice, followed by a line per contributor.
+const COPYRIGHT_LINE: &str = "Copyright{{#if year}} {{year}}{{/if}} {{owner}}{{#each contributors}}\nSPDX-FileContributor: {{this}}{{/each}}";
+
+/// The sentence preceding the notices of licenses combined with `OR`.
+const EITHER_LICENSE: &str = "Licensed under either of the following licenses, at your option.";
+
+/// The sentence preceding the notices of licenses combined with `AND`.
+const ALL_LICENSES: &str = "Licensed under all of the following licenses.";
+
+/// Maximum line width of generated notices.
+const LINE_WIDTH: usize = 72;
+
+const APACHE_2_0: &str = r#"Licensed under the Apache License, Version 2.0 (the "License");
+you may not use this file except in compliance with the License.
+You may obtain a copy of the License at
+
+    http://www.apache.org/licenses/LICENSE-2.0
+
+Unless required by applicable law or agreed to in writing, software
+distributed under the License is distributed on an "AS IS" BASIS,
+WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+See the License for the specific language governing permissions and
+limitations under the License."#;
+
+const MIT: &str = r#"Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in
+all copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+THE SOFTWARE."#;
+
+const MPL_2_0: &str = r#"This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at https://mozilla.org/MPL/2.0/."#;
+
+const BSD_4_CLAUSE: &str = r#"This file is part of {{project}}, which is licensed under the BSD 4-Clause
+License. All advertising materials mentioning features or use of {{project}}
+must display the following acknowledgement: This product includes software
+developed by {{owner}}."#;
+
+const NCSA: &str = r#"Developed by: {{project}}
+              {{projectUrl}}
+
+Licensed under the University of Illinois/NCSA Open Source License.
+See the LICENSE file distributed with {{project}} for details."#;
+
+const VIM: &str = r#"This file is part of {{project}}, which is distributed under the Vim License.
+See the LICENSE file distributed with {{project}} for the terms of use."#;
+
+/// Licenses whose full notice names the project, along with the config fields it
+/// requires.
+const PROJECT_NOTICE_FIELDS: &[(&str, &[&str])] = &[
+    ("BSD-4-Clause", &["project"]),
+    ("NCSA", &["project", "projectUrl"]),
+    ("Vim", &["project"]),
+];
+
+/// A GNU license family, whose notices only differ in name and version.
+struct GnuLicense {
+    prefix: &'static str,
+    name: &'static str,
+}
+
+const GNU_LICENSES: &[GnuLicense] = &[
+    GnuLicense {
+        prefix: "AGPL-",
+        name: "GNU Affero General Public License",
+    },
+    GnuLicense {
+        prefix: "LGPL-",
+        name: "GNU Lesser General Public License",
+    },
+    GnuLicense {
+        prefix: "GPL-",
+        name: "GNU General Public License",
+    },
+];
+
+/// License IDs whose full notice can be recognized in existing headers.
+const RECOGNIZED_LICENSES: &[&str] = &[
+    "Apache-2.0",
+    "MIT",
+    "MPL-2.0",
+    "AGPL-3.0-only",
+    "AGPL-3.0-or-later",
+    "LGPL-2.1-only",
+    "LGPL-2.1-or-later",
+    "LGPL-3.0-only",
+    "LGPL-3.0-or-later",
+    "GPL-2.0-only",
+    "GPL-2.0-or-later",
+    "GPL-3.0-only",
+    "GPL-3.0-or-later",
+];
+
+/// Returns the full notice template of the license expression `expr`, if one is available.
+///
+/// Besides single SPDX license IDs, expressions combining license IDs with a single
+/// operator are supported, e.g. `MIT OR Apache-2.0`, if a full notice is available
+/// for every license. Expressions with parentheses, mixed operators or `WITH`
+/// exceptions are not.
+pub fn full_notice_template(expr: &str) -> Option<String> {
+    let (operator, ids) = split_expression(expr)?;
+    let body = match operator {
+        None => full_notice_body(ids[0])?,
+        Some(operator) => {
+            let bodies = ids
+                .iter()
+                .map(|id| full_notice_body(id))
+                .collect::<Option<Vec<_>>>()?;
+            format!("{}\n\n{}", operator.sentence(), bodies.join("\n\n"))
+        }
+    };
+    Some(format!("{COPYRIGHT_LINE}\n\n{body}"))
+}
+
+/// Returns the config fields, i.e. `project` and `projectUrl`, referenced by the
+/// full notice template of the license expression `expr`.
+pub fn required_notice_fields(expr: &str) -> Vec<&'static str> {
+    let Some((_, ids)) = split_expression(expr) else {
+        return vec![];
+    };
+    let mut fields: Vec<&str> = PROJECT_NOTICE_FIELDS
+        .iter()
+        .filter(|(id, _)| ids.contains(id))
+        .flat_map(|(_, fields)| fields.iter().copied())
+        .collect();
+    fields.sort_unstable();
+    fields.dedup();
+    fields
+}
+
+/// Returns the license expression whose full notice, without the copyright line,
+/// equals `text`. Differences in whitespace and line wrapping are ignored.
+pub fn identify_full_notice(text: &str) -> Option<String> {
+    let text = collapse_whitespace(text);
+    if let Some(id) = identify_license(&text) {
+        return Some(id.to_string());
+    }
+
+    // The notice of a compound expression, whose license notices follow each other
+    let (operator, mut rest) = [Operator::Or, Operator::And]
+        .into_iter()
+        .find_map(|operator| {
+            let rest = text.strip_prefix(operator.sentence())?;
+            Some((operator, rest.trim_start()))
+        })?;
+    let mut ids = vec![];
+    while !rest.is_empty() {
+        let (id, body) = RECOGNIZED_LICENSES
+            .iter()
+            .filter_map(|id| Some((*id, collapse_whitespace(&full_notice_body(id)?))))
+            .find(|(_, body)| rest.starts_with(body.as_str()))?;
+        ids.push(id);
+        rest = rest[body.len()..].trim_start();
+    }
+
+    let separator = format!(" {} ", operator.keyword());
+    (ids.len() > 1).then(|| ids.join(&separator))
+}
+
+/// Returns the ID of the license whose full notice equals the whitespace-collapsed `text`.
+fn identify_license(text: &str) -> Option<&'static str> {
+    RECOGNIZED_LICENSES
+        .iter()
+        .copied()
+        .find(|id| full_notice_body(id).is_some_and(|body| collapse_whitespace(&body) == text))
+}
+
+/// An operator combining the licenses of a compound license expression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Or,
+    And,
+}
+
+impl Operator {
+    fn keyword(&self) -> &'static str {
+        match self {
+            Operator::Or => "OR",
+            Operator::And => "AND",
+        }
+    }
+
+    /// Returns the sentence preceding the notices of the combined licenses.
+    fn sentence(&self) -> &'static str {
+        match self {
+            Operator::Or => EITHER_LICENSE,
+            Operator::And => ALL_LICENSES,
+        }
+    }
+}
+
+/// Splits the license expression `expr` into its license IDs and the operator
+/// combining them, which is `None` for a single license ID.
+///
+/// Returns `None` if `expr` contains parentheses, `WITH` exceptions or more than
+/// one kind of operator.
+fn split_expression(expr: &str) -> Option<(Option<Operator>, Vec<&str>)> {
+    let tokens: Vec<&str> = expr.split_whitespace().collect();
+    let mut operator = None;
+    let mut ids = vec![];
+    for (i, token) in tokens.iter().enumerate() {
+        if i % 2 == 0 {
+            if token.contains(['(', ')']) {
+                return None;
+            }
+            ids.push(*token);
+            continue;
+        }
+
+        let token_operator = match *token {
+            "OR" => Operator::Or,
+            "AND" => Operator::And,
+            _ => return None,
+        };
+        if operator.is_some_and(|operator| operator != token_operator) {
+            return None;
+        }
+        operator = Some(token_operator);
+    }
+
+    // An expression must not end with an operator
+    if ids.is_empty() || tokens.len() % 2 == 0 {
+        return None;
+    }
+    Some((operator, ids))
+}
+
+fn full_notice_body(id: &str) -> Option<String> {
+    match id {
+        "Apache-2.0" => Some(APACHE_2_0.to_string()),
+        "MIT" => Some(MIT.to_string()),
+        "MPL-2.0" => Some(MPL_2_0.to_string()),
+        "BSD-4-Clause" => Some(BSD_4_CLAUSE.to_string()),
+        "NCSA" => Some(NCSA.to_string()),
+        "Vim" => Some(VIM.to_string()),
+        id => gnu_notice(id),
+    }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Composes the notice of a GNU license ID such as `GPL-3.0-or-later` or `LGPL-2.1-only`.
+fn gnu_notice(id: &str) -> Option<String> {
+    let license = GNU_LICENSES.iter().find(|l| id.starts_with(l.prefix))?;
+    let version = &id[license.prefix.len()..];
+
+    // Deprecated IDs, e.g. `GPL-3.0` and `GPL-3.0+`
+    let (version, or_later) = if let Some(version) = version.strip_suffix("-or-later") {
+        (version, true)
+    } else if let Some(version) = version.strip_suffix('+') {
+        (version, true)
+    } else {
+        (version.strip_suffix("-only").unwrap_or(version), false)
+    };
+    if !matches!(version, "2.0" | "2.1" | "3.0") {
+        return None;
+    }
+    let version = version.strip_suffix(".0").unwrap_or(version);
+
+    let terms = match or_later {
+        true => format!(
+            "either version {version} of the License, or (at your option) any later version"
+        ),
+        false => format!("version {version} of the License"),
+    };
+    let name = license.name;
+    let paragraphs = [
+        format!(
+            "This program is free software: you can redistribute it and/or modify it under \
+             the terms of the {name} as published by the Free Software Foundation, {terms}."
+        ),
+        format!(
+            "This program is distributed in the hope that it will be useful, but WITHOUT ANY \
+             WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR A \
+             PARTICULAR PURPOSE. See the {name} for more details."
+        ),
+        format!(
+            "You should have received a copy of the {name} along with this program. \
+             If not, see <https://www.gnu.org/licenses/>."
+        ),
+    ];
+
+    let paragraphs = paragraphs
+        .iter()
+        .map(|p| wrap(p, LINE_WIDTH))
+        .collect::<Vec<_>>();
+    Some(paragraphs.join("\n\n"))
+}
+
+/// Wraps `text` into lines of at most `width` characters, breaking at spaces.
+fn wrap(text: &str, width: usize) -> String {
+    let mut lines = vec![];
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.len() + 1 + word.len() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_full_notice_template() {
+        let notice = full_notice_template("Apache-2.0").unwrap();
+        assert!(notice.starts_with(COPYRIGHT_LINE));
+        assert!(notice.contains("http://www.apache.org/licenses/LICENSE-2.0"));
+
+        assert!(full_notice_template("MIT")
+            .unwrap()
+            .contains("Permission is hereby granted"));
+        assert!(full_notice_template("MPL-2.0")
+            .unwrap()
+            .contains("https://mozilla.org/MPL/2.0/"));
+        assert!(full_notice_template("Unlicense").is_none());
+    }
+
+    #[test]
+    fn test_full_notice_template_compound_expression() {
+        let notice = full_notice_template("MIT OR Apache-2.0").unwrap();
+        assert!(notice.starts_with(&format!("{COPYRIGHT_LINE}\n\n{EITHER_LICENSE}\n\n")));
+        let mit = notice.find("Permission is hereby granted").unwrap();
+        let apache = notice.find("Licensed under the Apache License").unwrap();
+        assert!(mit < apache);
+
+        let notice = full_notice_template("MIT AND MPL-2.0").unwrap();
+        assert!(notice.contains(ALL_LICENSES));
+
+        assert!(full_notice_template("MIT OR Unlicense").is_none());
+        assert!(full_notice_template("MIT OR Apache-2.0 AND MPL-2.0").is_none());
+        assert!(full_notice_template("(MIT OR Apache-2.0)").is_none());
+        assert!(full_notice_template("Apache-2.0 WITH LLVM-exception").is_none());
+        assert!(full_notice_template("MIT OR").is_none());
+    }
+
+    #[test]
+    fn test_required_notice_fields() {
+        assert_eq!(required_notice_fields("NCSA"), ["project", "projectUrl"]);
+        assert_eq!(required_notice_fields("MIT OR Vim"), ["project"]);
+        assert_eq!(
+            required_notice_fields("BSD-4-Clause AND NCSA"),
+            ["project", "projectUrl"]
+        );
+        assert!(required_notice_fields("MIT OR Apache-2.0").is_empty());
+        assert!(required_notice_fields("(NCSA)").is_empty());
+
+        let notice = full_notice_template("NCSA").unwrap();
+        assert!(notice.contains("Developed by: {{project}}"));
+    }
+
+    #[test]
+    fn test_identify_full_notice() {
+        let notice = MPL_2_0.replace('\n', "\n\n   ");
+        assert_eq!(identify_full_notice(&notice), Some("MPL-2.0".into()));
+
+        let notice = gnu_notice("GPL-2.0-or-later").unwrap();
+        assert_eq!(
+            identify_full_notice(&notice),
+            Some("GPL-2.0-or-later".into())
+        );
+
+        let notice = format!("{APACHE_2_0}\nAdditional terms apply.");
+        assert_eq!(identify_full_notice(&notice), None);
+
+        let notice = format!("{EITHER_LICENSE}\n\n{MIT}\n\n{APACHE_2_0}");
+        assert_eq!(
+            identify_full_notice(&notice),
+            Some("MIT OR Apache-2.0".into())
+        );
+
+        let notice = format!("{EITHER_LICENSE}\n\n{MIT}");
+        assert_eq!(identify_full_notice(&notice), None);
+    }
+
+    #[test]
+    fn test_gnu_notice() {
+        let notice = gnu_notice("GPL-3.0-or-later").unwrap();
+        assert!(notice.lines().all(|line| line.len() <= LINE_WIDTH));
+        let notice = notice.replace('\n', " ");
+        assert!(notice.contains("GNU General Public License"));
+        assert!(notice.contains("either version 3 of the License, or (at your option) any later"));
+
+        let notice = gnu_notice("LGPL-2.1-only").unwrap().replace('\n', " ");
+        assert!(notice.contains("GNU Lesser General Public License"));
+        assert!(notice.contains("version 2.1 of the License."));
+        assert!(!notice.contains("any later version"));
+
+        let notice = gnu_notice("AGPL-3.0").unwrap().replace('\n', " ");
+        assert!(notice.contains("GNU Affero General Public License"));
+        assert!(notice.contains("Foundation, version 3 of the License."));
+
+        let notice = gnu_notice("GPL-2.0+").unwrap().replace('\n', " ");
+        assert!(notice.contains("any later version"));
+        assert!(gnu_notice("GPL-1.0-or-later").is_none());
+        assert!(gnu_notice("GFDL-1.3-only").is_none());
+    }
+
+    #[test]
+    fn test_wrap() {
+        assert_eq!(wrap("aaa bbb ccc", 7), "aaa bbb\nccc");
+        assert_eq!(wrap("", 7), "");
+    }
+}
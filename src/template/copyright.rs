@@ -4,9 +4,23 @@
 use serde::{Deserialize, Serialize};
 
 /// Represents a simple SPDX copyright notice.
-pub const SPDX_COPYRIGHT_NOTICE: &str = r#"Copyright{{#if year}} {{year}}{{/if}} {{owner}}
+pub const SPDX_COPYRIGHT_NOTICE: &str = r#"Copyright{{#if year}} {{year}}{{/if}} {{owner}}{{#each contributors}}
+SPDX-FileContributor: {{this}}{{/each}}
 SPDX-License-Identifier: {{license}}"#;
 
+/// Represents a copyright notice following the [REUSE](https://reuse.software) specification.
+pub const REUSE_COPYRIGHT_NOTICE: &str = r#"SPDX-FileCopyrightText:{{#if year}} {{year}}{{/if}} {{owner}}{{#each contributors}}
+SPDX-FileContributor: {{this}}{{/each}}
+SPDX-License-Identifier: {{license}}"#;
+
+/// Represents a compact copyright notice, i.e. a copyright line followed by a single
+/// sentence pointing to the license.
+///
+/// The sentence is inserted verbatim, without HTML escaping.
+pub const COMPACT_COPYRIGHT_NOTICE: &str = r#"Copyright{{#if year}} {{year}}{{/if}} {{owner}}{{#each contributors}}
+SPDX-FileContributor: {{this}}{{/each}}
+{{{sentence}}}"#;
+
 /// Holds information for a simple SPDX copyright notice.
 #[derive(Debug, Deserialize, Serialize, Default)]
 pub struct SpdxCopyrightNotice {
@@ -60,4 +74,43 @@ mod tests {
         assert!(template.is_ok());
         assert_eq!(template.unwrap(), expected_template.to_string());
     }
+
+    #[test]
+    fn test_reuse_template() {
+        let reg = handlebars::Handlebars::new();
+
+        let data = json!({
+            "owner": "Gandalf",
+            "license": "MIT",
+            "year": 2012
+        });
+        let expected_template =
+            "SPDX-FileCopyrightText: 2012 Gandalf\nSPDX-License-Identifier: MIT";
+        let template = reg.render_template(REUSE_COPYRIGHT_NOTICE, &data).unwrap();
+        assert_eq!(template, expected_template);
+
+        let data = json!({
+            "owner": "Gandalf",
+            "license": "MIT"
+        });
+        let expected_template = "SPDX-FileCopyrightText: Gandalf\nSPDX-License-Identifier: MIT";
+        let template = reg.render_template(REUSE_COPYRIGHT_NOTICE, &data).unwrap();
+        assert_eq!(template, expected_template);
+    }
+
+    #[test]
+    fn test_compact_template() {
+        let reg = handlebars::Handlebars::new();
+
+        let data = json!({
+            "owner": "Gandalf",
+            "year": 2012,
+            "sentence": "See the project's LICENSE file."
+        });
+        let expected_template = "Copyright 2012 Gandalf\nSee the project's LICENSE file.";
+        let template = reg
+            .render_template(COMPACT_COPYRIGHT_NOTICE, &data)
+            .unwrap();
+        assert_eq!(template, expected_template);
+    }
 }
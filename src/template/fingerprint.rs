@@ -0,0 +1,109 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Fingerprints of license headers.
+//!
+//! `verify --strict-origin` compares the fingerprint of the header `apply` would
+//! write with the fingerprint of the header found in a file, to tell headers
+//! written by Licensa apart from hand-written or edited ones. `verify --strict`
+//! compares headers word by word instead, to report headers in the wrong format.
+
+use crate::utils::sha256_hex;
+
+/// Number of hex digits of a fingerprint.
+const FINGERPRINT_LEN: usize = 16;
+
+/// Returns a stable fingerprint of the license header `header`.
+///
+/// Line endings, trailing whitespace and blank lines surrounding the header don't
+/// affect the fingerprint, so that headers reformatted by editors or Git aren't
+/// considered modified.
+pub fn fingerprint(header: &str) -> String {
+    let mut hash = sha256_hex(normalized_lines(header).join("\n"));
+    hash.truncate(FINGERPRINT_LEN);
+    hash
+}
+
+/// Returns `true` if `block` matches the license header `header`, ignoring all
+/// differences in whitespace, including line endings and indentation.
+pub fn matches_ignoring_whitespace(block: &str, header: &str) -> bool {
+    let words = |text| {
+        normalized_lines(text)
+            .into_iter()
+            .map(|line| line.split_whitespace().collect::<Vec<_>>())
+            .collect::<Vec<_>>()
+    };
+    words(block) == words(header)
+}
+
+/// Returns the block of `text` starting at byte `start` that spans as many lines
+/// as `header`, skipping leading blank lines.
+///
+/// The returned block is where `header` is expected if it was written by `apply`.
+pub fn find_header_block<'a>(text: &'a str, start: usize, header: &str) -> &'a str {
+    let rest = &text[start..];
+    let blank_lines: usize = rest
+        .split_inclusive('\n')
+        .take_while(|line| line.trim().is_empty())
+        .map(str::len)
+        .sum();
+    let rest = &rest[blank_lines..];
+    let len = rest
+        .split_inclusive('\n')
+        .take(normalized_lines(header).len())
+        .map(str::len)
+        .sum();
+    &rest[..len]
+}
+
+/// Returns the lines of `text` without trailing whitespace and surrounding blank lines.
+fn normalized_lines(text: &str) -> Vec<&str> {
+    let lines: Vec<&str> = text.lines().map(str::trim_end).collect();
+    let start = lines.iter().position(|line| !line.is_empty());
+    let end = lines.iter().rposition(|line| !line.is_empty());
+    match (start, end) {
+        (Some(start), Some(end)) => lines[start..=end].to_vec(),
+        _ => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = "// Copyright 2024 Bilbo\n// SPDX-License-Identifier: MIT\n\n";
+
+    #[test]
+    fn test_fingerprint_ignores_formatting() {
+        let reformatted = "\r\n// Copyright 2024 Bilbo  \r\n// SPDX-License-Identifier: MIT\r\n";
+        assert_eq!(fingerprint(HEADER), fingerprint(reformatted));
+        assert_eq!(fingerprint(HEADER).len(), FINGERPRINT_LEN);
+
+        let edited = "// Copyright 2024 Frodo\n// SPDX-License-Identifier: MIT\n";
+        assert_ne!(fingerprint(HEADER), fingerprint(edited));
+    }
+
+    #[test]
+    fn test_matches_ignoring_whitespace() {
+        let reformatted = "//  Copyright\t2024 Bilbo\r\n// SPDX-License-Identifier: MIT\r\n";
+        assert!(matches_ignoring_whitespace(reformatted, HEADER));
+
+        let edited = "// Copyright 2024 Bilbo Baggins\n// SPDX-License-Identifier: MIT\n";
+        assert!(!matches_ignoring_whitespace(edited, HEADER));
+
+        let split = "// Copyright 2024\n// Bilbo\n// SPDX-License-Identifier: MIT\n";
+        assert!(!matches_ignoring_whitespace(split, HEADER));
+    }
+
+    #[test]
+    fn test_find_header_block() {
+        let text =
+            "#!/bin/sh\n\n// Copyright 2024 Bilbo\n// SPDX-License-Identifier: MIT\n\necho\n";
+        let block = find_header_block(text, 10, HEADER);
+        assert_eq!(
+            block,
+            "// Copyright 2024 Bilbo\n// SPDX-License-Identifier: MIT\n"
+        );
+        assert_eq!(find_header_block("x", 0, HEADER), "x");
+    }
+}
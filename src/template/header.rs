@@ -6,8 +6,13 @@
 //! The `SourceHeaders` struct provides methods for finding header definitions and prefixes based on file extensions.
 //! It contains a predefined list of `SourceHeaderDefinition` instances.
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use clap::ValueEnum;
 use lazy_static::lazy_static;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use std::collections::BTreeMap;
 
 lazy_static! {
   /// Represents a predefined list of source header definitions.
@@ -18,14 +23,14 @@ lazy_static! {
     },
     HeaderDefinition {
       extensions: vec![
-        ".js", ".mjs", ".cjs", ".jsx", ".tsx", ".css", ".scss", ".sass", ".ts",
+        ".js", ".mjs", ".cjs", ".jsx", ".tsx", ".css", ".scss", ".sass", ".ts", ".less",
       ],
       header_prefix: HeaderPrefix::new("/**", " * ", " */"),
     },
     HeaderDefinition {
       extensions: vec![
         ".cc", ".cpp", ".cs", ".go", ".hcl", ".hh", ".hpp", ".m", ".mm", ".proto", ".rs",
-        ".swift", ".dart", ".groovy", ".v", ".sv", ".php",
+        ".swift", ".dart", ".groovy", ".v", ".sv", ".php", ".zig",
       ],
       header_prefix: HeaderPrefix::new("", "// ", ""),
     },
@@ -47,23 +52,47 @@ lazy_static! {
         "build",
         ".build",
         ".toml",
+        ".cmake",
+        ".nim",
+        ".nims",
+        ".nimble",
+        ".ps1",
+        ".psm1",
+        ".psd1",
+        ".r",
+        ".jl",
+        ".ex",
+        ".exs",
+        ".pm",
+        ".nix",
+        ".mk",
       ],
       header_prefix: HeaderPrefix::new("", "# ", ""),
     },
     HeaderDefinition {
-      extensions: vec![".el", ".lisp"],
+      extensions: vec![".el", ".lisp", ".clj", ".cljs", ".cljc", ".edn"],
       header_prefix: HeaderPrefix::new("", ";; ", ""),
     },
     HeaderDefinition {
-      extensions: vec![".erl"],
+      extensions: vec![".erl", ".tex", ".sty", ".cls"],
       header_prefix: HeaderPrefix::new("", "% ", ""),
     },
     HeaderDefinition {
-      extensions: vec![".hs", ".sql", ".sdl"],
+      extensions: vec![".hs", ".sql", ".sdl", ".lua", ".elm", ".adb", ".ads", ".vhd", ".vhdl"],
       header_prefix: HeaderPrefix::new("", "-- ", ""),
     },
     HeaderDefinition {
-      extensions: vec![".html", ".xml", ".vue", ".wxi", ".wxl", ".wxs"],
+      extensions: vec![".bat", ".cmd"],
+      header_prefix: HeaderPrefix::new("", "REM ", ""),
+    },
+    HeaderDefinition {
+      extensions: vec![".f", ".for", ".f90", ".f95", ".f03", ".f08"],
+      header_prefix: HeaderPrefix::new("", "! ", ""),
+    },
+    HeaderDefinition {
+      extensions: vec![
+        ".html", ".xml", ".svg", ".vue", ".wxi", ".wxl", ".wxs", ".md", ".markdown",
+      ],
       header_prefix: HeaderPrefix::new("<!--", " ", "-->"),
     },
     HeaderDefinition {
@@ -74,10 +103,60 @@ lazy_static! {
       extensions: vec![".ml", ".mli", ".mll", ".mly"],
       header_prefix: HeaderPrefix::new("(**", "   ", "*)"),
     },
-    // TODO: 	handle cmake files
   ];
 }
 
+/// Well-known files matched by exact file name, along with the extension whose
+/// header definition applies to them.
+///
+/// File names are consulted before extensions, e.g. `CMakeLists.txt` is a CMake file
+/// rather than a text file.
+const FILE_NAMES: &[(&str, &str)] = &[
+    ("BUILD", ".bzl"),
+    ("BUILD.bazel", ".bzl"),
+    ("Brewfile", ".rb"),
+    ("CMakeLists.txt", ".cmake"),
+    ("Containerfile", ".dockerfile"),
+    ("Dockerfile", ".dockerfile"),
+    ("GNUmakefile", ".mk"),
+    ("Gemfile", ".rb"),
+    ("Jenkinsfile", ".groovy"),
+    ("MODULE.bazel", ".bzl"),
+    ("Makefile", ".mk"),
+    ("Podfile", ".rb"),
+    ("Rakefile", ".rb"),
+    ("Snakefile", ".py"),
+    ("Tiltfile", ".bzl"),
+    ("Vagrantfile", ".rb"),
+    ("WORKSPACE", ".bzl"),
+    ("WORKSPACE.bazel", ".bzl"),
+    ("justfile", ".mk"),
+    ("makefile", ".mk"),
+];
+
+/// Script interpreters, along with the extension whose header definition applies
+/// to extensionless scripts starting with a hash-bang line invoking them.
+const INTERPRETERS: &[(&str, &str)] = &[
+    ("ash", ".sh"),
+    ("bash", ".sh"),
+    ("dash", ".sh"),
+    ("elixir", ".exs"),
+    ("fish", ".sh"),
+    ("julia", ".jl"),
+    ("ksh", ".sh"),
+    ("lua", ".lua"),
+    ("node", ".js"),
+    ("perl", ".pl"),
+    ("php", ".php"),
+    ("pwsh", ".ps1"),
+    ("python", ".py"),
+    ("Rscript", ".r"),
+    ("ruby", ".rb"),
+    ("sh", ".sh"),
+    ("tclsh", ".tcl"),
+    ("zsh", ".sh"),
+];
+
 const HEAD: &[&str] = &[
     // shell script
     "#!",
@@ -96,24 +175,182 @@ const HEAD: &[&str] = &[
     "# syntax",
 ];
 
+/// Number of leading bytes of a file inspected for a hash-bang line.
+pub const HASH_BANG_READ_WINDOW: usize = 256;
+
+/// File suffixes configured with `fileTypes`, along with the extension whose header
+/// definition applies to them. Suffixes are lowercase.
+///
+/// The default has no file types, so that only built-in comment styles apply.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    try_from = "BTreeMap<String, String>",
+    into = "BTreeMap<String, String>"
+)]
+pub struct FileTypes(Vec<(String, String)>);
+
+impl FileTypes {
+    /// Parses the `fileTypes` of a config, mapping file suffixes such as `.html.tpl`
+    /// to the extension whose comment style applies, e.g. `.html`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a suffix doesn't start with a dot, or if no comment style
+    /// is known for an extension.
+    pub fn parse(file_types: &BTreeMap<String, String>) -> Result<Self> {
+        let mut parsed = Vec::with_capacity(file_types.len());
+        for (suffix, extension) in file_types {
+            if !suffix.starts_with('.') || suffix.len() < 2 {
+                return Err(anyhow!(
+                    "file type `{suffix}` must be a file suffix starting with a dot, e.g. `.html.tpl`"
+                ));
+            }
+            let extension = match extension.starts_with('.') {
+                true => extension.to_lowercase(),
+                false => format!(".{}", extension.to_lowercase()),
+            };
+            if FileTypes::default()
+                .find_header_definition(&extension)
+                .is_none()
+            {
+                return Err(anyhow!(
+                    "file type `{suffix}` maps to `{extension}`, which has no known comment style"
+                ));
+            }
+            parsed.push((suffix.to_lowercase(), extension));
+        }
+        Ok(Self(parsed))
+    }
+
+    /// Checks whether `suffix` is one of the file types.
+    pub fn contains(&self, suffix: &str) -> bool {
+        self.0.iter().any(|(custom, _)| custom == suffix)
+    }
+
+    /// Returns the extension whose header definition applies to files with the
+    /// suffix `suffix`, e.g. `.mk` for `Makefile` or `.html` for a file type mapping
+    /// `.html.tpl` to `.html`.
+    ///
+    /// Extensions are matched case-insensitively, so other suffixes are returned
+    /// in lowercase, e.g. `.rs` for `.RS`.
+    pub fn resolve_extension(&self, suffix: &str) -> String {
+        if let Some(extension) = SourceHeaders::well_known_file_extension(suffix) {
+            return extension.to_owned();
+        }
+        let suffix = suffix.to_lowercase();
+        self.0
+            .iter()
+            .find(|(custom, _)| *custom == suffix)
+            .map_or(suffix, |(_, extension)| extension.clone())
+    }
+
+    /// Finds the header definition of files with the suffix `extension`, see
+    /// [`SourceHeaders::find_header_definition_by_extension`].
+    pub fn find_header_definition(
+        &self,
+        extension: &str,
+    ) -> Option<&'static HeaderDefinition<'static>> {
+        let extension = self.resolve_extension(extension);
+        HEADER_DEFINITIONS
+            .iter()
+            .find(|source| source.contains_extension(Some(&extension)))
+    }
+}
+
+impl TryFrom<BTreeMap<String, String>> for FileTypes {
+    type Error = anyhow::Error;
+
+    fn try_from(file_types: BTreeMap<String, String>) -> Result<Self> {
+        FileTypes::parse(&file_types)
+    }
+}
+
+impl From<FileTypes> for BTreeMap<String, String> {
+    fn from(file_types: FileTypes) -> Self {
+        file_types.0.into_iter().collect()
+    }
+}
+
+/// Prefixes of first lines that license headers are inserted after, configured with
+/// `headDirectives`, e.g. `#cloud-config`. Prefixes are lowercase.
+///
+/// The default has no directives, so that only built-in prologues apply.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "Vec<String>", into = "Vec<String>")]
+pub struct HeadDirectives(Vec<String>);
+
+impl HeadDirectives {
+    /// Parses the `headDirectives` of a config.
+    ///
+    /// Prefixes are matched case-insensitively, so they're stored in lowercase.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a prefix is blank.
+    pub fn parse(directives: &[String]) -> Result<Self> {
+        directives
+            .iter()
+            .map(|directive| match directive.trim().is_empty() {
+                true => Err(anyhow!("head directive must not be blank")),
+                false => Ok(directive.to_lowercase()),
+            })
+            .collect::<Result<_>>()
+            .map(Self)
+    }
+
+    /// Checks whether the lowercase `line` starts with one of the directives.
+    fn matches(&self, line: &str) -> bool {
+        self.0
+            .iter()
+            .any(|directive| line.starts_with(directive.as_str()))
+    }
+}
+
+impl TryFrom<Vec<String>> for HeadDirectives {
+    type Error = anyhow::Error;
+
+    fn try_from(directives: Vec<String>) -> Result<Self> {
+        HeadDirectives::parse(&directives)
+    }
+}
+
+impl From<HeadDirectives> for Vec<String> {
+    fn from(directives: HeadDirectives) -> Self {
+        directives.0
+    }
+}
+
 /// Represents a utility for working with source headers.
 pub struct SourceHeaders;
 
 impl SourceHeaders {
-    /// Finds the header definition based on the given file extension.
-    pub fn find_header_definition_by_extension<'a, E: AsRef<str>>(
+    /// Finds the header definition based on the given file extension, including the
+    /// extensions configured with `file_types`.
+    ///
+    /// Well-known file names, e.g. `Makefile`, are accepted as well.
+    pub fn find_header_definition_by_extension<E: AsRef<str>>(
         extension: E,
-    ) -> Option<&'a HeaderDefinition<'a>> {
-        HEADER_DEFINITIONS
+        file_types: &FileTypes,
+    ) -> Option<&'static HeaderDefinition<'static>> {
+        file_types.find_header_definition(extension.as_ref())
+    }
+
+    /// Returns the extension whose header definition applies to the well-known
+    /// file `file_name`, if any.
+    pub fn well_known_file_extension(file_name: &str) -> Option<&'static str> {
+        FILE_NAMES
             .iter()
-            .find(|source| source.contains_extension(Some(&extension)))
+            .find(|(name, _)| *name == file_name)
+            .map(|(_, extension)| *extension)
     }
 
-    /// Finds the header prefix based on the given file extension.
-    pub fn find_header_prefix_for_extension<'a, E: AsRef<str>>(
+    /// Finds the header prefix based on the given file extension, including the
+    /// extensions configured with `file_types`.
+    pub fn find_header_prefix_for_extension<E: AsRef<str>>(
         extension: E,
-    ) -> Option<&'a HeaderPrefix<'a>> {
-        SourceHeaders::find_header_definition_by_extension(&extension)
+        file_types: &FileTypes,
+    ) -> Option<&'static HeaderPrefix<'static>> {
+        SourceHeaders::find_header_definition_by_extension(&extension, file_types)
             .map(|source| &source.header_prefix)
     }
 }
@@ -130,7 +367,7 @@ impl HeaderDefinition<'_> {
     /// Checks if the given extension is contained in the list of file extensions.
     pub fn contains_extension<E: AsRef<str>>(&self, extension: Option<E>) -> bool {
         extension
-            .map_or(false, |e| self.extensions.contains(&e.as_ref()))
+            .is_some_and(|e| self.extensions.contains(&e.as_ref()))
             .to_owned()
     }
 }
@@ -146,10 +383,37 @@ pub struct HeaderPrefix<'a> {
     pub bottom: &'a str,
 }
 
+/// Number of blank lines following a header, unless configured otherwise.
+pub const DEFAULT_BLANK_LINES_AFTER_HEADER: u8 = 1;
+
+/// Maximum number of blank lines following a header.
+pub const MAX_BLANK_LINES_AFTER_HEADER: u8 = 2;
+
 impl<'a> HeaderPrefix<'a> {
-    // execute_template will execute a license template t with data d
-    // and prefix the result with top, middle and bottom.
+    /// Comments `template`, a rendered notice, in this style, followed by
+    /// [`DEFAULT_BLANK_LINES_AFTER_HEADER`] blank lines.
+    ///
+    /// The notice is enclosed in the top and bottom lines of this prefix, and each
+    /// of its lines is prefixed with the middle part.
+    ///
+    /// ```
+    /// use licensa::template::HeaderPrefix;
+    ///
+    /// let prefix = HeaderPrefix::new("/*", " * ", " */");
+    /// let header = prefix.apply("Copyright ACME\nSPDX-License-Identifier: MIT")?;
+    /// assert_eq!(header, "/*\n * Copyright ACME\n * SPDX-License-Identifier: MIT\n */\n\n");
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
     pub fn apply<T>(&self, template: T) -> Result<String>
+    where
+        T: AsRef<str>,
+    {
+        self.apply_with_blank_lines(template, DEFAULT_BLANK_LINES_AFTER_HEADER)
+    }
+
+    /// Same as [`HeaderPrefix::apply`], followed by `blank_lines` blank lines instead
+    /// of [`DEFAULT_BLANK_LINES_AFTER_HEADER`].
+    pub fn apply_with_blank_lines<T>(&self, template: T, blank_lines: u8) -> Result<String>
     where
         T: AsRef<str>,
     {
@@ -173,7 +437,9 @@ impl<'a> HeaderPrefix<'a> {
             out.push('\n');
         }
 
-        out.push('\n');
+        for _ in 0..blank_lines {
+            out.push('\n');
+        }
 
         Ok(out)
     }
@@ -187,10 +453,11 @@ impl<'a> HeaderPrefix<'a> {
 /// Extracts the hash-bang line from the given byte slice.
 ///
 /// The hash-bang line is the first line in the slice ending with a newline character.
-/// It checks if the lowercase hash-bang line starts with any of the specified prefixes.
+/// It checks if the lowercase hash-bang line starts with any of the specified prefixes,
+/// or any of the head `directives` of the config.
 ///
 /// Returns the hash-bang line if a matching prefix is found, otherwise returns `None`.
-pub fn extract_hash_bang(b: &[u8]) -> Option<Vec<u8>> {
+pub fn extract_hash_bang(b: &[u8], directives: &HeadDirectives) -> Option<Vec<u8>> {
     let mut line = Vec::new();
 
     for &c in b {
@@ -208,6 +475,208 @@ pub fn extract_hash_bang(b: &[u8]) -> Option<Vec<u8>> {
         }
     }
 
+    directives.matches(&first).then_some(line)
+}
+
+/// Returns the extension whose header definition applies to a script starting
+/// with the hash-bang line in `b`, e.g. `.sh` for `#!/usr/bin/env bash`.
+///
+/// Versioned interpreters such as `python3.12` are recognized as well.
+pub fn find_interpreter_extension(b: &[u8]) -> Option<&'static str> {
+    let line = b.split(|&c| c == b'\n').next()?;
+    let line = std::str::from_utf8(line).ok()?.strip_prefix("#!")?;
+
+    let mut args = line.split_whitespace();
+    let mut program = args.next()?.rsplit('/').next()?;
+    if program == "env" {
+        // Skip options of `env`, e.g. `-S`
+        program = args.find(|arg| !arg.starts_with('-'))?;
+    }
+    let program = program.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+
+    INTERPRETERS
+        .iter()
+        .find(|(interpreter, _)| *interpreter == program)
+        .map(|(_, extension)| *extension)
+}
+
+/// Extracts the prologue preceding the license header from the given byte slice.
+///
+/// For XML, HTML and SVG documents, this is the XML declaration, processing
+/// instructions such as `<?xml-stylesheet ...?>` and the document type declaration,
+/// all of which may span multiple lines. For other files, it's the hash-bang line
+/// as returned by [`extract_hash_bang`].
+pub fn extract_prologue(b: &[u8], directives: &HeadDirectives) -> Option<Vec<u8>> {
+    let len = std::str::from_utf8(b).ok().and_then(find_markup_prologue);
+    match len {
+        Some(len) => Some(b[..len].to_vec()),
+        None => extract_hash_bang(b, directives),
+    }
+}
+
+/// Returns the length in bytes of the markup prologue at the start of `text`,
+/// including the remainder of its last line.
+fn find_markup_prologue(text: &str) -> Option<usize> {
+    let mut end = 0;
+    loop {
+        let rest = &text[end..];
+        let item = rest.trim_start();
+        let offset = end + rest.len() - item.len();
+
+        let item_len = if starts_with_ignore_case(item, "<?xml") {
+            item.find("?>")? + 2
+        } else if starts_with_ignore_case(item, "<!doctype") {
+            find_doctype_end(item)?
+        } else {
+            break;
+        };
+        end = offset + item_len;
+    }
+    if end == 0 {
+        return None;
+    }
+
+    // Content following the prologue on the same line isn't part of it
+    let rest = &text[end..];
+    let line_end = rest.find('\n').map_or(rest.len(), |i| i + 1);
+    if rest[..line_end].trim().is_empty() {
+        end += line_end;
+    }
+    Some(end)
+}
+
+/// Returns the length of a document type declaration, including an internal subset,
+/// e.g. `<!DOCTYPE note [<!ENTITY writer "Bilbo">]>`.
+fn find_doctype_end(doctype: &str) -> Option<usize> {
+    let close = doctype.find('>')?;
+    match doctype[..close].find('[') {
+        Some(subset) => {
+            let subset_end = subset + doctype[subset..].find(']')?;
+            Some(subset_end + doctype[subset_end..].find('>')? + 1)
+        }
+        None => Some(close + 1),
+    }
+}
+
+fn starts_with_ignore_case(text: &str, prefix: &str) -> bool {
+    text.get(..prefix.len())
+        .is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+}
+
+/// Where license headers are inserted into a file.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum, JsonSchema,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum HeaderPlacement {
+    /// At the top of the file, after a hash-bang line or similar directive.
+    #[default]
+    Top,
+
+    /// Below the module-level documentation of Rust (`//!`, `/*! */`), Python
+    /// (module docstring) and Go (package docs, followed by the `package` clause)
+    /// files. Other files and files without such documentation use `top`.
+    AfterDocs,
+}
+
+/// Returns the length in bytes of the module-level documentation at the start of `text`,
+/// according to the conventions of the language associated with `extension`.
+///
+/// Returns `None` if the language isn't supported or `text` doesn't start with
+/// module-level documentation.
+pub fn find_doc_block(extension: &str, text: &str) -> Option<usize> {
+    match extension {
+        ".rs" => find_rust_doc_block(text),
+        ".py" => find_python_docstring(text),
+        ".go" => find_go_package_doc(text),
+        _ => None,
+    }
+}
+
+/// Finds inner doc comments, i.e. `//!` lines or a `/*! */` block, after blank lines.
+fn find_rust_doc_block(text: &str) -> Option<usize> {
+    let mut offset = 0;
+    let mut end = None;
+    let mut in_block = false;
+
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim();
+        if in_block {
+            in_block = !trimmed.contains("*/");
+        } else if trimmed.starts_with("//!") {
+            // Line docs
+        } else if let Some(comment) = trimmed.strip_prefix("/*!") {
+            in_block = !comment.contains("*/");
+        } else if trimmed.is_empty() && end.is_none() {
+            offset += line.len();
+            continue;
+        } else {
+            break;
+        }
+        offset += line.len();
+        end = Some(offset);
+    }
+
+    if in_block {
+        return None;
+    }
+    end
+}
+
+/// Finds the module docstring, which may be preceded by blank lines and comments.
+fn find_python_docstring(text: &str) -> Option<usize> {
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if trimmed.trim_end().is_empty() || trimmed.starts_with('#') {
+            offset += line.len();
+            continue;
+        }
+
+        // String prefixes, e.g. `r"""` or `u'`
+        let unprefixed = trimmed.trim_start_matches(['r', 'R', 'u', 'U']);
+        let quote = ["\"\"\"", "\'\'\'", "\"", "\'"]
+            .into_iter()
+            .find(|quote| unprefixed.starts_with(quote))?;
+
+        let start = offset + (line.len() - unprefixed.len()) + quote.len();
+        let close = text[start..].find(quote)? + start + quote.len();
+        let end = text[close..]
+            .find('\n')
+            .map_or(text.len(), |i| close + i + 1);
+        return Some(end);
+    }
+    None
+}
+
+/// Finds the package documentation and the `package` clause it documents.
+///
+/// The header is placed after the `package` clause, since package documentation
+/// must immediately precede it.
+fn find_go_package_doc(text: &str) -> Option<usize> {
+    let mut offset = 0;
+    let mut documented = false;
+    let mut in_block = false;
+
+    for line in text.split_inclusive('\n') {
+        offset += line.len();
+        let trimmed = line.trim();
+        if in_block {
+            in_block = !trimmed.contains("*/");
+            documented = true;
+        } else if trimmed.starts_with("//") {
+            documented = true;
+        } else if let Some(comment) = trimmed.strip_prefix("/*") {
+            in_block = !comment.contains("*/");
+            documented = true;
+        } else if trimmed.is_empty() {
+            documented = false;
+        } else if trimmed.starts_with("package ") && documented {
+            return Some(offset);
+        } else {
+            return None;
+        }
+    }
     None
 }
 
@@ -218,7 +687,8 @@ mod tests {
 
     #[test]
     fn test_execute_template_spdx_copyright_notice() {
-        let rs_header_prefix = SourceHeaders::find_header_prefix_for_extension(".rs").unwrap();
+        let rs_header_prefix =
+            SourceHeaders::find_header_prefix_for_extension(".rs", &FileTypes::default()).unwrap();
         let reg = handlebars::Handlebars::new();
 
         // Test case 1
@@ -246,7 +716,8 @@ mod tests {
         assert_eq!(&result, expected);
 
         // JavaScript
-        let js_header_prefix = SourceHeaders::find_header_prefix_for_extension(".js").unwrap();
+        let js_header_prefix =
+            SourceHeaders::find_header_prefix_for_extension(".js", &FileTypes::default()).unwrap();
         let result = js_header_prefix.apply(template).unwrap();
 
         // Disable linting for template whitespace to be valid
@@ -260,11 +731,160 @@ mod tests {
         assert_eq!(&result, expected);
     }
 
+    #[test]
+    fn test_apply_with_blank_lines() {
+        let rs_header_prefix =
+            SourceHeaders::find_header_prefix_for_extension(".rs", &FileTypes::default()).unwrap();
+        let template = "Copyright Bilbo Baggins\nSPDX-License-Identifier: MIT";
+
+        let result = rs_header_prefix
+            .apply_with_blank_lines(template, 0)
+            .unwrap();
+        let expected = "// Copyright Bilbo Baggins\n// SPDX-License-Identifier: MIT\n";
+        assert_eq!(result, expected);
+
+        let result = rs_header_prefix
+            .apply_with_blank_lines(template, 2)
+            .unwrap();
+        assert_eq!(result, format!("{expected}\n\n"));
+    }
+
+    #[test]
+    fn test_language_header_prefixes() {
+        let template = "Copyright Bilbo Baggins\nSPDX-License-Identifier: MIT";
+        let cases = [
+            (
+                ".cmake",
+                "# Copyright Bilbo Baggins\n# SPDX-License-Identifier: MIT\n\n",
+            ),
+            (
+                ".lua",
+                "-- Copyright Bilbo Baggins\n-- SPDX-License-Identifier: MIT\n\n",
+            ),
+            (
+                ".zig",
+                "// Copyright Bilbo Baggins\n// SPDX-License-Identifier: MIT\n\n",
+            ),
+            (
+                ".nim",
+                "# Copyright Bilbo Baggins\n# SPDX-License-Identifier: MIT\n\n",
+            ),
+            (
+                ".tex",
+                "% Copyright Bilbo Baggins\n% SPDX-License-Identifier: MIT\n\n",
+            ),
+            (
+                ".ps1",
+                "# Copyright Bilbo Baggins\n# SPDX-License-Identifier: MIT\n\n",
+            ),
+            (
+                ".bat",
+                "REM Copyright Bilbo Baggins\nREM SPDX-License-Identifier: MIT\n\n",
+            ),
+            (
+                ".f90",
+                "! Copyright Bilbo Baggins\n! SPDX-License-Identifier: MIT\n\n",
+            ),
+            (
+                ".md",
+                "<!--\n Copyright Bilbo Baggins\n SPDX-License-Identifier: MIT\n-->\n\n",
+            ),
+            (
+                ".R",
+                "# Copyright Bilbo Baggins\n# SPDX-License-Identifier: MIT\n\n",
+            ),
+            (
+                ".clj",
+                ";; Copyright Bilbo Baggins\n;; SPDX-License-Identifier: MIT\n\n",
+            ),
+            (
+                ".less",
+                "/**\n * Copyright Bilbo Baggins\n * SPDX-License-Identifier: MIT\n */\n\n",
+            ),
+        ];
+
+        for (extension, expected) in cases {
+            let prefix =
+                SourceHeaders::find_header_prefix_for_extension(extension, &FileTypes::default())
+                    .unwrap_or_else(|| panic!("missing header definition for {extension}"));
+            assert_eq!(prefix.apply(template).unwrap(), expected, "{extension}");
+        }
+    }
+
+    #[test]
+    fn test_well_known_file_names() {
+        for (file_name, extension) in FILE_NAMES {
+            assert!(
+                SourceHeaders::find_header_definition_by_extension(
+                    extension,
+                    &FileTypes::default()
+                )
+                .is_some(),
+                "missing header definition for {file_name}"
+            );
+        }
+
+        let makefile =
+            SourceHeaders::find_header_prefix_for_extension("Makefile", &FileTypes::default())
+                .unwrap();
+        assert_eq!(makefile.mid, "# ");
+        let jenkinsfile =
+            SourceHeaders::find_header_prefix_for_extension("Jenkinsfile", &FileTypes::default())
+                .unwrap();
+        assert_eq!(jenkinsfile.mid, "// ");
+        assert!(
+            SourceHeaders::find_header_prefix_for_extension("README", &FileTypes::default())
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_find_interpreter_extension() {
+        assert_eq!(
+            find_interpreter_extension(b"#!/bin/bash\necho"),
+            Some(".sh")
+        );
+        assert_eq!(
+            find_interpreter_extension(b"#!/usr/bin/env -S python3.12 -u\n"),
+            Some(".py")
+        );
+        assert_eq!(
+            find_interpreter_extension(b"#!/usr/bin/env node"),
+            Some(".js")
+        );
+        assert_eq!(find_interpreter_extension(b"#!/usr/bin/awk -f\n"), None);
+        assert_eq!(find_interpreter_extension(b"echo\n#!/bin/sh\n"), None);
+        assert_eq!(find_interpreter_extension(b""), None);
+
+        for (interpreter, extension) in INTERPRETERS {
+            assert!(
+                SourceHeaders::find_header_definition_by_extension(
+                    extension,
+                    &FileTypes::default()
+                )
+                .is_some(),
+                "missing header definition for {interpreter}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_header_definitions_are_unique() {
+        let mut extensions = HEADER_DEFINITIONS
+            .iter()
+            .flat_map(|definition| definition.extensions.iter())
+            .collect::<Vec<_>>();
+        let total = extensions.len();
+        extensions.sort();
+        extensions.dedup();
+        assert_eq!(extensions.len(), total);
+    }
+
     #[test]
     fn test_hash_bang_with_valid_prefix() {
         // Test with a valid hash-bang line
         let input = "#!/bin/bash\nrest of the script".as_bytes();
-        let result = extract_hash_bang(input);
+        let result = extract_hash_bang(input, &HeadDirectives::default());
         let expected = Some(b"#!/bin/bash\n".to_vec());
         assert_eq!(result, expected);
     }
@@ -273,7 +893,7 @@ mod tests {
     fn test_hash_bang_with_invalid_prefix() {
         // Test with an invalid hash-bang line
         let input = "Invalid hash-bang line\nrest of the script".as_bytes();
-        let result = extract_hash_bang(input);
+        let result = extract_hash_bang(input, &HeadDirectives::default());
         let expected = None;
         assert_eq!(result, expected);
     }
@@ -282,16 +902,42 @@ mod tests {
     fn test_hash_bang_with_multiple_valid_prefixes() {
         // Test with multiple valid hash-bang prefixes
         let input = "<?xml\nrest of the content".as_bytes();
-        let result = extract_hash_bang(input);
+        let result = extract_hash_bang(input, &HeadDirectives::default());
         let expected = Some(b"<?xml\n".to_vec());
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn test_hash_bang_with_head_directive() {
+        assert!(HeadDirectives::parse(&[" ".to_string()]).is_err());
+        let directives = [
+            "#Cloud-Config".to_string(),
+            "// dart format off".to_string(),
+        ];
+        let directives = HeadDirectives::parse(&directives).unwrap();
+
+        let input = b"#cloud-config\npackages: []\n";
+        assert_eq!(
+            extract_hash_bang(input, &directives),
+            Some(b"#cloud-config\n".to_vec())
+        );
+        let input = b"// Dart format off\nvoid main() {}\n";
+        assert_eq!(
+            extract_hash_bang(input, &directives),
+            Some(b"// Dart format off\n".to_vec())
+        );
+        assert_eq!(extract_hash_bang(b"# cloud-config\n", &directives), None);
+        assert_eq!(
+            extract_hash_bang(b"#cloud-config\n", &HeadDirectives::default()),
+            None
+        );
+    }
+
     #[test]
     fn test_hash_bang_with_empty_input() {
         // Test with an empty input
         let input = "".as_bytes();
-        let result = extract_hash_bang(input);
+        let result = extract_hash_bang(input, &HeadDirectives::default());
         let expected = None;
         assert_eq!(result, expected);
     }
@@ -300,8 +946,99 @@ mod tests {
     fn test_hash_bang_with_partial_line() {
         // Test with a partial line (no newline character)
         let input = "#!/usr/bin/env python".as_bytes();
-        let result = extract_hash_bang(input);
+        let result = extract_hash_bang(input, &HeadDirectives::default());
         let expected = Some("#!/usr/bin/env python".as_bytes().to_vec());
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn test_extract_prologue_markup() {
+        let input = "<?xml version=\"1.0\"\n  encoding=\"UTF-8\"?>\n<!DOCTYPE svg PUBLIC\n  \"-//W3C//DTD SVG 1.1//EN\">\n<svg/>\n";
+        let expected = input.strip_suffix("<svg/>\n").unwrap();
+        assert_eq!(
+            extract_prologue(input.as_bytes(), &HeadDirectives::default()),
+            Some(expected.into())
+        );
+
+        let input = "<?xml version=\"1.0\"?>\n<?xml-stylesheet href=\"a.xsl\"?>\n<!DOCTYPE note [\n  <!ENTITY writer \"Bilbo\">\n]>\n<note/>";
+        let expected = input.strip_suffix("<note/>").unwrap();
+        assert_eq!(
+            extract_prologue(input.as_bytes(), &HeadDirectives::default()),
+            Some(expected.into())
+        );
+
+        let input = "<!doctype html><html>\n";
+        assert_eq!(
+            extract_prologue(input.as_bytes(), &HeadDirectives::default()),
+            Some(b"<!doctype html>".to_vec())
+        );
+    }
+
+    #[test]
+    fn test_extract_prologue_fallback() {
+        let input = "#!/bin/sh\necho\n";
+        assert_eq!(
+            extract_prologue(input.as_bytes(), &HeadDirectives::default()),
+            Some(b"#!/bin/sh\n".to_vec())
+        );
+
+        // Unterminated declarations fall back to the first line
+        let input = "<?xml\nrest of the content";
+        assert_eq!(
+            extract_prologue(input.as_bytes(), &HeadDirectives::default()),
+            Some(b"<?xml\n".to_vec())
+        );
+
+        let input = "<?php\necho 'x'; ?>\n";
+        assert_eq!(
+            extract_prologue(input.as_bytes(), &HeadDirectives::default()),
+            Some(b"<?php\n".to_vec())
+        );
+        assert_eq!(
+            extract_prologue(b"fn main() {}\n", &HeadDirectives::default()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_find_doc_block_rust() {
+        let text = "//! Module docs.\n//!\n//! More docs.\n\nuse std::fs;\n";
+        assert_eq!(find_doc_block(".rs", text), Some(36));
+
+        let text = "\n/*!\n * Module docs.\n */\nfn main() {}\n";
+        assert_eq!(find_doc_block(".rs", text), Some(25));
+
+        assert_eq!(find_doc_block(".rs", "//! Unterminated"), Some(16));
+        assert_eq!(
+            find_doc_block(".rs", "/// Item docs.\nfn main() {}\n"),
+            None
+        );
+        assert_eq!(find_doc_block(".rs", "/*! Unterminated\n"), None);
+    }
+
+    #[test]
+    fn test_find_doc_block_python() {
+        let text = "# -*- coding: utf-8 -*-\n\"\"\"Module docs.\n\nMore docs.\n\"\"\"\nimport os\n";
+        assert_eq!(find_doc_block(".py", text), Some(56));
+
+        let text = "r'Module docs.'\nimport os\n";
+        assert_eq!(find_doc_block(".py", text), Some(16));
+
+        assert_eq!(find_doc_block(".py", "import os\n"), None);
+        assert_eq!(find_doc_block(".py", "raise 'x'\n"), None);
+        assert_eq!(find_doc_block(".py", "\"\"\"Unterminated\n"), None);
+    }
+
+    #[test]
+    fn test_find_doc_block_go() {
+        let text = "// Package foo does things.\npackage foo\n\nimport \"os\"\n";
+        assert_eq!(find_doc_block(".go", text), Some(40));
+
+        let text = "//go:build linux\n\n/*\nPackage foo does things.\n*/\npackage foo\n";
+        assert_eq!(find_doc_block(".go", text), Some(61));
+
+        assert_eq!(find_doc_block(".go", "// Comment\n\npackage foo\n"), None);
+        assert_eq!(find_doc_block(".go", "package foo\n"), None);
+        assert_eq!(find_doc_block(".js", "// Docs\n"), None);
+    }
 }
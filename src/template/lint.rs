@@ -0,0 +1,333 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Detection of common problems in existing license headers.
+//!
+//! Only the first comment of a file is linted, and only if it contains a copyright
+//! line or an `SPDX-License-Identifier` tag. Unlike [`parse_header`], the linter
+//! accepts headers with arbitrary additional content.
+//!
+//! [`parse_header`]: crate::template::parse::parse_header
+
+use crate::spdx::find_id_ignore_case;
+use crate::template::header::HeadDirectives;
+use crate::template::parse::{
+    find_header_comment, is_year_word, strip_prefix_ignore_case, BLOCK_COMMENTS,
+    COPYRIGHT_PREFIXES, LINE_COMMENTS, SPDX_LICENSE_ID_MARKER,
+};
+
+use serde::Serialize;
+
+use std::fmt;
+
+/// The word every copyright line should contain.
+const COPYRIGHT: &str = "copyright";
+
+/// Misspellings of "copyright" too far off to be detected by their edit distance.
+const COPYRIGHT_MISSPELLINGS: &[&str] = &["copyrite", "copywrite", "copywright", "copywrited"];
+
+/// Operators of SPDX license expressions.
+const SPDX_OPERATORS: &[&str] = &["AND", "OR", "WITH"];
+
+/// The kind of problem found in a license header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LintCode {
+    /// The word "Copyright" is misspelled, e.g. as "Copyrigth".
+    MisspelledCopyright,
+
+    /// A copyright line doesn't state a year.
+    MissingYear,
+
+    /// An SPDX license or exception ID isn't written in its canonical casing.
+    SpdxIdCase,
+
+    /// The header contains more than one `SPDX-License-Identifier` tag.
+    DuplicateSpdxIdentifier,
+
+    /// A header line contains a tab character.
+    Tab,
+
+    /// A header line ends with whitespace.
+    TrailingWhitespace,
+}
+
+impl fmt::Display for LintCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let code = match self {
+            LintCode::MisspelledCopyright => "misspelled-copyright",
+            LintCode::MissingYear => "missing-year",
+            LintCode::SpdxIdCase => "spdx-id-case",
+            LintCode::DuplicateSpdxIdentifier => "duplicate-spdx-identifier",
+            LintCode::Tab => "tab",
+            LintCode::TrailingWhitespace => "trailing-whitespace",
+        };
+        f.write_str(code)
+    }
+}
+
+/// A problem found in a license header.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    /// The 1-based line number of the problem.
+    pub line: usize,
+
+    /// The 1-based column of the problem, counted in characters.
+    pub column: usize,
+
+    /// The kind of problem.
+    pub code: LintCode,
+
+    /// A description of the problem.
+    pub message: String,
+}
+
+/// Lints the license header at the top of `text`, following a prologue such as a
+/// hash-bang line or one of the head `directives`.
+///
+/// Returns the problems found, ordered by their position. Returns no problems if
+/// `text` doesn't start with a license header.
+pub fn lint_header(text: &str, directives: &HeadDirectives) -> Vec<Diagnostic> {
+    let Some(range) = find_header_comment(text, directives) else {
+        return vec![];
+    };
+    let first_line = text[..range.start].matches('\n').count() + 1;
+    let lines: Vec<&str> = text[range].lines().collect();
+    let is_license_header = lines.iter().any(|line| {
+        let content = comment_content(line).1;
+        is_copyright_line(content)
+            || strip_prefix_ignore_case(content, SPDX_LICENSE_ID_MARKER).is_some()
+            || words(content)
+                .next()
+                .is_some_and(|(_, word)| is_misspelled_copyright(word))
+    });
+    if !is_license_header {
+        return vec![];
+    }
+
+    let mut diagnostics = vec![];
+    let mut has_spdx_tag = false;
+    for (index, line) in lines.iter().enumerate() {
+        let mut report = |byte_offset: usize, code, message| {
+            diagnostics.push(Diagnostic {
+                line: first_line + index,
+                column: line[..byte_offset].chars().count() + 1,
+                code,
+                message,
+            })
+        };
+
+        let (offset, content) = comment_content(line);
+        if is_copyright_line(content) && !content.split_whitespace().any(is_year) {
+            report(
+                offset,
+                LintCode::MissingYear,
+                "copyright line has no year".into(),
+            );
+        }
+
+        for (word_offset, word) in words(content) {
+            if is_misspelled_copyright(word) {
+                report(
+                    offset + word_offset,
+                    LintCode::MisspelledCopyright,
+                    format!("`{word}` is a misspelling of `Copyright`"),
+                );
+            }
+        }
+
+        if let Some(expr) = strip_prefix_ignore_case(content, SPDX_LICENSE_ID_MARKER) {
+            if has_spdx_tag {
+                report(
+                    offset,
+                    LintCode::DuplicateSpdxIdentifier,
+                    "duplicate SPDX-License-Identifier tag".into(),
+                );
+            }
+            has_spdx_tag = true;
+
+            let expr_offset = offset + content.len() - expr.len();
+            for (id_offset, id) in license_ids(expr) {
+                if let Some(canonical) = find_id_ignore_case(id).filter(|c| *c != id) {
+                    report(
+                        expr_offset + id_offset,
+                        LintCode::SpdxIdCase,
+                        format!("SPDX ID `{id}` should be written as `{canonical}`"),
+                    );
+                }
+            }
+        }
+
+        if let Some(tab) = line.find('\t') {
+            report(tab, LintCode::Tab, "tab character in header".into());
+        }
+        let trimmed = line.trim_end();
+        if trimmed.len() < line.len() {
+            report(
+                trimmed.len(),
+                LintCode::TrailingWhitespace,
+                "trailing whitespace".into(),
+            );
+        }
+    }
+
+    diagnostics.sort_by_key(|d| (d.line, d.column, d.code));
+    diagnostics
+}
+
+/// Strips comment delimiters from the start of a header line.
+///
+/// Returns the byte offset of the remaining content in `line`, along with the content.
+fn comment_content(line: &str) -> (usize, &str) {
+    let mut content = line.trim_start();
+    let tokens = BLOCK_COMMENTS
+        .iter()
+        .map(|(open, _)| *open)
+        .chain(LINE_COMMENTS.iter().copied())
+        .chain(["*"]);
+    for token in tokens {
+        if let Some(rest) = content.strip_prefix(token) {
+            content = rest.trim_start_matches('*');
+            break;
+        }
+    }
+    let content = content.trim_start();
+    (line.len() - content.len(), content)
+}
+
+fn is_copyright_line(content: &str) -> bool {
+    COPYRIGHT_PREFIXES
+        .iter()
+        .any(|prefix| strip_prefix_ignore_case(content, prefix).is_some())
+}
+
+fn is_year(word: &str) -> bool {
+    word != "-" && is_year_word(word.trim_end_matches(['.', ';']))
+}
+
+/// Returns the alphabetic words of `text`, along with their byte offsets.
+fn words(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.split(|c: char| !c.is_alphabetic())
+        .filter(|word| !word.is_empty())
+        .map(move |word| (word.as_ptr() as usize - text.as_ptr() as usize, word))
+}
+
+/// Returns the license and exception IDs of the license expression `expr`, along
+/// with their byte offsets.
+fn license_ids(expr: &str) -> impl Iterator<Item = (usize, &str)> {
+    expr.split(|c: char| c.is_whitespace() || c == '(' || c == ')')
+        .filter(|token| !token.is_empty())
+        .filter(|token| {
+            !SPDX_OPERATORS
+                .iter()
+                .any(|op| op.eq_ignore_ascii_case(token))
+        })
+        .map(move |token| {
+            let offset = token.as_ptr() as usize - expr.as_ptr() as usize;
+            (offset, token.trim_end_matches('+'))
+        })
+}
+
+fn is_misspelled_copyright(word: &str) -> bool {
+    let word = word.to_lowercase();
+    if word.starts_with(COPYRIGHT) {
+        return false;
+    }
+    if COPYRIGHT_MISSPELLINGS.contains(&word.as_str()) {
+        return true;
+    }
+    word.starts_with('c') && word.len() >= 7 && edit_distance(&word, COPYRIGHT) <= 2
+}
+
+/// Returns the number of insertions, deletions, substitutions and transpositions of
+/// adjacent characters needed to turn `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn codes(text: &str) -> Vec<(usize, usize, LintCode)> {
+        lint_header(text, &HeadDirectives::default())
+            .into_iter()
+            .map(|d| (d.line, d.column, d.code))
+            .collect()
+    }
+
+    #[test]
+    fn test_lint_header_valid() {
+        let text = "#!/bin/sh\n# Copyright 2024 Bilbo\n# SPDX-License-Identifier: MIT OR Apache-2.0\n\necho \n";
+        assert_eq!(codes(text), []);
+
+        // Comments that aren't license headers are ignored
+        assert_eq!(codes("// Helpers \t\nfn main() {}\n"), []);
+        assert_eq!(codes("fn main() {}\n"), []);
+    }
+
+    #[test]
+    fn test_lint_header_problems() {
+        let text = "/*\n * Copyrigth 2024 Bilbo\n * (c) Frodo\n * SPDX-License-Identifier: mit OR apache-2.0+\n *\tSPDX-License-Identifier: MIT \n */\n";
+        assert_eq!(
+            codes(text),
+            [
+                (2, 4, LintCode::MisspelledCopyright),
+                (3, 4, LintCode::MissingYear),
+                (4, 29, LintCode::SpdxIdCase),
+                (4, 36, LintCode::SpdxIdCase),
+                (5, 3, LintCode::Tab),
+                (5, 4, LintCode::DuplicateSpdxIdentifier),
+                (5, 32, LintCode::TrailingWhitespace),
+            ]
+        );
+
+        let diagnostics = lint_header(text, &HeadDirectives::default());
+        assert_eq!(
+            diagnostics[2].message,
+            "SPDX ID `mit` should be written as `MIT`"
+        );
+    }
+
+    #[test]
+    fn test_is_misspelled_copyright() {
+        for word in [
+            "Copyrigth",
+            "COPYRIHGT",
+            "copyrite",
+            "Copywrite",
+            "coypright",
+        ] {
+            assert!(is_misspelled_copyright(word), "{word}");
+        }
+        for word in [
+            "Copyright",
+            "copyrighted",
+            "copyleft",
+            "copying",
+            "Contributors",
+        ] {
+            assert!(!is_misspelled_copyright(word), "{word}");
+        }
+    }
+}
@@ -1,9 +1,51 @@
 // Copyright 2024 Nelson Dominguez
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
+//! Rendering and detection of license headers.
+//!
+//! Headers are rendered in two steps, which are available to other tools, e.g.
+//! project scaffolders, to generate headers compatible with Licensa:
+//!
+//! 1. [`render_notice`] renders the copyright notice of a license in one of the
+//!    [`LicenseNoticeFormat`]s, with the values of a [`NoticeContext`].
+//! 2. [`HeaderPrefix::apply`] comments the notice in the style of a file type. The
+//!    [`HeaderPrefix`] of a file extension is looked up with
+//!    [`SourceHeaders::find_header_prefix_for_extension`], where [`FileTypes`] map
+//!    other extensions to those of known file types.
+//!
+//! ```
+//! use licensa::template::{
+//!     render_notice, FileTypes, LicenseNoticeFormat, NoticeContext, SourceHeaders,
+//! };
+//!
+//! let context = NoticeContext::new("ACME Inc.").with_year("2024");
+//! let notice = render_notice(LicenseNoticeFormat::Spdx, "MIT", &context)?;
+//! let prefix = SourceHeaders::find_header_prefix_for_extension(".rs", &FileTypes::default());
+//! let prefix = prefix.unwrap();
+//!
+//! assert_eq!(
+//!     prefix.apply(&notice)?,
+//!     "// Copyright 2024 ACME Inc.\n// SPDX-License-Identifier: MIT\n\n"
+//! );
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+
 pub mod cache;
 pub mod copyright;
+pub mod fingerprint;
 pub mod header;
+pub mod lint;
+pub mod notice;
+pub mod parse;
+pub mod partials;
+
+pub use header::{FileTypes, HeaderPrefix, SourceHeaders};
+pub use notice::{render_notice, LicenseNoticeFormat, NoticeContext};
+
+use crate::schema::LicenseYear;
+use crate::utils::current_year;
+
+use std::ops::Range;
 
 const BREAKWORDS: &[&str] = &[
     "spdx-license-identifier: ",
@@ -22,9 +64,11 @@ const BREAKWORDS: &[&str] = &[
 // FIXME: This is a simple, naive attempt to detect licene headers.
 // One improvement would be to only consider breakwords within
 // comment lines.
+//
+// The entire slice is searched, so callers are expected to only pass the
+// leading bytes of a file (see `ops::work_tree::DEFAULT_READ_WINDOW`).
 pub fn has_copyright_notice(b: &[u8]) -> bool {
-    let n = std::cmp::min(1000, b.len());
-    let lower_b: Vec<u8> = b[..n].iter().map(|&c| c.to_ascii_lowercase()).collect();
+    let lower_b: Vec<u8> = b.iter().map(|&c| c.to_ascii_lowercase()).collect();
 
     let bytes = BREAKWORDS.iter().map(|w| w.as_bytes());
 
@@ -39,3 +83,267 @@ pub fn has_copyright_notice(b: &[u8]) -> bool {
 
     false
 }
+
+/// Markers of files generated by tools, e.g. `// Code generated by protoc-gen-go. DO NOT EDIT.`
+/// or `/* automatically generated by rust-bindgen */`.
+const GENERATED_MARKERS: &[&str] = &[
+    "@generated",
+    "do not edit",
+    "code generated by",
+    "automatically generated",
+    "auto-generated",
+    "autogenerated",
+    "generated by the protocol buffer compiler",
+];
+
+/// Number of leading lines searched for markers of generated files.
+const GENERATED_MARKER_LINES: usize = 10;
+
+/// Checks whether `b`, the leading bytes of a file, marks the file as generated by a tool.
+pub fn is_generated(b: &[u8]) -> bool {
+    String::from_utf8_lossy(b)
+        .lines()
+        .take(GENERATED_MARKER_LINES)
+        .map(str::to_ascii_lowercase)
+        .any(|line| GENERATED_MARKERS.iter().any(|marker| line.contains(marker)))
+}
+
+/// Marker preceding the license expression of an SPDX license header.
+const SPDX_LICENSE_ID_MARKER: &str = "spdx-license-identifier:";
+
+/// Comment closing tokens that may trail a license expression on the same line.
+const COMMENT_CLOSERS: &[&str] = &["*/", "-->", "*)", "-}", "#>", "]]"];
+
+/// Returns the license expression of the first `SPDX-License-Identifier` tag
+/// found in `b`, if any.
+pub fn find_license_id(b: &[u8]) -> Option<String> {
+    let content = String::from_utf8_lossy(b);
+    let lower = content.to_ascii_lowercase();
+    let start = lower.find(SPDX_LICENSE_ID_MARKER)? + SPDX_LICENSE_ID_MARKER.len();
+
+    let line = content[start..].lines().next().unwrap_or_default();
+    let mut expr = line.trim();
+    for closer in COMMENT_CLOSERS {
+        expr = expr.trim_end_matches(closer).trim_end();
+    }
+
+    if expr.is_empty() {
+        return None;
+    }
+    Some(expr.to_string())
+}
+
+/// Comment opening tokens that may precede a copyright notice on the same line.
+const COMMENT_OPENERS: &[&str] = &[
+    "/**", "/*", "//!", "///", "//", "<!--", "(*", "{-", "<#", "--[[", "--", "#", ";", "*", "%",
+    "'", "rem ", "REM ", "!",
+];
+
+/// Returns the first line of `b` holding a copyright notice, without comment tokens.
+pub fn find_copyright_text(b: &[u8]) -> Option<String> {
+    let content = String::from_utf8_lossy(b);
+    let line = content
+        .lines()
+        .find(|line| line.to_ascii_lowercase().contains("copyright"))?;
+
+    let mut text = line.trim();
+    for opener in COMMENT_OPENERS {
+        if let Some(stripped) = text.strip_prefix(opener) {
+            text = stripped.trim_start();
+            break;
+        }
+    }
+    for closer in COMMENT_CLOSERS {
+        text = text.trim_end_matches(closer).trim_end();
+    }
+
+    if text.is_empty() {
+        return None;
+    }
+    Some(text.to_string())
+}
+
+/// Returns the latest year of the first copyright line in `b`, e.g. `2024` for
+/// `Copyright 2020-2024 Bilbo`.
+///
+/// A year range ending in `present` is considered to end in the current year.
+pub fn find_copyright_year(b: &[u8]) -> Option<u32> {
+    let text = find_copyright_text(b)?;
+    let mut latest = None;
+    for token in text.split(|c: char| !c.is_ascii_alphanumeric()) {
+        let year = if token.eq_ignore_ascii_case("present") && latest.is_some() {
+            current_year()
+        } else if token.len() == 4 && token.bytes().all(|c| c.is_ascii_digit()) {
+            token.parse().ok()?
+        } else {
+            continue;
+        };
+        latest = latest.max(Some(year));
+    }
+    latest
+}
+
+/// Finds the copyright year of the first copyright line within the first `limit`
+/// bytes of `text` and returns its byte range along with the year extended to `year`,
+/// e.g. `2020-2024` for `Copyright 2020 Bilbo`.
+///
+/// Returns `None` if the copyright line has no year, or if the year already extends
+/// to `year` or the present.
+pub fn extend_copyright_year(
+    text: &str,
+    limit: usize,
+    year: u32,
+) -> Option<(Range<usize>, String)> {
+    let mut offset = 0;
+    let line = text.split_inclusive('\n').find(|line| {
+        let found = line.to_ascii_lowercase().contains("copyright");
+        if !found {
+            offset += line.len();
+        }
+        found
+    })?;
+    if offset >= limit {
+        return None;
+    }
+
+    // The last year of a list such as `2019, 2021-2023` is extended
+    let (range, license_year) = line
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .rev()
+        .find_map(|token| {
+            let start = offset + (token.as_ptr() as usize - line.as_ptr() as usize);
+            Some((
+                start..start + token.len(),
+                token.parse::<LicenseYear>().ok()?,
+            ))
+        })?;
+
+    if license_year.last_year()? >= year {
+        return None;
+    }
+    let extended = LicenseYear::year_range(license_year.first_year(), year).ok()?;
+    Some((range, extended.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_generated() {
+        assert!(is_generated(
+            b"// Code generated by protoc-gen-go. DO NOT EDIT.\npackage pb\n"
+        ));
+        assert!(is_generated(
+            b"/* automatically generated by rust-bindgen 0.69.4 */\n"
+        ));
+        assert!(is_generated(
+            b"#!/usr/bin/env python\n# -*- coding: utf-8 -*-\n# Generated by the protocol buffer compiler.\n"
+        ));
+        assert!(is_generated(
+            b"# This file is automatically @generated by Cargo.\n"
+        ));
+        assert!(!is_generated(b"fn main() {}\n"));
+
+        let late_marker = format!("{}// @generated\n", "\n".repeat(GENERATED_MARKER_LINES));
+        assert!(!is_generated(late_marker.as_bytes()));
+    }
+
+    #[test]
+    fn test_has_copyright_notice() {
+        assert!(has_copyright_notice(b"// Copyright 2024 Bilbo Baggins\n"));
+        assert!(has_copyright_notice(b"# SPDX-License-Identifier: MIT\n"));
+        assert!(!has_copyright_notice(b"fn main() {}\n"));
+        assert!(!has_copyright_notice(b""));
+    }
+
+    #[test]
+    fn test_has_copyright_notice_searches_entire_window() {
+        let mut content = "\n".repeat(4096).into_bytes();
+        content.extend_from_slice(b"// Copyright 2024 Bilbo Baggins\n");
+        assert!(has_copyright_notice(&content));
+        assert!(!has_copyright_notice(&content[..1024]));
+    }
+
+    #[test]
+    fn test_find_license_id() {
+        let content =
+            b"// Copyright 2024 Bilbo Baggins\n// SPDX-License-Identifier: MIT OR Apache-2.0\n";
+        assert_eq!(find_license_id(content), Some("MIT OR Apache-2.0".into()));
+
+        let content = b"/* spdx-license-identifier: GPL-3.0-only */\n";
+        assert_eq!(find_license_id(content), Some("GPL-3.0-only".into()));
+
+        let content = b"<!-- SPDX-License-Identifier: MIT -->\r\n";
+        assert_eq!(find_license_id(content), Some("MIT".into()));
+
+        assert_eq!(find_license_id(b"// SPDX-License-Identifier:\n"), None);
+        assert_eq!(find_license_id(b"// Copyright 2024 Bilbo Baggins\n"), None);
+    }
+
+    #[test]
+    fn test_find_copyright_text() {
+        let content =
+            b"#!/bin/sh\n# Copyright 2024 Bilbo Baggins\n# SPDX-License-Identifier: MIT\n";
+        assert_eq!(
+            find_copyright_text(content),
+            Some("Copyright 2024 Bilbo Baggins".into())
+        );
+
+        let content = b"/*\n * Copyright (c) 2024 Frodo */\n";
+        assert_eq!(
+            find_copyright_text(content),
+            Some("Copyright (c) 2024 Frodo".into())
+        );
+
+        assert_eq!(find_copyright_text(b"fn main() {}\n"), None);
+    }
+
+    #[test]
+    fn test_extend_copyright_year() {
+        let text = "// Copyright 2020 Bilbo\n// SPDX-License-Identifier: MIT\n";
+        assert_eq!(
+            extend_copyright_year(text, 1024, 2024),
+            Some((13..17, "2020-2024".into()))
+        );
+
+        let text = "#!/bin/sh\n# Copyright (c) 2019, 2021-2023 Frodo\n";
+        assert_eq!(
+            extend_copyright_year(text, 1024, 2024),
+            Some((32..41, "2021-2024".into()))
+        );
+
+        let text = "// Copyright 2020-present Bilbo\n";
+        assert_eq!(extend_copyright_year(text, 1024, 2024), None);
+        assert_eq!(
+            extend_copyright_year("// Copyright 2024 Bilbo\n", 1024, 2024),
+            None
+        );
+        assert_eq!(
+            extend_copyright_year("// Copyright Bilbo\n", 1024, 2024),
+            None
+        );
+        assert_eq!(
+            extend_copyright_year("\n// Copyright 2020 Bilbo\n", 1, 2024),
+            None
+        );
+    }
+
+    #[test]
+    fn test_find_copyright_year() {
+        assert_eq!(
+            find_copyright_year(b"// Copyright 2021 Bilbo\n"),
+            Some(2021)
+        );
+        assert_eq!(
+            find_copyright_year(b"# Copyright (c) 2019-2023, 2024 Frodo\n"),
+            Some(2024)
+        );
+        assert_eq!(
+            find_copyright_year(b"// Copyright 2020-present Bilbo\n"),
+            Some(current_year())
+        );
+        assert_eq!(find_copyright_year(b"// Copyright Bilbo\n"), None);
+        assert_eq!(find_copyright_year(b"// Copyright 12345 Bilbo\n"), None);
+    }
+}
@@ -0,0 +1,674 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Formats and variables of copyright notices.
+//!
+//! Config values rendered into a notice may reference variables as `$(name)`,
+//! e.g. `"owner": "ACME Inc. $(division)"`. Besides the user-defined `variables`
+//! of the config file, the built-in variables `owner`, `email`, `project`,
+//! `projectUrl`, `license` and `year` are available. A literal `$(` is written as `$$(`.
+//! Shared fragments are included as `$(> name)`, see [`partials`](crate::template::partials).
+//!
+//! The sentence of the `compact` format additionally has access to `determiner`,
+//! the indefinite article preceding the license, and `location`, where the full
+//! license text can be found.
+
+use crate::spdx::notices::{full_notice_template, required_notice_fields};
+use crate::template::copyright::{
+    COMPACT_COPYRIGHT_NOTICE, REUSE_COPYRIGHT_NOTICE, SPDX_COPYRIGHT_NOTICE,
+};
+use crate::template::partials::Partials;
+
+use anyhow::{anyhow, Context, Result};
+use clap::ValueEnum;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use thiserror::Error;
+use tracing::warn;
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// The format of the copyright notice written to files.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum, JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum LicenseNoticeFormat {
+    /// A copyright line followed by an `SPDX-License-Identifier` tag.
+    #[default]
+    Spdx,
+
+    /// A copyright line followed by the notice recommended by the license authors,
+    /// e.g. the Apache-2.0 boilerplate. Falls back to `spdx` for other licenses.
+    Full,
+
+    /// An `SPDX-FileCopyrightText` tag followed by an `SPDX-License-Identifier` tag,
+    /// as required by the [REUSE](https://reuse.software) specification.
+    Reuse,
+
+    /// A copyright line followed by a single sentence pointing to the license,
+    /// see [`DEFAULT_COMPACT_SENTENCE`].
+    Compact,
+}
+
+impl LicenseNoticeFormat {
+    /// Returns the handlebars template of the notice of `license` in this format.
+    ///
+    /// Returns `None` if this format isn't available for `license`.
+    pub fn template(&self, license: &str) -> Option<Cow<'static, str>> {
+        match self {
+            LicenseNoticeFormat::Spdx => Some(Cow::Borrowed(SPDX_COPYRIGHT_NOTICE)),
+            LicenseNoticeFormat::Full => full_notice_template(license).map(Cow::Owned),
+            LicenseNoticeFormat::Reuse => Some(Cow::Borrowed(REUSE_COPYRIGHT_NOTICE)),
+            LicenseNoticeFormat::Compact => Some(Cow::Borrowed(COMPACT_COPYRIGHT_NOTICE)),
+        }
+    }
+}
+
+/// Values rendered into a copyright notice.
+#[derive(Debug, Clone, Default)]
+pub struct NoticeContext {
+    /// The copyright owner, which may reference variables.
+    pub owner: String,
+
+    /// The email address of the copyright owner, rendered as `Owner <email>`.
+    pub email: Option<String>,
+
+    /// The name of the project, required by the full notice of some licenses.
+    pub project: Option<String>,
+
+    /// The URL of the project homepage, required by the full notice of some licenses.
+    pub project_url: Option<String>,
+
+    /// Contributors rendered as `SPDX-FileContributor` lines beneath the copyright line.
+    pub contributors: Vec<String>,
+
+    /// The copyright year(s), e.g. `2024` or `2020-present`.
+    pub year: Option<String>,
+
+    /// Custom variables that may be referenced as `$(name)`.
+    pub variables: BTreeMap<String, String>,
+
+    /// The sentence of the `compact` format, see [`DEFAULT_COMPACT_SENTENCE`].
+    pub compact_sentence: Option<String>,
+
+    /// Partials that may be included as `$(> name)`.
+    pub partials: Option<Arc<Partials>>,
+}
+
+impl NoticeContext {
+    /// Creates a context for notices of the copyright owner `owner`, without a year.
+    pub fn new<S>(owner: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            owner: owner.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Sets the email address of the copyright owner.
+    pub fn with_email<S>(mut self, email: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.email = Some(email.into());
+        self
+    }
+
+    /// Sets the name of the project.
+    pub fn with_project<S>(mut self, project: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.project = Some(project.into());
+        self
+    }
+
+    /// Sets the URL of the project homepage.
+    pub fn with_project_url<S>(mut self, url: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.project_url = Some(url.into());
+        self
+    }
+
+    /// Adds a contributor, which may reference variables.
+    pub fn with_contributor<S>(mut self, contributor: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.contributors.push(contributor.into());
+        self
+    }
+
+    /// Sets the copyright year(s), e.g. `2024` or `2020-present`.
+    pub fn with_year<S>(mut self, year: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.year = Some(year.into());
+        self
+    }
+
+    /// Defines the variable `name`, which may be referenced as `$(name)`.
+    pub fn with_variable<K, V>(mut self, name: K, value: V) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.variables.insert(name.into(), value.into());
+        self
+    }
+
+    /// Sets the sentence of the `compact` format, see [`DEFAULT_COMPACT_SENTENCE`].
+    pub fn with_compact_sentence<S>(mut self, sentence: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.compact_sentence = Some(sentence.into());
+        self
+    }
+
+    /// Sets the partials that may be included as `$(> name)`.
+    pub fn with_partials(mut self, partials: Arc<Partials>) -> Self {
+        self.partials = Some(partials);
+        self
+    }
+
+    /// Expands the variable references and partials of `text`.
+    fn interpolate(&self, text: &str, variables: &BTreeMap<String, String>) -> Result<String> {
+        match self.partials.as_deref() {
+            Some(partials) => interpolate_with_partials(text, variables, partials),
+            None => Ok(interpolate(text, variables)?),
+        }
+    }
+}
+
+/// Renders the copyright notice of `license` in `format`, expanding `$(name)` variable references.
+///
+/// Falls back to the `spdx` format if `format` isn't available for `license`.
+/// The notice is not commented, see [`HeaderPrefix::apply`](crate::template::HeaderPrefix::apply).
+///
+/// ```
+/// use licensa::template::{render_notice, LicenseNoticeFormat, NoticeContext};
+///
+/// let context = NoticeContext::new("$(team) at ACME").with_variable("team", "Platform");
+/// let notice = render_notice(LicenseNoticeFormat::Reuse, "Apache-2.0", &context)?;
+/// assert_eq!(
+///     notice,
+///     "SPDX-FileCopyrightText: Platform at ACME\nSPDX-License-Identifier: Apache-2.0"
+/// );
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if a config value references an undefined variable, or if the
+/// `full` notice of `license` names the project but `project` or `projectUrl` is unset.
+pub fn render_notice(
+    format: LicenseNoticeFormat,
+    license: &str,
+    context: &NoticeContext,
+) -> Result<String> {
+    let mut variables = context.variables.clone();
+    variables.insert("owner".into(), context.owner.clone());
+    variables.insert("license".into(), license.to_string());
+    if let Some(year) = context.year.as_ref() {
+        variables.insert("year".into(), year.clone());
+    }
+    if let Some(email) = context.email.as_ref() {
+        variables.insert("email".into(), email.clone());
+    }
+
+    let mut owner = context
+        .interpolate(&context.owner, &variables)
+        .context("failed to render copyright owner")?;
+    if let Some(email) = context.email.as_ref() {
+        let email = context
+            .interpolate(email, &variables)
+            .context("failed to render owner email")?;
+        owner = format!("{owner} <{email}>");
+    }
+    let project = context
+        .project
+        .as_deref()
+        .map(|project| context.interpolate(project, &variables))
+        .transpose()
+        .context("failed to render project name")?;
+    let project_url = context
+        .project_url
+        .as_deref()
+        .map(|url| context.interpolate(url, &variables))
+        .transpose()
+        .context("failed to render project URL")?;
+    if let Some(project) = project.as_ref() {
+        variables.insert("project".into(), project.clone());
+    }
+    if let Some(project_url) = project_url.as_ref() {
+        variables.insert("projectUrl".into(), project_url.clone());
+    }
+    let contributors = context
+        .contributors
+        .iter()
+        .map(|contributor| context.interpolate(contributor, &variables))
+        .collect::<Result<Vec<_>>>()
+        .context("failed to render contributor")?;
+    let mut data = json!({
+        "owner": owner,
+        "contributors": contributors,
+        "license": license,
+        "year": context.year,
+        "project": project,
+        "projectUrl": project_url,
+    });
+
+    if format == LicenseNoticeFormat::Full {
+        let missing: Vec<_> = required_notice_fields(license)
+            .into_iter()
+            .filter(|field| data[*field].is_null())
+            .map(|field| format!("`{field}`"))
+            .collect();
+        if !missing.is_empty() {
+            let verb = if missing.len() == 1 { "is" } else { "are" };
+            return Err(anyhow!(
+                "the full notice of {license} names the project, but {} {verb} not configured",
+                missing.join(" and ")
+            ));
+        }
+    }
+
+    if format == LicenseNoticeFormat::Compact {
+        variables.insert("determiner".into(), determiner(license).into());
+        variables.insert("location".into(), COMPACT_LOCATION.into());
+        let sentence = context
+            .compact_sentence
+            .as_deref()
+            .unwrap_or(DEFAULT_COMPACT_SENTENCE);
+        data["sentence"] = context
+            .interpolate(sentence, &variables)
+            .context("failed to render compact notice sentence")?
+            .into();
+    }
+
+    let template = format.template(license).unwrap_or_else(|| {
+        warn!("{format:?} notice format is not available for {license}, using SPDX notice");
+        LicenseNoticeFormat::Spdx.template(license).unwrap()
+    });
+
+    // Notices are plain text, e.g. the brackets of an email address must not be escaped
+    let mut template_engine = handlebars::Handlebars::new();
+    template_engine.register_escape_fn(handlebars::no_escape);
+    Ok(template_engine.render_template(&template, &data)?)
+}
+
+/// The sentence of the `compact` format, unless overridden by `compactSentence`.
+pub const DEFAULT_COMPACT_SENTENCE: &str =
+    "Use of this source code is governed by $(determiner) $(license) license that can be found in $(location).";
+
+/// Where the full license text of the `compact` format can be found.
+pub const COMPACT_LOCATION: &str = "the LICENSE file at the root of this project";
+
+/// Letters whose name starts with a vowel sound when spelled out, e.g. "an MIT license".
+const VOWEL_SOUND_LETTERS: &str = "AEFHILMNORSX";
+
+/// Returns the indefinite article preceding `license` in a sentence, i.e. `a` or `an`.
+///
+/// Acronyms such as `MIT` or `GPL-3.0-only` are read letter by letter, while
+/// words such as `Apache-2.0` or `Zlib` are read as such.
+pub fn determiner(license: &str) -> &'static str {
+    let word = license
+        .split(|c: char| c.is_whitespace() || c == '-')
+        .next()
+        .unwrap_or_default();
+    let Some(first) = word.chars().next() else {
+        return "a";
+    };
+
+    let is_acronym = word.len() > 1 && word.chars().all(|c| c.is_ascii_uppercase());
+    let vowel_sound = match is_acronym {
+        true => VOWEL_SOUND_LETTERS.contains(first),
+        false => "AEIOUaeiou".contains(first),
+    };
+    match vowel_sound {
+        true => "an",
+        false => "a",
+    }
+}
+
+/// Error returned when a template references a variable that isn't defined.
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("undefined template variable `$({0})`")]
+pub struct UndefinedVariable(pub String);
+
+/// Replaces every `$(name)` reference in `text` with the value of the variable `name`.
+///
+/// References without a closing parenthesis are left as is.
+pub fn interpolate(
+    text: &str,
+    variables: &BTreeMap<String, String>,
+) -> Result<String, UndefinedVariable> {
+    expand(text, variables, None)
+}
+
+/// Like [`interpolate`], additionally replacing every `$(> name)` reference with the
+/// partial `name` of `partials`.
+///
+/// Partials are interpolated themselves, so that they may reference variables and
+/// include other partials.
+///
+/// ```
+/// use licensa::template::notice::interpolate_with_partials;
+/// use licensa::template::partials::Partials;
+/// use std::collections::BTreeMap;
+///
+/// let partials = Partials::default().with_partial("disclaimer", "Provided by $(owner) as is.");
+/// let variables = BTreeMap::from([("owner".to_string(), "ACME Inc.".to_string())]);
+/// assert_eq!(
+///     interpolate_with_partials("$(> disclaimer)", &variables, &partials)?,
+///     "Provided by ACME Inc. as is."
+/// );
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if a variable or partial is undefined, or if a partial includes
+/// itself.
+pub fn interpolate_with_partials(
+    text: &str,
+    variables: &BTreeMap<String, String>,
+    partials: &Partials,
+) -> Result<String> {
+    expand_partials(text, variables, partials, &mut vec![])
+}
+
+/// Interpolates `text`, where `included` are the partials currently being expanded.
+fn expand_partials(
+    text: &str,
+    variables: &BTreeMap<String, String>,
+    partials: &Partials,
+    included: &mut Vec<String>,
+) -> Result<String> {
+    expand(
+        text,
+        variables,
+        Some(&mut |name: &str| {
+            if included.iter().any(|partial| partial == name) {
+                return Err(anyhow!("partial `{name}` includes itself"));
+            }
+            let content = partials.get(name)?;
+            included.push(name.to_string());
+            let expanded = expand_partials(&content, variables, partials, included)
+                .with_context(|| format!("failed to render partial `{name}`"));
+            included.pop();
+            expanded
+        }),
+    )
+}
+
+/// Returns the expanded content of the partial with the given name.
+type PartialResolver<'a, E> = &'a mut dyn FnMut(&str) -> Result<String, E>;
+
+/// Replaces variable references in `text`, and `$(> name)` references with the
+/// result of `partial`, if given.
+fn expand<E>(
+    text: &str,
+    variables: &BTreeMap<String, String>,
+    mut partial: Option<PartialResolver<E>>,
+) -> Result<String, E>
+where
+    E: From<UndefinedVariable>,
+{
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find("$(") {
+        // `$$(` escapes a reference
+        if rest[..start].ends_with('$') {
+            output.push_str(&rest[..start - 1]);
+            output.push_str("$(");
+            rest = &rest[start + 2..];
+            continue;
+        }
+
+        output.push_str(&rest[..start]);
+        let reference = &rest[start + 2..];
+        let Some(end) = reference.find(')') else {
+            output.push_str(&rest[start..]);
+            rest = "";
+            break;
+        };
+
+        let name = reference[..end].trim();
+        if let (Some(partial), Some(partial_name)) = (partial.as_mut(), name.strip_prefix('>')) {
+            output.push_str(&partial(partial_name.trim())?);
+            rest = &reference[end + 1..];
+            continue;
+        }
+        let value = variables
+            .get(name)
+            .ok_or_else(|| UndefinedVariable(name.to_string()))?;
+        output.push_str(value);
+        rest = &reference[end + 1..];
+    }
+    output.push_str(rest);
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variables() -> BTreeMap<String, String> {
+        BTreeMap::from([
+            ("owner".to_string(), "ACME Inc.".to_string()),
+            ("division".to_string(), "Research".to_string()),
+        ])
+    }
+
+    #[test]
+    fn test_notice_format_template() {
+        let spdx = LicenseNoticeFormat::Spdx
+            .template("MIT OR Apache-2.0")
+            .unwrap();
+        assert_eq!(spdx, SPDX_COPYRIGHT_NOTICE);
+
+        let full = LicenseNoticeFormat::Full.template("Apache-2.0").unwrap();
+        assert!(full.contains("Licensed under the Apache License, Version 2.0"));
+        let full = LicenseNoticeFormat::Full
+            .template("MIT OR Apache-2.0")
+            .unwrap();
+        assert!(full.contains("Permission is hereby granted"));
+        assert!(full.contains("Licensed under the Apache License, Version 2.0"));
+        assert!(LicenseNoticeFormat::Full
+            .template("MIT OR Unlicense")
+            .is_none());
+
+        let reuse = LicenseNoticeFormat::Reuse
+            .template("MIT OR Apache-2.0")
+            .unwrap();
+        assert_eq!(reuse, REUSE_COPYRIGHT_NOTICE);
+    }
+
+    #[test]
+    fn test_render_notice() {
+        let context = NoticeContext {
+            owner: "ACME Inc. $(division)".into(),
+            email: None,
+            project: None,
+            project_url: None,
+            contributors: vec![],
+            year: Some("2024".into()),
+            variables: variables(),
+            compact_sentence: None,
+            partials: None,
+        };
+
+        let notice = render_notice(LicenseNoticeFormat::Spdx, "MIT", &context).unwrap();
+        assert_eq!(
+            notice,
+            "Copyright 2024 ACME Inc. Research\nSPDX-License-Identifier: MIT"
+        );
+
+        let notice = render_notice(LicenseNoticeFormat::Compact, "MIT", &context).unwrap();
+        assert!(notice.ends_with("governed by an MIT license that can be found in the LICENSE file at the root of this project."));
+
+        let notice = render_notice(LicenseNoticeFormat::Full, "MIT OR Apache-2.0", &context);
+        assert!(notice.unwrap().starts_with(
+            "Copyright 2024 ACME Inc. Research\n\nLicensed under either of the following licenses"
+        ));
+
+        // Falls back to the SPDX format
+        let notice = render_notice(LicenseNoticeFormat::Full, "MIT OR Unlicense", &context);
+        assert!(notice
+            .unwrap()
+            .ends_with("SPDX-License-Identifier: MIT OR Unlicense"));
+    }
+
+    #[test]
+    fn test_render_notice_with_email() {
+        let context = NoticeContext::new("Jane & Co.")
+            .with_email("jane@acme.io")
+            .with_year("2024")
+            .with_compact_sentence("Contact $(email) for licensing.");
+
+        let notice = render_notice(LicenseNoticeFormat::Spdx, "MIT", &context).unwrap();
+        assert_eq!(
+            notice,
+            "Copyright 2024 Jane & Co. <jane@acme.io>\nSPDX-License-Identifier: MIT"
+        );
+
+        let notice = render_notice(LicenseNoticeFormat::Compact, "MIT", &context).unwrap();
+        assert_eq!(
+            notice,
+            "Copyright 2024 Jane & Co. <jane@acme.io>\nContact jane@acme.io for licensing."
+        );
+
+        let notice = render_notice(LicenseNoticeFormat::Full, "Apache-2.0", &context).unwrap();
+        assert!(notice.starts_with("Copyright 2024 Jane & Co. <jane@acme.io>\n"));
+
+        // The variable is only defined if an email is configured
+        let context = NoticeContext::new("Jane").with_compact_sentence("$(email)");
+        assert!(render_notice(LicenseNoticeFormat::Compact, "MIT", &context).is_err());
+    }
+
+    #[test]
+    fn test_render_notice_with_contributors() {
+        let context = NoticeContext::new("ACME Inc.")
+            .with_year("2024")
+            .with_contributor("Jane Doe")
+            .with_contributor("$(team) team")
+            .with_variable("team", "Platform");
+
+        let notice = render_notice(LicenseNoticeFormat::Reuse, "MIT", &context).unwrap();
+        assert_eq!(
+            notice,
+            "SPDX-FileCopyrightText: 2024 ACME Inc.\nSPDX-FileContributor: Jane Doe\nSPDX-FileContributor: Platform team\nSPDX-License-Identifier: MIT"
+        );
+
+        let notice = render_notice(LicenseNoticeFormat::Full, "MPL-2.0", &context).unwrap();
+        assert!(notice.starts_with("Copyright 2024 ACME Inc.\nSPDX-FileContributor: Jane Doe\nSPDX-FileContributor: Platform team\n\nThis Source Code Form"));
+
+        let notice = render_notice(LicenseNoticeFormat::Compact, "MIT", &context).unwrap();
+        assert!(notice.contains("Platform team\nUse of this source code"));
+    }
+
+    #[test]
+    fn test_render_notice_with_project() {
+        let context = NoticeContext::new("ACME Inc.")
+            .with_project("Rocket $(division)")
+            .with_project_url("https://rocket.acme.io")
+            .with_variable("division", "Engine")
+            .with_compact_sentence("Part of $(project), see $(projectUrl).");
+
+        let notice = render_notice(LicenseNoticeFormat::Full, "NCSA", &context).unwrap();
+        assert!(notice.starts_with("Copyright ACME Inc.\n\nDeveloped by: Rocket Engine\n"));
+        assert!(notice.contains("https://rocket.acme.io\n"));
+
+        let notice = render_notice(LicenseNoticeFormat::Compact, "MIT", &context).unwrap();
+        assert!(notice.ends_with("Part of Rocket Engine, see https://rocket.acme.io."));
+
+        let context = NoticeContext::new("ACME Inc.").with_project("Rocket");
+        let err = render_notice(LicenseNoticeFormat::Full, "MIT OR NCSA", &context).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "the full notice of MIT OR NCSA names the project, but `projectUrl` is not configured"
+        );
+        assert!(render_notice(LicenseNoticeFormat::Full, "Vim", &context).is_ok());
+
+        // Other formats don't name the project
+        let context = NoticeContext::new("ACME Inc.");
+        assert!(render_notice(LicenseNoticeFormat::Spdx, "NCSA", &context).is_ok());
+        assert!(render_notice(LicenseNoticeFormat::Full, "BSD-4-Clause", &context).is_err());
+    }
+
+    #[test]
+    fn test_render_notice_with_partials() {
+        let partials = Partials::default()
+            .with_partial("disclaimer", "$(> owner) provides $(project) as is.")
+            .with_partial("owner", "$(owner)")
+            .with_partial("loop", "$(> loop)");
+        let context = NoticeContext::new("ACME Inc.")
+            .with_project("Rocket")
+            .with_compact_sentence("$(> disclaimer)")
+            .with_partials(Arc::new(partials));
+
+        let notice = render_notice(LicenseNoticeFormat::Compact, "MIT", &context).unwrap();
+        assert_eq!(
+            notice,
+            "Copyright ACME Inc.\nACME Inc. provides Rocket as is."
+        );
+
+        let context = context.with_compact_sentence("$(> loop)");
+        let err = render_notice(LicenseNoticeFormat::Compact, "MIT", &context).unwrap_err();
+        assert!(format!("{err:#}").ends_with("partial `loop` includes itself"));
+
+        // Without partials, the reference is an undefined variable
+        let context = NoticeContext::new("ACME Inc. $(> owner)");
+        assert!(render_notice(LicenseNoticeFormat::Spdx, "MIT", &context).is_err());
+    }
+
+    #[test]
+    fn test_determiner() {
+        assert_eq!(determiner("MIT"), "an");
+        assert_eq!(determiner("Apache-2.0"), "an");
+        assert_eq!(determiner("MPL-2.0"), "an");
+        assert_eq!(determiner("GPL-3.0-only"), "a");
+        assert_eq!(determiner("BSD-3-Clause"), "a");
+        assert_eq!(determiner("UPL-1.0"), "a");
+        assert_eq!(determiner("Zlib"), "a");
+        assert_eq!(determiner("MIT OR Apache-2.0"), "an");
+    }
+
+    #[test]
+    fn test_interpolate() {
+        let text = interpolate("$(owner) $( division ) team", &variables()).unwrap();
+        assert_eq!(text, "ACME Inc. Research team");
+
+        let text = interpolate("no variables", &variables()).unwrap();
+        assert_eq!(text, "no variables");
+    }
+
+    #[test]
+    fn test_interpolate_escaped_and_unterminated() {
+        let text = interpolate("$$(owner) $(owner", &variables()).unwrap();
+        assert_eq!(text, "$(owner) $(owner");
+    }
+
+    #[test]
+    fn test_interpolate_undefined_variable() {
+        let err = interpolate("$(owner), $(department)", &variables()).unwrap_err();
+        assert_eq!(err, UndefinedVariable("department".into()));
+        assert_eq!(
+            err.to_string(),
+            "undefined template variable `$(department)`"
+        );
+    }
+}
@@ -0,0 +1,403 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Parsing of existing license headers.
+//!
+//! A header is recognized if it's the first comment at the top of a file (after
+//! a hash-bang line or similar directive) and consists of exactly one copyright
+//! line, optionally followed by `SPDX-FileContributor` tags, plus either an `SPDX-License-Identifier` tag or the full notice of a
+//! supported license. Headers with any other content are not recognized, so
+//! that rewriting a header never drops information.
+
+use crate::spdx::notices::identify_full_notice;
+use crate::template::header::{extract_prologue, HeadDirectives};
+
+use std::ops::Range;
+
+/// Block comment delimiters recognized in headers.
+pub(crate) const BLOCK_COMMENTS: &[(&str, &str)] = &[
+    ("/*", "*/"),
+    ("<!--", "-->"),
+    ("(*", "*)"),
+    ("{#", "#}"),
+    ("{-", "-}"),
+];
+
+/// Line comment tokens recognized in headers.
+pub(crate) const LINE_COMMENTS: &[&str] = &["//", "#", "--", ";", "%", "!", "REM "];
+
+/// Line prefixes that look like comments but aren't part of a header.
+const NON_HEADER_PREFIXES: &[&str] = &["///", "//!", "#!", "#["];
+
+/// Prefixes of a copyright line, stripped in order.
+pub(crate) const COPYRIGHT_PREFIXES: &[&str] =
+    &["spdx-filecopyrighttext:", "copyright", "(c)", "©"];
+
+/// Marker preceding a contributor of an SPDX license header.
+pub(crate) const SPDX_FILE_CONTRIBUTOR_MARKER: &str = "spdx-filecontributor:";
+
+/// Marker preceding the license expression of an SPDX license header.
+pub(crate) const SPDX_LICENSE_ID_MARKER: &str = "spdx-license-identifier:";
+
+/// The semantic content of an existing license header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParsedHeader {
+    /// Byte range of the header, including blank lines following it.
+    pub range: Range<usize>,
+
+    /// The copyright owner.
+    pub owner: String,
+
+    /// The copyright year(s) as written in the header, e.g. `2020-2024`.
+    pub year: Option<String>,
+
+    /// The SPDX license expression.
+    pub license: String,
+
+    /// The contributors listed in `SPDX-FileContributor` tags.
+    pub contributors: Vec<String>,
+}
+
+/// Parses the license header at the top of `text`, following a prologue such as a
+/// hash-bang line or one of the head `directives`.
+///
+/// Returns `None` if `text` doesn't start with a recognized header.
+pub fn parse_header(text: &str, directives: &HeadDirectives) -> Option<ParsedHeader> {
+    let (Range { start, end }, lines) = locate_comment(text, directives)?;
+    let blank_lines: usize = text[end..]
+        .split_inclusive('\n')
+        .take_while(|line| line.trim().is_empty())
+        .map(str::len)
+        .sum();
+
+    let mut copyright = None;
+    let mut license = None;
+    let mut contributors = vec![];
+    let mut body = vec![];
+    for line in lines.iter().filter(|line| !line.is_empty()) {
+        if let Some(expr) = strip_prefix_ignore_case(line, SPDX_LICENSE_ID_MARKER) {
+            let expr = expr.trim();
+            if license.is_some() || expr.is_empty() {
+                return None;
+            }
+            license = Some(expr.to_string());
+        } else if let Some(contributor) =
+            strip_prefix_ignore_case(line, SPDX_FILE_CONTRIBUTOR_MARKER)
+        {
+            let contributor = contributor.trim();
+            if contributor.is_empty() {
+                return None;
+            }
+            contributors.push(contributor.to_string());
+        } else if COPYRIGHT_PREFIXES
+            .iter()
+            .any(|prefix| strip_prefix_ignore_case(line, prefix).is_some())
+        {
+            if copyright.is_some() {
+                return None;
+            }
+            copyright = Some(parse_copyright(line)?);
+        } else {
+            body.push(line.as_str());
+        }
+    }
+
+    if !body.is_empty() {
+        let id = identify_full_notice(&body.join("\n"))?;
+        if license.as_ref().is_some_and(|license| *license != id) {
+            return None;
+        }
+        license = Some(id);
+    }
+
+    let (owner, year) = copyright?;
+    Some(ParsedHeader {
+        range: start..end + blank_lines,
+        owner,
+        year,
+        license: license?,
+        contributors,
+    })
+}
+
+/// Returns the byte range of the first comment of `text`, following a hash-bang line
+/// or one of the head `directives`, and blank lines.
+///
+/// Returns `None` if `text` doesn't start with a comment, or with a doc comment.
+pub fn find_header_comment(text: &str, directives: &HeadDirectives) -> Option<Range<usize>> {
+    locate_comment(text, directives).map(|(range, _)| range)
+}
+
+/// Returns the byte range of the first comment of `text`, as [`find_header_comment`],
+/// if it holds the copyright notice of an owner other than the owner of `notice`
+/// and no `SPDX-License-Identifier` tag.
+///
+/// `notice` is an uncommented notice starting with a copyright line, e.g. as
+/// rendered by [`render_notice`](crate::template::render_notice).
+pub fn find_foreign_header(
+    text: &str,
+    notice: &str,
+    directives: &HeadDirectives,
+) -> Option<Range<usize>> {
+    let (owner, _) = parse_copyright(notice.lines().next()?)?;
+    let (range, lines) = locate_comment(text, directives)?;
+    if lines
+        .iter()
+        .any(|line| strip_prefix_ignore_case(line, SPDX_LICENSE_ID_MARKER).is_some())
+    {
+        return None;
+    }
+
+    let copyright = lines.iter().find(|line| {
+        COPYRIGHT_PREFIXES
+            .iter()
+            .any(|prefix| strip_prefix_ignore_case(line, prefix).is_some())
+    })?;
+    let (foreign_owner, _) = parse_copyright(copyright)?;
+    (!foreign_owner.eq_ignore_ascii_case(&owner)).then_some(range)
+}
+
+fn locate_comment(text: &str, directives: &HeadDirectives) -> Option<(Range<usize>, Vec<String>)> {
+    let mut start = extract_prologue(text.as_bytes(), directives).map_or(0, |line| line.len());
+    for line in text[start..].split_inclusive('\n') {
+        if !line.trim().is_empty() {
+            break;
+        }
+        start += line.len();
+    }
+
+    let (end, lines) = find_comment(&text[start..])?;
+    Some((start..start + end, lines))
+}
+
+/// Finds the comment at the start of `text`.
+///
+/// Returns the length of the comment in bytes and its lines without comment tokens.
+fn find_comment(text: &str) -> Option<(usize, Vec<String>)> {
+    let first = text.lines().next()?.trim_start();
+    if NON_HEADER_PREFIXES.iter().any(|p| first.starts_with(p)) {
+        return None;
+    }
+
+    if let Some((open, close)) = BLOCK_COMMENTS
+        .iter()
+        .find(|(open, _)| first.starts_with(open))
+    {
+        let content_start = text.find(open)? + open.len();
+        let content_end = content_start + text[content_start..].find(close)?;
+        let end = text[content_end..]
+            .find('\n')
+            .map_or(text.len(), |i| content_end + i + 1);
+        if !text[content_end + close.len()..end].trim().is_empty() {
+            // Code following the comment on the same line
+            return None;
+        }
+
+        let lines = text[content_start..content_end]
+            .lines()
+            .map(|line| {
+                let line = line.trim();
+                // Leading asterisks of `/** ... */` comments
+                let line = match *open {
+                    "/*" | "(*" => line.trim_start_matches('*'),
+                    _ => line,
+                };
+                line.trim().to_string()
+            })
+            .collect();
+        return Some((end, lines));
+    }
+
+    let token = LINE_COMMENTS
+        .iter()
+        .find(|token| first.starts_with(*token))?;
+    let mut end = 0;
+    let mut lines = vec![];
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        let Some(comment) = trimmed.strip_prefix(token) else {
+            break;
+        };
+        if NON_HEADER_PREFIXES.iter().any(|p| trimmed.starts_with(p)) {
+            break;
+        }
+        end += line.len();
+        lines.push(comment.trim().to_string());
+    }
+    Some((end, lines))
+}
+
+/// Parses the owner and optional year(s) of a copyright line.
+fn parse_copyright(line: &str) -> Option<(String, Option<String>)> {
+    let mut rest = line.trim();
+    while let Some(stripped) = COPYRIGHT_PREFIXES
+        .iter()
+        .find_map(|prefix| strip_prefix_ignore_case(rest, prefix))
+    {
+        rest = stripped.trim_start();
+    }
+
+    let mut words = rest.split_whitespace().peekable();
+    let mut years = vec![];
+    while let Some(word) = words.next_if(|word| is_year_word(word)) {
+        years.push(word);
+    }
+    let owner = words.collect::<Vec<_>>().join(" ");
+    if owner.is_empty() {
+        return None;
+    }
+
+    let year = (!years.is_empty()).then(|| years.join(" ").trim_end_matches(',').to_string());
+    Some((owner, year))
+}
+
+pub(crate) fn strip_prefix_ignore_case<'a>(text: &'a str, prefix: &str) -> Option<&'a str> {
+    text.get(..prefix.len())
+        .filter(|start| start.eq_ignore_ascii_case(prefix))
+        .map(|_| &text[prefix.len()..])
+}
+
+/// Checks whether `word` is part of a year, range of years or list of years,
+/// e.g. `2024`, `2020-present`, `2019,` or `-`.
+pub(crate) fn is_year_word(word: &str) -> bool {
+    let word = word.trim_end_matches(',');
+    if word == "-" {
+        return true;
+    }
+    let mut parts = word.splitn(2, '-');
+    let is_year = |part: &str| part.len() == 4 && part.chars().all(|c| c.is_ascii_digit());
+    let start = parts.next().is_some_and(is_year);
+    match parts.next() {
+        Some(end) => start && (is_year(end) || end == "present"),
+        None => start,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_header_line_comments() {
+        let text = "#!/bin/sh\n#  Copyright (c) 2020-2024  Bilbo Baggins\n#SPDX-License-Identifier: MIT\n\n\necho\n";
+        let header = parse_header(text, &HeadDirectives::default()).unwrap();
+        assert_eq!(header.owner, "Bilbo Baggins");
+        assert_eq!(header.year.as_deref(), Some("2020-2024"));
+        assert_eq!(header.license, "MIT");
+        assert_eq!(&text[header.range.end..], "echo\n");
+        assert_eq!(&text[..header.range.start], "#!/bin/sh\n");
+    }
+
+    #[test]
+    fn test_parse_header_block_comment() {
+        let text = "/**\n * SPDX-FileCopyrightText: 2019, 2021 ACME Inc.\n *\n * SPDX-License-Identifier: Apache-2.0\n */\nimport x;\n";
+        let header = parse_header(text, &HeadDirectives::default()).unwrap();
+        assert_eq!(header.owner, "ACME Inc.");
+        assert_eq!(header.year.as_deref(), Some("2019, 2021"));
+        assert_eq!(header.license, "Apache-2.0");
+        assert_eq!(&text[header.range.end..], "import x;\n");
+    }
+
+    #[test]
+    fn test_parse_header_contributors() {
+        let text = "// Copyright 2024 ACME Inc.\n// SPDX-FileContributor: Jane Doe\n// spdx-filecontributor:  John Doe \n// SPDX-License-Identifier: MIT\n";
+        let header = parse_header(text, &HeadDirectives::default()).unwrap();
+        assert_eq!(header.owner, "ACME Inc.");
+        assert_eq!(header.contributors, ["Jane Doe", "John Doe"]);
+        assert_eq!(header.license, "MIT");
+
+        let text =
+            "// Copyright ACME Inc.\n// SPDX-FileContributor:\n// SPDX-License-Identifier: MIT\n";
+        assert_eq!(parse_header(text, &HeadDirectives::default()), None);
+    }
+
+    #[test]
+    fn test_parse_header_full_notice() {
+        let text = "// Copyright Frodo\n//\n// This Source Code Form is subject to the terms of the Mozilla Public License,\n// v. 2.0. If a copy of the MPL was not distributed with this file, You can\n// obtain one at https://mozilla.org/MPL/2.0/.\nfn main() {}\n";
+        let header = parse_header(text, &HeadDirectives::default()).unwrap();
+        assert_eq!(header.owner, "Frodo");
+        assert_eq!(header.year, None);
+        assert_eq!(header.license, "MPL-2.0");
+    }
+
+    #[test]
+    fn test_find_foreign_header() {
+        let notice = "Copyright 2024 ACME Inc.\nSPDX-License-Identifier: MIT";
+        let text = "#!/bin/sh\n# Copyright (c) 2019 Foo Corp.\n# All rights reserved.\n\necho\n";
+        let range = find_foreign_header(text, notice, &HeadDirectives::default()).unwrap();
+        assert_eq!(
+            &text[range],
+            "# Copyright (c) 2019 Foo Corp.\n# All rights reserved.\n"
+        );
+
+        // Own copyright notices
+        let text = "// Copyright 2020 acme inc.\n// All rights reserved.\n";
+        assert_eq!(
+            find_foreign_header(text, notice, &HeadDirectives::default()),
+            None
+        );
+
+        // Notices with a license identifier
+        let text = "// Copyright Foo Corp.\n// SPDX-License-Identifier: Apache-2.0\n";
+        assert_eq!(
+            find_foreign_header(text, notice, &HeadDirectives::default()),
+            None
+        );
+
+        // Copyright notices outside of the header comment
+        let text = "// Utilities\nfn main() {}\n// Copyright Foo Corp.\n";
+        assert_eq!(
+            find_foreign_header(text, notice, &HeadDirectives::default()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_header_unrecognized() {
+        // Additional content
+        let text =
+            "// Copyright 2024 Bilbo\n// All rights reserved.\n// SPDX-License-Identifier: MIT\n";
+        assert_eq!(parse_header(text, &HeadDirectives::default()), None);
+
+        // Multiple copyright lines
+        let text =
+            "// Copyright 2024 Bilbo\n// Copyright 2024 Frodo\n// SPDX-License-Identifier: MIT\n";
+        assert_eq!(parse_header(text, &HeadDirectives::default()), None);
+
+        // No license
+        assert_eq!(
+            parse_header("// Copyright 2024 Bilbo\n", &HeadDirectives::default()),
+            None
+        );
+
+        // Doc comments and code
+        assert_eq!(
+            parse_header("//! Copyright 2024 Bilbo\n", &HeadDirectives::default()),
+            None
+        );
+        assert_eq!(
+            parse_header(
+                "/* Copyright Bilbo\nSPDX-License-Identifier: MIT */ int x;\n",
+                &HeadDirectives::default()
+            ),
+            None
+        );
+        assert_eq!(
+            parse_header("fn main() {}\n", &HeadDirectives::default()),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_copyright() {
+        assert_eq!(
+            parse_copyright("Copyright (C) 2020 - 2024 Bilbo"),
+            Some(("Bilbo".into(), Some("2020 - 2024".into())))
+        );
+        assert_eq!(
+            parse_copyright("© 2021-present ACME, Inc."),
+            Some(("ACME, Inc.".into(), Some("2021-present".into())))
+        );
+        assert_eq!(parse_copyright("Copyright 2024"), None);
+    }
+}
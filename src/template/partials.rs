@@ -0,0 +1,127 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Shared fragments of copyright notices, e.g. a common legal disclaimer.
+//!
+//! Config values rendered into a notice may include the partial `name` as
+//! `$(> name)`, which expands to the content of `name.txt` in the templates
+//! directory. Partials may reference variables and other partials themselves.
+//! Each partial is read once and cached for all notices rendered afterwards.
+
+use anyhow::{anyhow, Context, Result};
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// The directory partials are loaded from, relative to the workspace root, unless
+/// overridden by `templates`.
+pub const DEFAULT_TEMPLATES_DIRNAME: &str = ".licensa-templates";
+
+/// The extension of partial files in the templates directory.
+pub const PARTIAL_EXTENSION: &str = "txt";
+
+/// Partials loaded from a templates directory.
+#[derive(Debug, Default)]
+pub struct Partials {
+    /// The templates directory, or `None` if only registered partials are available.
+    dir: Option<PathBuf>,
+
+    /// Content of the partials read so far, by name.
+    cache: Mutex<BTreeMap<String, String>>,
+}
+
+impl Partials {
+    /// Creates partials loaded from the templates directory `dir`.
+    pub fn new<P>(dir: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        Self {
+            dir: Some(dir.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Registers the partial `name` with `content`, taking precedence over a file
+    /// of the templates directory.
+    pub fn with_partial<K, V>(self, name: K, content: V) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.cache
+            .lock()
+            .unwrap()
+            .insert(name.into(), content.into());
+        self
+    }
+
+    /// Returns the content of the partial `name`, without trailing line breaks.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `name` is not a plain file name, or if the partial
+    /// doesn't exist or can't be read.
+    pub fn get(&self, name: &str) -> Result<String> {
+        if let Some(content) = self.cache.lock().unwrap().get(name) {
+            return Ok(content.clone());
+        }
+        if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+            return Err(anyhow!("invalid partial name `{name}`"));
+        }
+        let Some(dir) = self.dir.as_ref() else {
+            return Err(anyhow!("undefined partial `{name}`"));
+        };
+
+        let path = dir.join(format!("{name}.{PARTIAL_EXTENSION}"));
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content.trim_end_matches(['\r', '\n']).to_string(),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                return Err(anyhow!(
+                    "undefined partial `{name}`, expected it in {}",
+                    path.display()
+                ))
+            }
+            Err(err) => {
+                return Err(err).with_context(|| format!("failed to read {}", path.display()))
+            }
+        };
+
+        self.cache
+            .lock()
+            .unwrap()
+            .insert(name.to_string(), content.clone());
+        Ok(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_partials() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("disclaimer.txt");
+        fs::write(&path, "Provided as is.\n\n").unwrap();
+
+        let partials = Partials::new(dir.path()).with_partial("team", "Platform");
+        assert_eq!(partials.get("disclaimer").unwrap(), "Provided as is.");
+        assert_eq!(partials.get("team").unwrap(), "Platform");
+
+        // Partials are read once
+        fs::remove_file(&path).unwrap();
+        assert_eq!(partials.get("disclaimer").unwrap(), "Provided as is.");
+
+        let err = partials.get("missing").unwrap_err();
+        assert!(err.to_string().starts_with("undefined partial `missing`"));
+        assert!(partials.get("../disclaimer").is_err());
+        assert!(Partials::default().get("disclaimer").is_err());
+
+        dir.close().unwrap();
+    }
+}
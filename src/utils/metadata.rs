@@ -0,0 +1,160 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Copying file metadata that survives neither a copy nor a rename of file content.
+//!
+//! Files are rewritten by renaming a temporary file over them, so that ownership and
+//! extended attributes of the original file have to be carried over explicitly.
+//! Both are copied on a best-effort basis: a user can't give away files it doesn't
+//! own, and file systems may not support extended attributes at all.
+
+use std::fs::Metadata;
+use std::io;
+use std::path::Path;
+
+/// Makes the file at `path` owned by the owner and group of `metadata`.
+///
+/// Changing the owner requires elevated privileges, so that only the group is
+/// changed if the owner can't be.
+#[cfg(unix)]
+pub fn copy_ownership(path: &Path, metadata: &Metadata) -> io::Result<()> {
+    use std::os::unix::fs::{chown, MetadataExt};
+
+    let current = path.metadata()?;
+    if (current.uid(), current.gid()) == (metadata.uid(), metadata.gid()) {
+        return Ok(());
+    }
+    chown(path, Some(metadata.uid()), Some(metadata.gid()))
+        .or_else(|_| chown(path, None, Some(metadata.gid())))
+}
+
+#[cfg(not(unix))]
+pub fn copy_ownership(path: &Path, metadata: &Metadata) -> io::Result<()> {
+    Ok(())
+}
+
+/// Copies all extended attributes of the file at `from` to the file at `to`.
+///
+/// Returns the names of attributes that couldn't be copied, e.g. attributes of
+/// the `trusted` namespace for unprivileged users.
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+pub fn copy_xattrs(from: &Path, to: &Path) -> io::Result<Vec<String>> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let from = CString::new(from.as_os_str().as_bytes())?;
+    let to = CString::new(to.as_os_str().as_bytes())?;
+
+    let names = match read_buffer(|buf, size| unsafe { sys::listxattr(from.as_ptr(), buf, size) }) {
+        Ok(names) => names,
+        Err(err) if is_unsupported(&err) => return Ok(vec![]),
+        Err(err) => return Err(err),
+    };
+
+    let mut failed = vec![];
+    for name in names.split(|&b| b == 0).filter(|name| !name.is_empty()) {
+        let name = CString::new(name)?;
+        let copied = read_buffer(|buf, size| unsafe {
+            sys::getxattr(from.as_ptr(), name.as_ptr(), buf.cast(), size)
+        })
+        .and_then(|value| {
+            let result = unsafe {
+                sys::setxattr(
+                    to.as_ptr(),
+                    name.as_ptr(),
+                    value.as_ptr().cast(),
+                    value.len(),
+                )
+            };
+            match result {
+                0 => Ok(()),
+                _ => Err(io::Error::last_os_error()),
+            }
+        });
+        if copied.is_err() {
+            failed.push(name.to_string_lossy().into_owned());
+        }
+    }
+    Ok(failed)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn copy_xattrs(from: &Path, to: &Path) -> io::Result<Vec<String>> {
+    Ok(vec![])
+}
+
+/// Reads a variable-length value with `read`, which follows the convention of the
+/// extended attribute functions: called with a size of 0, it returns the required
+/// buffer size.
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn read_buffer<F>(read: F) -> io::Result<Vec<u8>>
+where
+    F: Fn(*mut libc::c_char, usize) -> libc::ssize_t,
+{
+    loop {
+        let size = read(std::ptr::null_mut(), 0);
+        if size < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let mut buf = vec![0u8; size as usize];
+        let read_size = read(buf.as_mut_ptr().cast(), buf.len());
+        match read_size {
+            // The value grew in the meantime
+            -1 if io::Error::last_os_error().raw_os_error() == Some(libc::ERANGE) => continue,
+            -1 => return Err(io::Error::last_os_error()),
+            read_size => {
+                buf.truncate(read_size as usize);
+                return Ok(buf);
+            }
+        }
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn is_unsupported(err: &io::Error) -> bool {
+    err.raw_os_error() == Some(libc::ENOTSUP)
+}
+
+/// Extended attribute functions with the signatures of Linux.
+#[cfg(target_os = "linux")]
+mod sys {
+    pub use libc::{getxattr, listxattr, setxattr as setxattr_flags};
+
+    pub unsafe fn setxattr(
+        path: *const libc::c_char,
+        name: *const libc::c_char,
+        value: *const libc::c_void,
+        size: usize,
+    ) -> libc::c_int {
+        setxattr_flags(path, name, value, size, 0)
+    }
+}
+
+/// Extended attribute functions with the signatures of Linux, wrapping those of
+/// macOS which take additional position and option arguments.
+#[cfg(target_os = "macos")]
+mod sys {
+    use libc::{c_char, c_int, c_void, ssize_t};
+
+    pub unsafe fn listxattr(path: *const c_char, list: *mut c_char, size: usize) -> ssize_t {
+        libc::listxattr(path, list, size, 0)
+    }
+
+    pub unsafe fn getxattr(
+        path: *const c_char,
+        name: *const c_char,
+        value: *mut c_void,
+        size: usize,
+    ) -> ssize_t {
+        libc::getxattr(path, name, value, size, 0, 0)
+    }
+
+    pub unsafe fn setxattr(
+        path: *const c_char,
+        name: *const c_char,
+        value: *const c_void,
+        size: usize,
+    ) -> c_int {
+        libc::setxattr(path, name, value, size, 0, 0)
+    }
+}
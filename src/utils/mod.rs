@@ -4,15 +4,19 @@
 #[cfg(test)]
 pub mod testing;
 
+pub mod metadata;
 pub mod validate;
 
 use validate::is_valid_year;
 
 use anyhow::{anyhow, Result};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use tracing::debug;
 
 use std::{
-    fs::File,
-    io::Write,
+    fs::{self, File},
+    io::{self, Read, Write},
     path::{Path, PathBuf},
     time::{SystemTime, UNIX_EPOCH},
 };
@@ -30,7 +34,8 @@ fn is_leap_year(year: u32) -> bool {
     (year % 4 == 0 && year % 100 != 0) || (year % 400 == 0)
 }
 
-fn current_year() -> u32 {
+/// Returns the current calendar year in UTC.
+pub fn current_year() -> u32 {
     let now = SystemTime::now();
     let seconds_since_epoch = now
         .duration_since(UNIX_EPOCH)
@@ -92,6 +97,174 @@ pub fn write_json<P: AsRef<Path>>(file_path: P, json_data: &serde_json::Value) -
     Ok(())
 }
 
+/// Returns the hex-encoded SHA-256 hash of `content`.
+pub fn sha256_hex<C: AsRef<[u8]>>(content: C) -> String {
+    format!("{:x}", Sha256::digest(content.as_ref()))
+}
+
+/// Returns the hex-encoded SHA-1 hash of `content`.
+pub fn sha1_hex<C: AsRef<[u8]>>(content: C) -> String {
+    format!("{:x}", Sha1::digest(content.as_ref()))
+}
+
+/// Returns the current UTC time formatted as `YYYY-MM-DDTHH:MM:SSZ`.
+pub fn utc_timestamp() -> String {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    format_utc_timestamp(secs)
+}
+
+/// Formats seconds since the Unix epoch as `YYYY-MM-DDTHH:MM:SSZ`.
+fn format_utc_timestamp(secs: u64) -> String {
+    let days = (secs / 86400) as i64;
+    let time = secs % 86400;
+
+    // Converts days since epoch into a civil date (proleptic Gregorian calendar)
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        time / 3600,
+        time % 3600 / 60,
+        time % 60
+    )
+}
+
+/// Atomically replaces the content of the file at `path`.
+///
+/// The content is written to a temporary file in the same directory, which is then
+/// renamed to `path`. A crash mid-write therefore never leaves a truncated file behind.
+///
+/// The permissions of an existing file at `path` are preserved, as are its owner and
+/// extended attributes where possible. If `path` is a symbolic link, the file it
+/// points to is replaced instead of the link.
+///
+/// # Errors
+///
+/// Returns an error if the temporary file cannot be created or written, or if it
+/// cannot be renamed to `path`.
+pub fn write_atomic<P, C>(path: P, content: C) -> io::Result<()>
+where
+    P: AsRef<Path>,
+    C: AsRef<[u8]>,
+{
+    write_atomic_with_mtime(path, content, None)
+}
+
+/// Atomically replaces the content of the file at `path`, like [`write_atomic`], and
+/// sets its modification time to `mtime`, if given.
+pub fn write_atomic_with_mtime<P, C>(
+    path: P,
+    content: C,
+    mtime: Option<SystemTime>,
+) -> io::Result<()>
+where
+    P: AsRef<Path>,
+    C: AsRef<[u8]>,
+{
+    let path = path.as_ref();
+    let resolved;
+    let path = match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_symlink() => {
+            resolved = fs::canonicalize(path)?;
+            resolved.as_path()
+        }
+        _ => path,
+    };
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+
+    let mut tmp_file = tempfile::Builder::new()
+        .prefix(".licensa")
+        .suffix(".tmp")
+        .tempfile_in(dir)?;
+    tmp_file.write_all(content.as_ref())?;
+    // Renaming the file keeps its modification time
+    if let Some(mtime) = mtime {
+        tmp_file.as_file().set_modified(mtime)?;
+    }
+    tmp_file.as_file().sync_all()?;
+
+    if let Ok(metadata) = fs::metadata(path) {
+        // Changing the owner may clear the setuid and setgid bits, so permissions
+        // are set afterwards
+        if let Err(err) = metadata::copy_ownership(tmp_file.path(), &metadata) {
+            debug!("failed to preserve owner of {}: {err}", path.display());
+        }
+        fs::set_permissions(tmp_file.path(), metadata.permissions())?;
+        match metadata::copy_xattrs(path, tmp_file.path()) {
+            Ok(failed) if failed.is_empty() => {}
+            Ok(failed) => debug!(
+                "failed to preserve extended attributes {} of {}",
+                failed.join(", "),
+                path.display()
+            ),
+            Err(err) => debug!(
+                "failed to preserve extended attributes of {}: {err}",
+                path.display()
+            ),
+        }
+    }
+
+    tmp_file.persist(path).map_err(|err| err.error)?;
+    Ok(())
+}
+
+/// Reads at most `limit` leading bytes of the file at `path`.
+///
+/// This allows inspecting the beginning of a file (e.g. to detect a license header)
+/// without loading its entire content into memory.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be opened or read.
+pub fn read_head<P: AsRef<Path>>(path: P, limit: usize) -> io::Result<Vec<u8>> {
+    let file = File::open(path)?;
+    let mut head = Vec::with_capacity(limit.min(8 * 1024));
+    file.take(limit as u64).read_to_end(&mut head)?;
+    Ok(head)
+}
+
+/// Strips the `\\?\` prefix of verbatim paths, as returned by [`Path::canonicalize`]
+/// on Windows, e.g. `\\?\C:\src` becomes `C:\src` and `\\?\UNC\server\share`
+/// becomes `\\server\share`.
+///
+/// Verbatim paths without a drive letter or UNC prefix, such as volume GUID paths,
+/// are returned unchanged, as are all other paths.
+pub fn strip_verbatim_prefix<P: AsRef<Path>>(path: P) -> PathBuf {
+    let path = path.as_ref();
+    let Some(text) = path.to_str() else {
+        return path.to_path_buf();
+    };
+    if let Some(unc) = text.strip_prefix(r"\\?\UNC\") {
+        return PathBuf::from(format!(r"\\{unc}"));
+    }
+    match text.strip_prefix(r"\\?\") {
+        Some(rest) if rest.as_bytes().get(1) == Some(&b':') => PathBuf::from(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Returns `path` as displayed in command output, i.e. without a verbatim prefix
+/// and with `/` separators on all platforms.
+pub fn display_path<P: AsRef<Path>>(path: P) -> String {
+    strip_verbatim_prefix(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
 /// Checks if any of the specified filenames exist in the given path.
 ///
 /// # Arguments
@@ -129,6 +302,28 @@ mod tests {
     use std::io::{Read, Seek, SeekFrom};
     use tempfile::tempdir;
 
+    #[test]
+    fn test_strip_verbatim_prefix() {
+        assert_eq!(
+            strip_verbatim_prefix(r"\\?\C:\src\main.rs"),
+            PathBuf::from(r"C:\src\main.rs")
+        );
+        assert_eq!(
+            strip_verbatim_prefix(r"\\?\UNC\server\share\main.rs"),
+            PathBuf::from(r"\\server\share\main.rs")
+        );
+        assert_eq!(
+            strip_verbatim_prefix(r"\\?\Volume{b75e2c83}\main.rs"),
+            PathBuf::from(r"\\?\Volume{b75e2c83}\main.rs")
+        );
+        assert_eq!(
+            strip_verbatim_prefix("/home/src/main.rs"),
+            PathBuf::from("/home/src/main.rs")
+        );
+        assert_eq!(display_path(r"\\?\C:\src\main.rs"), "C:/src/main.rs");
+        assert_eq!(display_path(r"src\main.rs"), "src/main.rs");
+    }
+
     #[test]
     fn test_leap_year() {
         // Leap years: 2000, 2004, 2008, ...
@@ -236,6 +431,191 @@ mod tests {
         temp_dir.close().expect("Failed to close temp directory");
     }
 
+    #[test]
+    fn test_sha1_hex() {
+        assert_eq!(sha1_hex(b""), "da39a3ee5e6b4b0d3255bfef95601890afd80709");
+    }
+
+    #[test]
+    fn test_format_utc_timestamp() {
+        assert_eq!(format_utc_timestamp(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_utc_timestamp(951782400), "2000-02-29T00:00:00Z");
+        assert_eq!(format_utc_timestamp(1713199815), "2024-04-15T16:50:15Z");
+    }
+
+    #[test]
+    fn test_sha256_hex() {
+        assert_eq!(sha256_hex(b"licensa"), sha256_hex("licensa"));
+        assert_eq!(
+            sha256_hex(b""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    #[test]
+    fn test_write_atomic() {
+        let temp_dir = tempdir().expect("Failed to create temporary directory");
+        let file_path = temp_dir.path().join("atomic.rs");
+
+        write_atomic(&file_path, "fn main() {}").unwrap();
+        assert_eq!(std::fs::read_to_string(&file_path).unwrap(), "fn main() {}");
+
+        write_atomic(&file_path, "// header\nfn main() {}").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&file_path).unwrap(),
+            "// header\nfn main() {}"
+        );
+
+        // No temporary files are left behind
+        assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+
+        temp_dir.close().expect("Failed to close temp directory");
+    }
+
+    #[test]
+    fn test_write_atomic_preserves_readonly_permission() {
+        let temp_dir = tempdir().expect("Failed to create temporary directory");
+        let file_path = temp_dir.path().join("readonly.rs");
+        std::fs::write(&file_path, "fn main() {}").unwrap();
+
+        let mut permissions = std::fs::metadata(&file_path).unwrap().permissions();
+        permissions.set_readonly(true);
+        std::fs::set_permissions(&file_path, permissions).unwrap();
+
+        write_atomic(&file_path, "// header\nfn main() {}").unwrap();
+        let permissions = std::fs::metadata(&file_path).unwrap().permissions();
+        assert!(permissions.readonly());
+
+        let mut permissions = permissions;
+        #[allow(clippy::permissions_set_readonly_false)]
+        permissions.set_readonly(false);
+        std::fs::set_permissions(&file_path, permissions).unwrap();
+        temp_dir.close().expect("Failed to close temp directory");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_atomic_preserves_executable_script() {
+        use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+        let temp_dir = tempdir().expect("Failed to create temporary directory");
+        let file_path = temp_dir.path().join("run.sh");
+        std::fs::write(&file_path, "#!/bin/sh\necho ok\n").unwrap();
+        let permissions = std::fs::Permissions::from_mode(0o750);
+        std::fs::set_permissions(&file_path, permissions).unwrap();
+        let metadata = std::fs::metadata(&file_path).unwrap();
+
+        write_atomic(&file_path, "#!/bin/sh\n# header\necho ok\n").unwrap();
+        let rewritten = std::fs::metadata(&file_path).unwrap();
+        assert_eq!(rewritten.permissions().mode() & 0o7777, 0o750);
+        assert_eq!(
+            (rewritten.uid(), rewritten.gid()),
+            (metadata.uid(), metadata.gid())
+        );
+
+        let output = std::process::Command::new(&file_path).output().unwrap();
+        assert_eq!(output.stdout, b"ok\n");
+
+        temp_dir.close().expect("Failed to close temp directory");
+    }
+
+    #[test]
+    fn test_write_atomic_with_mtime() {
+        let temp_dir = tempdir().expect("Failed to create temporary directory");
+        let file_path = temp_dir.path().join("main.rs");
+        std::fs::write(&file_path, "fn main() {}").unwrap();
+
+        let mtime = UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000);
+        write_atomic_with_mtime(&file_path, "// header\nfn main() {}", Some(mtime)).unwrap();
+        let metadata = std::fs::metadata(&file_path).unwrap();
+        assert_eq!(metadata.modified().unwrap(), mtime);
+        assert_eq!(
+            std::fs::read_to_string(&file_path).unwrap(),
+            "// header\nfn main() {}"
+        );
+
+        temp_dir.close().expect("Failed to close temp directory");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_atomic_follows_symlink() {
+        let temp_dir = tempdir().expect("Failed to create temporary directory");
+        let target = temp_dir.path().join("target.rs");
+        let link = temp_dir.path().join("link.rs");
+        std::fs::write(&target, "fn main() {}").unwrap();
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        write_atomic(&link, "// header\nfn main() {}").unwrap();
+        assert!(std::fs::symlink_metadata(&link)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(
+            std::fs::read_to_string(&target).unwrap(),
+            "// header\nfn main() {}"
+        );
+
+        temp_dir.close().expect("Failed to close temp directory");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_write_atomic_preserves_xattrs() {
+        use std::ffi::CString;
+        use std::os::unix::ffi::OsStrExt;
+
+        let temp_dir = tempdir().expect("Failed to create temporary directory");
+        let file_path = temp_dir.path().join("main.rs");
+        std::fs::write(&file_path, "fn main() {}").unwrap();
+
+        let path = CString::new(file_path.as_os_str().as_bytes()).unwrap();
+        let name = CString::new("user.licensa.test").unwrap();
+        let value = b"kept";
+        let result = unsafe {
+            libc::setxattr(
+                path.as_ptr(),
+                name.as_ptr(),
+                value.as_ptr().cast(),
+                value.len(),
+                0,
+            )
+        };
+        if result != 0 {
+            // The file system doesn't support user attributes
+            return;
+        }
+
+        write_atomic(&file_path, "// header\nfn main() {}").unwrap();
+        let mut buf = [0u8; 16];
+        let size = unsafe {
+            libc::getxattr(
+                path.as_ptr(),
+                name.as_ptr(),
+                buf.as_mut_ptr().cast(),
+                buf.len(),
+            )
+        };
+        assert_eq!(size, value.len() as isize);
+        assert_eq!(&buf[..value.len()], value);
+
+        temp_dir.close().expect("Failed to close temp directory");
+    }
+
+    #[test]
+    fn test_read_head() {
+        let temp_dir = tempdir().expect("Failed to create temporary directory");
+        let file_path = temp_dir.path().join("head.txt");
+        std::fs::write(&file_path, "0123456789").unwrap();
+
+        assert_eq!(read_head(&file_path, 4).unwrap(), b"0123");
+        assert_eq!(read_head(&file_path, 10).unwrap(), b"0123456789");
+        assert_eq!(read_head(&file_path, 100).unwrap(), b"0123456789");
+        assert!(read_head(temp_dir.path().join("missing.txt"), 4).is_err());
+
+        temp_dir.close().expect("Failed to close temp directory");
+    }
+
     #[test]
     fn test_check_any_file_exists_single_file_exists() {
         let temp_dir = tempdir().expect("Failed to create temporary directory");
@@ -268,11 +648,9 @@ mod tests {
 
         let result = resolve_any_path(base_path, &filenames);
         assert!(result.is_some());
-        assert!(filenames.iter().any(|&filename| {
-            result
-                .as_ref()
-                .map_or(false, |path| path.ends_with(filename))
-        }));
+        assert!(filenames
+            .iter()
+            .any(|&filename| { result.as_ref().is_some_and(|path| path.ends_with(filename)) }));
 
         // Cleanup
         temp_dir.close().expect("Failed to close temp directory");
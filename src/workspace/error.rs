@@ -66,6 +66,13 @@ pub enum WorkspaceError {
     #[error("invalid config data type. Provided value must be an object")]
     InvalidConfigDataType,
 
+    /// Error indicating a config value references an unset environment variable.
+    ///
+    /// This error occurs when a string field of the configuration file contains
+    /// a `${VAR}` reference, but `VAR` is not defined in the environment.
+    #[error("environment variable {0:?} referenced in config file is not set")]
+    UndefinedEnvVar(String),
+
     /// Transparent error wrapper for serialization/deserialization issues.
     #[error(transparent)]
     Data(#[from] serde_json::error::Error),
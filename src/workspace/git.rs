@@ -0,0 +1,376 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Helpers for inspecting the Git repository of a workspace.
+
+use anyhow::{anyhow, Context, Result};
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// The filename of the Git submodule configuration.
+pub const GITMODULES_FILENAME: &str = ".gitmodules";
+
+/// The filename of the Git attributes configuration.
+pub const GITATTRIBUTES_FILENAME: &str = ".gitattributes";
+
+/// Returns the paths of all submodules declared in the `.gitmodules` file of `root`.
+///
+/// Paths are joined with `root`. Returns an empty list if `root` doesn't contain
+/// a `.gitmodules` file.
+pub fn submodule_paths<P>(root: P) -> Vec<PathBuf>
+where
+    P: AsRef<Path>,
+{
+    let root = root.as_ref();
+    let Ok(content) = fs::read_to_string(root.join(GITMODULES_FILENAME)) else {
+        return vec![];
+    };
+    parse_submodule_paths(&content)
+        .into_iter()
+        .map(|path| root.join(path))
+        .collect()
+}
+
+/// Returns the patterns of the `.gitattributes` file of `root` that set or unset `attribute`.
+///
+/// Patterns are returned in *.gitignore* syntax: patterns setting the attribute as is,
+/// and patterns unsetting it negated with a leading `!`, so that the last matching
+/// pattern decides whether a path has the attribute. Returns an empty list if `root`
+/// doesn't contain a `.gitattributes` file.
+pub fn attribute_patterns<P>(root: P, attribute: &str) -> Vec<String>
+where
+    P: AsRef<Path>,
+{
+    let Ok(content) = fs::read_to_string(root.as_ref().join(GITATTRIBUTES_FILENAME)) else {
+        return vec![];
+    };
+    parse_attribute_patterns(&content, attribute)
+}
+
+/// Reads the value of a Git config `key` as seen from `dir`.
+///
+/// Repository-local settings take precedence over global ones, just like in Git.
+/// Returns `None` if the key is unset or Git is not available.
+pub fn config_value<P>(dir: P, key: &str) -> Option<String>
+where
+    P: AsRef<Path>,
+{
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir.as_ref())
+        .args(["config", "--get", key])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let value = String::from_utf8(output.stdout).ok()?;
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Returns the paths of the files changed in the revision range `range` that are
+/// located in `dir`, e.g. of `v1.0..HEAD`.
+///
+/// The range is passed to `git diff` as is, so that `A...B` compares `B` with the
+/// merge base of both revisions, and a single revision is compared with the working
+/// tree. Deleted files are left out. Paths are joined with `dir`.
+///
+/// # Errors
+///
+/// Returns an error if Git is not available, `dir` is not inside a Git repository or
+/// the range doesn't name existing revisions.
+pub fn changed_files<P>(dir: P, range: &str) -> Result<HashSet<PathBuf>>
+where
+    P: AsRef<Path>,
+{
+    let dir = dir.as_ref();
+    if range.is_empty() || range.starts_with('-') {
+        return Err(anyhow!("invalid revision range `{range}`"));
+    }
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args([
+            "diff",
+            "--name-only",
+            "-z",
+            "--no-renames",
+            "--diff-filter=d",
+        ])
+        .args(["--relative", range, "--"])
+        .output()
+        .context("failed to run git")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!(
+            "failed to list files changed in `{range}`: {}",
+            stderr.trim()
+        ));
+    }
+
+    let stdout = String::from_utf8(output.stdout).context("invalid path in git output")?;
+    Ok(stdout
+        .split('\0')
+        .filter(|path| !path.is_empty())
+        .map(|path| dir.join(path))
+        .collect())
+}
+
+/// Returns the commit ID of the best common ancestor of `rev` and `HEAD` in the
+/// repository of `dir`, e.g. of the target branch of a pull request.
+///
+/// # Errors
+///
+/// Returns an error if Git is not available, `rev` doesn't name an existing revision
+/// or the revisions have no common ancestor, e.g. in a shallow clone.
+pub fn merge_base<P>(dir: P, rev: &str) -> Result<String>
+where
+    P: AsRef<Path>,
+{
+    if rev.is_empty() || rev.starts_with('-') {
+        return Err(anyhow!("invalid revision `{rev}`"));
+    }
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir.as_ref())
+        .args(["merge-base", rev, "HEAD"])
+        .output()
+        .context("failed to run git")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!(
+            "failed to find the merge base of `{rev}` and HEAD{}\n\n\
+            Make sure that `{rev}` is fetched, including the history of HEAD since it diverged, \
+            e.g. with `git fetch --unshallow` in shallow clones.",
+            match stderr.trim() {
+                "" => String::new(),
+                stderr => format!(": {stderr}"),
+            }
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Returns the Git user configured for `dir` along with the config key it was read from.
+///
+/// Prefers `user.name` and falls back to `user.email`.
+pub fn user_identity<P>(dir: P) -> Option<(String, &'static str)>
+where
+    P: AsRef<Path>,
+{
+    ["user.name", "user.email"]
+        .into_iter()
+        .find_map(|key| config_value(dir.as_ref(), key).map(|value| (value, key)))
+}
+
+/// Checks whether `dir` is the root of a nested Git repository or an initialized submodule.
+///
+/// Submodules contain a `.git` file pointing to the parent repository's module store,
+/// while nested repositories contain a `.git` directory.
+pub fn is_nested_repository<P>(dir: P) -> bool
+where
+    P: AsRef<Path>,
+{
+    dir.as_ref().join(".git").exists()
+}
+
+/// Extracts the `path` values of all `[submodule]` sections of a `.gitmodules` file.
+fn parse_submodule_paths(content: &str) -> Vec<String> {
+    let mut paths = vec![];
+    let mut in_submodule = false;
+
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if line.starts_with('[') {
+            in_submodule = line.starts_with("[submodule");
+            continue;
+        }
+        if !in_submodule {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        if key.trim() == "path" {
+            let path = value.trim().trim_matches('"').trim_end_matches('/');
+            if !path.is_empty() {
+                paths.push(path.to_string());
+            }
+        }
+    }
+
+    paths
+}
+
+/// Extracts the patterns setting or unsetting `attribute` from a `.gitattributes` file.
+fn parse_attribute_patterns(content: &str, attribute: &str) -> Vec<String> {
+    let mut patterns = vec![];
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') || line.starts_with("[attr]") {
+            continue;
+        }
+        let mut fields = line.split_whitespace();
+        let Some(pattern) = fields.next() else {
+            continue;
+        };
+        // The last occurrence of an attribute on a line wins
+        let state = fields.fold(None, |state, field| match field.split_once('=') {
+            Some((name, value)) if name == attribute => Some(value != "false"),
+            None if field == attribute => Some(true),
+            None if field.strip_prefix('-') == Some(attribute) => Some(false),
+            None if field.strip_prefix('!') == Some(attribute) => Some(false),
+            _ => state,
+        });
+        match state {
+            Some(true) => patterns.push(pattern.to_string()),
+            Some(false) => patterns.push(format!("!{pattern}")),
+            None => {}
+        }
+    }
+    patterns
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_submodule_paths() {
+        let content = r#"
+[submodule "vendor/lib"]
+	path = vendor/lib
+	url = https://example.com/lib.git
+[core]
+	path = not/a/submodule
+# [submodule "commented"]
+[submodule "docs"]
+	url = https://example.com/docs.git
+	path = "third_party/docs/"
+"#;
+        assert_eq!(
+            parse_submodule_paths(content),
+            ["vendor/lib", "third_party/docs"]
+        );
+    }
+
+    #[test]
+    fn test_parse_attribute_patterns() {
+        let content = "\
+# Generated code
+*.pb.go linguist-generated=true
+api/*.go linguist-generated -diff
+api/client.go -linguist-generated
+[attr]binary -diff -merge -text
+/docs export-ignore
+*.svg linguist-generated=false
+";
+        assert_eq!(
+            parse_attribute_patterns(content, "linguist-generated"),
+            vec!["*.pb.go", "api/*.go", "!api/client.go", "!*.svg"]
+        );
+        assert_eq!(
+            parse_attribute_patterns(content, "export-ignore"),
+            vec!["/docs"]
+        );
+    }
+
+    #[test]
+    fn test_submodule_paths_without_gitmodules() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(submodule_paths(dir.path()).is_empty());
+    }
+
+    #[test]
+    fn test_user_identity_prefers_repository_config() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let dir = tmp_dir.path();
+        let git = |args: &[&str]| {
+            Command::new("git")
+                .arg("-C")
+                .arg(dir)
+                .args(args)
+                .status()
+                .is_ok_and(|status| status.success())
+        };
+        if !git(&["init", "-q"]) {
+            // Git is not available
+            return;
+        }
+        assert!(git(&["config", "user.name", "Jane Doe"]));
+        assert!(git(&["config", "user.email", "jane@example.com"]));
+
+        assert_eq!(
+            user_identity(dir),
+            Some(("Jane Doe".to_string(), "user.name"))
+        );
+        assert_eq!(
+            config_value(dir, "user.email").as_deref(),
+            Some("jane@example.com")
+        );
+        assert_eq!(config_value(dir, "licensa.unset-key"), None);
+    }
+
+    #[test]
+    fn test_changed_files() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let dir = tmp_dir.path();
+        let git = |args: &[&str]| {
+            Command::new("git")
+                .arg("-C")
+                .arg(dir)
+                .args([
+                    "-c",
+                    "user.name=Jane Doe",
+                    "-c",
+                    "user.email=jane@example.com",
+                ])
+                .args(args)
+                .status()
+                .is_ok_and(|status| status.success())
+        };
+        if !git(&["init", "-q"]) {
+            // Git is not available
+            return;
+        }
+        fs::create_dir(dir.join("src")).unwrap();
+        fs::write(dir.join("src/lib.rs"), "").unwrap();
+        fs::write(dir.join("old.rs"), "").unwrap();
+        assert!(git(&["add", "."]));
+        assert!(git(&["commit", "-q", "-m", "Initial commit"]));
+        assert!(git(&["tag", "v1"]));
+
+        fs::write(dir.join("src/main.rs"), "fn main() {}").unwrap();
+        fs::write(dir.join("src/lib.rs"), "pub fn lib() {}").unwrap();
+        fs::remove_file(dir.join("old.rs")).unwrap();
+        assert!(git(&["add", "-A"]));
+        assert!(git(&["commit", "-q", "-m", "Add main"]));
+
+        let changed = changed_files(dir, "v1..HEAD").unwrap();
+        assert_eq!(
+            changed,
+            HashSet::from([dir.join("src/lib.rs"), dir.join("src/main.rs")])
+        );
+
+        // Paths are relative to subdirectories
+        let changed = changed_files(dir.join("src"), "v1..HEAD").unwrap();
+        assert_eq!(changed.len(), 2);
+        assert!(changed.contains(&dir.join("src").join("main.rs")));
+
+        assert!(changed_files(dir, "HEAD..HEAD").unwrap().is_empty());
+
+        let base = merge_base(dir, "v1").unwrap();
+        assert_eq!(base.len(), 40);
+        assert_eq!(changed_files(dir, &base).unwrap(), changed);
+        assert!(merge_base(dir, "v2").is_err());
+        assert!(changed_files(dir, "v2..HEAD").is_err());
+        assert!(changed_files(dir, "--output=x").is_err());
+    }
+}
@@ -0,0 +1,219 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Advisory lock preventing concurrent runs of commands that modify a workspace.
+//!
+//! Commands such as `apply` take the lock by creating the `lock` entry in the
+//! `.licensa` store, and release it by removing the entry once they're done. A run
+//! that finds the lock held fails right away, or waits for the time selected with
+//! `--lock-timeout`. Locks left behind by runs that were killed are detected by
+//! their process ID and taken over, where the platform allows checking for it.
+
+use crate::workspace::store::Store;
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// The name of the store entry holding the workspace lock.
+pub const LOCK_FILENAME: &str = "lock";
+
+/// Interval in which a held lock is checked again while waiting for it.
+const RETRY_INTERVAL: Duration = Duration::from_millis(200);
+
+static LOCK_TIMEOUT: OnceLock<Duration> = OnceLock::new();
+
+/// Sets the time to wait for a lock held by another run, in seconds.
+///
+/// Without a timeout, taking a held lock fails immediately. Calling this function
+/// more than once has no effect.
+pub fn init_lock_timeout(seconds: Option<u64>) {
+    if let Some(seconds) = seconds {
+        let _ = LOCK_TIMEOUT.set(Duration::from_secs(seconds));
+    }
+}
+
+/// The run holding a workspace lock, as recorded in the lock file.
+#[derive(Debug, Serialize, Deserialize)]
+struct LockHolder {
+    /// ID of the process holding the lock.
+    pid: u32,
+
+    /// Name of the command holding the lock, e.g. `apply`.
+    command: String,
+}
+
+/// A held workspace lock, released when dropped.
+#[derive(Debug)]
+pub struct WorkspaceLock {
+    path: PathBuf,
+}
+
+impl WorkspaceLock {
+    /// Takes the lock of the workspace backing `store` for the command `command`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if another run holds the lock and doesn't release it within
+    /// the timeout selected with [`init_lock_timeout`], or if the lock file can't be
+    /// created.
+    pub fn acquire(store: &Store, command: &str) -> Result<Self> {
+        store.ensure()?;
+        let path = store.path(LOCK_FILENAME);
+        let timeout = LOCK_TIMEOUT.get().copied().unwrap_or_default();
+        let started = Instant::now();
+        let mut waiting = false;
+
+        loop {
+            match Self::try_create(&path, command) {
+                Ok(lock) => {
+                    debug!("acquired workspace lock {}", path.display());
+                    return Ok(lock);
+                }
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {}
+                Err(err) => {
+                    return Err(err).with_context(|| format!("failed to create {}", path.display()))
+                }
+            }
+
+            let holder = read_holder(&path);
+            if let Some(holder) = holder.as_ref().filter(|holder| !is_running(holder.pid)) {
+                warn!(
+                    "removing stale workspace lock of `licensa {}` (process {}), which is no longer running",
+                    holder.command, holder.pid
+                );
+                remove_lock(&path)?;
+                continue;
+            }
+
+            if started.elapsed() >= timeout {
+                let holder = holder.map_or("another run".to_string(), |holder| {
+                    format!("`licensa {}` (process {})", holder.command, holder.pid)
+                });
+                return Err(anyhow!(
+                    "the workspace is locked by {holder}\n\n\
+                    Wait for it to finish, or pass `--lock-timeout <SECONDS>` to wait for the lock. \
+                    If no other run is in progress, remove {} and try again.",
+                    path.display()
+                ));
+            }
+            if !waiting {
+                info!("waiting for the workspace lock {}", path.display());
+                waiting = true;
+            }
+            thread::sleep(RETRY_INTERVAL.min(timeout.saturating_sub(started.elapsed())));
+        }
+    }
+
+    /// Creates the lock file at `path`, failing if it already exists.
+    fn try_create(path: &Path, command: &str) -> io::Result<Self> {
+        let mut file = OpenOptions::new().write(true).create_new(true).open(path)?;
+        let lock = Self {
+            path: path.to_path_buf(),
+        };
+        let holder = LockHolder {
+            pid: std::process::id(),
+            command: command.to_string(),
+        };
+        // The lock is released on drop if recording its holder fails
+        file.write_all(serde_json::to_string(&holder)?.as_bytes())?;
+        Ok(lock)
+    }
+}
+
+impl Drop for WorkspaceLock {
+    fn drop(&mut self) {
+        if let Err(err) = fs::remove_file(&self.path) {
+            warn!(
+                "failed to release workspace lock {}: {err}",
+                self.path.display()
+            );
+        }
+    }
+}
+
+/// Reads the holder of the lock at `path`.
+///
+/// Returns `None` if the lock was released in the meantime, or if the holder
+/// hasn't been written yet.
+fn read_holder(path: &Path) -> Option<LockHolder> {
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn remove_lock(path: &Path) -> Result<()> {
+    match fs::remove_file(path) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => {
+            Err(err).with_context(|| format!("failed to remove {}", path.display()))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Returns `false` if no process with the ID `pid` is running.
+///
+/// Processes are assumed to be running on platforms where this can't be checked.
+fn is_running(pid: u32) -> bool {
+    if cfg!(target_os = "linux") {
+        Path::new("/proc").join(pid.to_string()).exists()
+    } else {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_workspace_lock() {
+        let dir = tempdir().unwrap();
+        let store = Store::new(dir.path());
+
+        let lock = WorkspaceLock::acquire(&store, "apply").unwrap();
+        let holder = read_holder(&store.path(LOCK_FILENAME)).unwrap();
+        assert_eq!(holder.pid, std::process::id());
+        assert_eq!(holder.command, "apply");
+
+        let err = WorkspaceLock::acquire(&store, "undo").unwrap_err();
+        assert!(err.to_string().contains("`licensa apply`"));
+
+        drop(lock);
+        assert!(!store.path(LOCK_FILENAME).exists());
+        let lock = WorkspaceLock::acquire(&store, "undo").unwrap();
+        drop(lock);
+
+        dir.close().unwrap();
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_workspace_lock_stale() {
+        let dir = tempdir().unwrap();
+        let store = Store::new(dir.path());
+        store
+            .write(
+                LOCK_FILENAME,
+                &LockHolder {
+                    pid: u32::MAX,
+                    command: "apply".to_string(),
+                },
+            )
+            .unwrap();
+
+        let lock = WorkspaceLock::acquire(&store, "normalize").unwrap();
+        let holder = read_holder(&store.path(LOCK_FILENAME)).unwrap();
+        assert_eq!(holder.command, "normalize");
+        drop(lock);
+
+        dir.close().unwrap();
+    }
+}
@@ -0,0 +1,146 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Member packages of a monorepo workspace.
+//!
+//! The root `.licensarc` of a monorepo may list its packages in a `workspaces`
+//! array. Entries are either directories relative to the workspace root, or glob
+//! patterns matching multiple directories, e.g. `packages/*`.
+
+use anyhow::{anyhow, Result};
+use globset::{GlobBuilder, GlobSetBuilder};
+use ignore::WalkBuilder;
+
+use std::path::{Component, Path, PathBuf};
+
+/// Resolves the member directories of the workspace at `root`.
+///
+/// Returns a sorted, deduplicated list of absolute paths. Directories ignored by
+/// Git, hidden directories and the workspace root itself are never members.
+///
+/// # Errors
+///
+/// Returns an error if a pattern is not a valid glob, points outside of the
+/// workspace root, or if a literal directory doesn't exist.
+pub fn resolve_members<P>(root: P, patterns: &[String]) -> Result<Vec<PathBuf>>
+where
+    P: AsRef<Path>,
+{
+    let root = root.as_ref();
+    let mut members = vec![];
+    let mut globs = GlobSetBuilder::new();
+    let mut max_depth = Some(0);
+
+    for pattern in patterns {
+        let pattern = pattern
+            .trim()
+            .trim_start_matches("./")
+            .trim_end_matches('/');
+        if Path::new(pattern)
+            .components()
+            .any(|c| !matches!(c, Component::Normal(_)))
+        {
+            return Err(anyhow!(
+                "invalid workspace member {pattern:?}, expected a path relative to the workspace root"
+            ));
+        }
+
+        if !is_glob(pattern) {
+            let dir = root.join(pattern);
+            if !dir.is_dir() {
+                return Err(anyhow!("workspace member {pattern} is not a directory"));
+            }
+            members.push(dir);
+            continue;
+        }
+
+        let glob = GlobBuilder::new(pattern)
+            .literal_separator(true)
+            .build()
+            .map_err(|err| anyhow!("invalid workspace member pattern {pattern:?}: {err}"))?;
+        globs.add(glob);
+
+        // Limit the walk to the depth of the deepest pattern, unless it's recursive
+        max_depth = match pattern.contains("**") {
+            true => None,
+            false => max_depth.map(|depth: usize| depth.max(pattern.split('/').count())),
+        };
+    }
+
+    let globs = globs.build()?;
+    if !globs.is_empty() {
+        let walk = WalkBuilder::new(root).max_depth(max_depth).build();
+        for entry in walk.filter_map(Result::ok) {
+            if entry.depth() == 0 || !entry.file_type().is_some_and(|t| t.is_dir()) {
+                continue;
+            }
+            let relative = entry.path().strip_prefix(root).unwrap_or(entry.path());
+            if globs.is_match(relative) {
+                members.push(entry.into_path());
+            }
+        }
+    }
+
+    members.sort();
+    members.dedup();
+    Ok(members)
+}
+
+fn is_glob(pattern: &str) -> bool {
+    pattern.contains(['*', '?', '[', '{'])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn create_monorepo() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        for path in [
+            "packages/a/src",
+            "packages/b",
+            "tools/cli",
+            "node_modules/x",
+        ] {
+            fs::create_dir_all(dir.path().join(path)).unwrap();
+        }
+        fs::write(dir.path().join(".gitignore"), "node_modules\n").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_resolve_members() {
+        let dir = create_monorepo();
+        let root = dir.path();
+
+        let members = resolve_members(
+            root,
+            &[
+                "packages/*".into(),
+                "./tools/cli/".into(),
+                "packages/a".into(),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            members,
+            [
+                root.join("packages/a"),
+                root.join("packages/b"),
+                root.join("tools/cli")
+            ]
+        );
+
+        let members = resolve_members(root, &["**/src".into()]).unwrap();
+        assert_eq!(members, [root.join("packages/a/src")]);
+    }
+
+    #[test]
+    fn test_resolve_members_invalid() {
+        let dir = create_monorepo();
+        assert!(resolve_members(dir.path(), &["missing".into()]).is_err());
+        assert!(resolve_members(dir.path(), &["../outside".into()]).is_err());
+        assert!(resolve_members(dir.path(), &["packages/[".into()]).is_err());
+    }
+}
@@ -4,13 +4,29 @@
 //! Licensa configuration file parser and utils
 
 pub mod error;
+pub mod git;
+pub mod lock;
+pub mod members;
 pub mod ops;
+pub mod presets;
+pub mod store;
+pub mod vendor;
 pub mod walker;
 
+use crate::config::policy::LicensePolicy;
+use crate::config::remote::RemoteConfig;
+use crate::config::{FileLicense, FilePlugin, PathOwner};
+use crate::ops::permissions::ReadOnlyPolicy;
 use crate::schema::{LicenseId, LicenseYear};
+use crate::template::header::{FileTypes, HeadDirectives, HeaderPlacement};
+use crate::template::notice::LicenseNoticeFormat;
+use crate::workspace::walker::SymlinkPolicy;
 
 use serde::{Deserialize, Serialize};
 
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
 /// Represents the container for a Licensa config file that may be
 /// included in root directory of a software project.
 ///
@@ -35,7 +51,48 @@ use serde::{Deserialize, Serialize};
 #[serde(deny_unknown_fields)]
 pub struct LicensaWorkspace {
     pub owner: String,
+    pub email: Option<String>,
+    pub project: Option<String>,
+    pub project_url: Option<String>,
+    #[serde(default)]
+    pub contributors: Vec<String>,
     pub license: LicenseId,
     pub exclude: Vec<String>,
     pub year: Option<LicenseYear>,
+    pub format: Option<LicenseNoticeFormat>,
+    pub placement: Option<HeaderPlacement>,
+    pub blank_lines_after_header: Option<u8>,
+    pub read_window: Option<usize>,
+    pub max_file_size: Option<u64>,
+    pub symlinks: Option<SymlinkPolicy>,
+    pub read_only: Option<ReadOnlyPolicy>,
+    pub preserve_mtime: Option<bool>,
+    pub hidden: Option<bool>,
+    pub include_submodules: Option<bool>,
+    pub include_vendored: Option<bool>,
+    pub ignore_gitattributes: Option<bool>,
+    pub require_current_year: Option<bool>,
+    #[serde(default)]
+    pub workspaces: Vec<String>,
+    pub scan_limit: Option<usize>,
+    pub scan_threads: Option<usize>,
+    #[serde(default)]
+    pub file_licenses: Vec<FileLicense>,
+    #[serde(default)]
+    pub path_owners: Vec<PathOwner>,
+    #[serde(default)]
+    pub policy: LicensePolicy,
+    #[serde(default)]
+    pub variables: BTreeMap<String, String>,
+    pub compact_sentence: Option<String>,
+    pub templates: Option<PathBuf>,
+    #[serde(default)]
+    pub attributions: Vec<String>,
+    #[serde(default)]
+    pub plugins: Vec<FilePlugin>,
+    #[serde(default)]
+    pub file_types: FileTypes,
+    #[serde(default)]
+    pub head_directives: HeadDirectives,
+    pub extends: Option<RemoteConfig>,
 }
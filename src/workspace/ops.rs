@@ -9,6 +9,8 @@
 //! - Writing configurations and ignore files to workspace directories.
 //! - Ensuring workspace directories are valid and checking for existing files.
 //! - Cleaning config specific JSON values by removing null fields.
+//! - Expanding `${VAR}` environment variable references in config values.
+//! - Parsing config files with comments and trailing commas.
 //!
 //! # Errors
 //!
@@ -51,7 +53,9 @@ where
     F: AsRef<str>,
 {
     let config = read_config(workspace_root, file_name)?;
-    let content = serde_json::from_str::<T>(&config)
+    let config =
+        parse_config_json(&config).with_context(|| "failed to parse .licensarc config file")?;
+    let content = serde_json::from_value::<T>(interpolate_env(config)?)
         .with_context(|| "failed to parse .licensarc config file")?;
     Ok(content)
 }
@@ -127,7 +131,9 @@ where
         let content =
             fs::read_to_string(path).with_context(|| "failed to read .licensarc config file")?;
 
-        let config = serde_json::from_str::<T>(&content)
+        let config = parse_config_json(&content)
+            .with_context(|| "failed to parse .licensarc config file")?;
+        let config = serde_json::from_value::<T>(interpolate_env(config)?)
             .with_context(|| "failed to parse .licensarc config file")?;
 
         return Ok(Some(config));
@@ -308,6 +314,128 @@ where
     path.exists() && path.is_file()
 }
 
+/// Parses the content of a config file as JSON with comments (JSONC).
+///
+/// `//` line comments, `/* */` block comments and trailing commas in objects and
+/// arrays are allowed, see [`strip_json_comments`].
+pub fn parse_config_json(content: &str) -> serde_json::Result<Value> {
+    serde_json::from_str(&strip_json_comments(content))
+}
+
+/// Turns JSON with comments and trailing commas into strict JSON.
+///
+/// Comments and trailing commas are replaced with spaces rather than removed, so
+/// that line and column numbers in parse errors still match the original content.
+/// Comment markers inside of strings are left as is.
+pub fn strip_json_comments(content: &str) -> String {
+    let mut output = content.as_bytes().to_vec();
+    let mut i = 0;
+    while i < output.len() {
+        match output[i] {
+            b'"' => {
+                i += 1;
+                while i < output.len() && output[i] != b'"' {
+                    i += if output[i] == b'\\' { 2 } else { 1 };
+                }
+            }
+            b'/' if output.get(i + 1) == Some(&b'/') => {
+                while i < output.len() && output[i] != b'\n' {
+                    output[i] = b' ';
+                    i += 1;
+                }
+                continue;
+            }
+            b'/' if output.get(i + 1) == Some(&b'*') => {
+                let end = content[i + 2..]
+                    .find("*/")
+                    .map_or(output.len(), |end| i + 2 + end + 2);
+                for byte in &mut output[i..end] {
+                    if !byte.is_ascii_whitespace() {
+                        *byte = b' ';
+                    }
+                }
+                i = end;
+                continue;
+            }
+            b'}' | b']' => {
+                if let Some(last) = output[..i].iter().rposition(|b| !b.is_ascii_whitespace()) {
+                    if output[last] == b',' {
+                        output[last] = b' ';
+                    }
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    // Only ASCII characters and all bytes of multi-byte characters are replaced
+    String::from_utf8(output).expect("valid UTF-8")
+}
+
+/// Expands environment variable references in all string values of a config.
+///
+/// Strings may reference variables as `${VAR}`, e.g. `"owner": "${COMPANY_NAME}"`,
+/// which allows CI pipelines to inject values without templating the config file.
+/// A literal `${` is written as `$${`.
+///
+/// # Errors
+///
+/// Returns [`WorkspaceError::UndefinedEnvVar`] if a referenced variable is not set.
+pub fn interpolate_env(value: Value) -> WorkspaceResult<Value> {
+    let value = match value {
+        Value::String(s) => Value::String(expand_env_vars(&s, |name| std::env::var(name).ok())?),
+        Value::Array(arr) => Value::Array(
+            arr.into_iter()
+                .map(interpolate_env)
+                .collect::<WorkspaceResult<_>>()?,
+        ),
+        Value::Object(obj) => Value::Object(
+            obj.into_iter()
+                .map(|(key, val)| Ok((key, interpolate_env(val)?)))
+                .collect::<WorkspaceResult<_>>()?,
+        ),
+        _ => value,
+    };
+    Ok(value)
+}
+
+/// Replaces every `${VAR}` reference in `input` with the value returned by `lookup`.
+///
+/// References without a closing brace are left as is.
+fn expand_env_vars<F>(input: &str, lookup: F) -> WorkspaceResult<String>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(start) = rest.find("${") {
+        // `$${` escapes a reference
+        if rest[..start].ends_with('$') {
+            output.push_str(&rest[..start - 1]);
+            output.push_str("${");
+            rest = &rest[start + 2..];
+            continue;
+        }
+
+        output.push_str(&rest[..start]);
+        let reference = &rest[start + 2..];
+        let Some(end) = reference.find('}') else {
+            output.push_str(&rest[start..]);
+            rest = "";
+            break;
+        };
+
+        let name = &reference[..end];
+        let value = lookup(name).ok_or_else(|| WorkspaceError::UndefinedEnvVar(name.into()))?;
+        output.push_str(&value);
+        rest = &reference[end + 1..];
+    }
+    output.push_str(rest);
+
+    Ok(output)
+}
+
 /// Recursively removes all fields with `null` values from a JSON object.
 ///
 /// This function takes a serde_json Value representing a JSON object and recursively
@@ -522,6 +650,79 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_expand_env_vars() {
+        let lookup = |name: &str| match name {
+            "COMPANY" => Some("ACME Inc.".to_string()),
+            "EMPTY" => Some(String::new()),
+            _ => None,
+        };
+
+        let expanded = expand_env_vars("${COMPANY} and contributors", lookup).unwrap();
+        assert_eq!(expanded, "ACME Inc. and contributors");
+
+        let expanded = expand_env_vars("a${EMPTY}b ${COMPANY}", lookup).unwrap();
+        assert_eq!(expanded, "ab ACME Inc.");
+
+        let expanded = expand_env_vars("$${COMPANY} ${COMPANY", lookup).unwrap();
+        assert_eq!(expanded, "${COMPANY} ${COMPANY");
+
+        let result = expand_env_vars("${MISSING}", lookup);
+        assert!(matches!(result, Err(WorkspaceError::UndefinedEnvVar(name)) if name == "MISSING"));
+    }
+
+    #[test]
+    fn test_strip_json_comments() {
+        let content = r#"{
+  // The license of the project
+  "license": "MIT", /* block
+  comment */ "owner": "ACME // Inc. /* */",
+  "exclude": ["dist/", "*.min.js",],
+}"#;
+        let stripped = strip_json_comments(content);
+        assert_eq!(stripped.lines().count(), content.lines().count());
+        assert_eq!(
+            parse_config_json(content).unwrap(),
+            json!({
+                "license": "MIT",
+                "owner": "ACME // Inc. /* */",
+                "exclude": ["dist/", "*.min.js"]
+            })
+        );
+
+        // Escaped quotes don't end strings
+        let content = r#"{"owner": "\"a\" // b", "year": 2024 /* ü */}"#;
+        assert_eq!(
+            parse_config_json(content).unwrap(),
+            json!({ "owner": "\"a\" // b", "year": 2024 })
+        );
+
+        // Commas are only removed before closing brackets
+        assert!(parse_config_json(r#"{"a": 1,, }"#).is_err());
+        assert!(parse_config_json("[1 /* unterminated").is_err());
+        assert_eq!(strip_json_comments(r#"{"a": 1}"#), r#"{"a": 1}"#);
+    }
+
+    #[test]
+    fn test_interpolate_env() {
+        let path = std::env::var("PATH").unwrap();
+        let value = json!({
+            "owner": "${PATH}",
+            "exclude": ["${PATH}/*"],
+            "year": 2024
+        });
+
+        let value = interpolate_env(value).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "owner": path,
+                "exclude": [format!("{path}/*")],
+                "year": 2024
+            })
+        );
+    }
+
     #[test]
     fn test_ensure_is_dir() {
         let dir = tempdir().unwrap();
@@ -0,0 +1,157 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Language presets for the generated `.licensaignore` file.
+//!
+//! Each preset contributes a fragment of ignore patterns covering the build
+//! output, dependency and cache directories of a language ecosystem. Fragments
+//! are inserted before the `Overrides` section of the default ignore file, so
+//! user-defined overrides keep taking precedence.
+
+use clap::ValueEnum;
+
+use std::collections::HashSet;
+
+/// Heading of the section user-defined patterns are added to.
+const OVERRIDES_HEADING: &str = "# Overrides";
+
+const SECTION_RULE: &str = "# =====================================================";
+
+/// Language ecosystem providing a fragment of ignore patterns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Preset {
+    Rust,
+    Node,
+    Python,
+    Go,
+}
+
+impl Preset {
+    /// Returns the title of the preset's section in the ignore file.
+    pub fn title(&self) -> &'static str {
+        match self {
+            Preset::Rust => "Rust",
+            Preset::Node => "Node.js",
+            Preset::Python => "Python",
+            Preset::Go => "Go",
+        }
+    }
+
+    /// Returns the ignore patterns contributed by the preset.
+    pub fn patterns(&self) -> &'static [&'static str] {
+        match self {
+            Preset::Rust => &["**/target/", "**/*.rs.bk"],
+            Preset::Node => &[
+                "**/node_modules/",
+                "**/dist/",
+                "**/build/",
+                "**/coverage/",
+                "**/.next/",
+                "**/.nuxt/",
+                "**/*.min.js",
+                "**/*.min.css",
+                "**/*.map",
+            ],
+            Preset::Python => &[
+                "**/__pycache__/",
+                "**/*.py[cod]",
+                "**/.venv/",
+                "**/venv/",
+                "**/.tox/",
+                "**/.mypy_cache/",
+                "**/.pytest_cache/",
+                "**/*.egg-info/",
+                "**/dist/",
+                "**/build/",
+            ],
+            Preset::Go => &["**/vendor/", "**/bin/", "**/go.sum"],
+        }
+    }
+}
+
+/// Composes an ignore file from `base` and the fragments of `presets`.
+///
+/// Patterns already contributed by a preceding preset are omitted.
+pub fn compose_ignore_file(base: &str, presets: &[Preset]) -> String {
+    let mut seen = HashSet::new();
+    let mut fragments = String::new();
+
+    for preset in presets {
+        let patterns: Vec<&str> = preset
+            .patterns()
+            .iter()
+            .copied()
+            .filter(|pattern| seen.insert(*pattern))
+            .collect();
+        if patterns.is_empty() {
+            continue;
+        }
+
+        fragments.push_str(&format!(
+            "{SECTION_RULE}\n# Preset: {}\n{SECTION_RULE}\n\n",
+            preset.title()
+        ));
+        for pattern in patterns {
+            fragments.push_str(pattern);
+            fragments.push('\n');
+        }
+        fragments.push('\n');
+    }
+
+    if fragments.is_empty() {
+        return base.to_string();
+    }
+
+    // Insert fragments before the rule preceding the overrides heading
+    let insert_at = base.find(OVERRIDES_HEADING).map(|pos| {
+        base[..pos]
+            .trim_end_matches('\n')
+            .rfind(SECTION_RULE)
+            .unwrap_or(pos)
+    });
+
+    match insert_at {
+        Some(pos) => format!("{}{fragments}{}", &base[..pos], &base[pos..]),
+        None => format!("{}\n\n{fragments}", base.trim_end()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "**/*.json\n\n# =====================================================\n# Overrides\n# =====================================================\n";
+
+    #[test]
+    fn test_compose_ignore_file_without_presets() {
+        assert_eq!(compose_ignore_file(BASE, &[]), BASE);
+    }
+
+    #[test]
+    fn test_compose_ignore_file_inserts_before_overrides() {
+        let content = compose_ignore_file(BASE, &[Preset::Rust, Preset::Go]);
+
+        let rust = content.find("# Preset: Rust").unwrap();
+        let go = content.find("# Preset: Go").unwrap();
+        let overrides = content.find(OVERRIDES_HEADING).unwrap();
+        assert!(rust < go && go < overrides);
+        assert!(content.contains("**/target/\n"));
+        assert!(content
+            .ends_with("# Overrides\n# =====================================================\n"));
+    }
+
+    #[test]
+    fn test_compose_ignore_file_deduplicates_patterns() {
+        let content = compose_ignore_file(BASE, &[Preset::Node, Preset::Python]);
+        assert_eq!(content.matches("**/dist/\n").count(), 1);
+        assert_eq!(content.matches("**/build/\n").count(), 1);
+        assert!(content.contains("**/__pycache__/\n"));
+    }
+
+    #[test]
+    fn test_compose_ignore_file_without_overrides_section() {
+        let content = compose_ignore_file("**/*.json\n", &[Preset::Rust]);
+        assert!(content.starts_with("**/*.json\n\n# ==="));
+        assert!(content.contains("**/target/\n"));
+    }
+}
@@ -0,0 +1,134 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Provides access to the `.licensa` state directory of a workspace.
+//!
+//! The store holds artifacts Licensa maintains between runs, such as the
+//! modification journal. It is created lazily and excludes itself from version
+//! control by means of its own `.gitignore` file.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The name of the directory holding Licensa's workspace state.
+pub const LICENSA_STORE_DIRNAME: &str = ".licensa";
+
+/// Represents the `.licensa` state directory of a workspace.
+#[derive(Debug, Clone)]
+pub struct Store {
+    root: PathBuf,
+}
+
+impl Store {
+    /// Creates a new store handle for the workspace at `workspace_root`.
+    ///
+    /// This does not create the directory on disk.
+    pub fn new<P>(workspace_root: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        Self {
+            root: workspace_root.as_ref().join(LICENSA_STORE_DIRNAME),
+        }
+    }
+
+    /// Returns the path to the store directory.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Returns the path to the store entry named `name`.
+    pub fn path<N>(&self, name: N) -> PathBuf
+    where
+        N: AsRef<Path>,
+    {
+        self.root.join(name)
+    }
+
+    /// Creates the store directory if it doesn't exist yet.
+    pub fn ensure(&self) -> Result<()> {
+        if self.root.is_dir() {
+            return Ok(());
+        }
+        fs::create_dir_all(&self.root)
+            .with_context(|| format!("failed to create {}", self.root.display()))?;
+        fs::write(self.root.join(".gitignore"), "*\n")
+            .with_context(|| format!("failed to initialize {}", self.root.display()))?;
+        Ok(())
+    }
+
+    /// Reads and deserializes the JSON store entry named `name`.
+    ///
+    /// Returns `Ok(None)` if the entry doesn't exist.
+    pub fn read<T, N>(&self, name: N) -> Result<Option<T>>
+    where
+        for<'de> T: Deserialize<'de>,
+        N: AsRef<Path>,
+    {
+        let path = self.path(name);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let value = serde_json::from_str::<T>(&content)
+            .with_context(|| format!("failed to parse {}", path.display()))?;
+        Ok(Some(value))
+    }
+
+    /// Serializes `value` as JSON and writes it to the store entry named `name`.
+    pub fn write<T, N>(&self, name: N, value: &T) -> Result<()>
+    where
+        T: Serialize,
+        N: AsRef<Path>,
+    {
+        self.ensure()?;
+        let path = self.path(name);
+        let content = serde_json::to_string_pretty(value)?;
+        fs::write(&path, content).with_context(|| format!("failed to write {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Removes the store entry named `name`, if it exists.
+    pub fn remove<N>(&self, name: N) -> Result<()>
+    where
+        N: AsRef<Path>,
+    {
+        let path = self.path(name);
+        if path.exists() {
+            fs::remove_file(&path)
+                .with_context(|| format!("failed to remove {}", path.display()))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_store_write_read_remove() {
+        let dir = tempdir().unwrap();
+        let store = Store::new(dir.path());
+        assert!(!store.root().exists());
+
+        let missing: Option<Vec<u32>> = store.read("entry").unwrap();
+        assert!(missing.is_none());
+
+        store.write("entry", &vec![1, 2, 3]).unwrap();
+        assert!(store.root().join(".gitignore").is_file());
+
+        let value: Option<Vec<u32>> = store.read("entry").unwrap();
+        assert_eq!(value, Some(vec![1, 2, 3]));
+
+        store.remove("entry").unwrap();
+        assert!(!store.path("entry").exists());
+
+        dir.close().unwrap();
+    }
+}
@@ -0,0 +1,123 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Detection of directories containing vendored third-party code.
+//!
+//! Vendored code carries the copyright of its authors, so stamping the workspace's
+//! own header on it would misattribute it. A directory is considered vendored if
+//! its name is commonly used for third-party code, e.g. `vendor` or `node_modules`,
+//! or if it contains a license file differing from those in the workspace root.
+//! Directories with their own Licensa config file are never considered vendored.
+
+use crate::ops::workspace::find_workspace_config_path;
+
+use std::fs;
+use std::path::Path;
+
+/// Names of directories commonly holding third-party code.
+pub const VENDORED_DIRNAMES: &[&str] = &[
+    "vendor",
+    "vendored",
+    "third_party",
+    "third-party",
+    "thirdparty",
+    "3rdparty",
+    "node_modules",
+    "bower_components",
+];
+
+/// Filename prefixes of license files (case-insensitive).
+const LICENSE_FILE_PREFIXES: &[&str] = &["license", "licence", "copying"];
+
+/// Detects vendored directories within a workspace.
+#[derive(Debug, Clone, Default)]
+pub struct VendorDetector {
+    /// Normalized contents of the license files in the workspace root.
+    root_licenses: Vec<String>,
+}
+
+impl VendorDetector {
+    /// Creates a new detector comparing license files against those in `workspace_root`.
+    pub fn new<P>(workspace_root: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        Self {
+            root_licenses: license_files(workspace_root.as_ref()),
+        }
+    }
+
+    /// Checks whether the directory `dir` contains vendored code.
+    pub fn is_vendored<P>(&self, dir: P) -> bool
+    where
+        P: AsRef<Path>,
+    {
+        let dir = dir.as_ref();
+        if find_workspace_config_path(dir).is_some() {
+            return false;
+        }
+        let is_vendor_name = dir
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| VENDORED_DIRNAMES.contains(&name.to_lowercase().as_str()));
+        is_vendor_name
+            || license_files(dir)
+                .iter()
+                .any(|license| !self.root_licenses.contains(license))
+    }
+}
+
+/// Returns the whitespace-normalized contents of the license files in `dir`.
+fn license_files(dir: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+    entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_ok_and(|ftype| ftype.is_file()))
+        .filter(|entry| {
+            let name = entry.file_name().to_string_lossy().to_lowercase();
+            LICENSE_FILE_PREFIXES
+                .iter()
+                .any(|prefix| name.starts_with(prefix))
+        })
+        .filter_map(|entry| fs::read(entry.path()).ok())
+        .map(|content| {
+            let content = String::from_utf8_lossy(&content);
+            content.split_whitespace().collect::<Vec<_>>().join(" ")
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_vendor_detector() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let create = |path: &str, content: &str| {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        };
+        create("LICENSE", "MIT License\n\nCopyright ACME");
+        create("Vendor/lib.rs", "");
+        create("crates/same/LICENSE.txt", "MIT License\nCopyright  ACME\n");
+        create("crates/foreign/COPYING", "GNU GENERAL PUBLIC LICENSE");
+        create("crates/member/LICENSE", "Apache License");
+        create("crates/member/.licensarc", "{}");
+        create("src/main.rs", "");
+
+        let detector = VendorDetector::new(root);
+        assert!(detector.is_vendored(root.join("Vendor")));
+        assert!(detector.is_vendored(root.join("crates/foreign")));
+        assert!(!detector.is_vendored(root.join("crates/same")));
+        assert!(!detector.is_vendored(root.join("crates/member")));
+        assert!(!detector.is_vendored(root.join("src")));
+
+        dir.close().unwrap();
+    }
+}
@@ -4,14 +4,50 @@
 //! This module provides tools for efficiently walking through a directory tree,
 //! filtering entries based on various criteria and providing control over the walk flow.
 
+use crate::config::LICENSA_IGNORE_FILENAME;
+use crate::workspace::git;
+use crate::workspace::store::LICENSA_STORE_DIRNAME;
+use crate::workspace::vendor::VendorDetector;
+
 use anyhow::Result;
+use clap::ValueEnum;
 use crossbeam_channel::{Receiver, Sender};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
 use ignore::overrides::OverrideBuilder;
 use ignore::{DirEntry, WalkBuilder as InternalWalkBuilder, WalkParallel, WalkState};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use tracing::debug;
 
 use std::ffi::OsStr;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::thread;
+
+/// Git attributes marking paths that are excluded from the walk, unless disabled
+/// with [`WalkBuilder::respect_git_attributes`].
+///
+/// `linguist-generated` marks generated code, `export-ignore` paths that are left out
+/// of source archives, e.g. test fixtures.
+const EXCLUDING_GIT_ATTRIBUTES: &[&str] = &["linguist-generated", "export-ignore"];
+
+/// Determines how symbolic links are treated during a walk.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum, JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum SymlinkPolicy {
+    /// Ignore symbolic links.
+    #[default]
+    Skip,
+
+    /// Follow symbolic links to files and directories.
+    Follow,
+
+    /// Fail when a symbolic link is encountered.
+    Error,
+}
 
 /// Represents the result of visiting a directory entry during the walk.
 ///
@@ -55,20 +91,28 @@ impl Walk {
     }
 
     /// Starts the walk asynchronously and returns a receiver for collecting [WalkResult]s.
+    ///
+    /// The walk runs on a background thread, so results can be consumed while the
+    /// walk is still in progress. With a maximum capacity set, walker threads block
+    /// until the receiver catches up. The walk stops early once the receiver is dropped.
     pub fn run_task(self) -> Receiver<WalkResult> {
         let (tx, rx) = self.chan::<WalkResult>();
-        self.inner.run(|| {
-            let tx = tx.clone();
-            let quit_fn = self.quit_while.clone();
-            let send_fn = self.send_while.clone();
-            Box::new(move |result| {
-                if quit_fn(result.clone()) {
-                    return WalkState::Quit;
-                }
-                if send_fn(result.clone()) {
-                    tx.send(result.clone()).unwrap();
-                }
-                WalkState::Continue
+        thread::spawn(move || {
+            let quit_fn = self.quit_while;
+            let send_fn = self.send_while;
+            self.inner.run(|| {
+                let tx = tx.clone();
+                let quit_fn = quit_fn.clone();
+                let send_fn = send_fn.clone();
+                Box::new(move |result| {
+                    if quit_fn(result.clone()) {
+                        return WalkState::Quit;
+                    }
+                    if send_fn(result.clone()) && tx.send(result).is_err() {
+                        return WalkState::Quit;
+                    }
+                    WalkState::Continue
+                })
             })
         });
 
@@ -135,16 +179,29 @@ pub struct WalkBuilder {
 
     exclude: Vec<String>,
     include: Vec<String>,
+
+    /// Whether to walk Git submodules and nested repositories.
+    include_submodules: bool,
+
+    /// Whether to walk directories containing vendored third-party code.
+    include_vendored: bool,
+
+    /// Whether to skip paths excluded by attributes in `.gitattributes`.
+    git_attributes: bool,
 }
 
 impl WalkBuilder {
     /// Creates a new builder with the workspace root directory.
+    ///
+    /// `.licensaignore` files are honored in the workspace root and in all of its
+    /// subdirectories.
     pub fn new<P>(workspace_root: P) -> Self
     where
         P: AsRef<Path>,
     {
         let workspace_root = workspace_root.as_ref();
-        let walker_builder = InternalWalkBuilder::new(workspace_root);
+        let mut walker_builder = InternalWalkBuilder::new(workspace_root);
+        walker_builder.add_custom_ignore_filename(LICENSA_IGNORE_FILENAME);
         let override_builder = OverrideBuilder::new(workspace_root);
         Self {
             walker_builder,
@@ -153,25 +210,88 @@ impl WalkBuilder {
             max_capacity: None,
             exclude: vec![],
             include: vec![],
+            include_submodules: false,
+            include_vendored: false,
+            git_attributes: true,
         }
     }
 
     /// Builds and returns a Walk instance based on the provided configuration.
     pub fn build(mut self) -> Result<Walk> {
         self.build_overrides()?;
+        self.build_entry_filter();
         let walk_parallel = self.walker_builder.build_parallel();
         let walk = Walk::new(walk_parallel, self.max_capacity);
         Ok(walk)
     }
 
-    /// Adds a custom file containing *.gitignore*-like patterns to ignore during the walk.
+    /// Adds the name of a custom file containing *.gitignore*-like patterns to ignore
+    /// during the walk.
+    ///
+    /// Files with this name are picked up in every directory of the walk. Patterns
+    /// are matched relative to the directory containing the file, and files in deeper
+    /// directories take precedence over those in their ancestors.
     #[inline]
     pub fn add_ignore<P>(&mut self, file_name: P) -> &Self
     where
         P: AsRef<OsStr>,
     {
-        let file_path = &self.workspace_root().join(file_name.as_ref());
-        self.walker_builder.add_custom_ignore_filename(file_path);
+        self.walker_builder
+            .add_custom_ignore_filename(file_name.as_ref());
+        self
+    }
+
+    /// Sets the number of threads used to walk the workspace.
+    ///
+    /// A value of `0` picks the number of threads automatically.
+    #[inline]
+    pub fn threads(&mut self, threads: usize) -> &Self {
+        self.walker_builder.threads(threads);
+        self
+    }
+
+    /// Controls whether to include hidden files and directories (default: disabled).
+    ///
+    /// `.git` directories and the Licensa store are skipped regardless.
+    #[inline]
+    pub fn include_hidden(&mut self, yes: bool) -> &Self {
+        self.walker_builder.hidden(!yes);
+        self
+    }
+
+    /// Controls whether to walk Git submodules and nested repositories (default: disabled).
+    ///
+    /// Submodules are detected by their entry in the workspace's `.gitmodules` file,
+    /// and nested repositories by a `.git` file or directory.
+    #[inline]
+    pub fn include_submodules(&mut self, yes: bool) -> &Self {
+        self.include_submodules = yes;
+        self
+    }
+
+    /// Controls whether to walk directories containing vendored code (default: disabled).
+    ///
+    /// See [`VendorDetector`] for how vendored directories are detected.
+    #[inline]
+    pub fn include_vendored(&mut self, yes: bool) -> &Self {
+        self.include_vendored = yes;
+        self
+    }
+
+    /// Controls whether to skip paths marked `linguist-generated` or `export-ignore`
+    /// in the workspace's `.gitattributes` file (default: enabled).
+    #[inline]
+    pub fn respect_git_attributes(&mut self, yes: bool) -> &Self {
+        self.git_attributes = yes;
+        self
+    }
+
+    /// Controls whether to follow symbolic links (default: disabled).
+    ///
+    /// Symbolic link cycles are reported as errors of the walk.
+    #[inline]
+    pub fn follow_links(&mut self, yes: bool) -> &Self {
+        self.walker_builder.follow_links(yes);
         self
     }
 
@@ -223,17 +343,71 @@ impl WalkBuilder {
         Ok(())
     }
 
-    // `include` patterns take precedence over exclude patterns.
+    // Never descend into VCS metadata or the Licensa store, even if hidden files are included.
+    // Unless requested otherwise, submodules, nested repositories, vendored directories
+    // and paths excluded by Git attributes are skipped as well.
+    fn build_entry_filter(&mut self) {
+        let submodules = match self.include_submodules {
+            true => None,
+            false => Some(git::submodule_paths(&self.workspace_root)),
+        };
+        let vendor_detector = match self.include_vendored {
+            true => None,
+            false => Some(VendorDetector::new(&self.workspace_root)),
+        };
+        let attribute_matchers = match self.git_attributes {
+            true => git_attribute_matchers(&self.workspace_root),
+            false => vec![],
+        };
+        self.walker_builder.filter_entry(move |entry| {
+            let name = entry.file_name();
+            if name == ".git" || name == LICENSA_STORE_DIRNAME {
+                return false;
+            }
+            if entry.depth() > 0 {
+                let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+                let is_excluded = attribute_matchers.iter().any(|matcher| {
+                    matcher
+                        .matched_path_or_any_parents(entry.path(), is_dir)
+                        .is_ignore()
+                });
+                if is_excluded {
+                    return false;
+                }
+                let is_vendored = is_dir
+                    && vendor_detector
+                        .as_ref()
+                        .is_some_and(|detector| detector.is_vendored(entry.path()));
+                if is_vendored {
+                    debug!("skip {}: vendored directory", entry.path().display());
+                    return false;
+                }
+            }
+            let Some(submodules) = submodules.as_ref() else {
+                return true;
+            };
+            if entry.depth() == 0 || !entry.file_type().is_some_and(|t| t.is_dir()) {
+                return true;
+            }
+            !submodules.iter().any(|path| path == entry.path())
+                && !git::is_nested_repository(entry.path())
+        });
+    }
+
+    // `include` patterns take precedence over exclude patterns, since the last matching
+    // override glob wins. Once any include pattern is set, files that don't match one
+    // of them are skipped. Negated include patterns, e.g. `!src/generated/**`, carve out
+    // exceptions and are added last, so they win regardless of their position.
     // Leave the override builder untouched if both include and exclude patterns are empty.
     fn build_overrides(&mut self) -> Result<()> {
         if self.include.is_empty() && self.exclude.is_empty() {
             return Ok(());
         }
-        let patterns = match self.include.is_empty() {
-            true => &self.exclude,
-            false => &self.include,
-        };
-        for pattern in patterns {
+        let (negated, include): (Vec<&String>, Vec<&String>) = self
+            .include
+            .iter()
+            .partition(|pattern| pattern.starts_with('!'));
+        for pattern in self.exclude.iter().chain(include).chain(negated) {
             self.override_builder.add(pattern)?;
         }
         let overrides = self.override_builder.build()?;
@@ -243,6 +417,25 @@ impl WalkBuilder {
     }
 }
 
+/// Builds a matcher for each of the [`EXCLUDING_GIT_ATTRIBUTES`] set in the `.gitattributes`
+/// file of `root`.
+///
+/// Invalid patterns are skipped, as Git does.
+fn git_attribute_matchers(root: &Path) -> Vec<Gitignore> {
+    EXCLUDING_GIT_ATTRIBUTES
+        .iter()
+        .map(|attribute| git::attribute_patterns(root, attribute))
+        .filter(|patterns| !patterns.is_empty())
+        .filter_map(|patterns| {
+            let mut builder = GitignoreBuilder::new(root);
+            for pattern in patterns {
+                let _ = builder.add_line(None, &pattern);
+            }
+            builder.build().ok()
+        })
+        .collect()
+}
+
 /// Helper function to negate glob patterns (add/remove leading `!`).
 ///
 /// Patterns without a leading `!` are prefixed with one.
@@ -270,6 +463,27 @@ mod tests {
     use rayon::prelude::*;
     use tempfile::{tempdir, TempDir};
 
+    // Helper function to walk the files of `builder`, returning their sorted,
+    // `/`-separated paths relative to `root`
+    fn walk_relative_files(builder: WalkBuilder, root: &Path) -> Vec<String> {
+        let mut walker = builder.build().unwrap();
+        walker.send_while(|res| res.is_ok_and(|e| e.file_type().unwrap().is_file()));
+        let mut files: Vec<String> = walker
+            .run_task()
+            .into_iter()
+            .filter_map(Result::ok)
+            .map(|e| {
+                e.path()
+                    .strip_prefix(root)
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect();
+        files.sort();
+        files
+    }
+
     // Helper function to create a test workspace walk builder
     fn create_test_builder() -> (TempDir, WalkBuilder) {
         let dir = tempdir().unwrap();
@@ -477,6 +691,188 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_workspace_walk_with_nested_licensaignore() {
+        let tmp_dir = tempdir().unwrap();
+        let root = tmp_dir.path();
+        let package = root.join("packages").join("app");
+        std::fs::create_dir_all(package.join("generated")).unwrap();
+
+        std::fs::write(root.join(LICENSA_IGNORE_FILENAME), "*.snap\n").unwrap();
+        std::fs::write(
+            package.join(LICENSA_IGNORE_FILENAME),
+            "generated/\n!keep.snap\n",
+        )
+        .unwrap();
+        for file in ["a.rs", "a.snap"] {
+            File::create(root.join(file)).unwrap();
+        }
+        for file in ["b.rs", "keep.snap", "generated/c.rs"] {
+            File::create(package.join(file)).unwrap();
+        }
+
+        let files = walk_relative_files(WalkBuilder::new(root), root);
+
+        assert_eq!(
+            files,
+            ["a.rs", "packages/app/b.rs", "packages/app/keep.snap"]
+        );
+    }
+
+    #[test]
+    fn test_workspace_walk_include_overrides_exclude() {
+        let tmp_dir = tempdir().unwrap();
+        let root = tmp_dir.path();
+        std::fs::create_dir_all(root.join("src").join("gen")).unwrap();
+        for file in [
+            "build.rs",
+            "src/lib.rs",
+            "src/lib.py",
+            "src/gen/a.rs",
+            "src/gen/b.rs",
+        ] {
+            File::create(root.join(file)).unwrap();
+        }
+
+        let mut builder = WalkBuilder::new(root);
+        builder.exclude(Some(vec!["src/gen/*", "*.py"])).unwrap();
+        builder.include(Some(vec!["src/**/*.rs"])).unwrap();
+        let files = walk_relative_files(builder, root);
+
+        assert_eq!(files, ["src/gen/a.rs", "src/gen/b.rs", "src/lib.rs"]);
+    }
+
+    #[test]
+    fn test_workspace_walk_negated_include_patterns() {
+        let tmp_dir = tempdir().unwrap();
+        let root = tmp_dir.path();
+        std::fs::create_dir_all(root.join("src").join("gen")).unwrap();
+        for file in ["build.rs", "src/lib.rs", "src/gen/a.rs"] {
+            File::create(root.join(file)).unwrap();
+        }
+        let walk = |include: Vec<&'static str>| {
+            let mut builder = WalkBuilder::new(root);
+            builder.include(Some(include)).unwrap();
+            walk_relative_files(builder, root)
+        };
+
+        assert_eq!(walk(vec!["src/**", "!src/gen/**"]), ["src/lib.rs"]);
+
+        // Negations win regardless of their position
+        assert_eq!(walk(vec!["!src/gen/**", "src/**"]), ["src/lib.rs"]);
+
+        // Without positive patterns, everything but the negated paths is included
+        assert_eq!(walk(vec!["!src/gen/**"]), ["build.rs", "src/lib.rs"]);
+    }
+
+    #[test]
+    fn test_workspace_walk_include_hidden() {
+        let tmp_dir = tempdir().unwrap();
+        let root = tmp_dir.path();
+        for dir in [".github/workflows", ".git/hooks", ".licensa"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        for file in [
+            ".bazelrc",
+            ".github/workflows/ci.yml",
+            ".git/hooks/pre-commit.sh",
+            ".licensa/journal",
+            "main.rs",
+        ] {
+            File::create(root.join(file)).unwrap();
+        }
+
+        let walk_files = |include_hidden: bool| {
+            let mut builder = WalkBuilder::new(root);
+            builder.include_hidden(include_hidden);
+            walk_relative_files(builder, root)
+        };
+
+        assert_eq!(walk_files(false), ["main.rs"]);
+        assert_eq!(
+            walk_files(true),
+            [".bazelrc", ".github/workflows/ci.yml", "main.rs"]
+        );
+    }
+
+    #[test]
+    fn test_workspace_walk_skips_submodules() {
+        let tmp_dir = tempdir().unwrap();
+        let root = tmp_dir.path();
+        for dir in ["src", "vendor/declared", "vendor/nested/.git"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        std::fs::write(
+            root.join(".gitmodules"),
+            "[submodule \"declared\"]\n\tpath = vendor/declared\n",
+        )
+        .unwrap();
+        for file in [
+            "src/main.rs",
+            "vendor/declared/lib.rs",
+            "vendor/nested/lib.rs",
+        ] {
+            File::create(root.join(file)).unwrap();
+        }
+
+        let walk_files = |include_submodules: bool| {
+            let mut builder = WalkBuilder::new(root);
+            builder.include_submodules(include_submodules);
+            // Submodules are placed in `vendor`, which is skipped as vendored otherwise
+            builder.include_vendored(true);
+            walk_relative_files(builder, root)
+        };
+
+        assert_eq!(walk_files(false), ["src/main.rs"]);
+        assert_eq!(
+            walk_files(true),
+            [
+                "src/main.rs",
+                "vendor/declared/lib.rs",
+                "vendor/nested/lib.rs"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_workspace_walk_skips_git_attributes() {
+        let tmp_dir = tempdir().unwrap();
+        let root = tmp_dir.path();
+        for dir in ["api", "fixtures/data"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        std::fs::write(
+            root.join(".gitattributes"),
+            "api/*.go linguist-generated\napi/client.go -linguist-generated\n/fixtures export-ignore\n",
+        )
+        .unwrap();
+        for file in [
+            "api/client.go",
+            "api/service.pb.go",
+            "fixtures/data/input.rs",
+            "main.go",
+        ] {
+            File::create(root.join(file)).unwrap();
+        }
+
+        let walk_files = |git_attributes: bool| {
+            let mut builder = WalkBuilder::new(root);
+            builder.respect_git_attributes(git_attributes);
+            walk_relative_files(builder, root)
+        };
+
+        assert_eq!(walk_files(true), ["api/client.go", "main.go"]);
+        assert_eq!(
+            walk_files(false),
+            [
+                "api/client.go",
+                "api/service.pb.go",
+                "fixtures/data/input.rs",
+                "main.go"
+            ]
+        );
+    }
+
     #[test]
     fn test_workspace_walk_with_disable_git_ignore() {
         let (tmp_dir, mut builder) = create_test_builder();
@@ -0,0 +1,87 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn licensa(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_licensa"))
+        .args(args)
+        .current_dir(dir)
+        .env("NO_COLOR", "1")
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_apply_exit_status() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    fs::write(root.join("main.rs"), "fn main() {}\n").unwrap();
+
+    let output = licensa(root, &["apply", "-t", "MIT", "-o", "ACME"]);
+    assert!(output.status.success());
+
+    // Files that fail to be licensed, e.g. by a failing plugin, fail the run
+    fs::write(root.join("data.xyz"), "data\n").unwrap();
+    fs::write(
+        root.join(".licensarc"),
+        r#"{ "plugins": [{ "extensions": ["xyz"], "command": ["false"] }] }"#,
+    )
+    .unwrap();
+    let output = licensa(root, &["apply", "-t", "MIT", "-o", "ACME"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("failed to license 1 file(s)"));
+}
+
+#[test]
+fn test_apply_member_file_types() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    fs::create_dir(root.join("pkg")).unwrap();
+    fs::write(root.join(".licensarc"), r#"{ "workspaces": ["pkg"] }"#).unwrap();
+    fs::write(
+        root.join("pkg").join(".licensarc"),
+        r#"{ "fileTypes": { ".tpl": ".sh" } }"#,
+    )
+    .unwrap();
+    fs::write(root.join("pkg").join("a.tpl"), "echo a\n").unwrap();
+    fs::write(root.join("b.tpl"), "echo b\n").unwrap();
+
+    let output = licensa(root, &["apply", "-t", "MIT", "-o", "ACME"]);
+    assert!(output.status.success());
+
+    // File types of a member only apply to its own files
+    let licensed = fs::read_to_string(root.join("pkg").join("a.tpl")).unwrap();
+    assert!(licensed.starts_with("# Copyright"));
+    assert!(licensed.ends_with("echo a\n"));
+    let unlicensed = fs::read_to_string(root.join("b.tpl")).unwrap();
+    assert_eq!(unlicensed, "echo b\n");
+}
+
+#[test]
+fn test_apply_member_head_directives() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    fs::create_dir(root.join("pkg")).unwrap();
+    fs::write(root.join(".licensarc"), r#"{ "workspaces": ["pkg"] }"#).unwrap();
+    fs::write(
+        root.join("pkg").join(".licensarc"),
+        r##"{ "headDirectives": ["#cloud-config"] }"##,
+    )
+    .unwrap();
+    fs::write(
+        root.join("pkg").join("c.yaml"),
+        "#cloud-config\nusers: []\n",
+    )
+    .unwrap();
+
+    let output = licensa(root, &["apply", "-t", "MIT", "-o", "ACME"]);
+    assert!(output.status.success());
+
+    // The header is inserted beneath the directive of the member config
+    let licensed = fs::read_to_string(root.join("pkg").join("c.yaml")).unwrap();
+    assert!(licensed.starts_with("#cloud-config\n# Copyright"));
+    assert!(licensed.ends_with("users: []\n"));
+}
@@ -0,0 +1,29 @@
+// Copyright 2024 Nelson Dominguez
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use std::fs;
+use std::process::Command;
+
+#[test]
+fn test_notice_from_cargo_manifest() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let root = temp_dir.path();
+    fs::write(
+        root.join("Cargo.toml"),
+        "[package]\nname = \"example\"\nauthors = [\"Jane Doe\"]\nlicense = \"MIT\"\n",
+    )
+    .unwrap();
+
+    // The project, owner and license are read from the manifest
+    let output = Command::new(env!("CARGO_BIN_EXE_licensa"))
+        .arg("notice")
+        .current_dir(root)
+        .env("NO_COLOR", "1")
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+
+    let notice = fs::read_to_string(root.join("NOTICE")).unwrap();
+    assert!(notice.starts_with("example\n"), "{notice}");
+    assert!(notice.contains("Jane Doe"), "{notice}");
+}